@@ -0,0 +1,11 @@
+//! Atomic types used in the crate.
+//!
+//! The `portable-atomic` feature replaces them with those of
+//! [`portable-atomic`](https://docs.rs/portable-atomic/) for targets without native atomic
+//! compare-and-swap operations.
+
+#[cfg(not(feature = "portable-atomic"))]
+pub(crate) use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU8, AtomicUsize};
+
+#[cfg(feature = "portable-atomic")]
+pub(crate) use portable_atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU8, AtomicUsize};
@@ -2,11 +2,11 @@
 
 use super::ebr::Guard;
 use super::{LinkedEntry, LinkedList, Stack};
+use crate::atomic::AtomicUsize;
 use std::iter::FusedIterator;
 use std::mem::{needs_drop, MaybeUninit};
 use std::panic::UnwindSafe;
 use std::ptr::drop_in_place;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
 
 /// [`Bag`] is a lock-free concurrent unordered instance container.
@@ -17,6 +17,9 @@ use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
 ///
 /// The maximum value of `ARRAY_LEN` is limited to `usize::BITS / 2` which is the default value, and
 /// if a larger value is specified, [`Bag::new`] panics.
+///
+/// Unlike [`Queue`](crate::Queue) and [`Stack`], [`Bag`] does not preserve the order of instances,
+/// and any vacant slot can accept a new instance; this makes [`Bag`] suitable for object pooling.
 #[derive(Debug)]
 pub struct Bag<T, const ARRAY_LEN: usize = DEFAULT_ARRAY_LEN> {
     /// Primary storage.
@@ -0,0 +1,500 @@
+//! [`channel`](self) provides multi-producer multi-consumer channels built on [`BoundedQueue`].
+
+use super::queue::BoundedQueue;
+use super::wait_queue::{AsyncWait, WaitQueue};
+use crate::atomic::AtomicUsize;
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug};
+use std::pin::Pin;
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
+use std::sync::Arc;
+
+/// [`Sender`] sends instances to the [`Receiver`] instances of the channel.
+///
+/// [`Sender`] can be cloned, and the channel is disconnected when all the [`Sender`] instances
+/// or all the [`Receiver`] instances are dropped.
+pub struct Sender<T> {
+    channel: Arc<Channel<T>>,
+}
+
+/// [`Receiver`] receives instances sent by the [`Sender`] instances of the channel.
+///
+/// [`Receiver`] can be cloned, and each instance is received by exactly one [`Receiver`].
+pub struct Receiver<T> {
+    channel: Arc<Channel<T>>,
+}
+
+/// [`Channel`] is the state shared among [`Sender`] and [`Receiver`] instances.
+struct Channel<T> {
+    /// `queue` stores instances that are sent and yet to be received.
+    queue: BoundedQueue<Message<T>>,
+
+    /// `num_senders` is the number of [`Sender`] instances.
+    num_senders: AtomicUsize,
+
+    /// `num_receivers` is the number of [`Receiver`] instances.
+    num_receivers: AtomicUsize,
+
+    /// `send_wait_queue` holds senders waiting for the channel to have a vacant slot.
+    send_wait_queue: WaitQueue,
+
+    /// `recv_wait_queue` holds receivers waiting for an instance to be sent.
+    recv_wait_queue: WaitQueue,
+}
+
+/// [`Message`] stores an instance in the [`BoundedQueue`] until it is received.
+struct Message<T>(UnsafeCell<Option<T>>);
+
+/// Creates an unbounded channel.
+///
+/// # Examples
+///
+/// ```
+/// use scc::channel;
+///
+/// let (sender, receiver) = channel::unbounded();
+///
+/// assert!(sender.send(11).is_ok());
+/// assert_eq!(receiver.recv(), Some(11));
+/// ```
+#[inline]
+#[must_use]
+pub fn unbounded<T: 'static>() -> (Sender<T>, Receiver<T>) {
+    bounded(usize::MAX)
+}
+
+/// Creates a channel that can hold at most `capacity` instances.
+///
+/// [`Sender::send`] blocks and [`Sender::send_async`] waits if the channel is full.
+///
+/// # Examples
+///
+/// ```
+/// use scc::channel;
+///
+/// let (sender, receiver) = channel::bounded(1);
+///
+/// assert!(sender.try_send(11).is_ok());
+/// assert_eq!(sender.try_send(17), Err(17));
+/// assert_eq!(receiver.recv(), Some(11));
+/// ```
+#[inline]
+#[must_use]
+pub fn bounded<T: 'static>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let channel = Arc::new(Channel {
+        queue: BoundedQueue::new(capacity),
+        num_senders: AtomicUsize::new(1),
+        num_receivers: AtomicUsize::new(1),
+        send_wait_queue: WaitQueue::default(),
+        recv_wait_queue: WaitQueue::default(),
+    });
+    (
+        Sender {
+            channel: channel.clone(),
+        },
+        Receiver { channel },
+    )
+}
+
+impl<T: 'static> Sender<T> {
+    /// Sends an instance of `T`, blocking the current thread while the channel is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the supplied instance if all the [`Receiver`] instances have
+    /// been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded();
+    ///
+    /// assert!(sender.send(11).is_ok());
+    /// drop(receiver);
+    /// assert_eq!(sender.send(17), Err(17));
+    /// ```
+    #[inline]
+    pub fn send(&self, val: T) -> Result<(), T> {
+        let mut val = val;
+        loop {
+            val = match self.try_send(val) {
+                Ok(()) => return Ok(()),
+                Err(val) if self.is_disconnected() => return Err(val),
+                Err(val) => val,
+            };
+            let _result = self.channel.send_wait_queue.wait_sync(|| {
+                if !self.channel.queue.is_full() || self.is_disconnected() {
+                    Ok(())
+                } else {
+                    Err(())
+                }
+            });
+        }
+    }
+
+    /// Sends an instance of `T`, waiting while the channel is full.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the supplied instance if all the [`Receiver`] instances have
+    /// been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::bounded(4);
+    /// let future_send = sender.send_async(11);
+    /// ```
+    #[inline]
+    pub async fn send_async(&self, val: T) -> Result<(), T> {
+        let mut val = val;
+        loop {
+            val = match self.try_send(val) {
+                Ok(()) => return Ok(()),
+                Err(val) if self.is_disconnected() => return Err(val),
+                Err(val) => val,
+            };
+            let mut async_wait = AsyncWait::default();
+            let _result = self
+                .channel
+                .send_wait_queue
+                .push_async_entry(&mut async_wait, || {
+                    if !self.channel.queue.is_full() || self.is_disconnected() {
+                        Ok(())
+                    } else {
+                        Err(())
+                    }
+                });
+            Pin::new(&mut async_wait).await;
+        }
+    }
+
+    /// Sends an instance of `T` if the channel is not full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the supplied instance if the channel is full or all the
+    /// [`Receiver`] instances have been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::bounded(1);
+    ///
+    /// assert!(sender.try_send(11).is_ok());
+    /// assert_eq!(sender.try_send(17), Err(17));
+    /// ```
+    #[inline]
+    pub fn try_send(&self, val: T) -> Result<(), T> {
+        if self.is_disconnected() {
+            return Err(val);
+        }
+        match self.channel.queue.push(Message(UnsafeCell::new(Some(val)))) {
+            Ok(_) => {
+                self.channel.recv_wait_queue.signal();
+                Ok(())
+            }
+            Err(message) => Err(unsafe { message.take() }),
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Returns the number of instances in the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded();
+    ///
+    /// assert!(sender.send(11).is_ok());
+    /// assert_eq!(sender.len(), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.channel.queue.len()
+    }
+
+    /// Returns `true` if the channel is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded::<usize>();
+    ///
+    /// assert!(sender.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.channel.queue.is_empty()
+    }
+
+    /// Returns `true` if all the [`Receiver`] instances have been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded::<usize>();
+    ///
+    /// assert!(!sender.is_disconnected());
+    /// drop(receiver);
+    /// assert!(sender.is_disconnected());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_disconnected(&self) -> bool {
+        self.channel.num_receivers.load(Acquire) == 0
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Receives an instance of `T`, blocking the current thread while the channel is empty.
+    ///
+    /// Returns `None` if the channel is empty and all the [`Sender`] instances have been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded();
+    ///
+    /// assert!(sender.send(11).is_ok());
+    /// drop(sender);
+    /// assert_eq!(receiver.recv(), Some(11));
+    /// assert!(receiver.recv().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn recv(&self) -> Option<T> {
+        loop {
+            if let Some(val) = self.try_recv() {
+                return Some(val);
+            }
+            if self.is_disconnected() {
+                // Instances sent before the last `Sender` was dropped have to be received.
+                return self.try_recv();
+            }
+            let result = self.channel.recv_wait_queue.wait_sync(|| {
+                if let Some(val) = self.try_recv() {
+                    Ok(Some(val))
+                } else if self.is_disconnected() {
+                    Ok(None)
+                } else {
+                    Err(())
+                }
+            });
+            if let Ok(Some(val)) = result {
+                return Some(val);
+            }
+        }
+    }
+
+    /// Receives an instance of `T`, waiting while the channel is empty.
+    ///
+    /// Returns `None` if the channel is empty and all the [`Sender`] instances have been dropped.
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded::<usize>();
+    /// let future_recv = receiver.recv_async();
+    /// ```
+    #[inline]
+    pub async fn recv_async(&self) -> Option<T> {
+        loop {
+            if let Some(val) = self.try_recv() {
+                return Some(val);
+            }
+            if self.is_disconnected() {
+                // Instances sent before the last `Sender` was dropped have to be received.
+                return self.try_recv();
+            }
+            let mut received = None;
+            let mut async_wait = AsyncWait::default();
+            let _result = self
+                .channel
+                .recv_wait_queue
+                .push_async_entry(&mut async_wait, || {
+                    received = self.try_recv();
+                    if received.is_some() || self.is_disconnected() {
+                        Ok(())
+                    } else {
+                        Err(())
+                    }
+                });
+            Pin::new(&mut async_wait).await;
+            if received.is_some() {
+                return received;
+            }
+        }
+    }
+
+    /// Receives an instance of `T` if the channel is not empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded();
+    ///
+    /// assert!(receiver.try_recv().is_none());
+    /// assert!(sender.send(11).is_ok());
+    /// assert_eq!(receiver.try_recv(), Some(11));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn try_recv(&self) -> Option<T> {
+        let entry = self.channel.queue.pop()?;
+        self.channel.send_wait_queue.signal();
+
+        // The entry was popped by the current thread, therefore no other threads can access it.
+        Some(unsafe { entry.take() })
+    }
+
+    /// Returns the number of instances in the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded();
+    ///
+    /// assert!(sender.send(11).is_ok());
+    /// assert_eq!(receiver.len(), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.channel.queue.len()
+    }
+
+    /// Returns `true` if the channel is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded::<usize>();
+    ///
+    /// assert!(receiver.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.channel.queue.is_empty()
+    }
+
+    /// Returns `true` if all the [`Sender`] instances have been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::channel;
+    ///
+    /// let (sender, receiver) = channel::unbounded::<usize>();
+    ///
+    /// assert!(!receiver.is_disconnected());
+    /// drop(sender);
+    /// assert!(receiver.is_disconnected());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_disconnected(&self) -> bool {
+        self.channel.num_senders.load(Acquire) == 0
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.channel.num_senders.fetch_add(1, Relaxed);
+        Self {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl<T> Debug for Sender<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.channel.num_senders.fetch_sub(1, AcqRel) == 1 {
+            // Wake up receivers waiting for an instance.
+            self.channel.recv_wait_queue.signal();
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.channel.num_receivers.fetch_add(1, Relaxed);
+        Self {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl<T> Debug for Receiver<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.channel.num_receivers.fetch_sub(1, AcqRel) == 1 {
+            // Wake up senders waiting for a vacant slot.
+            self.channel.send_wait_queue.signal();
+        }
+    }
+}
+
+impl<T> Message<T> {
+    /// Takes the instance out of the [`Message`].
+    ///
+    /// # Safety
+    ///
+    /// The caller has to be the only one that accesses the [`Message`].
+    unsafe fn take(&self) -> T {
+        (*self.0.get()).take().unwrap_unchecked()
+    }
+}
+
+unsafe impl<T: Send> Send for Message<T> {}
+unsafe impl<T: Send> Sync for Message<T> {}
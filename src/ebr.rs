@@ -3,13 +3,45 @@
 //! The epoch consensus algorithm and the use of memory barriers and RMW semantics are similar to
 //! that of [`crossbeam_epoch`](https://docs.rs/crossbeam-epoch/), however the API set is vastly
 //! different, for instance, `unsafe` blocks are not required to read an instance subject to EBR.
+//!
+//! A thread holding a [`Guard`] for a long time delays reclamation of instances retired by any
+//! container in the process. Use [`statistics`] to detect such a thread, and keep the lifetime of
+//! each [`Guard`] short, or call [`suspend`] before a thread goes idle.
+//!
+//! A thread that has to keep an instance alive for a long time should hold a [`Shared`] instead of
+//! a [`Guard`]; a [`Shared`] keeps only its own instance alive without stalling the global epoch.
+//!
+//! An instance managed by [`std::sync::Arc`] can be published through an [`AtomicArc`] without
+//! moving it into a new allocation.
+//!
+//! ```
+//! use scc::ebr::{AtomicArc, Guard};
+//! use std::sync::atomic::Ordering::Acquire;
+//! use std::sync::Arc;
+//!
+//! let arc: Arc<String> = Arc::new(String::from("scc"));
+//! let atomic_arc: AtomicArc<String> = AtomicArc::new(arc.clone());
+//!
+//! let guard = Guard::new();
+//! let published = atomic_arc.load(Acquire, &guard).unwrap();
+//! assert!(std::ptr::eq(published, &*arc));
+//! ```
+
+mod atomic_arc;
+pub use atomic_arc::AtomicArc;
 
 mod atomic_owned;
 pub use atomic_owned::AtomicOwned;
 
+mod atomic_owned_cell;
+pub use atomic_owned_cell::AtomicOwnedCell;
+
 mod atomic_shared;
 pub use atomic_shared::AtomicShared;
 
+mod epoch;
+pub use epoch::Epoch;
+
 mod guard;
 pub use guard::Guard;
 
@@ -25,10 +57,22 @@ pub use ptr::Ptr;
 mod shared;
 pub use shared::Shared;
 
+mod statistics;
+#[cfg(feature = "ebr-stats")]
+pub use statistics::CollectorStatistics;
+pub use statistics::Statistics;
+
 mod tag;
 pub use tag::Tag;
 
+mod watchdog;
+pub use watchdog::StalledThread;
+
+mod weak;
+pub use weak::Weak;
+
 mod collector;
+mod reclaimer;
 mod ref_counted;
 
 /// Suspends the garbage collector of the current thread.
@@ -36,6 +80,11 @@ mod ref_counted;
 /// If returns `false` if there is an active [`Guard`] in the thread. Otherwise, it passes all its
 /// retired instances to a free flowing garbage container that can be cleaned up by other threads.
 ///
+/// A thread without an active [`Guard`] never prevents the global epoch from advancing, however its
+/// retired instances are only reclaimed when the thread creates a [`Guard`] again; therefore, a
+/// thread that is about to be parked for a long time should call [`suspend`] to let other threads
+/// reclaim them.
+///
 /// # Examples
 ///
 /// ```
@@ -61,3 +110,273 @@ mod ref_counted;
 pub fn suspend() -> bool {
     collector::Collector::pass_garbage()
 }
+
+/// Detaches the current thread from the garbage collector.
+///
+/// It returns `false` if there is an active [`Guard`] in the thread. Otherwise, it passes all its
+/// retired instances to other threads, and releases the thread-local data structure that is
+/// usually released when the thread exits; this is useful if thread-local destructors may not
+/// run, e.g., when the thread is handed over to foreign code. The thread is attached to the
+/// garbage collector again when it creates a [`Guard`].
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{detach, Guard, Shared};
+///
+/// let shared: Shared<usize> = Shared::new(47);
+/// let guard = Guard::new();
+/// assert!(shared.release(&guard));
+/// assert!(!detach());
+///
+/// drop(guard);
+/// assert!(detach());
+/// ```
+#[inline]
+#[must_use]
+pub fn detach() -> bool {
+    collector::Collector::detach()
+}
+
+/// Runs a garbage collection pass in the current thread.
+///
+/// It returns `false` without doing anything if there is an active [`Guard`] in the thread.
+/// Otherwise, the thread reclaims its retired instances that have become unreachable, and tries
+/// to update the global epoch which allows other threads to reclaim their retired instances. A
+/// thread usually does this when it creates and drops a [`Guard`] at a fixed cadence, therefore a
+/// long-running thread that rarely creates a [`Guard`] can call [`flush`] periodically to
+/// contribute to garbage collection.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{flush, Guard, Shared};
+///
+/// let shared: Shared<usize> = Shared::new(47);
+/// let guard = Guard::new();
+/// assert!(shared.release(&guard));
+/// assert!(!flush());
+///
+/// drop(guard);
+/// assert!(flush());
+/// ```
+#[inline]
+#[must_use]
+pub fn flush() -> bool {
+    collector::Collector::flush()
+}
+
+/// Waits until no thread can reach the instances retired before the call, and all the instances
+/// retired by the current thread are reclaimed.
+///
+/// It returns `false` without waiting if there is an active [`Guard`] in the thread. Otherwise, it
+/// waits until the global epoch has been updated twice, after which no [`Guard`] that was alive
+/// at the time of the call remains, therefore the instances retired by any thread before the call
+/// are unreachable; instances retired by other threads are dropped when those threads next create
+/// a [`Guard`]. It also waits until all the instances retired by the current thread, including
+/// instances retired by terminated threads that the current thread has taken over, are dropped,
+/// and the background reclamation thread drops the instances handed over to it; this is useful if
+/// the destructor of an instance has external side effects that have to be observed, e.g., on
+/// shutdown.
+///
+/// The thread is parked while other threads are holding a [`Guard`], and threads dropping their
+/// last [`Guard`] try to update the global epoch while a thread is waiting. It blocks as long as
+/// another thread holds a [`Guard`] without ever dropping it.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{synchronize, Guard, Shared};
+/// use std::sync::atomic::AtomicBool;
+/// use std::sync::atomic::Ordering::Relaxed;
+///
+/// static DROPPED: AtomicBool = AtomicBool::new(false);
+///
+/// struct R;
+/// impl Drop for R {
+///     fn drop(&mut self) {
+///         DROPPED.store(true, Relaxed);
+///     }
+/// }
+///
+/// let shared: Shared<R> = Shared::new(R);
+/// let guard = Guard::new();
+/// assert!(shared.release(&guard));
+/// assert!(!synchronize());
+///
+/// drop(guard);
+/// assert!(synchronize());
+/// assert!(DROPPED.load(Relaxed));
+/// ```
+#[must_use]
+pub fn synchronize() -> bool {
+    if !collector::Collector::synchronize() {
+        return false;
+    }
+    reclaimer::wait_reclaimed(reclaimer::ticket());
+    true
+}
+
+/// Waits until no thread can reach the instances retired before the call, and all the instances
+/// retired by the current thread are reclaimed.
+///
+/// It is an asynchronous version of [`synchronize`]; the task is woken up whenever the global
+/// epoch is updated, and the current thread is the thread polling the future at the time the
+/// future completes.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{synchronize_async, Guard, Shared};
+///
+/// let shared: Shared<usize> = Shared::new(47);
+/// assert!(shared.release(&Guard::new()));
+///
+/// let future_synchronize = synchronize_async();
+/// ```
+pub async fn synchronize_async() -> bool {
+    if !collector::Collector::synchronize_async().await {
+        return false;
+    }
+    reclaimer::wait_reclaimed_async(reclaimer::ticket()).await;
+    true
+}
+
+/// Enables or disables background reclamation.
+///
+/// Retired instances are dropped by whichever thread observes an epoch update, which may cause a
+/// long pause if the instances have expensive destructors. Once background reclamation is enabled,
+/// reclaimable instances are handed over to a dedicated thread instead. The thread is spawned when
+/// background reclamation is enabled for the first time, and it survives disabling it. A panic in
+/// an instance being dropped by the thread is caught, and the thread proceeds to the next instance.
+///
+/// Returns the previous state; if the dedicated thread cannot be spawned, the state does not
+/// change.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{set_background_reclamation, Guard, Shared};
+///
+/// assert!(!set_background_reclamation(true));
+///
+/// let shared: Shared<Vec<u8>> = Shared::new(vec![0; 1024]);
+/// let guard = Guard::new();
+/// assert!(shared.release(&guard));
+/// drop(guard);
+///
+/// assert!(set_background_reclamation(false));
+/// ```
+#[inline]
+#[must_use]
+pub fn set_background_reclamation(enabled: bool) -> bool {
+    reclaimer::set_enabled(enabled)
+}
+
+/// Sets the maximum number and total size of retired instances in a thread.
+///
+/// A thread usually tries to update the global epoch at a fixed cadence; once the number or the
+/// total size of retired instances in the thread exceeds the limit, the thread immediately tries
+/// to update the global epoch, and then keeps trying whenever the last [`Guard`] in the thread is
+/// dropped until enough instances are reclaimed. This bounds memory growth under bursty removals
+/// at the cost of more frequent epoch updates. The limit is not enforced by default, which is
+/// equivalent to setting both values to [`usize::MAX`]; retired instances are only counted while a
+/// limit is set unless the `ebr-stats` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{set_garbage_limit, Guard, Shared};
+///
+/// set_garbage_limit(1024, 1 << 20);
+///
+/// let shared: Shared<[u8; 4096]> = Shared::new([0; 4096]);
+/// let guard = Guard::new();
+/// assert!(shared.release(&guard));
+/// drop(guard);
+///
+/// set_garbage_limit(usize::MAX, usize::MAX);
+/// ```
+#[inline]
+pub fn set_garbage_limit(max_num_garbage: usize, max_garbage_bytes: usize) {
+    collector::Collector::set_garbage_limit(max_num_garbage, max_garbage_bytes);
+}
+
+/// Returns the garbage collection [`Statistics`].
+///
+/// It can be used to detect stalled garbage collection, e.g., caused by a thread holding a
+/// [`Guard`] for a long time, before running out of memory. Returns `None` without waiting if
+/// another thread is registering or cleaning up a thread-local garbage collector.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{statistics, Guard};
+///
+/// drop(Guard::new());
+///
+/// if let Some(statistics) = statistics() {
+///     assert!(statistics.num_threads() >= 1);
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn statistics() -> Option<Statistics> {
+    collector::Collector::statistics()
+}
+
+/// Returns the threads that prevent the global epoch from advancing.
+///
+/// A thread holding a [`Guard`] created in a previous epoch prevents the global epoch from
+/// advancing, and no instances retired after that can be reclaimed until the thread drops all its
+/// [`Guard`] instances. The returned list is sorted by the time the threads have been observed
+/// stalling the global epoch in descending order; calling this function periodically, e.g., from a
+/// watchdog thread, allows the caller to find a thread holding a [`Guard`] for a long time.
+/// Returns `None` without waiting if another thread is registering or cleaning up a thread-local
+/// garbage collector.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{stalled_threads, Guard};
+/// use std::time::Duration;
+///
+/// let guard = Guard::new();
+///
+/// for stalled_thread in stalled_threads().unwrap_or_default() {
+///     if stalled_thread.duration() > Duration::from_secs(1) {
+///         println!("{:?} is stalling the garbage collector", stalled_thread.thread_id());
+///     }
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn stalled_threads() -> Option<Vec<StalledThread>> {
+    watchdog::stalled_threads()
+}
+
+/// Returns the garbage collection statistics of each thread-local garbage collector.
+///
+/// It is only available with the `ebr-stats` feature, and it can be used to find the thread that
+/// retires instances that are never reclaimed. Returns `None` without waiting if another thread is
+/// registering or cleaning up a thread-local garbage collector.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{collector_statistics, Guard};
+///
+/// let guard = Guard::new();
+/// guard.defer_execute(|| ());
+///
+/// if let Some(statistics) = collector_statistics() {
+///     let retired: usize = statistics.iter().map(|s| s.num_retired()).sum();
+///     assert!(retired >= 1);
+/// }
+/// ```
+#[cfg(feature = "ebr-stats")]
+#[inline]
+#[must_use]
+pub fn collector_statistics() -> Option<Vec<CollectorStatistics>> {
+    collector::Collector::collector_statistics()
+}
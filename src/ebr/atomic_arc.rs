@@ -0,0 +1,282 @@
+use super::{Collectible, Guard};
+use crate::atomic::AtomicPtr;
+use std::mem::forget;
+use std::panic::UnwindSafe;
+use std::ptr::{null_mut, NonNull};
+use std::sync::atomic::Ordering::{self, Relaxed};
+use std::sync::Arc;
+
+/// [`AtomicArc`] holds a strong reference to an instance managed by [`Arc`], and allows users to
+/// perform atomic operations on the pointer to it.
+///
+/// The instance is published as it is without being moved into a new allocation, and references to
+/// it obtained via [`AtomicArc::load`] stay valid as long as the associated [`Guard`] is alive;
+/// the strong reference held by the [`AtomicArc`] is dropped after every [`Guard`] that may be
+/// reading the instance is dropped. Unlike [`AtomicShared`](super::AtomicShared), [`Tag`](super::Tag)
+/// is not supported, since the pointer to the instance may not leave any spare bits.
+#[derive(Debug)]
+pub struct AtomicArc<T: 'static> {
+    instance_ptr: AtomicPtr<T>,
+}
+
+/// A pair of [`Arc`] and a reference of the same type.
+pub type ArcRefPair<'g, T> = (Option<Arc<T>>, Option<&'g T>);
+
+/// A strong reference to be dropped by the garbage collector.
+struct RetiredArc<T: 'static> {
+    _arc: Arc<T>,
+    link: Option<NonNull<dyn Collectible>>,
+}
+
+impl<T: 'static> AtomicArc<T> {
+    /// Creates a new [`AtomicArc`] from an [`Arc`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::AtomicArc;
+    /// use std::sync::Arc;
+    ///
+    /// let atomic_arc: AtomicArc<usize> = AtomicArc::new(Arc::new(10));
+    /// ```
+    #[inline]
+    pub fn new(arc: Arc<T>) -> Self {
+        Self {
+            instance_ptr: AtomicPtr::new(Arc::into_raw(arc).cast_mut()),
+        }
+    }
+
+    /// Creates a null [`AtomicArc`].
+    ///
+    /// It is a `const` function, therefore it can be used to initialize a `static` variable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicArc, Guard};
+    /// use std::sync::atomic::Ordering::{Acquire, Release};
+    /// use std::sync::Arc;
+    ///
+    /// static GLOBAL: AtomicArc<usize> = AtomicArc::null();
+    ///
+    /// GLOBAL.swap(Some(Arc::new(7)), Release);
+    /// assert_eq!(GLOBAL.load(Acquire, &Guard::new()), Some(&7));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn null() -> Self {
+        Self {
+            instance_ptr: AtomicPtr::new(null_mut()),
+        }
+    }
+
+    /// Returns `true` if the [`AtomicArc`] is null.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::AtomicArc;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// let atomic_arc: AtomicArc<usize> = AtomicArc::null();
+    /// assert!(atomic_arc.is_null(Relaxed));
+    /// ```
+    #[inline]
+    pub fn is_null(&self, order: Ordering) -> bool {
+        self.instance_ptr.load(order).is_null()
+    }
+
+    /// Loads a reference to the instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicArc, Guard};
+    /// use std::sync::atomic::Ordering::Acquire;
+    /// use std::sync::Arc;
+    ///
+    /// let arc: Arc<usize> = Arc::new(11);
+    /// let atomic_arc: AtomicArc<usize> = AtomicArc::new(arc.clone());
+    /// let guard = Guard::new();
+    /// assert!(std::ptr::eq(atomic_arc.load(Acquire, &guard).unwrap(), &*arc));
+    /// ```
+    #[inline]
+    pub fn load<'g>(&self, order: Ordering, _guard: &'g Guard) -> Option<&'g T> {
+        unsafe { self.instance_ptr.load(order).as_ref() }
+    }
+
+    /// Returns a new [`Arc`] pointing to the instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicArc, Guard};
+    /// use std::sync::atomic::Ordering::Acquire;
+    /// use std::sync::Arc;
+    ///
+    /// let arc: Arc<usize> = Arc::new(12);
+    /// let atomic_arc: AtomicArc<usize> = AtomicArc::new(arc.clone());
+    /// let loaded = atomic_arc.get_arc(Acquire, &Guard::new()).unwrap();
+    /// assert!(Arc::ptr_eq(&loaded, &arc));
+    /// ```
+    #[inline]
+    pub fn get_arc(&self, order: Ordering, _guard: &Guard) -> Option<Arc<T>> {
+        let ptr = self.instance_ptr.load(order);
+        if ptr.is_null() {
+            return None;
+        }
+
+        // The strong reference held by `self` is not dropped while the `Guard` is alive.
+        unsafe {
+            Arc::increment_strong_count(ptr);
+            Some(Arc::from_raw(ptr))
+        }
+    }
+
+    /// Stores the given value into the [`AtomicArc`] and returns the original value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::AtomicArc;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    /// use std::sync::Arc;
+    ///
+    /// let atomic_arc: AtomicArc<usize> = AtomicArc::new(Arc::new(14));
+    /// let old = atomic_arc.swap(Some(Arc::new(15)), Relaxed);
+    /// assert_eq!(*old.unwrap(), 14);
+    /// let old = atomic_arc.swap(None, Relaxed);
+    /// assert_eq!(*old.unwrap(), 15);
+    /// assert!(atomic_arc.swap(None, Relaxed).is_none());
+    /// ```
+    #[inline]
+    pub fn swap(&self, new: Option<Arc<T>>, order: Ordering) -> Option<Arc<T>> {
+        let desired = new.map_or_else(null_mut, |arc| Arc::into_raw(arc).cast_mut());
+        let prev = self.instance_ptr.swap(desired, order);
+        Self::retire(prev)
+    }
+
+    /// Stores `new` into the [`AtomicArc`] if the current instance is the same as `current`.
+    ///
+    /// Returns the previously held value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the supplied [`Arc`] and a reference to the current instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicArc, Guard};
+    /// use std::sync::atomic::Ordering::{AcqRel, Acquire};
+    /// use std::sync::Arc;
+    ///
+    /// let atomic_arc: AtomicArc<usize> = AtomicArc::new(Arc::new(17));
+    /// let guard = Guard::new();
+    ///
+    /// let current = atomic_arc.load(Acquire, &guard);
+    /// let old = atomic_arc
+    ///     .compare_exchange(current, Some(Arc::new(18)), AcqRel, Acquire, &guard)
+    ///     .unwrap();
+    /// assert_eq!(*old.unwrap(), 17);
+    ///
+    /// let (new, actual) = atomic_arc
+    ///     .compare_exchange(current, Some(Arc::new(19)), AcqRel, Acquire, &guard)
+    ///     .unwrap_err();
+    /// assert_eq!(*new.unwrap(), 19);
+    /// assert_eq!(actual, Some(&18));
+    /// ```
+    #[inline]
+    pub fn compare_exchange<'g>(
+        &self,
+        current: Option<&'g T>,
+        new: Option<Arc<T>>,
+        success: Ordering,
+        failure: Ordering,
+        _guard: &'g Guard,
+    ) -> Result<Option<Arc<T>>, ArcRefPair<'g, T>> {
+        let current = current.map_or_else(null_mut, |r| (r as *const T).cast_mut());
+        let desired = new
+            .as_ref()
+            .map_or_else(null_mut, |arc| Arc::as_ptr(arc).cast_mut());
+        match self
+            .instance_ptr
+            .compare_exchange(current, desired, success, failure)
+        {
+            Ok(prev) => {
+                forget(new);
+                Ok(Self::retire(prev))
+            }
+            Err(actual) => Err((new, unsafe { actual.as_ref() })),
+        }
+    }
+
+    /// Converts `self` into an [`Arc`].
+    ///
+    /// Returns `None` if `self` did not hold an instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::AtomicArc;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    /// use std::sync::Arc;
+    ///
+    /// let atomic_arc: AtomicArc<usize> = AtomicArc::new(Arc::new(55));
+    /// let arc: Arc<usize> = atomic_arc.into_arc(Relaxed).unwrap();
+    /// assert_eq!(*arc, 55);
+    /// ```
+    #[inline]
+    pub fn into_arc(self, order: Ordering) -> Option<Arc<T>> {
+        Self::retire(self.instance_ptr.swap(null_mut(), order))
+    }
+
+    /// Passes the strong reference that was held by the [`AtomicArc`] to the garbage collector, and
+    /// returns a new [`Arc`].
+    fn retire(ptr: *mut T) -> Option<Arc<T>> {
+        if ptr.is_null() {
+            return None;
+        }
+        let arc = unsafe { Arc::from_raw(ptr) };
+        let cloned = arc.clone();
+        Guard::new().defer(Box::new(RetiredArc {
+            _arc: arc,
+            link: None,
+        }));
+        Some(cloned)
+    }
+}
+
+impl<T: 'static> Default for AtomicArc<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T: 'static> Drop for AtomicArc<T> {
+    #[inline]
+    fn drop(&mut self) {
+        drop(Self::retire(self.instance_ptr.swap(null_mut(), Relaxed)));
+    }
+}
+
+impl<T: 'static> From<Arc<T>> for AtomicArc<T> {
+    #[inline]
+    fn from(arc: Arc<T>) -> Self {
+        Self::new(arc)
+    }
+}
+
+unsafe impl<T: 'static + Send + Sync> Send for AtomicArc<T> {}
+
+unsafe impl<T: 'static + Send + Sync> Sync for AtomicArc<T> {}
+
+impl<T: 'static + UnwindSafe> UnwindSafe for AtomicArc<T> {}
+
+impl<T: 'static> Collectible for RetiredArc<T> {
+    #[inline]
+    fn next_ptr_mut(&mut self) -> &mut Option<NonNull<dyn Collectible>> {
+        &mut self.link
+    }
+}
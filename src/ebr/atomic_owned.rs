@@ -1,9 +1,9 @@
 use super::ref_counted::RefCounted;
 use super::{Guard, Owned, Ptr, Tag};
+use crate::atomic::AtomicPtr;
 use std::mem::forget;
 use std::panic::UnwindSafe;
 use std::ptr::{null_mut, NonNull};
-use std::sync::atomic::AtomicPtr;
 use std::sync::atomic::Ordering::{self, Relaxed};
 
 /// [`AtomicOwned`] owns the underlying instance, and allows users to perform atomic operations
@@ -64,12 +64,20 @@ impl<T> AtomicOwned<T> {
 
     /// Creates a null [`AtomicOwned`].
     ///
+    /// It is a `const` function, therefore it can be used to initialize a `static` variable.
+    ///
     /// # Examples
     ///
     /// ```
-    /// use scc::ebr::AtomicOwned;
+    /// use scc::ebr::{AtomicOwned, Guard, Owned, Tag};
+    /// use std::sync::atomic::Ordering::{Acquire, Release};
+    ///
+    /// static GLOBAL: AtomicOwned<usize> = AtomicOwned::null();
     ///
     /// let atomic_owned: AtomicOwned<usize> = AtomicOwned::null();
+    ///
+    /// GLOBAL.swap((Some(Owned::new(7)), Tag::None), Release);
+    /// assert_eq!(GLOBAL.load(Acquire, &Guard::new()).as_ref(), Some(&7));
     /// ```
     #[inline]
     #[must_use]
@@ -150,6 +158,45 @@ impl<T> AtomicOwned<T> {
         (NonNull::new(prev_ptr).map(Owned::from), tag)
     }
 
+    /// Stores the given value into the [`AtomicOwned`] while keeping the existing [`Tag`], and returns
+    /// the original value.
+    ///
+    /// The pointer and the [`Tag`] are read and updated in a single atomic operation, therefore a
+    /// [`Tag`] set by another thread is never overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicOwned, Owned, Tag};
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// let atomic_owned: AtomicOwned<usize> = AtomicOwned::new(14);
+    /// assert!(atomic_owned.update_tag_if(Tag::First, |_| true, Relaxed, Relaxed));
+    /// let (old, tag) = atomic_owned.swap_preserving_tag(Some(Owned::new(15)), Relaxed);
+    /// assert_eq!(tag, Tag::First);
+    /// assert_eq!(*old.unwrap(), 14);
+    /// assert_eq!(atomic_owned.tag(Relaxed), Tag::First);
+    /// ```
+    #[inline]
+    pub fn swap_preserving_tag(
+        &self,
+        new: Option<Owned<T>>,
+        order: Ordering,
+    ) -> (Option<Owned<T>>, Tag) {
+        let desired = new
+            .as_ref()
+            .map_or_else(null_mut, Owned::get_underlying_ptr);
+        let prev = match self.instance_ptr.fetch_update(order, Relaxed, |p| {
+            Some(Tag::update_tag(desired, Tag::into_tag(p)).cast_mut())
+        }) {
+            Ok(prev) | Err(prev) => prev,
+        };
+        let tag = Tag::into_tag(prev);
+        let prev_ptr = Tag::unset_tag(prev).cast_mut();
+        forget(new);
+        (NonNull::new(prev_ptr).map(Owned::from), tag)
+    }
+
     /// Returns its [`Tag`].
     ///
     /// # Examples
@@ -168,6 +215,11 @@ impl<T> AtomicOwned<T> {
 
     /// Sets a new [`Tag`] if the given condition is met.
     ///
+    /// The condition receives the current [`Ptr`] along with its [`Tag`], and the pointer is
+    /// re-read and the condition is re-evaluated until the [`Tag`] is set or the condition is not
+    /// met; this can be used to logically delete an instance before unlinking it, e.g., setting a
+    /// [`Tag`] only if it has not been set by another thread.
+    ///
     /// Returns `true` if the new [`Tag`] has been successfully set.
     ///
     /// # Examples
@@ -329,6 +381,54 @@ impl<T> AtomicOwned<T> {
         }
     }
 
+    /// Replaces the [`Tag`] of the [`AtomicOwned`] if the current pointer and [`Tag`] are the same as
+    /// `current`.
+    ///
+    /// Returns the updated [`Ptr`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the current [`Ptr`] if the pointer or the [`Tag`] is different from `current`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicOwned, Guard, Tag};
+    /// use std::sync::atomic::Ordering::{AcqRel, Acquire};
+    ///
+    /// let atomic_owned: AtomicOwned<usize> = AtomicOwned::new(17);
+    /// let guard = Guard::new();
+    ///
+    /// let ptr = atomic_owned.load(Acquire, &guard);
+    /// let marked = atomic_owned.compare_exchange_tag(ptr, Tag::First, AcqRel, Acquire, &guard).unwrap();
+    /// assert_eq!(marked.tag(), Tag::First);
+    /// assert_eq!(marked.as_ref(), Some(&17));
+    ///
+    /// // The tag is compared as well.
+    /// let actual = atomic_owned.compare_exchange_tag(ptr, Tag::Second, AcqRel, Acquire, &guard);
+    /// assert_eq!(actual, Err(marked));
+    /// ```
+    #[inline]
+    pub fn compare_exchange_tag<'g>(
+        &self,
+        current: Ptr<'g, T>,
+        new_tag: Tag,
+        success: Ordering,
+        failure: Ordering,
+        _guard: &'g Guard,
+    ) -> Result<Ptr<'g, T>, Ptr<'g, T>> {
+        let desired = Tag::update_tag(current.as_underlying_ptr(), new_tag).cast_mut();
+        match self.instance_ptr.compare_exchange(
+            current.as_underlying_ptr().cast_mut(),
+            desired,
+            success,
+            failure,
+        ) {
+            Ok(_) => Ok(Ptr::from(desired)),
+            Err(actual) => Err(Ptr::from(actual)),
+        }
+    }
+
     /// Converts `self` into an [`Owned`].
     ///
     /// Returns `None` if `self` did not own an instance.
@@ -0,0 +1,121 @@
+use super::{AtomicOwned, Guard, Owned, Tag};
+use std::fmt::{self, Debug};
+use std::sync::atomic::Ordering::{AcqRel, Acquire};
+
+/// [`AtomicOwnedCell`] holds a replaceable instance that can be read without locking.
+///
+/// Unlike [`AtomicOwned`], an [`AtomicOwnedCell`] always holds an instance, and a reference to
+/// the instance can be obtained without checking the pointer. Replaced instances are passed to the
+/// EBR garbage collector, therefore readers can keep using the reference as long as they hold a
+/// [`Guard`].
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{AtomicOwnedCell, Guard};
+///
+/// let config: AtomicOwnedCell<String> = AtomicOwnedCell::new(String::from("old"));
+///
+/// let guard = Guard::new();
+/// let old = config.load(&guard);
+/// config.store(String::from("new"));
+///
+/// assert_eq!(old, "old");
+/// assert_eq!(config.load(&guard), "new");
+/// ```
+pub struct AtomicOwnedCell<T> {
+    instance: AtomicOwned<T>,
+}
+
+impl<T: 'static> AtomicOwnedCell<T> {
+    /// Creates a new [`AtomicOwnedCell`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::AtomicOwnedCell;
+    ///
+    /// let cell: AtomicOwnedCell<usize> = AtomicOwnedCell::new(7);
+    /// ```
+    #[inline]
+    pub fn new(t: T) -> Self {
+        Self {
+            instance: AtomicOwned::new(t),
+        }
+    }
+
+    /// Replaces the instance, and passes the old one to the EBR garbage collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicOwnedCell, Guard};
+    ///
+    /// let cell: AtomicOwnedCell<usize> = AtomicOwnedCell::new(7);
+    /// cell.store(11);
+    ///
+    /// assert_eq!(*cell.load(&Guard::new()), 11);
+    /// ```
+    #[inline]
+    pub fn store(&self, t: T) {
+        drop(self.swap(t));
+    }
+
+    /// Replaces the instance, and returns the old one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicOwnedCell, Guard};
+    ///
+    /// let cell: AtomicOwnedCell<usize> = AtomicOwnedCell::new(7);
+    /// let old = cell.swap(11);
+    ///
+    /// assert_eq!(*old, 7);
+    /// assert_eq!(*cell.load(&Guard::new()), 11);
+    /// ```
+    #[inline]
+    pub fn swap(&self, t: T) -> Owned<T> {
+        let (old, _) = self.instance.swap((Some(Owned::new(t)), Tag::None), AcqRel);
+        debug_assert!(old.is_some());
+        unsafe { old.unwrap_unchecked() }
+    }
+}
+
+impl<T> AtomicOwnedCell<T> {
+    /// Returns a reference to the instance that may live as long as the supplied [`Guard`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicOwnedCell, Guard};
+    ///
+    /// let cell: AtomicOwnedCell<usize> = AtomicOwnedCell::new(7);
+    /// let guard = Guard::new();
+    ///
+    /// assert_eq!(*cell.load(&guard), 7);
+    /// ```
+    #[inline]
+    pub fn load<'g>(&self, guard: &'g Guard) -> &'g T {
+        let ptr = self.instance.load(Acquire, guard);
+        debug_assert!(!ptr.is_null());
+        unsafe { ptr.as_ref().unwrap_unchecked() }
+    }
+}
+
+impl<T: Debug> Debug for AtomicOwnedCell<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = Guard::new();
+        f.debug_tuple("AtomicOwnedCell")
+            .field(self.load(&guard))
+            .finish()
+    }
+}
+
+impl<T: 'static + Default> Default for AtomicOwnedCell<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
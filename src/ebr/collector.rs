@@ -1,9 +1,17 @@
-use super::{Collectible, Guard, Tag};
+#[cfg(feature = "ebr-stats")]
+use super::CollectorStatistics;
+use super::{reclaimer, Collectible, Guard, Statistics, Tag};
+use crate::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU8, AtomicUsize};
 use crate::exit_guard::ExitGuard;
+use crate::wait_queue::{AsyncWait, WaitQueue};
+use std::mem::size_of_val;
 use std::panic;
+use std::pin::Pin;
 use std::ptr::{self, NonNull};
+use std::sync::atomic::fence;
 use std::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
-use std::sync::atomic::{fence, AtomicPtr, AtomicU8};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
 
 /// [`Collector`] is a garbage collector that reclaims thread-locally unreachable instances
 /// when they are globally unreachable.
@@ -13,7 +21,15 @@ pub(super) struct Collector {
     announcement: u8,
     next_epoch_update: u8,
     has_garbage: bool,
-    num_readers: u32,
+    num_readers: AtomicU32,
+    num_pins: AtomicUsize,
+    thread_id: ThreadId,
+    num_garbage: AtomicUsize,
+    garbage_bytes: AtomicUsize,
+    #[cfg(feature = "ebr-stats")]
+    num_retired: AtomicUsize,
+    #[cfg(feature = "ebr-stats")]
+    num_reclaimed: AtomicUsize,
     previous_instance_link: Option<NonNull<dyn Collectible>>,
     current_instance_link: Option<NonNull<dyn Collectible>>,
     next_instance_link: Option<NonNull<dyn Collectible>>,
@@ -41,9 +57,12 @@ impl Collector {
     /// The method may panic if the number of readers has reached `u32::MAX`.
     #[inline]
     pub(super) fn new_guard(&mut self) -> bool {
-        if self.num_readers == 0 {
+        let num_readers = self.num_readers.load(Relaxed);
+        if num_readers == 0 {
             debug_assert_eq!(self.state.load(Relaxed) & Self::INACTIVE, Self::INACTIVE);
-            self.num_readers = 1;
+            self.num_readers.store(1, Relaxed);
+            self.num_pins
+                .store(self.num_pins.load(Relaxed).wrapping_add(1), Relaxed);
             let new_epoch = EPOCH.load(Relaxed);
             if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
                 // This special optimization is excerpted from
@@ -64,23 +83,50 @@ impl Collector {
                 self.announcement = new_epoch;
                 true
             }
-        } else if self.num_readers == u32::MAX {
+        } else if num_readers == u32::MAX {
             panic!("Too many EBR guards");
         } else {
             debug_assert_eq!(self.state.load(Relaxed) & Self::INACTIVE, 0);
-            self.num_readers += 1;
+            self.num_readers.store(num_readers + 1, Relaxed);
             false
         }
     }
 
+    /// Acknowledges a new [`Guard`] being instantiated if there is an active [`Guard`] in the
+    /// thread.
+    ///
+    /// Returns the [`Collector`] attached to the current thread if successful.
+    #[inline]
+    pub(super) fn try_reuse() -> Option<*mut Collector> {
+        LOCAL_COLLECTOR.with(|local_collector| {
+            let collector_ptr = local_collector.load(Relaxed);
+            let collector = unsafe { collector_ptr.as_mut()? };
+            let num_readers = collector.num_readers.load(Relaxed);
+            if num_readers == 0 || num_readers == u32::MAX {
+                return None;
+            }
+            debug_assert_eq!(collector.state.load(Relaxed) & Self::INACTIVE, 0);
+            collector.num_readers.store(num_readers + 1, Relaxed);
+            Some(collector_ptr)
+        })
+    }
+
+    /// Returns `true` if the number of [`Guard`] instances in the thread has reached the limit.
+    #[inline]
+    pub(super) fn is_saturated(&self) -> bool {
+        self.num_readers.load(Relaxed) == u32::MAX
+    }
+
     /// Acknowledges an existing [`Guard`] being dropped.
     #[inline]
     pub(super) fn end_guard(&mut self) {
         debug_assert_eq!(self.state.load(Relaxed) & Self::INACTIVE, 0);
         debug_assert_eq!(self.state.load(Relaxed), self.announcement);
 
-        if self.num_readers == 1 {
-            if self.next_epoch_update == 0 {
+        let num_readers = self.num_readers.load(Relaxed);
+        if num_readers == 1 {
+            // The thread helps threads waiting in `synchronize` update the global epoch.
+            if self.next_epoch_update == 0 || NUM_SYNCHRONIZERS.load(Relaxed) != 0 {
                 if self.has_garbage || Tag::into_tag(GLOBAL_ANCHOR.load(Relaxed)) != Tag::First {
                     self.try_scan();
                 }
@@ -96,9 +142,9 @@ impl Collector {
             // What has happened cannot happen after the thread setting itself inactive.
             self.state
                 .store(self.announcement | Self::INACTIVE, Release);
-            self.num_readers = 0;
+            self.num_readers.store(0, Relaxed);
         } else {
-            self.num_readers -= 1;
+            self.num_readers.store(num_readers - 1, Relaxed);
         }
     }
 
@@ -106,6 +152,7 @@ impl Collector {
     #[inline]
     pub(super) fn reclaim(&mut self, instance_ptr: *mut dyn Collectible) {
         if let Some(mut ptr) = NonNull::new(instance_ptr) {
+            self.update_garbage_stats(ptr, true);
             unsafe {
                 *ptr.as_mut().next_ptr_mut() = self.current_instance_link.take();
                 self.current_instance_link.replace(ptr);
@@ -115,9 +162,36 @@ impl Collector {
                     .min(Self::CADENCE / 4);
                 self.has_garbage = true;
             }
+            if self.num_garbage.load(Relaxed) > MAX_NUM_GARBAGE.load(Relaxed)
+                || self.garbage_bytes.load(Relaxed) > MAX_GARBAGE_BYTES.load(Relaxed)
+            {
+                // Too much garbage: try to update the epoch right away if the limit has just been
+                // exceeded, and then whenever the last `Guard` is dropped.
+                if self.next_epoch_update != 0
+                    && self.num_readers.load(Relaxed) != 0
+                    && self.state.load(Relaxed) == self.announcement
+                {
+                    self.try_scan();
+                }
+                self.next_epoch_update = 0;
+            }
         }
     }
 
+    /// Returns the epoch announced by the thread.
+    #[inline]
+    pub(super) fn announcement(&self) -> u8 {
+        self.announcement
+    }
+
+    /// Accelerates garbage collection.
+    ///
+    /// The thread tries to update the global epoch when the last [`Guard`] is dropped.
+    #[inline]
+    pub(super) fn accelerate(&mut self) {
+        self.next_epoch_update = 0;
+    }
+
     /// Returns the [`Collector`] attached to the current thread.
     #[inline]
     pub(super) fn current() -> *mut Collector {
@@ -131,13 +205,22 @@ impl Collector {
         })
     }
 
+    /// Returns the [`Collector`] attached to the current thread if it has been allocated.
+    #[inline]
+    pub(super) fn try_current() -> Option<*mut Collector> {
+        LOCAL_COLLECTOR
+            .try_with(|local_collector| local_collector.load(Relaxed))
+            .ok()
+            .filter(|collector_ptr| !collector_ptr.is_null())
+    }
+
     /// Passes its garbage instances to other threads.
     #[inline]
     pub(super) fn pass_garbage() -> bool {
         LOCAL_COLLECTOR.with(|local_collector| {
             let collector_ptr = local_collector.load(Relaxed);
             if let Some(collector) = unsafe { collector_ptr.as_mut() } {
-                if collector.num_readers != 0 {
+                if collector.num_readers.load(Relaxed) != 0 {
                     return false;
                 }
                 if collector.has_garbage {
@@ -150,6 +233,101 @@ impl Collector {
         })
     }
 
+    /// Detaches the [`Collector`] from the current thread.
+    #[inline]
+    pub(super) fn detach() -> bool {
+        let collector_ptr = LOCAL_COLLECTOR.with(|local_collector| local_collector.load(Relaxed));
+        if let Some(collector) = unsafe { collector_ptr.as_ref() } {
+            if collector.num_readers.load(Relaxed) != 0 {
+                return false;
+            }
+            try_drop_local_collector();
+            LOCAL_COLLECTOR.with(|local_collector| local_collector.store(ptr::null_mut(), Relaxed));
+        }
+        true
+    }
+
+    /// Runs a garbage collection pass.
+    ///
+    /// Returns `false` if there is an active [`Guard`] in the thread.
+    pub(super) fn flush() -> bool {
+        let collector_ptr = LOCAL_COLLECTOR.with(|local_collector| local_collector.load(Relaxed));
+        if unsafe { collector_ptr.as_ref() }.map_or(false, |c| c.num_readers.load(Relaxed) != 0) {
+            return false;
+        }
+
+        // The thread acknowledges a new epoch when creating a `Guard`, and tries to update the
+        // global epoch when dropping it.
+        let guard = Guard::new();
+        guard.accelerate();
+        drop(guard);
+        true
+    }
+
+    /// Waits until the global epoch has been updated twice since the call and all the instances
+    /// retired by the current thread have been reclaimed.
+    ///
+    /// Returns `false` if there is an active [`Guard`] in the thread.
+    pub(super) fn synchronize() -> bool {
+        let Some(target) = Self::begin_synchronize() else {
+            return false;
+        };
+        let _guard = ExitGuard::new((), |()| Self::end_synchronize());
+
+        // A thread that drops its last `Guard` right before `NUM_SYNCHRONIZERS` is incremented
+        // may not update the global epoch, therefore the thread wakes up periodically.
+        let mut timeout = Duration::from_micros(64);
+        while !Self::try_synchronize(target) {
+            let num_epoch_updates = Self::num_epoch_updates();
+            let _result = EPOCH_WAIT_QUEUE.wait_sync_until(
+                || Self::synchronize_wait_condition(target, num_epoch_updates),
+                Some(Instant::now() + timeout),
+            );
+            timeout = (timeout * 2).min(Duration::from_millis(16));
+        }
+        true
+    }
+
+    /// Waits until the global epoch has been updated twice since the call and all the instances
+    /// retired by the current thread have been reclaimed.
+    ///
+    /// Returns `false` if there is an active [`Guard`] in the thread.
+    pub(super) async fn synchronize_async() -> bool {
+        let Some(target) = Self::begin_synchronize() else {
+            return false;
+        };
+        let _guard = ExitGuard::new((), |()| Self::end_synchronize());
+        while !Self::try_synchronize(target) {
+            let num_epoch_updates = Self::num_epoch_updates();
+            let mut async_wait = AsyncWait::default();
+            let _result = EPOCH_WAIT_QUEUE.push_async_entry(&mut async_wait, || {
+                Self::synchronize_wait_condition(target, num_epoch_updates)
+            });
+            Pin::new(&mut async_wait).await;
+        }
+        true
+    }
+
+    /// Tries to reclaim the instances retired by the current thread.
+    ///
+    /// Returns `None` if there is an active [`Guard`] in the thread, and `Some(true)` if all the
+    /// instances retired by the thread have been reclaimed.
+    pub(super) fn try_flush() -> Option<bool> {
+        let collector_ptr = LOCAL_COLLECTOR.with(|local_collector| local_collector.load(Relaxed));
+        if let Some(collector) = unsafe { collector_ptr.as_ref() } {
+            if collector.num_readers.load(Relaxed) != 0 {
+                return None;
+            }
+            if !collector.has_garbage {
+                return Some(true);
+            }
+        } else {
+            return Some(true);
+        }
+        Self::flush();
+        Some(unsafe { !(*collector_ptr).has_garbage })
+    }
+
     /// Acknowledges a new global epoch.
     pub(super) fn epoch_updated(&mut self) {
         debug_assert_eq!(self.state.load(Relaxed) & Self::INACTIVE, 0);
@@ -160,8 +338,16 @@ impl Collector {
         self.previous_instance_link = self.current_instance_link.take();
         self.has_garbage =
             self.next_instance_link.is_some() || self.previous_instance_link.is_some();
+        if reclaimer::is_enabled() {
+            if let Some(link) = garbage_link {
+                if self.hand_over_garbage(link) {
+                    return;
+                }
+            }
+        }
         while let Some(mut instance_ptr) = garbage_link.take() {
             garbage_link = unsafe { *instance_ptr.as_mut().next_ptr_mut() };
+            self.update_garbage_stats(instance_ptr, false);
             let mut guard = ExitGuard::new(garbage_link, |mut garbage_link| {
                 while let Some(mut instance_ptr) = garbage_link.take() {
                     // Something went wrong during dropping and deallocating an instance.
@@ -169,6 +355,7 @@ impl Collector {
 
                     // Previous `drop_and_dealloc` may have accessed `self.current_instance_link`.
                     std::sync::atomic::compiler_fence(Acquire);
+                    self.update_garbage_stats(instance_ptr, false);
                     self.reclaim(instance_ptr.as_ptr());
                 }
             });
@@ -182,6 +369,212 @@ impl Collector {
         }
     }
 
+    /// Collects the garbage collection statistics.
+    ///
+    /// Returns `None` if the list of [`Collector`] instances is being modified.
+    pub(super) fn statistics() -> Option<Statistics> {
+        let mut collector_ptr = try_lock_global_anchor().ok()?;
+        let _guard = ExitGuard::new((), |()| unlock_global_anchor());
+
+        let epoch = EPOCH.load(Relaxed);
+        let mut statistics = Statistics {
+            epoch,
+            ..Statistics::default()
+        };
+        while let Some(collector) = unsafe { collector_ptr.as_ref() } {
+            let state = collector.state.load(Relaxed);
+            if (state & Self::INVALID) == 0 {
+                statistics.num_threads += 1;
+                if (state & Self::INACTIVE) == 0 && state != epoch {
+                    statistics.num_lagging_threads += 1;
+                }
+            }
+            statistics.num_garbage += collector.num_garbage.load(Relaxed);
+            statistics.garbage_bytes += collector.garbage_bytes.load(Relaxed);
+            collector_ptr = collector.next_link;
+        }
+        let (num_garbage, garbage_bytes) = reclaimer::pending();
+        statistics.num_garbage += num_garbage;
+        statistics.garbage_bytes += garbage_bytes;
+        Some(statistics)
+    }
+
+    /// Collects the garbage collection statistics of each [`Collector`].
+    ///
+    /// Returns `None` if the list of [`Collector`] instances is being modified.
+    #[cfg(feature = "ebr-stats")]
+    pub(super) fn collector_statistics() -> Option<Vec<CollectorStatistics>> {
+        let mut collector_ptr = try_lock_global_anchor().ok()?;
+        let _guard = ExitGuard::new((), |()| unlock_global_anchor());
+
+        let mut statistics = Vec::new();
+        while let Some(collector) = unsafe { collector_ptr.as_ref() } {
+            let state = collector.state.load(Relaxed);
+            statistics.push(CollectorStatistics {
+                is_active: (state & (Self::INACTIVE | Self::INVALID)) == 0,
+                is_terminated: (state & Self::INVALID) != 0,
+                num_retired: collector.num_retired.load(Relaxed),
+                num_reclaimed: collector.num_reclaimed.load(Relaxed),
+                num_garbage: collector.num_garbage.load(Relaxed),
+            });
+            collector_ptr = collector.next_link;
+        }
+        Some(statistics)
+    }
+
+    /// Returns the identifier, the number of [`Guard`] sessions, and the number of [`Guard`]
+    /// instances of each thread that holds a [`Guard`] created in a previous epoch.
+    ///
+    /// Returns `None` if the list of [`Collector`] instances is being modified.
+    pub(super) fn lagging_threads() -> Option<Vec<(ThreadId, usize, u32)>> {
+        let mut collector_ptr = try_lock_global_anchor().ok()?;
+        let _guard = ExitGuard::new((), |()| unlock_global_anchor());
+
+        let epoch = EPOCH.load(Relaxed);
+        let mut lagging_threads = Vec::new();
+        while let Some(collector) = unsafe { collector_ptr.as_ref() } {
+            let state = collector.state.load(Relaxed);
+            if (state & (Self::INACTIVE | Self::INVALID)) == 0 && state != epoch {
+                lagging_threads.push((
+                    collector.thread_id,
+                    collector.num_pins.load(Relaxed),
+                    collector.num_readers.load(Relaxed),
+                ));
+            }
+            collector_ptr = collector.next_link;
+        }
+        Some(lagging_threads)
+    }
+
+    /// Returns the number of global epoch updates so far.
+    #[inline]
+    pub(super) fn num_epoch_updates() -> usize {
+        NUM_EPOCH_UPDATES.load(SeqCst)
+    }
+
+    /// Registers the current thread as a thread waiting for global epoch updates.
+    ///
+    /// Returns the number of global epoch updates to wait for, or `None` if there is an active
+    /// [`Guard`] in the thread.
+    fn begin_synchronize() -> Option<usize> {
+        let collector_ptr = LOCAL_COLLECTOR.with(|local_collector| local_collector.load(Relaxed));
+        if unsafe { collector_ptr.as_ref() }.map_or(false, |c| c.num_readers.load(Relaxed) != 0) {
+            return None;
+        }
+        NUM_SYNCHRONIZERS.fetch_add(1, SeqCst);
+
+        // Any `Guard` that was alive at this point is dropped before the global epoch is updated
+        // twice.
+        Some(Self::num_epoch_updates() + 2)
+    }
+
+    /// Unregisters the current thread as a thread waiting for global epoch updates.
+    fn end_synchronize() {
+        NUM_SYNCHRONIZERS.fetch_sub(1, Relaxed);
+    }
+
+    /// Tries to update the global epoch, and returns `true` if the global epoch has been updated
+    /// `target` times and all the instances retired by the current thread have been reclaimed.
+    fn try_synchronize(target: usize) -> bool {
+        if Self::num_epoch_updates() < target {
+            let _: bool = Self::flush();
+        }
+        Self::num_epoch_updates() >= target && Self::try_flush() == Some(true)
+    }
+
+    /// Checks the condition of waiting for a global epoch update after the current thread is
+    /// enqueued in the wait queue.
+    fn synchronize_wait_condition(target: usize, num_epoch_updates: usize) -> Result<(), ()> {
+        if Self::try_synchronize(target) || Self::num_epoch_updates() != num_epoch_updates {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Sets the maximum number and total size of retired instances in a thread.
+    pub(super) fn set_garbage_limit(max_num_garbage: usize, max_garbage_bytes: usize) {
+        MAX_NUM_GARBAGE.store(max_num_garbage, Relaxed);
+        MAX_GARBAGE_BYTES.store(max_garbage_bytes, Relaxed);
+        GARBAGE_LIMITED.store(
+            max_num_garbage != usize::MAX || max_garbage_bytes != usize::MAX,
+            Relaxed,
+        );
+    }
+
+    /// Hands over reclaimable instances to the reclaimer thread.
+    ///
+    /// Returns `false` if the reclaimer thread has exited.
+    fn hand_over_garbage(&mut self, link: NonNull<dyn Collectible>) -> bool {
+        let mut num_garbage = 0;
+        let mut garbage_bytes = 0;
+        let mut garbage_link = Self::tracks_garbage().then_some(link);
+        while let Some(mut instance_ptr) = garbage_link.take() {
+            garbage_link = unsafe { *instance_ptr.as_mut().next_ptr_mut() };
+            num_garbage += 1;
+            garbage_bytes += unsafe { size_of_val(instance_ptr.as_ref()) };
+        }
+        if !reclaimer::hand_over(link, num_garbage, garbage_bytes) {
+            return false;
+        }
+
+        // The instances may have been dropped by the reclaimer thread.
+        #[cfg(feature = "ebr-stats")]
+        self.num_reclaimed
+            .store(self.num_reclaimed.load(Relaxed) + num_garbage, Relaxed);
+        self.num_garbage.store(
+            self.num_garbage.load(Relaxed).saturating_sub(num_garbage),
+            Relaxed,
+        );
+        self.garbage_bytes.store(
+            self.garbage_bytes
+                .load(Relaxed)
+                .saturating_sub(garbage_bytes),
+            Relaxed,
+        );
+        true
+    }
+
+    /// Returns `true` if the number and size of retired instances are tracked.
+    ///
+    /// They are only tracked with the `ebr-stats` feature or while a garbage limit is set.
+    #[inline]
+    fn tracks_garbage() -> bool {
+        cfg!(feature = "ebr-stats") || GARBAGE_LIMITED.load(Relaxed)
+    }
+
+    /// Updates the garbage statistics when an instance is retired or dropped.
+    ///
+    /// Only the owner of the [`Collector`] updates the statistics, therefore read-modify-write
+    /// operations are not required.
+    #[inline]
+    fn update_garbage_stats(&self, instance_ptr: NonNull<dyn Collectible>, retired: bool) {
+        if !Self::tracks_garbage() {
+            return;
+        }
+        let size = unsafe { size_of_val(instance_ptr.as_ref()) };
+        let num_garbage = self.num_garbage.load(Relaxed);
+        let garbage_bytes = self.garbage_bytes.load(Relaxed);
+        #[cfg(feature = "ebr-stats")]
+        {
+            let counter = if retired {
+                &self.num_retired
+            } else {
+                &self.num_reclaimed
+            };
+            counter.store(counter.load(Relaxed) + 1, Relaxed);
+        }
+        if retired {
+            self.num_garbage.store(num_garbage + 1, Relaxed);
+            self.garbage_bytes.store(garbage_bytes + size, Relaxed);
+        } else {
+            self.num_garbage
+                .store(num_garbage.saturating_sub(1), Relaxed);
+            self.garbage_bytes
+                .store(garbage_bytes.saturating_sub(size), Relaxed);
+        }
+    }
+
     /// Allocates a new [`Collector`].
     fn alloc() -> *mut Collector {
         let boxed = Box::new(Collector {
@@ -189,7 +582,15 @@ impl Collector {
             announcement: 0,
             next_epoch_update: Self::CADENCE,
             has_garbage: false,
-            num_readers: 0,
+            num_readers: AtomicU32::new(0),
+            num_pins: AtomicUsize::new(0),
+            thread_id: thread::current().id(),
+            num_garbage: AtomicUsize::new(0),
+            garbage_bytes: AtomicUsize::new(0),
+            #[cfg(feature = "ebr-stats")]
+            num_retired: AtomicUsize::new(0),
+            #[cfg(feature = "ebr-stats")]
+            num_reclaimed: AtomicUsize::new(0),
             previous_instance_link: None,
             current_instance_link: None,
             next_instance_link: None,
@@ -221,37 +622,12 @@ impl Collector {
         debug_assert_eq!(self.state.load(Relaxed) & Self::INACTIVE, 0);
         debug_assert_eq!(self.state.load(Relaxed), self.announcement);
 
+        let mut epoch_updated = false;
+
         // Only one thread that acquires the anchor lock is allowed to scan the thread-local
         // collectors.
-        let lock_result = GLOBAL_ANCHOR
-            .fetch_update(Acquire, Acquire, |p| {
-                let tag = Tag::into_tag(p);
-                if tag == Tag::First || tag == Tag::Both {
-                    None
-                } else {
-                    Some(Tag::update_tag(p, Tag::First).cast_mut())
-                }
-            })
-            .map(|p| Tag::unset_tag(p).cast_mut());
-        if let Ok(mut collector_ptr) = lock_result {
-            let _guard = ExitGuard::new(&GLOBAL_ANCHOR, |a| {
-                // Unlock the anchor.
-                loop {
-                    let result = a.fetch_update(Release, Relaxed, |p| {
-                        let tag = Tag::into_tag(p);
-                        debug_assert!(tag == Tag::First || tag == Tag::Both);
-                        let new_tag = if tag == Tag::Both {
-                            Tag::Second
-                        } else {
-                            Tag::None
-                        };
-                        Some(Tag::update_tag(p, new_tag).cast_mut())
-                    });
-                    if result.is_ok() {
-                        break;
-                    }
-                }
-            });
+        if let Ok(mut collector_ptr) = try_lock_global_anchor() {
+            let _guard = ExitGuard::new((), |()| unlock_global_anchor());
 
             let known_epoch = self.state.load(Relaxed);
             let mut update_global_epoch = true;
@@ -306,9 +682,15 @@ impl Collector {
                     1 => 2,
                     _ => 0,
                 };
+                NUM_EPOCH_UPDATES.fetch_add(1, SeqCst);
                 EPOCH.store(next_epoch, Relaxed);
+                epoch_updated = true;
             }
         }
+
+        if epoch_updated && NUM_SYNCHRONIZERS.load(Relaxed) != 0 {
+            EPOCH_WAIT_QUEUE.signal();
+        }
     }
 }
 
@@ -347,6 +729,39 @@ impl Drop for CollectorAnchor {
     }
 }
 
+/// Tries to lock `ANCHOR`, and returns the first `Collector` in the list if successful.
+fn try_lock_global_anchor() -> Result<*mut Collector, *mut Collector> {
+    GLOBAL_ANCHOR
+        .fetch_update(Acquire, Acquire, |p| {
+            let tag = Tag::into_tag(p);
+            if tag == Tag::First || tag == Tag::Both {
+                None
+            } else {
+                Some(Tag::update_tag(p, Tag::First).cast_mut())
+            }
+        })
+        .map(|p| Tag::unset_tag(p).cast_mut())
+}
+
+/// Unlocks `ANCHOR`.
+fn unlock_global_anchor() {
+    loop {
+        let result = GLOBAL_ANCHOR.fetch_update(Release, Relaxed, |p| {
+            let tag = Tag::into_tag(p);
+            debug_assert!(tag == Tag::First || tag == Tag::Both);
+            let new_tag = if tag == Tag::Both {
+                Tag::Second
+            } else {
+                Tag::None
+            };
+            Some(Tag::update_tag(p, new_tag).cast_mut())
+        });
+        if result.is_ok() {
+            break;
+        }
+    }
+}
+
 /// Marks `ANCHOR` that there is a potentially unreachable `Collector`.
 fn mark_scan_enforced() {
     // `Tag::Second` indicates that there is a garbage `Collector`.
@@ -396,5 +811,26 @@ thread_local! {
 /// a thread and the global is considered to be an epoch change to the thread.
 static EPOCH: AtomicU8 = AtomicU8::new(0);
 
+/// The number of global epoch updates.
+///
+/// Unlike [`EPOCH`], it never wraps around in practice, and therefore it can be used to find out
+/// whether the global epoch has been updated a certain number of times since a point in time.
+static NUM_EPOCH_UPDATES: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of threads waiting for global epoch updates in `synchronize`.
+static NUM_SYNCHRONIZERS: AtomicUsize = AtomicUsize::new(0);
+
+/// `EPOCH_WAIT_QUEUE` holds threads waiting for global epoch updates in `synchronize`.
+static EPOCH_WAIT_QUEUE: WaitQueue = WaitQueue::new();
+
+/// The maximum number of retired instances in a thread before forcing an epoch update.
+static MAX_NUM_GARBAGE: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// The maximum total size of retired instances in a thread before forcing an epoch update.
+static MAX_GARBAGE_BYTES: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// `true` if either of the garbage limits is set.
+static GARBAGE_LIMITED: AtomicBool = AtomicBool::new(false);
+
 /// The global anchor for thread-local instances of [`Collector`].
 static GLOBAL_ANCHOR: AtomicPtr<Collector> = AtomicPtr::new(ptr::null_mut());
@@ -0,0 +1,79 @@
+/// [`Epoch`] is an opaque epoch value of the [`ebr`](super) module.
+///
+/// The global epoch cycles through three values, therefore two [`Epoch`] values can only be
+/// compared for equality; the global epoch may have advanced any multiple of three times between
+/// two equal [`Epoch`] values.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::Guard;
+///
+/// let epoch = Guard::new().epoch();
+/// assert_ne!(epoch, epoch.next());
+/// assert_eq!(epoch, epoch.next().prev());
+/// assert_eq!(epoch, epoch.next().next().next());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Epoch {
+    value: u8,
+}
+
+impl Epoch {
+    /// Returns the next [`Epoch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    ///
+    /// let epoch = Guard::new().epoch();
+    /// assert_ne!(epoch, epoch.next());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn next(self) -> Self {
+        Self {
+            value: match self.value {
+                0 => 1,
+                1 => 2,
+                _ => 0,
+            },
+        }
+    }
+
+    /// Returns the previous [`Epoch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    ///
+    /// let epoch = Guard::new().epoch();
+    /// assert_ne!(epoch, epoch.prev());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn prev(self) -> Self {
+        Self {
+            value: match self.value {
+                0 => 2,
+                1 => 0,
+                _ => 1,
+            },
+        }
+    }
+
+    /// Creates an [`Epoch`] from an integer.
+    #[inline]
+    pub(super) const fn from_u8(value: u8) -> Self {
+        Self { value: value % 3 }
+    }
+}
+
+impl From<Epoch> for u8 {
+    #[inline]
+    fn from(epoch: Epoch) -> Self {
+        epoch.value
+    }
+}
@@ -1,5 +1,6 @@
 use super::collectible::{Collectible, DeferredClosure};
 use super::collector::Collector;
+use super::Epoch;
 use std::panic::UnwindSafe;
 
 /// [`Guard`] allows the user to read [`AtomicShared`](super::AtomicShared) and keeps the
@@ -20,6 +21,9 @@ impl Guard {
     /// The maximum number of [`Guard`] instances in a thread is limited to `u32::MAX`; a
     /// thread panics when the number of [`Guard`] instances in the thread exceeds the limit.
     ///
+    /// Creating a [`Guard`] is cheap if there is another active [`Guard`] in the thread, since
+    /// it only increments a thread-local counter without any memory fence.
+    ///
     /// # Examples
     ///
     /// ```
@@ -41,6 +45,119 @@ impl Guard {
         guard
     }
 
+    /// Creates a new [`Guard`] without allocating memory or reclaiming retired instances.
+    ///
+    /// It returns `None` if the thread-local data structure of the thread has not been allocated,
+    /// has been released by [`detach`](super::detach), or is being destroyed, or the number of
+    /// [`Guard`] instances in the thread has reached the limit. The thread-local data structure is
+    /// allocated when the thread creates a [`Guard`] via [`Guard::new`] for the first time;
+    /// therefore, a thread that may need a [`Guard`] in an allocation-restricted context, e.g., a
+    /// signal handler, should create a [`Guard`] via [`Guard::new`] in advance.
+    ///
+    /// Retired instances are not reclaimed when the [`Guard`] is created, however dropping the
+    /// [`Guard`] may update the global epoch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    ///
+    /// let thread = std::thread::spawn(|| {
+    ///     assert!(Guard::try_new().is_none());
+    ///     drop(Guard::new());
+    ///     assert!(Guard::try_new().is_some());
+    /// });
+    /// assert!(thread.join().is_ok());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn try_new() -> Option<Self> {
+        let collector_ptr = Collector::try_current()?;
+        if unsafe { (*collector_ptr).is_saturated() } {
+            return None;
+        }
+        unsafe {
+            (*collector_ptr).new_guard();
+        }
+        Some(Self { collector_ptr })
+    }
+
+    /// Creates a new [`Guard`] only if there is an active [`Guard`] in the thread.
+    ///
+    /// Creating a [`Guard`] while another [`Guard`] is active in the thread only increments a
+    /// thread-local counter, and this method never announces the thread to other threads or
+    /// allocates a thread-local data structure. It returns `None` if there is no active [`Guard`]
+    /// in the thread, or the number of [`Guard`] instances in the thread has reached the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    ///
+    /// assert!(Guard::try_reuse().is_none());
+    ///
+    /// let guard = Guard::new();
+    /// let nested = Guard::try_reuse();
+    /// assert!(nested.is_some());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn try_reuse() -> Option<Self> {
+        Collector::try_reuse().map(|collector_ptr| Self { collector_ptr })
+    }
+
+    /// Returns the [`Epoch`] announced by the thread when the first active [`Guard`] in the
+    /// thread was created.
+    ///
+    /// The global epoch never advances more than once while the [`Guard`] is active, and the
+    /// instances retired by other threads in the previous [`Epoch`] are not reclaimed until the
+    /// [`Guard`] is dropped. Comparing the value with that of a [`Guard`] created later after
+    /// dropping all the [`Guard`] instances in the thread tells whether the global epoch has
+    /// advanced in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    ///
+    /// let guard = Guard::new();
+    /// let epoch = guard.epoch();
+    /// assert_eq!(Guard::new().epoch(), epoch);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn epoch(&self) -> Epoch {
+        Epoch::from_u8(unsafe { (*self.collector_ptr).announcement() })
+    }
+
+    /// Forces the thread to try to advance the global epoch when the last [`Guard`] in the thread
+    /// is dropped.
+    ///
+    /// The global epoch is usually updated at a fixed cadence, and the cost of scanning other
+    /// threads and reclaiming garbage is paid by whichever thread happens to hit the cadence; this
+    /// method allows the caller, for instance a latency-insensitive background thread, to pay the
+    /// cost instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{Guard, Shared};
+    ///
+    /// let shared: Shared<usize> = Shared::new(37);
+    ///
+    /// let guard = Guard::new();
+    /// assert!(shared.release(&guard));
+    ///
+    /// guard.accelerate();
+    /// drop(guard);
+    /// ```
+    #[inline]
+    pub fn accelerate(&self) {
+        unsafe {
+            (*self.collector_ptr).accelerate();
+        }
+    }
+
     /// Defers dropping and memory reclamation of the supplied [`Box`] of a type implementing
     /// [`Collectible`].
     ///
@@ -81,19 +198,67 @@ impl Guard {
     /// Note that the supplied closure is stored in the heap memory, and it has to be `Sync` as it
     /// can be referred to by another thread.
     ///
+    /// This can be used to reclaim memory that is not managed by the [`ebr`](super) module, e.g.,
+    /// a node of a custom lock-free data structure that was unlinked by the current thread.
+    ///
     /// # Examples
     ///
     /// ```
     /// use scc::ebr::Guard;
+    /// use std::sync::atomic::AtomicPtr;
+    /// use std::sync::atomic::Ordering::AcqRel;
     ///
     /// let guard = Guard::new();
     /// guard.defer_execute(|| println!("deferred"));
+    ///
+    /// let head: AtomicPtr<usize> = AtomicPtr::new(Box::into_raw(Box::new(11)));
+    /// let unlinked = head.swap(Box::into_raw(Box::new(17)), AcqRel) as usize;
+    ///
+    /// // Other threads may still be reading the unlinked node.
+    /// guard.defer_execute(move || unsafe {
+    ///     drop(Box::from_raw(unlinked as *mut usize));
+    /// });
+    /// # drop(guard);
+    /// # unsafe { drop(Box::from_raw(head.into_inner())); }
     /// ```
     #[inline]
     pub fn defer_execute<F: 'static + FnOnce() + Sync>(&self, f: F) {
         self.defer(Box::new(DeferredClosure::new(f)));
     }
 
+    /// Passes the supplied value to the executor at a later point of time to drop it.
+    ///
+    /// The value is passed to the executor after every [`Guard`] at the moment when the method was
+    /// invoked is dropped, in the same manner as [`defer_execute`](Self::defer_execute). The
+    /// thread reclaiming retired instances only moves the value into the executor, therefore a
+    /// value whose destructor performs blocking operations can be dropped by a dedicated thread,
+    /// e.g., a blocking thread pool of an async runtime, instead of a latency-critical thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use std::fs::File;
+    /// use std::thread;
+    ///
+    /// let guard = Guard::new();
+    /// let file: Option<File> = None;
+    /// guard.defer_drop_on(
+    ///     |file| {
+    ///         thread::spawn(move || drop(file));
+    ///     },
+    ///     file,
+    /// );
+    /// ```
+    #[inline]
+    pub fn defer_drop_on<T, E>(&self, executor: E, value: T)
+    where
+        T: 'static + Send + Sync,
+        E: 'static + FnOnce(T) + Sync,
+    {
+        self.defer_execute(move || executor(value));
+    }
+
     /// Creates a new [`Guard`] for dropping an instance.
     #[inline]
     pub(super) fn new_for_drop() -> Self {
@@ -1,13 +1,37 @@
 use super::ref_counted::RefCounted;
-use super::{Collectible, Guard, Ptr};
+use super::{Collectible, Guard, Ptr, Shared};
 use std::mem::forget;
 use std::ops::Deref;
 use std::panic::UnwindSafe;
 use std::ptr::{addr_of, NonNull};
+use std::sync::atomic::Ordering::{Acquire, Relaxed};
 
 /// [`Owned`] uniquely owns an instance.
 ///
 /// The instance it passed to the `EBR` garbage collector when the [`Owned`] is dropped.
+///
+/// An [`Owned`] can be converted into a [`Shared`] without reallocating the instance, and a
+/// [`Shared`] can be converted back into an [`Owned`] if it is the only strong reference.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::{Owned, Shared};
+///
+/// let mut owned: Owned<usize> = Owned::new(17);
+/// unsafe {
+///     *owned.get_mut() += 1;
+/// }
+///
+/// let shared: Shared<usize> = Shared::from(owned);
+/// let shared_clone = shared.clone();
+///
+/// let shared = Owned::try_from(shared).unwrap_err();
+/// drop(shared_clone);
+///
+/// let owned = Owned::try_from(shared).unwrap();
+/// assert_eq!(*owned, 18);
+/// ```
 #[derive(Debug)]
 pub struct Owned<T> {
     instance_ptr: NonNull<RefCounted<T>>,
@@ -79,6 +103,7 @@ impl<T> Owned<T> {
     #[inline]
     #[must_use]
     pub fn get_guarded_ptr<'g>(&self, _guard: &'g Guard) -> Ptr<'g, T> {
+        self.underlying().mark_exposed();
         Ptr::from(self.instance_ptr.as_ptr())
     }
 
@@ -99,6 +124,7 @@ impl<T> Owned<T> {
     #[inline]
     #[must_use]
     pub fn get_guarded_ref<'g>(&self, _guard: &'g Guard) -> &'g T {
+        self.underlying().mark_exposed();
         unsafe { std::mem::transmute(&**self.underlying()) }
     }
 
@@ -124,6 +150,36 @@ impl<T> Owned<T> {
         self.instance_ptr.as_mut().get_mut_unique()
     }
 
+    /// Returns a mutable reference to the instance if no pointer can refer to the instance.
+    ///
+    /// It returns `None` if a [`Ptr`] may still refer to the instance: once a [`Ptr`] or a
+    /// reference bound to a [`Guard`] is created, or the [`Owned`] is taken out of an
+    /// [`AtomicOwned`](super::AtomicOwned), it returns `None` until the global epoch has been
+    /// updated twice after the next call. Raw pointers obtained through [`as_ptr`](Self::as_ptr)
+    /// are not tracked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{Guard, Owned};
+    ///
+    /// let mut owned: Owned<usize> = Owned::new(38);
+    /// *owned.try_get_mut().unwrap() += 1;
+    /// assert_eq!(*owned, 39);
+    ///
+    /// let guard = Guard::new();
+    /// let ptr = owned.get_guarded_ptr(&guard);
+    /// assert!(owned.try_get_mut().is_none());
+    /// ```
+    #[inline]
+    pub fn try_get_mut(&mut self) -> Option<&mut T> {
+        if self.underlying().is_exclusive(0) {
+            Some(unsafe { self.instance_ptr.as_mut().get_mut_unique() })
+        } else {
+            None
+        }
+    }
+
     /// Provides a raw pointer to the instance.
     ///
     /// # Examples
@@ -213,7 +269,9 @@ impl<T> Owned<T> {
             },
             0
         );
-        Self { instance_ptr: ptr }
+        let owned = Self { instance_ptr: ptr };
+        owned.underlying().mark_exposed();
+        owned
     }
 
     /// Returns a reference to the underlying instance.
@@ -254,6 +312,24 @@ impl<T> Drop for Owned<T> {
     }
 }
 
+impl<T> TryFrom<Shared<T>> for Owned<T> {
+    type Error = Shared<T>;
+
+    #[inline]
+    fn try_from(shared: Shared<T>) -> Result<Self, Self::Error> {
+        let ptr = shared.get_underlying_ptr();
+        if unsafe { (*ptr).ref_cnt().compare_exchange(1, 0, Acquire, Relaxed) }.is_ok() {
+            // No more strong references can be created.
+            forget(shared);
+            Ok(Self {
+                instance_ptr: unsafe { NonNull::new_unchecked(ptr) },
+            })
+        } else {
+            Err(shared)
+        }
+    }
+}
+
 unsafe impl<T: Send> Send for Owned<T> {}
 
 unsafe impl<T: Sync> Sync for Owned<T> {}
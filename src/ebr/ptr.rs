@@ -67,6 +67,36 @@ impl<'g, T> Ptr<'g, T> {
         unsafe { Tag::unset_tag(self.instance_ptr).as_ref().map(Deref::deref) }
     }
 
+    /// Projects the underlying instance to a reference that may live as long as the
+    /// [`Guard`](super::Guard).
+    ///
+    /// Returns `None` if the [`Ptr`] is null.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicShared, Guard, Ptr};
+    /// use std::sync::atomic::Ordering::Acquire;
+    ///
+    /// struct Entry {
+    ///     key: usize,
+    ///     value: String,
+    /// }
+    ///
+    /// fn read_value<'g>(atomic_shared: &AtomicShared<Entry>, guard: &'g Guard) -> Option<&'g str> {
+    ///     atomic_shared.load(Acquire, guard).map(|e| e.value.as_str())
+    /// }
+    ///
+    /// let atomic_shared = AtomicShared::new(Entry { key: 1, value: String::from("one") });
+    /// let guard = Guard::new();
+    /// assert_eq!(read_value(&atomic_shared, &guard), Some("one"));
+    /// assert!(Ptr::<Entry>::null().map(|e| &e.key).is_none());
+    /// ```
+    #[inline]
+    pub fn map<U: ?Sized, F: FnOnce(&'g T) -> &'g U>(&self, f: F) -> Option<&'g U> {
+        self.as_ref().map(f)
+    }
+
     /// Provides a raw pointer to the instance.
     ///
     /// # Examples
@@ -210,7 +240,7 @@ impl<'g, T> Ptr<'g, T> {
         unsafe {
             if let Some(ptr) = NonNull::new(Tag::unset_tag(self.instance_ptr).cast_mut()) {
                 if ptr.as_ref().try_add_ref(Relaxed) {
-                    return Some(Shared::from(ptr));
+                    return Some(Shared::from_new_ref(ptr));
                 }
             }
         }
@@ -0,0 +1,199 @@
+use super::Collectible;
+use crate::atomic::{AtomicBool, AtomicUsize};
+use crate::exit_guard::ExitGuard;
+use crate::wait_queue::{AsyncWait, WaitQueue};
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
+use std::sync::{Condvar, Mutex, Once, PoisonError};
+use std::thread;
+
+/// [`Batch`] is a linked list of reclaimable instances handed over to the reclaimer thread.
+struct Batch {
+    link: NonNull<dyn Collectible>,
+    num_garbage: usize,
+    garbage_bytes: usize,
+}
+
+/// Returns `true` if reclaimable instances are dropped by the reclaimer thread.
+#[inline]
+pub(super) fn is_enabled() -> bool {
+    ENABLED.load(Relaxed)
+}
+
+/// Enables or disables the reclaimer thread, and returns the previous state.
+///
+/// The reclaimer thread is spawned when it is enabled for the first time; if the thread cannot be
+/// spawned, it remains disabled.
+pub(super) fn set_enabled(enabled: bool) -> bool {
+    if enabled {
+        SPAWN.call_once(|| {
+            let result = thread::Builder::new()
+                .name(String::from("scc-ebr-reclaimer"))
+                .spawn(run);
+            SPAWNED.store(result.is_ok(), Relaxed);
+        });
+        if !SPAWNED.load(Relaxed) {
+            return ENABLED.load(Relaxed);
+        }
+    }
+    ENABLED.swap(enabled, AcqRel)
+}
+
+/// Hands over a linked list of reclaimable instances to the reclaimer thread.
+///
+/// Returns `false` if the reclaimer thread has exited; the caller has to reclaim the instances.
+pub(super) fn hand_over(
+    link: NonNull<dyn Collectible>,
+    num_garbage: usize,
+    garbage_bytes: usize,
+) -> bool {
+    let mut batches = BATCHES.lock().unwrap_or_else(PoisonError::into_inner);
+    if !SPAWNED.load(Relaxed) {
+        // `SPAWNED` is only cleared while `BATCHES` is locked.
+        return false;
+    }
+    NUM_GARBAGE.fetch_add(num_garbage, Relaxed);
+    GARBAGE_BYTES.fetch_add(garbage_bytes, Relaxed);
+    NUM_HANDED_OVER.fetch_add(1, Relaxed);
+    batches.push(Batch {
+        link,
+        num_garbage,
+        garbage_bytes,
+    });
+    drop(batches);
+    CONDVAR.notify_one();
+    true
+}
+
+/// Returns the number and the total size of instances waiting for the reclaimer thread.
+#[inline]
+pub(super) fn pending() -> (usize, usize) {
+    (NUM_GARBAGE.load(Relaxed), GARBAGE_BYTES.load(Relaxed))
+}
+
+/// Returns a ticket that is reclaimed when all the batches handed over so far are reclaimed.
+#[inline]
+pub(super) fn ticket() -> usize {
+    let batches = BATCHES.lock().unwrap_or_else(PoisonError::into_inner);
+    let ticket = NUM_HANDED_OVER.load(Relaxed);
+    drop(batches);
+    ticket
+}
+
+/// Returns `true` if the batches corresponding to the ticket have been reclaimed or will never be
+/// reclaimed as the reclaimer thread has exited.
+#[inline]
+pub(super) fn is_reclaimed(ticket: usize) -> bool {
+    NUM_RECLAIMED.load(Acquire) >= ticket || !SPAWNED.load(Relaxed)
+}
+
+/// Waits until the batches corresponding to the ticket are reclaimed.
+pub(super) fn wait_reclaimed(ticket: usize) {
+    while !is_reclaimed(ticket) {
+        let _result = RECLAIMED_WAIT_QUEUE.wait_sync(|| {
+            if is_reclaimed(ticket) {
+                Ok(())
+            } else {
+                Err(())
+            }
+        });
+    }
+}
+
+/// Waits until the batches corresponding to the ticket are reclaimed.
+pub(super) async fn wait_reclaimed_async(ticket: usize) {
+    while !is_reclaimed(ticket) {
+        let mut async_wait = AsyncWait::default();
+        let _result = RECLAIMED_WAIT_QUEUE.push_async_entry(&mut async_wait, || {
+            if is_reclaimed(ticket) {
+                Ok(())
+            } else {
+                Err(())
+            }
+        });
+        Pin::new(&mut async_wait).await;
+    }
+}
+
+/// Drops reclaimable instances handed over by other threads.
+fn run() {
+    // Batches that are handed over after the reclaimer thread has exited are reclaimed by each
+    // thread, and those that were handed over before are reclaimed here.
+    let _guard = ExitGuard::new((), |()| {
+        let mut batches = BATCHES.lock().unwrap_or_else(PoisonError::into_inner);
+        ENABLED.store(false, Relaxed);
+        SPAWNED.store(false, Relaxed);
+        let taken = std::mem::take(&mut *batches);
+        drop(batches);
+        RECLAIMED_WAIT_QUEUE.signal();
+        taken.iter().for_each(reclaim);
+    });
+    loop {
+        let mut batches = BATCHES.lock().unwrap_or_else(PoisonError::into_inner);
+        while batches.is_empty() {
+            batches = CONDVAR
+                .wait(batches)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        let taken = std::mem::take(&mut *batches);
+        drop(batches);
+
+        // Dropping an instance may retire other instances, therefore the lock must not be held.
+        taken.iter().for_each(reclaim);
+
+        // Instances retired by the reclaimer thread are reclaimed by other threads.
+        let _result = super::suspend();
+    }
+}
+
+/// Drops the instances in the batch.
+///
+/// A panic in an instance is caught so that the remaining instances are dropped; the memory of
+/// the panicking instance may leak.
+fn reclaim(batch: &Batch) {
+    let mut garbage_link = Some(batch.link);
+    while let Some(mut instance_ptr) = garbage_link.take() {
+        garbage_link = unsafe { *instance_ptr.as_mut().next_ptr_mut() };
+        let _result = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+            instance_ptr.as_mut().drop_and_dealloc();
+        }));
+    }
+    NUM_GARBAGE.fetch_sub(batch.num_garbage, Relaxed);
+    GARBAGE_BYTES.fetch_sub(batch.garbage_bytes, Relaxed);
+    NUM_RECLAIMED.fetch_add(1, Release);
+    RECLAIMED_WAIT_QUEUE.signal();
+}
+
+unsafe impl Send for Batch {}
+
+/// The reclaimer thread drops reclaimable instances if `true`.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The reclaimer thread has been spawned if `true`.
+static SPAWNED: AtomicBool = AtomicBool::new(false);
+
+/// Spawns the reclaimer thread only once.
+static SPAWN: Once = Once::new();
+
+/// Batches waiting for the reclaimer thread.
+static BATCHES: Mutex<Vec<Batch>> = Mutex::new(Vec::new());
+
+/// Wakes up the reclaimer thread.
+static CONDVAR: Condvar = Condvar::new();
+
+/// The number of instances waiting for the reclaimer thread.
+static NUM_GARBAGE: AtomicUsize = AtomicUsize::new(0);
+
+/// The total size of instances waiting for the reclaimer thread.
+static GARBAGE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of batches handed over to the reclaimer thread.
+static NUM_HANDED_OVER: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of batches reclaimed by the reclaimer thread.
+static NUM_RECLAIMED: AtomicUsize = AtomicUsize::new(0);
+
+/// `RECLAIMED_WAIT_QUEUE` holds threads waiting for batches to be reclaimed.
+static RECLAIMED_WAIT_QUEUE: WaitQueue = WaitQueue::new();
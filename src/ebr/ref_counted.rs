@@ -1,15 +1,34 @@
+use super::collector::Collector;
 use super::Collectible;
-use std::mem::ManuallyDrop;
+use crate::atomic::AtomicUsize;
+use crate::exit_guard::ExitGuard;
+use std::alloc::{alloc, handle_alloc_error, Layout};
+use std::mem::{ManuallyDrop, MaybeUninit};
 use std::ops::Deref;
-use std::ptr::NonNull;
-use std::sync::atomic::AtomicUsize;
-use std::sync::atomic::Ordering::{self, Relaxed};
+use std::ptr::{addr_of_mut, NonNull};
+use std::sync::atomic::fence;
+use std::sync::atomic::Ordering::{self, Acquire, Relaxed, Release};
 
-/// [`RefCounted`] stores an instance of type `T`, and a union of a link to the next
-/// [`Collectible`] or the reference counter.
+/// [`RefCounted`] stores an instance of type `T`, the reference counters, and a union of a link to
+/// the next [`Collectible`] or the exposure state.
+///
+/// The layout of `RefCounted<MaybeUninit<T>>` is identical to that of `RefCounted<T>`.
+#[repr(C)]
 pub(super) struct RefCounted<T> {
-    instance: T,
-    next_or_refcnt: LinkOrRefCnt,
+    instance: ManuallyDrop<T>,
+    next_or_exposed: LinkOrExposed,
+
+    /// The number of strong references; the least significant bit is set while the instance is
+    /// shared.
+    ///
+    /// It is never overwritten by the link to the next [`Collectible`], therefore a
+    /// [`Weak`](super::Weak) can read it after the instance is retired.
+    ref_cnt: AtomicUsize,
+
+    /// The number of weak references plus one held by all the strong references.
+    ///
+    /// The memory is deallocated when it reaches zero.
+    weak_cnt: AtomicUsize,
 }
 
 impl<T> RefCounted<T> {
@@ -17,8 +36,10 @@ impl<T> RefCounted<T> {
     #[inline]
     pub(super) const fn new_shared(t: T) -> Self {
         Self {
-            instance: t,
-            next_or_refcnt: LinkOrRefCnt::new_shared(),
+            instance: ManuallyDrop::new(t),
+            next_or_exposed: LinkOrExposed::new(),
+            ref_cnt: AtomicUsize::new(1),
+            weak_cnt: AtomicUsize::new(1),
         }
     }
 
@@ -28,8 +49,10 @@ impl<T> RefCounted<T> {
     #[inline]
     pub(super) const fn new_unique(t: T) -> Self {
         Self {
-            instance: t,
-            next_or_refcnt: LinkOrRefCnt::new_unique(),
+            instance: ManuallyDrop::new(t),
+            next_or_exposed: LinkOrExposed::new(),
+            ref_cnt: AtomicUsize::new(0),
+            weak_cnt: AtomicUsize::new(1),
         }
     }
 
@@ -96,14 +119,14 @@ impl<T> RefCounted<T> {
     #[inline]
     pub(super) fn drop_ref(&self) -> bool {
         // It does not have to be a load-acquire as everything's synchronized via the global
-        // epoch.
+        // epoch; the store-release is for `is_exclusive`.
         let mut current = self.ref_cnt().load(Relaxed);
         loop {
             debug_assert_ne!(current, 0);
             let new = if current <= 1 { 0 } else { current - 2 };
             match self
                 .ref_cnt()
-                .compare_exchange_weak(current, new, Relaxed, Relaxed)
+                .compare_exchange_weak(current, new, Release, Relaxed)
             {
                 Ok(_) => break,
                 Err(actual) => {
@@ -114,10 +137,83 @@ impl<T> RefCounted<T> {
         current == 1
     }
 
+    /// Adds a weak reference to the underlying memory.
+    #[inline]
+    pub(super) fn add_weak_ref(&self) {
+        self.weak_cnt.fetch_add(1, Relaxed);
+    }
+
+    /// Drops a weak reference to the underlying memory, and deallocates the memory if it was the
+    /// last reference.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own a weak reference, and `ptr` must not be used afterwards.
+    #[inline]
+    pub(super) unsafe fn drop_weak_ref(ptr: *mut Self) {
+        if (*ptr).weak_cnt.fetch_sub(1, Release) == 1 {
+            fence(Acquire);
+            let _: Box<Self> = Box::from_raw(ptr);
+        }
+    }
+
+    /// Moves the instance out of the [`RefCounted`], and deallocates the memory.
+    ///
+    /// # Safety
+    ///
+    /// The caller must be the only owner of the instance, and no other handle or pointer to the
+    /// instance may exist.
+    #[inline]
+    pub(super) unsafe fn take_and_dealloc(ptr: *mut Self) -> T {
+        let instance = ManuallyDrop::take(&mut (*ptr).instance);
+        let _: Box<Self> = Box::from_raw(ptr);
+        instance
+    }
+
+    /// Marks that the instance may be referred to by a [`Ptr`](super::Ptr) or a reference that
+    /// lives as long as a [`Guard`](super::Guard).
+    ///
+    /// The global epoch is not read here; [`is_exclusive`](Self::is_exclusive) records it when it
+    /// finds the mark.
+    #[inline]
+    pub(super) fn mark_exposed(&self) {
+        let exposed = unsafe { &self.next_or_exposed.exposed };
+        if exposed.load(Relaxed) != EXPOSED {
+            exposed.store(EXPOSED, Relaxed);
+        }
+    }
+
+    /// Returns `true` if no other handle or pointer can refer to the instance.
+    ///
+    /// `refcnt` is the expected reference count value of the only owner. If the instance is marked
+    /// exposed, the number of global epoch updates plus two is recorded instead; once the global
+    /// epoch has been updated twice more, no [`Guard`](super::Guard) that was alive at the time
+    /// can be alive.
+    #[inline]
+    pub(super) fn is_exclusive(&self, refcnt: usize) -> bool {
+        if self.ref_cnt().load(Acquire) != refcnt || self.weak_cnt.load(Acquire) != 1 {
+            return false;
+        }
+        let exposed = unsafe { &self.next_or_exposed.exposed };
+        match exposed.load(Relaxed) {
+            0 => true,
+            EXPOSED => {
+                let _result = exposed.compare_exchange(
+                    EXPOSED,
+                    Collector::num_epoch_updates() + 2,
+                    Relaxed,
+                    Relaxed,
+                );
+                false
+            }
+            target => Collector::num_epoch_updates() >= target,
+        }
+    }
+
     /// Returns a reference to its reference count.
     #[inline]
     pub(super) fn ref_cnt(&self) -> &AtomicUsize {
-        unsafe { &self.next_or_refcnt.refcnt.0 }
+        &self.ref_cnt
     }
 
     /// Returns a `dyn Collectible` reference to `self`.
@@ -127,6 +223,26 @@ impl<T> RefCounted<T> {
     }
 }
 
+impl<T> RefCounted<MaybeUninit<T>> {
+    /// Allocates a new [`RefCounted`] that allows ownership sharing without initializing the
+    /// instance.
+    ///
+    /// The instance is never placed on the stack.
+    #[inline]
+    pub(super) fn alloc_shared_uninit() -> NonNull<Self> {
+        let layout = Layout::new::<Self>();
+        unsafe {
+            let Some(ptr) = NonNull::new(alloc(layout).cast::<Self>()) else {
+                handle_alloc_error(layout);
+            };
+            addr_of_mut!((*ptr.as_ptr()).next_or_exposed).write(LinkOrExposed::new());
+            addr_of_mut!((*ptr.as_ptr()).ref_cnt).write(AtomicUsize::new(1));
+            addr_of_mut!((*ptr.as_ptr()).weak_cnt).write(AtomicUsize::new(1));
+            ptr
+        }
+    }
+}
+
 impl<T> Deref for RefCounted<T> {
     type Target = T;
 
@@ -139,28 +255,42 @@ impl<T> Deref for RefCounted<T> {
 impl<T> Collectible for RefCounted<T> {
     #[inline]
     fn next_ptr_mut(&mut self) -> &mut Option<NonNull<dyn Collectible>> {
-        unsafe { &mut self.next_or_refcnt.next }
+        unsafe { &mut self.next_or_exposed.next }
     }
-}
-
-/// [`LinkOrRefCnt`] is a union of a dynamic pointer to [`Collectible`] and a reference count.
-pub(super) union LinkOrRefCnt {
-    next: Option<NonNull<dyn Collectible>>,
-    refcnt: ManuallyDrop<(AtomicUsize, usize)>,
-}
 
-impl LinkOrRefCnt {
     #[inline]
-    const fn new_shared() -> Self {
-        LinkOrRefCnt {
-            refcnt: ManuallyDrop::new((AtomicUsize::new(1), 0)),
+    fn drop_and_dealloc(&mut self) {
+        let ptr: *mut Self = self;
+
+        // The memory is deallocated even if dropping the instance panics.
+        let _guard = ExitGuard::new(ptr, |ptr| unsafe { Self::drop_weak_ref(ptr) });
+        unsafe {
+            ManuallyDrop::drop(&mut (*ptr).instance);
         }
     }
+}
+
+// The two least significant bits of a pointer to a `RefCounted` are used for `Tag`, which is
+// satisfied on 32-bit targets as well, since `LinkOrExposed` is aligned to a pointer.
+const _: () = assert!(std::mem::align_of::<RefCounted<u8>>() >= 4);
+
+/// The exposure state of an instance that is marked exposed before the global epoch is recorded.
+const EXPOSED: usize = usize::MAX;
+
+/// [`LinkOrExposed`] is a union of a dynamic pointer to [`Collectible`] and the exposure state.
+///
+/// The exposure state is only accessed by the owners of the instance, and the link is only used
+/// after the last owner has passed the instance to the garbage collector.
+pub(super) union LinkOrExposed {
+    next: Option<NonNull<dyn Collectible>>,
+    exposed: ManuallyDrop<AtomicUsize>,
+}
 
+impl LinkOrExposed {
     #[inline]
-    const fn new_unique() -> Self {
-        LinkOrRefCnt {
-            refcnt: ManuallyDrop::new((AtomicUsize::new(0), 0)),
+    const fn new() -> Self {
+        LinkOrExposed {
+            exposed: ManuallyDrop::new(AtomicUsize::new(0)),
         }
     }
 }
@@ -1,6 +1,6 @@
 use super::ref_counted::RefCounted;
-use super::{Collectible, Guard, Ptr};
-use std::mem::forget;
+use super::{Collectible, Guard, Owned, Ptr, Weak};
+use std::mem::{forget, MaybeUninit};
 use std::ops::Deref;
 use std::panic::UnwindSafe;
 use std::ptr::{addr_of, NonNull};
@@ -37,6 +37,61 @@ impl<T: 'static> Shared<T> {
             instance_ptr: unsafe { NonNull::new_unchecked(Box::into_raw(boxed)) },
         }
     }
+
+    /// Creates a new [`Shared`] with uninitialized contents.
+    ///
+    /// The memory for the instance is directly allocated on the heap, therefore a large instance
+    /// can be initialized in place without being constructed on the stack.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Shared;
+    ///
+    /// let mut shared = Shared::<[u64; 1 << 20]>::new_uninit();
+    /// let uninit = shared.try_get_mut().unwrap();
+    /// unsafe {
+    ///     uninit.as_mut_ptr().cast::<u64>().write_bytes(0, 1 << 20);
+    /// }
+    /// let shared = unsafe { shared.assume_init() };
+    ///
+    /// assert!(shared.iter().all(|v| *v == 0));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new_uninit() -> Shared<MaybeUninit<T>> {
+        Shared {
+            instance_ptr: RefCounted::alloc_shared_uninit(),
+        }
+    }
+}
+
+impl<T> Shared<MaybeUninit<T>> {
+    /// Converts to `Shared<T>`.
+    ///
+    /// # Safety
+    ///
+    /// The instance must have been initialized, and no other [`Shared`] or [`Ptr`] may refer to
+    /// the instance as `MaybeUninit<T>` afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Shared;
+    ///
+    /// let mut shared = Shared::<usize>::new_uninit();
+    /// shared.try_get_mut().unwrap().write(11);
+    /// let shared = unsafe { shared.assume_init() };
+    ///
+    /// assert_eq!(*shared, 11);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub unsafe fn assume_init(self) -> Shared<T> {
+        let instance_ptr = self.instance_ptr.cast::<RefCounted<T>>();
+        forget(self);
+        Shared { instance_ptr }
+    }
 }
 
 impl<T> Shared<T> {
@@ -84,6 +139,7 @@ impl<T> Shared<T> {
     #[inline]
     #[must_use]
     pub fn get_guarded_ptr<'g>(&self, _guard: &'g Guard) -> Ptr<'g, T> {
+        self.underlying().mark_exposed();
         Ptr::from(self.instance_ptr.as_ptr())
     }
 
@@ -104,6 +160,7 @@ impl<T> Shared<T> {
     #[inline]
     #[must_use]
     pub fn get_guarded_ref<'g>(&self, _guard: &'g Guard) -> &'g T {
+        self.underlying().mark_exposed();
         unsafe { std::mem::transmute(&**self.underlying()) }
     }
 
@@ -131,6 +188,78 @@ impl<T> Shared<T> {
         self.instance_ptr.as_mut().get_mut_shared()
     }
 
+    /// Returns a mutable reference to the instance if no other handle or pointer can refer to the
+    /// instance.
+    ///
+    /// It returns `None` if there is another [`Shared`] or [`Weak`] handle to the instance, or a
+    /// [`Ptr`] may still refer to the instance: once a [`Ptr`] or a reference bound to a [`Guard`]
+    /// is created, or the [`Shared`] is taken out of an [`AtomicShared`](super::AtomicShared), it
+    /// returns `None` until the global epoch has been updated twice after the next call. Raw
+    /// pointers obtained through [`as_ptr`](Self::as_ptr) are not tracked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{AtomicShared, Guard, Shared};
+    ///
+    /// let mut shared: Shared<usize> = Shared::new(38);
+    /// *shared.try_get_mut().unwrap() += 1;
+    ///
+    /// let shared_clone = shared.clone();
+    /// assert!(shared.try_get_mut().is_none());
+    /// drop(shared_clone);
+    ///
+    /// *shared.try_get_mut().unwrap() += 1;
+    /// assert_eq!(*shared, 40);
+    ///
+    /// let guard = Guard::new();
+    /// let ptr = shared.get_guarded_ptr(&guard);
+    /// assert!(shared.try_get_mut().is_none());
+    /// ```
+    #[inline]
+    pub fn try_get_mut(&mut self) -> Option<&mut T> {
+        if self.underlying().is_exclusive(1) {
+            unsafe { self.instance_ptr.as_mut().get_mut_shared() }
+        } else {
+            None
+        }
+    }
+
+    /// Returns the instance if no other handle or pointer can refer to the instance.
+    ///
+    /// The instance is neither dropped nor passed to the garbage collector if successful, and this
+    /// can be used to reuse a large buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` back if there is another [`Shared`] or [`Weak`] handle to the instance, or
+    /// a [`Ptr`] may still refer to the instance as described in
+    /// [`try_get_mut`](Self::try_get_mut).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Shared;
+    ///
+    /// let shared: Shared<Vec<u8>> = Shared::new(vec![0; 64]);
+    /// let shared_clone = shared.clone();
+    /// let shared = shared.try_unwrap().unwrap_err();
+    /// drop(shared_clone);
+    ///
+    /// let buffer = shared.try_unwrap().unwrap();
+    /// assert_eq!(buffer.len(), 64);
+    /// ```
+    #[inline]
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        if self.underlying().is_exclusive(1) {
+            let ptr = self.instance_ptr.as_ptr();
+            forget(self);
+            Ok(unsafe { RefCounted::take_and_dealloc(ptr) })
+        } else {
+            Err(self)
+        }
+    }
+
     /// Provides a raw pointer to the instance.
     ///
     /// # Examples
@@ -152,6 +281,24 @@ impl<T> Shared<T> {
         addr_of!(**self.underlying())
     }
 
+    /// Creates a [`Weak`] handle to the instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Shared;
+    ///
+    /// let shared: Shared<usize> = Shared::new(23);
+    /// let weak = shared.downgrade();
+    /// assert_eq!(*weak.upgrade().unwrap(), 23);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn downgrade(&self) -> Weak<T> {
+        self.underlying().add_weak_ref();
+        Weak::from(self.instance_ptr)
+    }
+
     /// Releases the strong reference by passing `self` to the given [`Guard`].
     ///
     /// Returns `true` if the last reference was released.
@@ -232,9 +379,33 @@ impl<T> Shared<T> {
         self.instance_ptr.as_ptr()
     }
 
-    /// Creates a new [`Shared`] from the given pointer.
+    /// Creates a new [`Shared`] from the given pointer that has been owned by another handle,
+    /// e.g., an [`AtomicShared`](super::AtomicShared).
+    ///
+    /// [`Ptr`] instances may have been created from the other handle, and therefore the instance
+    /// is marked exposed.
     #[inline]
     pub(super) fn from(ptr: NonNull<RefCounted<T>>) -> Self {
+        debug_assert_ne!(
+            unsafe {
+                ptr.as_ref()
+                    .ref_cnt()
+                    .load(std::sync::atomic::Ordering::Relaxed)
+            },
+            0
+        );
+        let shared = Self { instance_ptr: ptr };
+        shared.underlying().mark_exposed();
+        shared
+    }
+
+    /// Creates a new [`Shared`] from the given pointer to which a strong reference has just been
+    /// added.
+    ///
+    /// The instance is not marked exposed, since the handle that the strong reference was
+    /// obtained through marks it when the handle releases the instance.
+    #[inline]
+    pub(super) fn from_new_ref(ptr: NonNull<RefCounted<T>>) -> Self {
         debug_assert_ne!(
             unsafe {
                 ptr.as_ref()
@@ -297,6 +468,21 @@ impl<T> Drop for Shared<T> {
     }
 }
 
+impl<T> From<Owned<T>> for Shared<T> {
+    #[inline]
+    fn from(owned: Owned<T>) -> Self {
+        let ptr = owned.get_underlying_ptr();
+        forget(owned);
+        unsafe {
+            // Only the `Owned` has been able to access the reference counter.
+            (*ptr).ref_cnt().store(1, Relaxed);
+            Self {
+                instance_ptr: NonNull::new_unchecked(ptr),
+            }
+        }
+    }
+}
+
 impl<'g, T> TryFrom<Ptr<'g, T>> for Shared<T> {
     type Error = Ptr<'g, T>;
 
@@ -0,0 +1,222 @@
+/// Garbage collection statistics of the [`ebr`](super) module.
+///
+/// The statistics are collected without blocking other threads, therefore they may not
+/// correspond to a consistent snapshot of the garbage collector when threads are retiring or
+/// reclaiming instances.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Statistics {
+    pub(super) epoch: u8,
+    pub(super) num_threads: usize,
+    pub(super) num_lagging_threads: usize,
+    pub(super) num_garbage: usize,
+    pub(super) garbage_bytes: usize,
+}
+
+impl Statistics {
+    /// Returns the current global epoch.
+    ///
+    /// The global epoch cycles through `0`, `1`, and `2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::statistics;
+    ///
+    /// assert!(statistics().unwrap().epoch() < 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn epoch(&self) -> u8 {
+        self.epoch
+    }
+
+    /// Returns the number of threads that are registered in the garbage collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{statistics, Guard};
+    ///
+    /// drop(Guard::new());
+    /// assert!(statistics().unwrap().num_threads() >= 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+
+    /// Returns the number of threads that hold a [`Guard`](super::Guard) created in a previous
+    /// epoch.
+    ///
+    /// The global epoch cannot advance until all those threads drop their
+    /// [`Guard`](super::Guard) instances; a persistently non-zero value indicates that garbage
+    /// collection is stalled by a long-lived [`Guard`](super::Guard).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::statistics;
+    ///
+    /// let num_lagging_threads = statistics().unwrap().num_lagging_threads();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_lagging_threads(&self) -> usize {
+        self.num_lagging_threads
+    }
+
+    /// Returns the number of retired instances that have not been reclaimed.
+    ///
+    /// Retired instances are only counted while a limit is set by
+    /// [`set_garbage_limit`](super::set_garbage_limit) unless the `ebr-stats` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{set_garbage_limit, statistics, Guard, Shared};
+    ///
+    /// set_garbage_limit(1024, usize::MAX);
+    ///
+    /// let shared: Shared<usize> = Shared::new(17);
+    /// let guard = Guard::new();
+    /// assert!(shared.release(&guard));
+    ///
+    /// assert!(statistics().unwrap().num_garbage() >= 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_garbage(&self) -> usize {
+        self.num_garbage
+    }
+
+    /// Returns the total size of retired instances that have not been reclaimed in bytes.
+    ///
+    /// Memory owned by the retired instances, e.g., heap memory owned by a retired [`String`], is
+    /// not included. Retired instances are only counted while a limit is set by
+    /// [`set_garbage_limit`](super::set_garbage_limit) unless the `ebr-stats` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{set_garbage_limit, statistics, Guard, Shared};
+    ///
+    /// set_garbage_limit(usize::MAX, 1 << 20);
+    ///
+    /// let shared: Shared<[u8; 64]> = Shared::new([0; 64]);
+    /// let guard = Guard::new();
+    /// assert!(shared.release(&guard));
+    ///
+    /// assert!(statistics().unwrap().garbage_bytes() >= 64);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn garbage_bytes(&self) -> usize {
+        self.garbage_bytes
+    }
+}
+
+/// Per-thread garbage collection statistics of the [`ebr`](super) module.
+///
+/// The statistics are only available with the `ebr-stats` feature; a thread-local garbage
+/// collector is created when a thread creates a [`Guard`](super::Guard) for the first time, and it
+/// is kept in the list until all its retired instances are reclaimed after the thread exits.
+#[cfg(feature = "ebr-stats")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CollectorStatistics {
+    pub(super) is_active: bool,
+    pub(super) is_terminated: bool,
+    pub(super) num_retired: usize,
+    pub(super) num_reclaimed: usize,
+    pub(super) num_garbage: usize,
+}
+
+#[cfg(feature = "ebr-stats")]
+impl CollectorStatistics {
+    /// Returns `true` if the thread holds a [`Guard`](super::Guard).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{collector_statistics, Guard};
+    ///
+    /// let guard = Guard::new();
+    /// assert!(collector_statistics().unwrap().iter().any(|s| s.is_active()));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Returns `true` if the thread has exited or detached itself from the garbage collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{collector_statistics, Guard};
+    ///
+    /// drop(Guard::new());
+    /// assert!(collector_statistics().unwrap().iter().any(|s| !s.is_terminated()));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+
+    /// Returns the number of instances and closures retired by the thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{collector_statistics, Guard};
+    ///
+    /// let guard = Guard::new();
+    /// guard.defer_execute(|| ());
+    /// assert!(collector_statistics().unwrap().iter().any(|s| s.num_retired() >= 1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_retired(&self) -> usize {
+        self.num_retired
+    }
+
+    /// Returns the number of instances and closures retired by the thread that have been dropped,
+    /// executed, or handed over to the background reclamation thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::collector_statistics;
+    ///
+    /// assert!(collector_statistics()
+    ///     .unwrap()
+    ///     .iter()
+    ///     .all(|s| s.num_reclaimed() + s.num_garbage() == s.num_retired()));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_reclaimed(&self) -> usize {
+        self.num_reclaimed
+    }
+
+    /// Returns the number of instances and closures retired by the thread that are pending.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::{collector_statistics, Guard, Shared};
+    ///
+    /// let shared: Shared<usize> = Shared::new(17);
+    /// let guard = Guard::new();
+    /// assert!(shared.release(&guard));
+    ///
+    /// assert!(collector_statistics().unwrap().iter().any(|s| s.num_garbage() >= 1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_garbage(&self) -> usize {
+        self.num_garbage
+    }
+}
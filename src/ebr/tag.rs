@@ -1,7 +1,23 @@
 use std::cmp::PartialEq;
+use std::ops::{BitAnd, BitOr, BitXor};
 
 /// [`Tag`] is a four-state `Enum` that can be embedded in a pointer as the two least
 /// significant bits of the pointer value.
+///
+/// Only two bits are available since pointers to instances managed by the [`ebr`](super)
+/// module are guaranteed to be aligned to four bytes on all the supported platforms. The two bits
+/// can be used either as a four-state value or as two independent flags combined with bitwise
+/// operators.
+///
+/// # Examples
+///
+/// ```
+/// use scc::ebr::Tag;
+///
+/// assert_eq!(Tag::First | Tag::Second, Tag::Both);
+/// assert_eq!(Tag::Both & Tag::Second, Tag::Second);
+/// assert_eq!(Tag::Both ^ Tag::First, Tag::Second);
+/// ```
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Tag {
     /// None tagged.
@@ -26,7 +42,19 @@ impl Tag {
         }
     }
 
+    /// Converts an integer into a [`Tag`] by taking the two least significant bits.
+    #[inline]
+    const fn from_value(val: usize) -> Self {
+        match val & 3 {
+            0 => Self::None,
+            1 => Self::First,
+            2 => Self::Second,
+            _ => Self::Both,
+        }
+    }
+
     /// Returns the tag embedded in the pointer.
+    #[cfg(not(feature = "strict_provenance"))]
     #[inline]
     pub(super) fn into_tag<P>(ptr: *const P) -> Self {
         match ((ptr as usize & 1) == 1, (ptr as usize & 2) == 2) {
@@ -37,17 +65,47 @@ impl Tag {
         }
     }
 
+    /// Returns the tag embedded in the pointer.
+    #[cfg(feature = "strict_provenance")]
+    #[clippy::msrv = "1.84"]
+    #[inline]
+    pub(super) fn into_tag<P>(ptr: *const P) -> Self {
+        Self::from_value(ptr.addr())
+    }
+
     /// Sets a tag, overwriting any existing tag in the pointer.
+    #[cfg(not(feature = "strict_provenance"))]
     #[inline]
     pub(super) fn update_tag<P>(ptr: *const P, tag: Tag) -> *const P {
         (((ptr as usize) & (!3)) | tag.value()) as *const P
     }
 
+    /// Sets a tag, overwriting any existing tag in the pointer.
+    ///
+    /// The provenance of the pointer is preserved.
+    #[cfg(feature = "strict_provenance")]
+    #[clippy::msrv = "1.84"]
+    #[inline]
+    pub(super) fn update_tag<P>(ptr: *const P, tag: Tag) -> *const P {
+        ptr.map_addr(|addr| (addr & (!3)) | tag.value())
+    }
+
     /// Returns the pointer with the tag bits erased.
+    #[cfg(not(feature = "strict_provenance"))]
     #[inline]
     pub(super) fn unset_tag<P>(ptr: *const P) -> *const P {
         ((ptr as usize) & (!3)) as *const P
     }
+
+    /// Returns the pointer with the tag bits erased.
+    ///
+    /// The provenance of the pointer is preserved.
+    #[cfg(feature = "strict_provenance")]
+    #[clippy::msrv = "1.84"]
+    #[inline]
+    pub(super) fn unset_tag<P>(ptr: *const P) -> *const P {
+        ptr.map_addr(|addr| addr & (!3))
+    }
 }
 
 impl TryFrom<u8> for Tag {
@@ -76,3 +134,30 @@ impl From<Tag> for u8 {
         }
     }
 }
+
+impl BitAnd for Tag {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::from_value(self.value() & rhs.value())
+    }
+}
+
+impl BitOr for Tag {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::from_value(self.value() | rhs.value())
+    }
+}
+
+impl BitXor for Tag {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self::from_value(self.value() ^ rhs.value())
+    }
+}
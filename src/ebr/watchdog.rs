@@ -0,0 +1,115 @@
+use super::collector::Collector;
+use std::cmp::Reverse;
+use std::sync::{Mutex, PoisonError};
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+/// [`StalledThread`] describes a thread that prevents the global epoch from advancing.
+///
+/// A thread stalls the global epoch when it holds a [`Guard`](super::Guard) created in a previous
+/// epoch; instances retired after the [`Guard`](super::Guard) was created cannot be reclaimed
+/// until the thread drops all its [`Guard`](super::Guard) instances.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StalledThread {
+    thread_id: ThreadId,
+    num_guards: u32,
+    duration: Duration,
+}
+
+impl StalledThread {
+    /// Returns the identifier of the thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::stalled_threads;
+    ///
+    /// let current = std::thread::current().id();
+    /// assert!(stalled_threads().unwrap().iter().all(|s| s.thread_id() != current));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn thread_id(&self) -> ThreadId {
+        self.thread_id
+    }
+
+    /// Returns the number of [`Guard`](super::Guard) instances held by the thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::stalled_threads;
+    ///
+    /// assert!(stalled_threads().unwrap().iter().all(|s| s.num_guards() >= 1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_guards(&self) -> u32 {
+        self.num_guards
+    }
+
+    /// Returns the time elapsed since the thread was first observed stalling the global epoch.
+    ///
+    /// The thread is observed only when [`stalled_threads`](super::stalled_threads) is called,
+    /// therefore the value is a lower bound of the actual duration; calling
+    /// [`stalled_threads`](super::stalled_threads) periodically makes the value more accurate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::stalled_threads;
+    /// use std::time::Duration;
+    ///
+    /// let long_running = stalled_threads()
+    ///     .unwrap_or_default()
+    ///     .into_iter()
+    ///     .filter(|s| s.duration() > Duration::from_secs(60))
+    ///     .count();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// Returns the threads stalling the global epoch, sorted by the duration in descending order.
+///
+/// Returns `None` if the list of thread-local garbage collectors is being modified.
+pub(super) fn stalled_threads() -> Option<Vec<StalledThread>> {
+    let lagging_threads = Collector::lagging_threads()?;
+    let now = Instant::now();
+    let mut observed = OBSERVED.lock().unwrap_or_else(PoisonError::into_inner);
+
+    // A thread is identified by its identifier and the number of `Guard` sessions in the thread.
+    observed.retain(|(thread_id, num_pins, _)| {
+        lagging_threads
+            .iter()
+            .any(|(t, n, _)| t == thread_id && n == num_pins)
+    });
+    let mut stalled_threads: Vec<StalledThread> = lagging_threads
+        .into_iter()
+        .map(|(thread_id, num_pins, num_guards)| {
+            let since = if let Some((_, _, since)) = observed
+                .iter()
+                .find(|(t, n, _)| *t == thread_id && *n == num_pins)
+            {
+                *since
+            } else {
+                observed.push((thread_id, num_pins, now));
+                now
+            };
+            StalledThread {
+                thread_id,
+                num_guards,
+                duration: now.saturating_duration_since(since),
+            }
+        })
+        .collect();
+    drop(observed);
+    stalled_threads.sort_by_key(|s| Reverse(s.duration));
+    Some(stalled_threads)
+}
+
+/// Threads observed stalling the global epoch and the time when they were first observed.
+static OBSERVED: Mutex<Vec<(ThreadId, usize, Instant)>> = Mutex::new(Vec::new());
@@ -0,0 +1,105 @@
+use super::ref_counted::RefCounted;
+use super::Shared;
+use std::panic::UnwindSafe;
+use std::ptr::NonNull;
+use std::sync::atomic::Ordering::Acquire;
+
+/// [`Weak`] is a non-owning handle to an instance managed by [`Shared`].
+///
+/// A [`Weak`] does not keep the instance alive; the instance is passed to the EBR garbage
+/// collector when the last strong reference is dropped regardless of [`Weak`] handles, and only
+/// the memory of the instance is kept allocated until all the [`Weak`] handles are dropped.
+#[derive(Debug)]
+pub struct Weak<T> {
+    instance_ptr: NonNull<RefCounted<T>>,
+}
+
+impl<T> Weak<T> {
+    /// Tries to create a strong reference to the instance.
+    ///
+    /// Returns `None` if all the strong references to the instance have been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Shared;
+    ///
+    /// let shared: Shared<usize> = Shared::new(17);
+    /// let weak = shared.downgrade();
+    ///
+    /// assert_eq!(weak.upgrade().map(|s| *s), Some(17));
+    ///
+    /// drop(shared);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn upgrade(&self) -> Option<Shared<T>> {
+        if self.underlying().try_add_ref(Acquire) {
+            Some(Shared::from_new_ref(self.instance_ptr))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the instance has not been passed to the EBR garbage collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Shared;
+    ///
+    /// let shared: Shared<usize> = Shared::new(19);
+    /// let weak = shared.downgrade();
+    /// assert!(weak.is_alive());
+    ///
+    /// drop(shared);
+    /// assert!(!weak.is_alive());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_alive(&self) -> bool {
+        self.underlying().ref_cnt().load(Acquire) % 2 == 1
+    }
+
+    /// Creates a new [`Weak`] from the given pointer.
+    ///
+    /// The weak reference count must have been incremented by the caller.
+    #[inline]
+    pub(super) fn from(ptr: NonNull<RefCounted<T>>) -> Self {
+        Self { instance_ptr: ptr }
+    }
+
+    /// Returns a reference to the underlying instance.
+    #[inline]
+    fn underlying(&self) -> &RefCounted<T> {
+        unsafe { self.instance_ptr.as_ref() }
+    }
+}
+
+impl<T> Clone for Weak<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.underlying().add_weak_ref();
+        Self {
+            instance_ptr: self.instance_ptr,
+        }
+    }
+}
+
+impl<T> Drop for Weak<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            RefCounted::drop_weak_ref(self.instance_ptr.as_ptr());
+        }
+    }
+}
+
+// A `Weak` can be upgraded to a `Shared` on any thread, thereby granting shared access to the
+// instance there, so both `Send` and `Sync` are required as in `std::sync::Weak`.
+unsafe impl<T: Send + Sync> Send for Weak<T> {}
+
+unsafe impl<T: Send + Sync> Sync for Weak<T> {}
+
+impl<T: UnwindSafe> UnwindSafe for Weak<T> {}
@@ -6,14 +6,14 @@ use super::hash_table::bucket::{DoublyLinkedList, EntryPtr, Locker, Reader, CACH
 use super::hash_table::bucket_array::BucketArray;
 use super::hash_table::{HashTable, LockedEntry};
 use super::wait_queue::AsyncWait;
-use std::borrow::Borrow;
+use super::Equivalent;
+use crate::atomic::AtomicUsize;
 use std::collections::hash_map::RandomState;
 use std::fmt::{self, Debug};
 use std::hash::{BuildHasher, Hash};
 use std::mem::replace;
 use std::ops::RangeInclusive;
 use std::pin::Pin;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::{Acquire, Relaxed};
 
 /// Scalable concurrent 32-way associative cache backed by [`HashMap`](super::HashMap).
@@ -366,8 +366,7 @@ where
     #[inline]
     pub fn get<Q>(&self, key: &Q) -> Option<OccupiedEntry<K, V, H>>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let guard = Guard::new();
         let mut locked_entry = self
@@ -403,8 +402,7 @@ where
     #[inline]
     pub async fn get_async<Q>(&self, key: &Q) -> Option<OccupiedEntry<K, V, H>>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let hash = self.hash(key);
         loop {
@@ -447,8 +445,7 @@ where
     #[inline]
     pub fn read<Q, R, F: FnOnce(&K, &V) -> R>(&self, key: &Q, reader: F) -> Option<R>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.read_entry(key, self.hash(key), &mut (), &Guard::new())
             .ok()
@@ -473,8 +470,7 @@ where
     #[inline]
     pub async fn read_async<Q, R, F: FnOnce(&K, &V) -> R>(&self, key: &Q, reader: F) -> Option<R>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let hash = self.hash(key);
         loop {
@@ -503,8 +499,7 @@ where
     #[inline]
     pub fn contains<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.read(key, |_, _| ()).is_some()
     }
@@ -525,8 +520,7 @@ where
     #[inline]
     pub async fn contains_async<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.read_async(key, |_, _| ()).await.is_some()
     }
@@ -550,8 +544,7 @@ where
     #[inline]
     pub fn remove<Q>(&self, key: &Q) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.remove_if(key, |_| true)
     }
@@ -573,8 +566,7 @@ where
     #[inline]
     pub async fn remove_async<Q>(&self, key: &Q) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.remove_if_async(key, |_| true).await
     }
@@ -598,8 +590,7 @@ where
     #[inline]
     pub fn remove_if<Q, F: FnOnce(&mut V) -> bool>(&self, key: &Q, condition: F) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.get(key).and_then(|mut o| {
             if condition(o.get_mut()) {
@@ -631,8 +622,7 @@ where
         condition: F,
     ) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         if let Some(mut occupied_entry) = self.get_async(key).await {
             if condition(occupied_entry.get_mut()) {
@@ -1060,7 +1050,7 @@ where
         while current_array.has_old_array() {
             let mut async_wait = AsyncWait::default();
             let mut async_wait_pinned = Pin::new(&mut async_wait);
-            if self.incremental_rehash::<_, _, false>(
+            if self.incremental_rehash::<_, false>(
                 current_array,
                 &mut async_wait_pinned,
                 &Guard::new(),
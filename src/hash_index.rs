@@ -5,7 +5,8 @@ use super::hash_table::bucket::{Bucket, EntryPtr, Locker, OPTIMISTIC};
 use super::hash_table::bucket_array::BucketArray;
 use super::hash_table::{HashTable, LockedEntry};
 use super::wait_queue::AsyncWait;
-use std::borrow::Borrow;
+use super::Equivalent;
+use crate::atomic::AtomicUsize;
 use std::collections::hash_map::RandomState;
 use std::fmt::{self, Debug};
 use std::hash::{BuildHasher, Hash};
@@ -14,7 +15,6 @@ use std::ops::{Deref, RangeInclusive};
 use std::panic::UnwindSafe;
 use std::pin::Pin;
 use std::ptr;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::{Acquire, Relaxed};
 
 /// Scalable concurrent hash index.
@@ -467,8 +467,7 @@ where
     #[inline]
     pub fn remove<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.remove_if(key, |_| true)
     }
@@ -493,8 +492,7 @@ where
     #[inline]
     pub async fn remove_async<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.remove_if_async(key, |_| true).await
     }
@@ -521,8 +519,7 @@ where
     #[inline]
     pub fn remove_if<Q, F: FnOnce(&V) -> bool>(&self, key: &Q, condition: F) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.remove_entry(
             key,
@@ -556,8 +553,7 @@ where
     #[inline]
     pub async fn remove_if_async<Q, F: FnOnce(&V) -> bool>(&self, key: &Q, condition: F) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let hash = self.hash(key);
         let mut condition = |v: &mut V| condition(v);
@@ -597,8 +593,7 @@ where
     #[inline]
     pub fn get<Q>(&self, key: &Q) -> Option<OccupiedEntry<K, V, H>>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let guard = Guard::new();
         let locked_entry = self
@@ -633,8 +628,7 @@ where
     #[inline]
     pub async fn get_async<Q>(&self, key: &Q) -> Option<OccupiedEntry<K, V, H>>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let hash = self.hash(key);
         loop {
@@ -682,8 +676,7 @@ where
     #[inline]
     pub fn peek<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<&'g V>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.read_entry(key, self.hash(key), &mut (), guard)
             .ok()
@@ -711,8 +704,7 @@ where
     #[inline]
     pub fn peek_with<Q, R, F: FnOnce(&K, &V) -> R>(&self, key: &Q, reader: F) -> Option<R>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let guard = Guard::new();
         self.read_entry(key, self.hash(key), &mut (), &guard)
@@ -737,8 +729,7 @@ where
     #[inline]
     pub fn contains<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.peek_with(key, |_, _| ()).is_some()
     }
@@ -971,8 +962,7 @@ where
     #[inline]
     pub fn bucket_index<Q>(&self, key: &Q) -> usize
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.calculate_bucket_index(key)
     }
@@ -1026,7 +1016,7 @@ where
         while current_array.has_old_array() {
             let mut async_wait = AsyncWait::default();
             let mut async_wait_pinned = Pin::new(&mut async_wait);
-            if self.incremental_rehash::<_, _, false>(
+            if self.incremental_rehash::<_, false>(
                 current_array,
                 &mut async_wait_pinned,
                 &Guard::new(),
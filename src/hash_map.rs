@@ -5,14 +5,14 @@ use super::hash_table::bucket::{EntryPtr, Locker, Reader, SEQUENTIAL};
 use super::hash_table::bucket_array::BucketArray;
 use super::hash_table::{HashTable, LockedEntry};
 use super::wait_queue::AsyncWait;
-use std::borrow::Borrow;
+use super::Equivalent;
+use crate::atomic::AtomicUsize;
 use std::collections::hash_map::RandomState;
 use std::fmt::{self, Debug};
 use std::hash::{BuildHasher, Hash};
 use std::mem::replace;
 use std::ops::{Deref, RangeInclusive};
 use std::pin::Pin;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::{Acquire, Relaxed};
 
 /// Scalable concurrent hash map.
@@ -30,6 +30,8 @@ use std::sync::atomic::Ordering::{Acquire, Relaxed};
 /// * Incremental resizing: entries in the old bucket array are incrementally relocated.
 /// * No busy waiting: no spin-locks or hot loops to wait for desired resources.
 /// * Linearizability: [`HashMap`] manipulation methods are linearizable.
+/// * Flexible lookups: keys can be looked up by any type implementing
+///   [`Equivalent`], e.g., a borrowed view of a composite key.
 ///
 /// ## The key statistics for [`HashMap`]
 ///
@@ -458,8 +460,7 @@ where
     #[inline]
     pub fn update<Q, U, R>(&self, key: &Q, updater: U) -> Option<R>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
         U: FnOnce(&K, &mut V) -> R,
     {
         let guard = Guard::new();
@@ -494,8 +495,7 @@ where
     #[inline]
     pub async fn update_async<Q, U, R>(&self, key: &Q, updater: U) -> Option<R>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
         U: FnOnce(&K, &mut V) -> R,
     {
         let hash = self.hash(key);
@@ -538,8 +538,7 @@ where
     #[inline]
     pub fn remove<Q>(&self, key: &Q) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.remove_if(key, |_| true)
     }
@@ -561,8 +560,7 @@ where
     #[inline]
     pub async fn remove_async<Q>(&self, key: &Q) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.remove_if_async(key, |_| true).await
     }
@@ -586,8 +584,7 @@ where
     #[inline]
     pub fn remove_if<Q, F: FnOnce(&mut V) -> bool>(&self, key: &Q, condition: F) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.remove_entry(
             key,
@@ -622,8 +619,7 @@ where
         mut condition: F,
     ) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let hash = self.hash(key);
         loop {
@@ -662,8 +658,7 @@ where
     #[inline]
     pub fn get<Q>(&self, key: &Q) -> Option<OccupiedEntry<K, V, H>>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let guard = Guard::new();
         let locked_entry = self
@@ -698,8 +693,7 @@ where
     #[inline]
     pub async fn get_async<Q>(&self, key: &Q) -> Option<OccupiedEntry<K, V, H>>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let hash = self.hash(key);
         loop {
@@ -741,8 +735,7 @@ where
     #[inline]
     pub fn read<Q, R, F: FnOnce(&K, &V) -> R>(&self, key: &Q, reader: F) -> Option<R>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.read_entry(key, self.hash(key), &mut (), &Guard::new())
             .ok()
@@ -767,8 +760,7 @@ where
     #[inline]
     pub async fn read_async<Q, R, F: FnOnce(&K, &V) -> R>(&self, key: &Q, reader: F) -> Option<R>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         let hash = self.hash(key);
         loop {
@@ -797,8 +789,7 @@ where
     #[inline]
     pub fn contains<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.read(key, |_, _| ()).is_some()
     }
@@ -819,8 +810,7 @@ where
     #[inline]
     pub async fn contains_async<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.read_async(key, |_, _| ()).await.is_some()
     }
@@ -1302,8 +1292,7 @@ where
     #[inline]
     pub fn bucket_index<Q>(&self, key: &Q) -> usize
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
         self.calculate_bucket_index(key)
     }
@@ -1313,7 +1302,7 @@ where
         while current_array.has_old_array() {
             let mut async_wait = AsyncWait::default();
             let mut async_wait_pinned = Pin::new(&mut async_wait);
-            if self.incremental_rehash::<_, _, false>(
+            if self.incremental_rehash::<_, false>(
                 current_array,
                 &mut async_wait_pinned,
                 &Guard::new(),
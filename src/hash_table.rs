@@ -1,16 +1,17 @@
 pub mod bucket;
 pub mod bucket_array;
 
+use crate::atomic::AtomicUsize;
 use crate::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
 use crate::exit_guard::ExitGuard;
 use crate::wait_queue::{AsyncWait, DeriveAsyncWait};
+use crate::Equivalent;
 use bucket::{DataBlock, EntryPtr, Locker, LruList, Reader, BUCKET_LEN, CACHE, OPTIMISTIC};
 use bucket_array::BucketArray;
-use std::borrow::Borrow;
 use std::hash::{BuildHasher, Hash, Hasher};
 use std::pin::Pin;
+use std::sync::atomic::fence;
 use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
-use std::sync::atomic::{fence, AtomicUsize};
 
 /// `HashTable` defines common functions for hash table implementations.
 pub(super) trait HashTable<K, V, H, L: LruList, const TYPE: char>
@@ -23,7 +24,6 @@ where
     #[inline]
     fn hash<Q>(&self, key: &Q) -> u64
     where
-        K: Borrow<Q>,
         Q: Hash + ?Sized,
     {
         let mut hasher = self.hasher().build_hasher();
@@ -46,7 +46,6 @@ where
     #[inline]
     fn calculate_bucket_index<Q>(&self, key: &Q) -> usize
     where
-        K: Borrow<Q>,
         Q: Hash + ?Sized,
     {
         self.bucket_array()
@@ -289,15 +288,14 @@ where
         guard: &'g Guard,
     ) -> Result<Option<(&'g K, &'g V)>, ()>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
         D: DeriveAsyncWait,
     {
         let mut current_array_ptr = self.bucket_array().load(Acquire, guard);
         while let Some(current_array) = current_array_ptr.as_ref() {
             if let Some(old_array) = current_array.old_array(guard).as_ref() {
                 if TYPE == OPTIMISTIC {
-                    if self.incremental_rehash::<Q, D, true>(current_array, async_wait, guard)
+                    if self.incremental_rehash::<D, true>(current_array, async_wait, guard)
                         != Ok(true)
                     {
                         let index = old_array.calculate_bucket_index(hash);
@@ -368,8 +366,7 @@ where
         guard: &'g Guard,
     ) -> Result<Option<LockedEntry<'g, K, V, L, TYPE>>, ()>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
         D: DeriveAsyncWait,
     {
         let mut current_array_ptr = self.bucket_array().load(Acquire, guard);
@@ -429,8 +426,7 @@ where
         guard: &Guard,
     ) -> Result<R, F>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
         D: DeriveAsyncWait,
     {
         while let Some(current_array) = self.bucket_array().load(Acquire, guard).as_ref() {
@@ -593,8 +589,7 @@ where
         guard: &'g Guard,
     ) -> Result<LockedEntry<'g, K, V, L, TYPE>, ()>
     where
-        K: Borrow<Q>,
-        Q: Hash + Eq + ?Sized,
+        Q: Equivalent<K> + Hash + ?Sized,
         D: DeriveAsyncWait,
     {
         // It is guaranteed that the thread reads a consistent snapshot of the current and old
@@ -659,20 +654,15 @@ where
     ///
     /// Returns `true` if no old array is attached to the current one.
     #[inline]
-    fn move_entry<Q, D>(
+    fn move_entry<D: DeriveAsyncWait>(
         &self,
         current_array: &BucketArray<K, V, L, TYPE>,
         old_array: &BucketArray<K, V, L, TYPE>,
         hash: u64,
         async_wait: &mut D,
         guard: &Guard,
-    ) -> Result<bool, ()>
-    where
-        K: Borrow<Q>,
-        Q: Hash + Eq + ?Sized,
-        D: DeriveAsyncWait,
-    {
-        if !self.incremental_rehash::<Q, D, false>(current_array, async_wait, guard)? {
+    ) -> Result<bool, ()> {
+        if !self.incremental_rehash::<D, false>(current_array, async_wait, guard)? {
             let index = old_array.calculate_bucket_index(hash);
             let bucket = old_array.bucket_mut(index);
             let lock_result = if let Some(async_wait) = async_wait.derive() {
@@ -681,7 +671,7 @@ where
                 Locker::lock(bucket, guard)
             };
             if let Some(mut locker) = lock_result {
-                self.relocate_bucket::<Q, _, false>(
+                self.relocate_bucket::<_, false>(
                     current_array,
                     old_array,
                     index,
@@ -699,7 +689,7 @@ where
     ///
     /// Returns an error if locking failed.
     #[inline]
-    fn relocate_bucket<Q, D, const TRY_LOCK: bool>(
+    fn relocate_bucket<D, const TRY_LOCK: bool>(
         &self,
         current_array: &BucketArray<K, V, L, TYPE>,
         old_array: &BucketArray<K, V, L, TYPE>,
@@ -709,8 +699,6 @@ where
         guard: &Guard,
     ) -> Result<(), ()>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
         D: DeriveAsyncWait,
     {
         debug_assert!(!old_locker.killed());
@@ -734,12 +722,12 @@ where
                 let (new_index, partial_hash) =
                     if old_array.num_buckets() >= current_array.num_buckets() {
                         debug_assert_eq!(
-                            current_array.calculate_bucket_index(self.hash(old_entry.0.borrow())),
+                            current_array.calculate_bucket_index(self.hash(&old_entry.0)),
                             target_index
                         );
                         (target_index, entry_ptr.partial_hash(&*old_locker))
                     } else {
-                        let hash = self.hash(old_entry.0.borrow());
+                        let hash = self.hash(&old_entry.0);
                         let new_index = current_array.calculate_bucket_index(hash);
                         debug_assert!(
                             new_index - target_index
@@ -801,7 +789,7 @@ where
     /// Clears the old array.
     fn clear_old_array(&self, current_array: &BucketArray<K, V, L, TYPE>, guard: &Guard) {
         while current_array.has_old_array() {
-            if self.incremental_rehash::<_, _, false>(current_array, &mut (), guard) == Ok(true) {
+            if self.incremental_rehash::<_, false>(current_array, &mut (), guard) == Ok(true) {
                 break;
             }
         }
@@ -810,15 +798,13 @@ where
     /// Relocates a fixed number of buckets from the old array to the current array.
     ///
     /// Returns `true` if `old_array` is null.
-    fn incremental_rehash<Q, D, const TRY_LOCK: bool>(
+    fn incremental_rehash<D, const TRY_LOCK: bool>(
         &self,
         current_array: &BucketArray<K, V, L, TYPE>,
         async_wait: &mut D,
         guard: &Guard,
     ) -> Result<bool, ()>
     where
-        K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
         D: DeriveAsyncWait,
     {
         if let Some(old_array) = current_array.old_array(guard).as_ref() {
@@ -888,7 +874,7 @@ where
                     Locker::lock(old_bucket, guard)
                 };
                 if let Some(mut locker) = lock_result {
-                    self.relocate_bucket::<_, _, TRY_LOCK>(
+                    self.relocate_bucket::<_, TRY_LOCK>(
                         current_array,
                         old_array,
                         index,
@@ -1128,7 +1114,7 @@ impl<'h, K: Eq + Hash + 'h, V: 'h, L: LruList, const TYPE: char> LockedEntry<'h,
             while current_array.has_old_array() {
                 let mut async_wait = AsyncWait::default();
                 let mut async_wait_pinned = Pin::new(&mut async_wait);
-                if hash_table.incremental_rehash::<_, _, false>(
+                if hash_table.incremental_rehash::<_, false>(
                     current_array.as_ref(),
                     &mut async_wait_pinned,
                     &Guard::new(),
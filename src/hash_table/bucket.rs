@@ -1,13 +1,14 @@
+use crate::atomic::AtomicPtr;
+use crate::atomic::AtomicU32;
 use crate::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
 use crate::wait_queue::{AsyncWait, WaitQueue};
-use std::borrow::Borrow;
+use crate::Equivalent;
 use std::fmt::{self, Debug};
 use std::mem::{needs_drop, MaybeUninit};
 use std::ops::{Deref, DerefMut};
 use std::ptr;
-use std::sync::atomic::AtomicPtr;
+use std::sync::atomic::fence;
 use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
-use std::sync::atomic::{fence, AtomicU32};
 
 /// [`Bucket`] is a fixed-size hash table with linear probing.
 ///
@@ -155,8 +156,7 @@ impl<K: Eq, V, L: LruList, const TYPE: char> Bucket<K, V, L, TYPE> {
         guard: &'g Guard,
     ) -> Option<&'g (K, V)>
     where
-        K: Borrow<Q>,
-        Q: Eq + ?Sized,
+        Q: Equivalent<K> + ?Sized,
     {
         if self.num_entries == 0 {
             return None;
@@ -226,8 +226,7 @@ impl<K: Eq, V, L: LruList, const TYPE: char> Bucket<K, V, L, TYPE> {
         guard: &'g Guard,
     ) -> EntryPtr<'g, K, V, TYPE>
     where
-        K: Borrow<Q>,
-        Q: Eq + ?Sized,
+        Q: Equivalent<K> + ?Sized,
     {
         if self.num_entries == 0 {
             return EntryPtr::new(guard);
@@ -265,8 +264,7 @@ impl<K: Eq, V, L: LruList, const TYPE: char> Bucket<K, V, L, TYPE> {
         partial_hash: u8,
     ) -> Option<(usize, &'g (K, V))>
     where
-        K: Borrow<Q>,
-        Q: Eq + ?Sized,
+        Q: Equivalent<K> + ?Sized,
     {
         let mut bitmap = if TYPE == OPTIMISTIC {
             metadata.occupied_bitmap & (!metadata.removed_bitmap_or_lru_tail)
@@ -289,7 +287,7 @@ impl<K: Eq, V, L: LruList, const TYPE: char> Bucket<K, V, L, TYPE> {
         let mut offset = bitmap.trailing_zeros();
         while offset != u32::BITS {
             let entry_ref = unsafe { &(*data_block[offset as usize].as_ptr()) };
-            if entry_ref.0.borrow() == key {
+            if key.equivalent(&entry_ref.0) {
                 return Some((offset as usize, entry_ref));
             }
             bitmap -= 1_u32 << offset;
@@ -323,14 +321,10 @@ impl<K: Eq, V, L: LruList, const TYPE: char> Bucket<K, V, L, TYPE> {
     /// Searches for the next closest occupied entry slot number from the current one in the bitmap.
     ///
     /// If the specified slot is occupied and reachable, just returns its index number.
-    fn next_entry<Q, const LEN: usize>(
+    fn next_entry<const LEN: usize>(
         metadata: &Metadata<K, V, LEN>,
         current_index: usize,
-    ) -> Option<usize>
-    where
-        K: Borrow<Q>,
-        Q: Eq + ?Sized,
-    {
+    ) -> Option<usize> {
         if current_index >= LEN {
             return None;
         }
@@ -611,8 +605,7 @@ impl<'g, K: Eq, V, L: LruList, const TYPE: char> Locker<'g, K, V, L, TYPE> {
         guard: &'g Guard,
     ) -> EntryPtr<'g, K, V, TYPE>
     where
-        K: Borrow<Q>,
-        Q: Eq + ?Sized,
+        Q: Equivalent<K> + ?Sized,
     {
         self.bucket.get(data_block, key, partial_hash, guard)
     }
@@ -1032,7 +1025,7 @@ impl<'g, K: Eq, V, L: LruList, const TYPE: char> Reader<'g, K, V, L, TYPE> {
             let next = (current - 1) & !(WAITING);
             match bucket
                 .state
-                .compare_exchange_weak(current, next, Relaxed, Relaxed)
+                .compare_exchange_weak(current, next, Release, Relaxed)
             {
                 Ok(_) => {
                     if wakeup {
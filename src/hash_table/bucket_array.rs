@@ -1,8 +1,8 @@
 use super::bucket::{Bucket, DataBlock, LruList, BUCKET_LEN, OPTIMISTIC};
+use crate::atomic::AtomicUsize;
 use crate::ebr::{AtomicShared, Guard, Ptr, Tag};
 use std::alloc::{alloc, alloc_zeroed, dealloc, Layout};
 use std::mem::{align_of, needs_drop, size_of};
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::Relaxed;
 
 /// [`BucketArray`] is a special purpose array to manage [`Bucket`] and [`DataBlock`].
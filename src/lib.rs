@@ -14,9 +14,14 @@ pub mod hash_cache;
 pub use hash_cache::HashCache;
 
 mod linked_list;
+pub use linked_list::Cursor as LinkedCursor;
 pub use linked_list::Entry as LinkedEntry;
+pub use linked_list::EntryPool as LinkedEntryPool;
 pub use linked_list::LinkedList;
 
+pub mod list;
+pub use list::List;
+
 pub mod bag;
 pub use bag::Bag;
 
@@ -26,11 +31,19 @@ pub use queue::Queue;
 pub mod stack;
 pub use stack::Stack;
 
+pub mod channel;
+
 pub mod tree_index;
 pub use tree_index::TreeIndex;
 
+pub mod tree_multi_index;
+pub use tree_multi_index::TreeMultiIndex;
+
 pub mod ebr;
 
+pub use equivalent::{Comparable, Equivalent};
+
+mod atomic;
 mod exit_guard;
 mod hash_table;
 mod wait_queue;
@@ -38,5 +51,18 @@ mod wait_queue;
 #[cfg(feature = "serde")]
 mod serde;
 
+#[cfg(not(any(
+    feature = "portable-atomic",
+    all(
+        target_has_atomic = "8",
+        target_has_atomic = "32",
+        target_has_atomic = "ptr"
+    )
+)))]
+compile_error!(
+    "scc requires 8-bit, 32-bit, and pointer-sized atomic compare-and-swap operations; enable the \
+    `portable-atomic` feature on targets without them"
+);
+
 #[cfg(test)]
 mod tests;
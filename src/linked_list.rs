@@ -1,7 +1,8 @@
 use super::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
+use super::Bag;
 use std::fmt::{self, Debug, Display};
 use std::ops::{Deref, DerefMut};
-use std::sync::atomic::Ordering::{self, Relaxed, Release};
+use std::sync::atomic::Ordering::{self, Acquire, Relaxed, Release};
 
 /// [`LinkedList`] is a type trait implementing a lock-free singly linked list.
 pub trait LinkedList: Sized {
@@ -391,6 +392,341 @@ pub trait LinkedList: Sized {
 
         next_valid_ptr
     }
+
+    /// Returns a [`Cursor`] pointing to the closest next valid entry of `self`.
+    ///
+    /// `self` is regarded as the head of the linked list, and the [`Cursor`] never points to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedList;
+    /// use scc::ebr::{AtomicShared, Guard, Shared};
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// #[derive(Default)]
+    /// struct L(AtomicShared<L>, usize);
+    /// impl LinkedList for L {
+    ///     fn link_ref(&self) -> &AtomicShared<L> {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let guard = Guard::new();
+    ///
+    /// let head: L = L::default();
+    /// assert!(
+    ///     head.push_back(Shared::new(L(AtomicShared::null(), 1)), false, Relaxed, &guard).is_ok());
+    ///
+    /// let cursor = head.cursor(&guard);
+    /// assert_eq!(cursor.get().unwrap().1, 1);
+    /// ```
+    #[inline]
+    fn cursor<'g>(&'g self, guard: &'g Guard) -> Cursor<'g, Self> {
+        Cursor {
+            head: self,
+            prev: self,
+            current: self.next_ptr(Acquire, guard),
+            guard,
+        }
+    }
+}
+
+/// [`Cursor`] points to an entry in a [`LinkedList`], and allows entries to be removed or
+/// inserted relative to it.
+///
+/// [`Cursor`] remembers the entry that it moved from in order to unlink removed entries and to
+/// insert new entries before the current one; the entry is looked up again from the head if it
+/// has been deleted by another thread, therefore other threads may concurrently modify the same
+/// linked list.
+pub struct Cursor<'g, L: LinkedList> {
+    /// The head of the linked list.
+    head: &'g L,
+
+    /// The entry that the cursor moved from, or the head of the linked list.
+    prev: &'g L,
+
+    /// The current entry, or null if the cursor has passed the end of the linked list.
+    current: Ptr<'g, L>,
+
+    /// The [`Guard`] protecting the entries.
+    guard: &'g Guard,
+}
+
+impl<'g, L: LinkedList> Cursor<'g, L> {
+    /// Returns a reference to the current entry.
+    ///
+    /// Returns `None` if the cursor has passed the end of the linked list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedList;
+    /// use scc::ebr::{AtomicShared, Guard, Shared};
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// #[derive(Default)]
+    /// struct L(AtomicShared<L>, usize);
+    /// impl LinkedList for L {
+    ///     fn link_ref(&self) -> &AtomicShared<L> {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let guard = Guard::new();
+    ///
+    /// let head: L = L::default();
+    /// assert!(head.cursor(&guard).get().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'g L> {
+        self.current.as_ref()
+    }
+
+    /// Moves the cursor to the closest next valid entry, and returns a reference to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedList;
+    /// use scc::ebr::{AtomicShared, Guard, Shared};
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// #[derive(Default)]
+    /// struct L(AtomicShared<L>, usize);
+    /// impl LinkedList for L {
+    ///     fn link_ref(&self) -> &AtomicShared<L> {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let guard = Guard::new();
+    ///
+    /// let head: L = L::default();
+    /// assert!(
+    ///     head.push_back(Shared::new(L(AtomicShared::null(), 2)), false, Relaxed, &guard).is_ok());
+    /// assert!(
+    ///     head.push_back(Shared::new(L(AtomicShared::null(), 1)), false, Relaxed, &guard).is_ok());
+    ///
+    /// let mut cursor = head.cursor(&guard);
+    /// assert_eq!(cursor.get().unwrap().1, 1);
+    /// assert_eq!(cursor.move_next().unwrap().1, 2);
+    /// assert!(cursor.move_next().is_none());
+    /// assert!(cursor.move_next().is_none());
+    /// ```
+    #[inline]
+    pub fn move_next(&mut self) -> Option<&'g L> {
+        if let Some(current) = self.current.as_ref() {
+            self.prev = current;
+            self.current = current.next_ptr(Acquire, self.guard);
+        }
+        self.current.as_ref()
+    }
+
+    /// Removes the current entry, and moves the cursor to the closest next valid entry.
+    ///
+    /// Returns `false` if there is no current entry or the current entry has already been
+    /// deleted by another thread; the cursor is moved forward in both cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedList;
+    /// use scc::ebr::{AtomicShared, Guard, Shared};
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// #[derive(Default)]
+    /// struct L(AtomicShared<L>, usize);
+    /// impl LinkedList for L {
+    ///     fn link_ref(&self) -> &AtomicShared<L> {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let guard = Guard::new();
+    ///
+    /// let head: L = L::default();
+    /// assert!(
+    ///     head.push_back(Shared::new(L(AtomicShared::null(), 2)), false, Relaxed, &guard).is_ok());
+    /// assert!(
+    ///     head.push_back(Shared::new(L(AtomicShared::null(), 1)), false, Relaxed, &guard).is_ok());
+    ///
+    /// let mut cursor = head.cursor(&guard);
+    /// assert!(cursor.remove());
+    /// assert_eq!(cursor.get().unwrap().1, 2);
+    /// assert_eq!(head.next_ptr(Relaxed, &guard).as_ref().unwrap().1, 2);
+    /// ```
+    #[inline]
+    pub fn remove(&mut self) -> bool {
+        let Some(current) = self.current.as_ref() else {
+            return false;
+        };
+        let removed = current.delete_self(Release);
+        self.current = self.prev.next_ptr(Acquire, self.guard);
+        removed
+    }
+
+    /// Inserts the given entry right after the current entry, and returns a pointer to it.
+    ///
+    /// The cursor stays at the current entry, and any mark on the current entry is preserved.
+    ///
+    /// # Errors
+    ///
+    /// Returns the supplied [`Shared`] if there is no current entry or the current entry has been
+    /// deleted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedList;
+    /// use scc::ebr::{AtomicShared, Guard, Shared};
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// #[derive(Default)]
+    /// struct L(AtomicShared<L>, usize);
+    /// impl LinkedList for L {
+    ///     fn link_ref(&self) -> &AtomicShared<L> {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let guard = Guard::new();
+    ///
+    /// let head: L = L::default();
+    /// assert!(head.cursor(&guard).insert_after(Shared::new(L::default())).is_err());
+    /// assert!(
+    ///     head.push_back(Shared::new(L(AtomicShared::null(), 1)), false, Relaxed, &guard).is_ok());
+    ///
+    /// let mut cursor = head.cursor(&guard);
+    /// assert!(cursor.insert_after(Shared::new(L(AtomicShared::null(), 2))).is_ok());
+    /// assert_eq!(cursor.get().unwrap().1, 1);
+    /// assert_eq!(cursor.move_next().unwrap().1, 2);
+    /// ```
+    #[inline]
+    pub fn insert_after(&self, entry: Shared<L>) -> Result<Ptr<'g, L>, Shared<L>> {
+        match self.current.as_ref() {
+            Some(current) => link_after(current, entry, self.guard),
+            None => Err(entry),
+        }
+    }
+
+    /// Inserts the given entry before the current entry, and returns a pointer to it.
+    ///
+    /// If the cursor has passed the end of the linked list, the new entry is appended to the last
+    /// entry. The cursor stays at the current entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns the supplied [`Shared`] if the current entry has been unlinked from the linked list
+    /// by another thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedList;
+    /// use scc::ebr::{AtomicShared, Guard, Shared};
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// #[derive(Default)]
+    /// struct L(AtomicShared<L>, usize);
+    /// impl LinkedList for L {
+    ///     fn link_ref(&self) -> &AtomicShared<L> {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let guard = Guard::new();
+    ///
+    /// let head: L = L::default();
+    /// assert!(
+    ///     head.push_back(Shared::new(L(AtomicShared::null(), 2)), false, Relaxed, &guard).is_ok());
+    ///
+    /// let mut cursor = head.cursor(&guard);
+    /// assert!(cursor.insert_before(Shared::new(L(AtomicShared::null(), 1))).is_ok());
+    /// assert_eq!(cursor.get().unwrap().1, 2);
+    /// assert!(cursor.move_next().is_none());
+    /// assert!(cursor.insert_before(Shared::new(L(AtomicShared::null(), 3))).is_ok());
+    ///
+    /// let mut cursor = head.cursor(&guard);
+    /// assert_eq!(cursor.get().unwrap().1, 1);
+    /// assert_eq!(cursor.move_next().unwrap().1, 2);
+    /// assert_eq!(cursor.move_next().unwrap().1, 3);
+    /// ```
+    #[inline]
+    pub fn insert_before(&mut self, mut entry: Shared<L>) -> Result<Ptr<'g, L>, Shared<L>> {
+        loop {
+            match link_after(self.prev, entry, self.guard) {
+                Ok(inserted) => {
+                    if let Some(new_prev) = inserted.as_ref() {
+                        self.prev = new_prev;
+                    }
+                    return Ok(inserted);
+                }
+                Err(returned) => {
+                    // The entry that the cursor moved from has been deleted.
+                    let Some(prev) = self.find_prev() else {
+                        return Err(returned);
+                    };
+                    self.prev = prev;
+                    entry = returned;
+                }
+            }
+        }
+    }
+
+    /// Finds the closest valid entry that links to the current entry from the head.
+    fn find_prev(&self) -> Option<&'g L> {
+        let mut prev = self.head;
+        loop {
+            let next_ptr = prev.next_ptr(Acquire, self.guard);
+            if next_ptr.without_tag() == self.current.without_tag() {
+                return Some(prev);
+            }
+            prev = next_ptr.as_ref()?;
+        }
+    }
+}
+
+impl<L: LinkedList + Debug> Debug for Cursor<'_, L> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("current", &self.get())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Links the given entry right after `prev` while keeping the mark on `prev`.
+fn link_after<'g, L: LinkedList>(
+    prev: &L,
+    mut entry: Shared<L>,
+    guard: &'g Guard,
+) -> Result<Ptr<'g, L>, Shared<L>> {
+    let mut next_ptr = prev.link_ref().load(Relaxed, guard);
+    loop {
+        let tag = next_ptr.tag();
+        if tag == Tag::Second || tag == Tag::Both {
+            // `prev` has been deleted.
+            return Err(entry);
+        }
+        entry
+            .link_ref()
+            .swap((next_ptr.get_shared(), Tag::None), Relaxed);
+        match prev.link_ref().compare_exchange_weak(
+            next_ptr,
+            (Some(entry), tag),
+            Release,
+            Relaxed,
+            guard,
+        ) {
+            Ok((_, updated)) => return Ok(updated),
+            Err((passed, actual)) => {
+                entry = unsafe { passed.unwrap_unchecked() };
+                next_ptr = actual;
+            }
+        }
+    }
 }
 
 /// [`Entry`] stores an instance of `T` and a link to the next entry.
@@ -437,6 +773,17 @@ impl<T> Entry<T> {
         }
     }
 
+    /// Creates an [`Entry`] without an instance that serves as the head of a linked list.
+    ///
+    /// The [`Entry`] must never be dereferenced.
+    #[inline]
+    pub(super) fn head() -> Self {
+        Self {
+            instance: None,
+            next: AtomicShared::default(),
+        }
+    }
+
     /// Returns a reference to `next`.
     #[inline]
     pub(super) fn next(&self) -> &AtomicShared<Self> {
@@ -530,3 +877,184 @@ impl<T: PartialEq> PartialEq for Entry<T> {
         self.instance == other.instance
     }
 }
+
+/// [`EntryPool`] holds pre-allocated vacant [`Entry`] instances.
+///
+/// [`Queue::push_pooled`](crate::Queue::push_pooled) and
+/// [`Stack::push_pooled`](crate::Stack::push_pooled) take a vacant [`Entry`] from an [`EntryPool`]
+/// instead of allocating memory, therefore no memory is allocated when pushing an instance as
+/// long as the [`EntryPool`] is not empty. Popped entries can be returned to the [`EntryPool`] by
+/// [`EntryPool::recycle`], and the [`EntryPool`] can be refilled by [`EntryPool::reserve`].
+pub struct EntryPool<T> {
+    /// Vacant entries.
+    entries: Bag<Shared<Entry<T>>>,
+
+    /// Recycled entries that may still be referenced by other threads.
+    recycled: Bag<Shared<Entry<T>>>,
+}
+
+impl<T: 'static> EntryPool<T> {
+    /// Creates an [`EntryPool`] holding `capacity` vacant entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedEntryPool;
+    ///
+    /// let pool: LinkedEntryPool<usize> = LinkedEntryPool::new(16);
+    /// assert_eq!(pool.len(), 16);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let pool = Self {
+            entries: Bag::default(),
+            recycled: Bag::default(),
+        };
+        pool.reserve(capacity);
+        pool
+    }
+
+    /// Allocates `additional` vacant entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedEntryPool;
+    ///
+    /// let pool: LinkedEntryPool<usize> = LinkedEntryPool::new(1);
+    ///
+    /// pool.reserve(3);
+    /// assert_eq!(pool.len(), 4);
+    /// ```
+    #[inline]
+    pub fn reserve(&self, additional: usize) {
+        for _ in 0..additional {
+            self.entries.push(Shared::new(Entry::head()));
+        }
+    }
+
+    /// Returns a popped entry to the [`EntryPool`].
+    ///
+    /// The entry becomes vacant once no other thread can read it, and the instance in it is
+    /// dropped when the entry is taken again. Entries that are still referenced by a container or
+    /// a [`Shared`] are not reused until the references are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::{LinkedEntryPool, Queue};
+    ///
+    /// let pool: LinkedEntryPool<usize> = LinkedEntryPool::new(1);
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// assert!(queue.push_pooled(11, &pool).is_ok());
+    /// let entry = queue.pop().unwrap();
+    /// assert_eq!(**entry, 11);
+    ///
+    /// pool.recycle(entry);
+    /// assert_eq!(pool.len(), 1);
+    /// ```
+    #[inline]
+    pub fn recycle(&self, entry: Shared<Entry<T>>) {
+        self.recycled.push(entry);
+    }
+}
+
+impl<T> EntryPool<T> {
+    /// Returns the number of vacant and recycled entries in the [`EntryPool`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedEntryPool;
+    /// use scc::Queue;
+    ///
+    /// let pool: LinkedEntryPool<usize> = LinkedEntryPool::new(2);
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// assert!(queue.push_pooled(11, &pool).is_ok());
+    /// assert_eq!(pool.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.recycled.len()
+    }
+
+    /// Returns `true` if the [`EntryPool`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedEntryPool;
+    ///
+    /// let pool: LinkedEntryPool<usize> = LinkedEntryPool::default();
+    /// assert!(pool.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.recycled.is_empty()
+    }
+
+    /// Takes a vacant entry, and moves the supplied instance into it.
+    ///
+    /// Returns the supplied instance if there is no vacant entry in the [`EntryPool`].
+    pub(crate) fn take(&self, val: T) -> Result<Shared<Entry<T>>, T> {
+        let Some(mut entry) = self.entries.pop().or_else(|| self.reclaim()) else {
+            return Err(val);
+        };
+        // Vacant entries are only referenced by the `EntryPool`.
+        let entry_mut = unsafe { entry.get_mut().unwrap_unchecked() };
+        entry_mut.instance.replace(val);
+        Ok(entry)
+    }
+
+    /// Moves recycled entries that no other thread can read to the vacant entry list, and returns
+    /// one of them.
+    fn reclaim(&self) -> Option<Shared<Entry<T>>> {
+        let mut reclaimed = None;
+        self.recycled.pop_all((), |(), mut entry| {
+            if let Some(entry_mut) = entry.try_get_mut() {
+                entry_mut.instance.take();
+                entry_mut.next.swap((None, Tag::None), Relaxed);
+                if reclaimed.is_none() {
+                    reclaimed.replace(entry);
+                } else {
+                    self.entries.push(entry);
+                }
+            } else {
+                self.recycled.push(entry);
+            }
+        });
+        reclaimed
+    }
+}
+
+impl<T> Debug for EntryPool<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntryPool")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<T> Default for EntryPool<T> {
+    /// Creates an empty [`EntryPool`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::LinkedEntryPool;
+    ///
+    /// let pool: LinkedEntryPool<usize> = LinkedEntryPool::default();
+    /// assert_eq!(pool.len(), 0);
+    /// ```
+    #[inline]
+    fn default() -> Self {
+        Self {
+            entries: Bag::default(),
+            recycled: Bag::default(),
+        }
+    }
+}
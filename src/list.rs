@@ -0,0 +1,815 @@
+//! [`List`] is a concurrent doubly linked list that can be modified through cursors.
+
+use super::atomic::{AtomicPtr, AtomicU8};
+use super::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
+use super::wait_queue::WaitQueue;
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::ops::Deref;
+use std::ptr;
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
+
+/// [`List`] is a concurrent doubly linked list that can be modified through cursors.
+///
+/// Entries are read without locking, and each entry holds a link to the previous entry so that
+/// any entry can be unlinked in `O(1)`; insertions and removals are serialized by the lock of the
+/// [`List`].
+pub struct List<T> {
+    /// `head` is an entry without an instance; its `next` points to the first entry, and its
+    /// `prev` points to the last entry, or itself if the [`List`] is empty.
+    head: Shared<Entry<T>>,
+
+    /// The state of the lock.
+    state: AtomicU8,
+
+    /// Threads waiting for the lock to be released.
+    wait_queue: WaitQueue,
+}
+
+/// [`Entry`] stores an instance of `T` and the links to the adjacent entries in a [`List`].
+pub struct Entry<T> {
+    /// `instance` is `None` only for the head of a [`List`].
+    instance: Option<T>,
+
+    /// `next` points to the next entry.
+    ///
+    /// It is kept after the entry is unlinked so that readers on the entry can move forward.
+    next: AtomicShared<Self>,
+
+    /// `prev` points to the previous entry while the entry is linked, and is null otherwise.
+    ///
+    /// It is only updated while the [`List`] is locked, and it never points to an unlinked entry.
+    prev: AtomicPtr<Self>,
+
+    /// The address of the head of the [`List`] that the entry was created for.
+    owner: usize,
+}
+
+/// [`Cursor`] points to an entry in a [`List`], and can insert or remove entries at its
+/// position.
+///
+/// A [`Cursor`] has a ghost position after the last entry and before the first entry, which is
+/// represented as `None`.
+pub struct Cursor<'l, 'g, T> {
+    list: &'l List<T>,
+    current: Option<&'g Entry<T>>,
+    guard: &'g Guard,
+}
+
+/// An iterator over the entries of a [`List`].
+///
+/// [`Iter`] reads the first entry first.
+pub struct Iter<'g, T> {
+    current: Option<&'g Entry<T>>,
+    guard: &'g Guard,
+}
+
+/// [`Locker`] holds the lock of a [`List`].
+struct Locker<'l, T> {
+    list: &'l List<T>,
+}
+
+/// The [`List`] is locked.
+const LOCK: u8 = 1_u8;
+
+/// There are threads waiting for the lock to be released.
+const WAITING: u8 = 1_u8 << 1;
+
+impl<T: 'static> List<T> {
+    /// Pushes an instance of `T` in front of the first entry.
+    ///
+    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// assert_eq!(**list.push_front(11), 11);
+    /// assert_eq!(**list.push_front(17), 17);
+    /// assert_eq!(list.pop_front().map(|e| **e), Some(17));
+    /// ```
+    #[inline]
+    pub fn push_front(&self, val: T) -> Shared<Entry<T>> {
+        let new_entry = Shared::new(Entry::new(val, &self.head));
+        let guard = Guard::new();
+        let _locker = Locker::lock(self);
+        self.link_after(&self.head, &new_entry, &guard);
+        new_entry
+    }
+
+    /// Pushes an instance of `T` after the last entry.
+    ///
+    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// assert_eq!(**list.push_back(11), 11);
+    /// assert_eq!(**list.push_back(17), 17);
+    /// assert_eq!(list.pop_front().map(|e| **e), Some(11));
+    /// ```
+    #[inline]
+    pub fn push_back(&self, val: T) -> Shared<Entry<T>> {
+        let new_entry = Shared::new(Entry::new(val, &self.head));
+        let guard = Guard::new();
+        let _locker = Locker::lock(self);
+        let last = unsafe { &*self.head.prev.load(Acquire) };
+        self.link_after(last, &new_entry, &guard);
+        new_entry
+    }
+}
+
+impl<T> List<T> {
+    /// Returns a guarded reference to the first entry.
+    ///
+    /// Returns `None` if the [`List`] is empty. The returned reference can survive as long as the
+    /// associated [`Guard`] is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// assert!(list.front(&Guard::new()).is_none());
+    ///
+    /// list.push_back(37);
+    /// list.push_back(3);
+    ///
+    /// assert_eq!(**list.front(&Guard::new()).unwrap(), 37);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn front<'g>(&self, guard: &'g Guard) -> Option<&'g Entry<T>> {
+        self.head.next.load(Acquire, guard).as_ref()
+    }
+
+    /// Returns a guarded reference to the last entry.
+    ///
+    /// Returns `None` if the [`List`] is empty. The returned reference can survive as long as the
+    /// associated [`Guard`] is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// assert!(list.back(&Guard::new()).is_none());
+    ///
+    /// list.push_back(37);
+    /// list.push_back(3);
+    ///
+    /// assert_eq!(**list.back(&Guard::new()).unwrap(), 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn back<'g>(&self, guard: &'g Guard) -> Option<&'g Entry<T>> {
+        self.prev_of(&self.head, guard)
+    }
+
+    /// Pops the first entry.
+    ///
+    /// Returns `None` if the [`List`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// list.push_back(37);
+    /// list.push_back(3);
+    ///
+    /// assert_eq!(list.pop_front().map(|e| **e), Some(37));
+    /// assert_eq!(list.pop_front().map(|e| **e), Some(3));
+    /// assert!(list.pop_front().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn pop_front(&self) -> Option<Shared<Entry<T>>> {
+        let guard = Guard::new();
+        let _locker = Locker::lock(self);
+        let first = self.head.next.load(Acquire, &guard).as_ref()?;
+        self.unlink_locked(first, &guard)
+    }
+
+    /// Pops the last entry.
+    ///
+    /// Returns `None` if the [`List`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// list.push_back(37);
+    /// list.push_back(3);
+    ///
+    /// assert_eq!(list.pop_back().map(|e| **e), Some(3));
+    /// assert_eq!(list.pop_back().map(|e| **e), Some(37));
+    /// assert!(list.pop_back().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn pop_back(&self) -> Option<Shared<Entry<T>>> {
+        let guard = Guard::new();
+        let _locker = Locker::lock(self);
+        let last = self.prev_of(&self.head, &guard)?;
+        self.unlink_locked(last, &guard)
+    }
+
+    /// Unlinks the supplied entry from the [`List`].
+    ///
+    /// The time complexity of the method is `O(1)`. Returns `None` if the entry does not belong
+    /// to the [`List`] or has already been unlinked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    /// let other: List<usize> = List::default();
+    ///
+    /// list.push_back(1);
+    /// let entry = list.push_back(2);
+    /// list.push_back(3);
+    ///
+    /// assert!(other.unlink(&entry).is_none());
+    /// assert_eq!(list.unlink(&entry).map(|e| **e), Some(2));
+    /// assert!(list.unlink(&entry).is_none());
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    #[inline]
+    pub fn unlink(&self, entry: &Entry<T>) -> Option<Shared<Entry<T>>> {
+        if entry.owner != self.head.as_ptr() as usize {
+            return None;
+        }
+        let guard = Guard::new();
+        let _locker = Locker::lock(self);
+        self.unlink_locked(entry, &guard)
+    }
+
+    /// Returns a [`Cursor`] pointing to the ghost position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// list.push_back(1);
+    /// list.push_back(3);
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = list.cursor(&guard);
+    /// assert!(cursor.get().is_none());
+    /// assert_eq!(cursor.move_next().map(|e| **e), Some(1));
+    /// assert!(cursor.insert_after(2).is_ok());
+    /// assert_eq!(cursor.move_next().map(|e| **e), Some(2));
+    /// assert_eq!(cursor.move_next().map(|e| **e), Some(3));
+    /// assert!(cursor.move_next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn cursor<'l, 'g>(&'l self, guard: &'g Guard) -> Cursor<'l, 'g, T> {
+        Cursor {
+            list: self,
+            current: None,
+            guard,
+        }
+    }
+
+    /// Returns the number of entries in the [`List`].
+    ///
+    /// It iterates over all the entries in the [`List`] to count them, therefore its time
+    /// complexity is `O(N)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    /// assert_eq!(list.len(), 0);
+    ///
+    /// list.push_back(7);
+    /// list.push_front(11);
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.iter(&Guard::new()).count()
+    }
+
+    /// Returns `true` if the [`List`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    /// assert!(list.is_empty());
+    ///
+    /// list.push_back(7);
+    /// assert!(!list.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.head.next.is_null(Acquire)
+    }
+
+    /// Returns an [`Iter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// list.push_back(7);
+    /// list.push_back(11);
+    ///
+    /// let guard = Guard::new();
+    /// let mut iter = list.iter(&guard);
+    /// assert_eq!(*iter.next().unwrap(), 7);
+    /// assert_eq!(*iter.next().unwrap(), 11);
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn iter<'g>(&self, guard: &'g Guard) -> Iter<'g, T> {
+        Iter {
+            current: self.head.next.load(Acquire, guard).as_ref(),
+            guard,
+        }
+    }
+
+    /// Returns the entry before the supplied entry.
+    ///
+    /// Returns `None` if the entry is the first entry or has been unlinked.
+    fn prev_of<'g>(&self, entry: &Entry<T>, _guard: &'g Guard) -> Option<&'g Entry<T>> {
+        let prev_ptr = entry.prev.load(Acquire);
+        if prev_ptr.is_null() || ptr::eq(prev_ptr, self.head.as_ptr()) {
+            return None;
+        }
+
+        // `prev` never points to an unlinked entry, and an entry is only retired after being
+        // unlinked, therefore the entry survives as long as the `Guard` is alive.
+        Some(unsafe { &*prev_ptr })
+    }
+
+    /// Returns the `prev` field pointing to the entry before `next_ptr`.
+    fn prev_link<'a>(&'a self, next_ptr: Ptr<'a, Entry<T>>) -> &'a AtomicPtr<Entry<T>> {
+        next_ptr.as_ref().map_or(&self.head.prev, |next| &next.prev)
+    }
+
+    /// Links the new entry after the supplied linked entry.
+    ///
+    /// The [`List`] must be locked.
+    fn link_after(&self, entry: &Entry<T>, new_entry: &Shared<Entry<T>>, guard: &Guard) {
+        let next_ptr = entry.next.load(Acquire, guard);
+        new_entry
+            .next
+            .swap((next_ptr.get_shared(), Tag::None), Relaxed);
+        new_entry
+            .prev
+            .store((entry as *const Entry<T>).cast_mut(), Relaxed);
+
+        // The new entry can be reached backward before it is reachable forward.
+        self.prev_link(next_ptr)
+            .store(new_entry.as_ptr().cast_mut(), Release);
+        entry
+            .next
+            .swap((Some(new_entry.clone()), Tag::None), Release);
+    }
+
+    /// Unlinks the supplied entry.
+    ///
+    /// The [`List`] must be locked.
+    fn unlink_locked(&self, entry: &Entry<T>, guard: &Guard) -> Option<Shared<Entry<T>>> {
+        let prev_ptr = entry.prev.load(Relaxed);
+        if prev_ptr.is_null() {
+            return None;
+        }
+
+        // Links to the entry must be removed before it is retired.
+        let next_ptr = entry.next.load(Acquire, guard);
+        self.prev_link(next_ptr).store(prev_ptr, Release);
+        entry.prev.store(ptr::null_mut(), Release);
+        let prev = unsafe { &*prev_ptr };
+        prev.next.swap((next_ptr.get_shared(), Tag::None), AcqRel).0
+    }
+}
+
+impl<T: Debug> Debug for List<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter(&Guard::new())).finish()
+    }
+}
+
+impl<T> Default for List<T> {
+    #[inline]
+    fn default() -> Self {
+        let head = unsafe {
+            Shared::new_unchecked(Entry::<T> {
+                instance: None,
+                next: AtomicShared::default(),
+                prev: AtomicPtr::default(),
+                owner: 0,
+            })
+        };
+        head.prev.store(head.as_ptr().cast_mut(), Relaxed);
+        Self {
+            head,
+            state: AtomicU8::new(0),
+            wait_queue: WaitQueue::default(),
+        }
+    }
+}
+
+impl<T> Drop for List<T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Entries outliving the `List` must not point to the head or to each other through
+        // `prev`.
+        let guard = Guard::new();
+        let mut current = self.head.next.load(Relaxed, &guard);
+        while let Some(entry) = current.as_ref() {
+            entry.prev.store(ptr::null_mut(), Relaxed);
+            current = entry.next.load(Relaxed, &guard);
+        }
+    }
+}
+
+impl<T> Entry<T> {
+    /// Creates a new [`Entry`] for the [`List`] of the supplied head.
+    fn new(val: T, head: &Shared<Entry<T>>) -> Self {
+        Self {
+            instance: Some(val),
+            next: AtomicShared::default(),
+            prev: AtomicPtr::default(),
+            owner: head.as_ptr() as usize,
+        }
+    }
+
+    /// Returns `true` if the [`Entry`] is linked to a [`List`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// let entry = list.push_back(11);
+    /// assert!(entry.is_linked());
+    ///
+    /// assert!(list.pop_front().is_some());
+    /// assert!(!entry.is_linked());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_linked(&self) -> bool {
+        !self.prev.load(Acquire).is_null()
+    }
+
+    /// Returns the next linked entry, skipping entries that have been unlinked.
+    fn next_linked<'g>(&self, guard: &'g Guard) -> Option<&'g Entry<T>> {
+        let mut next = self.next.load(Acquire, guard).as_ref();
+        while let Some(entry) = next {
+            if entry.is_linked() {
+                break;
+            }
+            next = entry.next.load(Acquire, guard).as_ref();
+        }
+        next
+    }
+}
+
+impl<T: Debug> Debug for Entry<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entry")
+            .field("instance", &self.instance)
+            .field("linked", &self.is_linked())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Deref for Entry<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.instance.as_ref().unwrap_unchecked() }
+    }
+}
+
+impl<'l, 'g, T> Cursor<'l, 'g, T> {
+    /// Returns a reference to the entry that the [`Cursor`] is pointing to.
+    ///
+    /// Returns `None` if the [`Cursor`] points to the ghost position. The entry may have been
+    /// removed by another thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// list.push_back(2);
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = list.cursor(&guard);
+    /// assert!(cursor.get().is_none());
+    /// cursor.move_next();
+    /// assert_eq!(cursor.get().map(|e| **e), Some(2));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'g Entry<T>> {
+        self.current
+    }
+
+    /// Moves the [`Cursor`] to the next entry, and returns a reference to it.
+    ///
+    /// If the [`Cursor`] points to the ghost position, it moves to the first entry; if the
+    /// [`Cursor`] points to the last entry, it moves to the ghost position. Entries that have
+    /// been removed by other threads are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = list.cursor(&guard);
+    /// assert_eq!(cursor.move_next().map(|e| **e), Some(1));
+    /// assert_eq!(cursor.move_next().map(|e| **e), Some(2));
+    /// assert!(cursor.move_next().is_none());
+    /// assert_eq!(cursor.move_next().map(|e| **e), Some(1));
+    /// ```
+    #[inline]
+    pub fn move_next(&mut self) -> Option<&'g Entry<T>> {
+        self.current = self
+            .current
+            .unwrap_or(&*self.list.head)
+            .next_linked(self.guard);
+        self.current
+    }
+
+    /// Moves the [`Cursor`] to the previous entry, and returns a reference to it.
+    ///
+    /// If the [`Cursor`] points to the ghost position, it moves to the last entry; if the
+    /// [`Cursor`] points to the first entry or an entry that has been removed, it moves to the
+    /// ghost position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = list.cursor(&guard);
+    /// assert_eq!(cursor.move_prev().map(|e| **e), Some(2));
+    /// assert_eq!(cursor.move_prev().map(|e| **e), Some(1));
+    /// assert!(cursor.move_prev().is_none());
+    /// assert_eq!(cursor.move_prev().map(|e| **e), Some(2));
+    /// ```
+    #[inline]
+    pub fn move_prev(&mut self) -> Option<&'g Entry<T>> {
+        self.current = self
+            .list
+            .prev_of(self.current.unwrap_or(&*self.list.head), self.guard);
+        self.current
+    }
+
+    /// Unlinks the entry that the [`Cursor`] is pointing to, and moves the [`Cursor`] to the
+    /// next entry.
+    ///
+    /// The time complexity of the method is `O(1)`. Returns `None` if the [`Cursor`] points to
+    /// the ghost position or the entry has been removed by another thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = list.cursor(&guard);
+    /// cursor.move_next();
+    /// cursor.move_next();
+    /// assert_eq!(cursor.unlink().map(|e| **e), Some(2));
+    /// assert_eq!(cursor.get().map(|e| **e), Some(3));
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    #[inline]
+    pub fn unlink(&mut self) -> Option<Shared<Entry<T>>> {
+        let current = self.current?;
+        let removed = {
+            let _locker = Locker::lock(self.list);
+            self.list.unlink_locked(current, self.guard)?
+        };
+        self.current = current.next_linked(self.guard);
+        Some(removed)
+    }
+}
+
+impl<'l, 'g, T: 'static> Cursor<'l, 'g, T> {
+    /// Inserts an instance of `T` after the entry that the [`Cursor`] is pointing to.
+    ///
+    /// If the [`Cursor`] points to the ghost position, the instance is inserted in front of the
+    /// first entry. The [`Cursor`] is not moved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the supplied instance if the entry has been removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = list.cursor(&guard);
+    /// assert!(cursor.insert_after(2).is_ok());
+    /// assert!(cursor.insert_after(1).is_ok());
+    /// assert_eq!(cursor.move_next().map(|e| **e), Some(1));
+    /// assert!(cursor.insert_after(3).is_ok());
+    /// assert_eq!(list.iter(&guard).copied().collect::<Vec<_>>(), vec![1, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn insert_after(&mut self, val: T) -> Result<Shared<Entry<T>>, T> {
+        let new_entry = Shared::new(Entry::new(val, &self.list.head));
+        let _locker = Locker::lock(self.list);
+        let target = match self.current {
+            Some(current) if !current.is_linked() => return Err(Self::take_instance(new_entry)),
+            Some(current) => current,
+            None => &*self.list.head,
+        };
+        self.list.link_after(target, &new_entry, self.guard);
+        Ok(new_entry)
+    }
+
+    /// Inserts an instance of `T` before the entry that the [`Cursor`] is pointing to.
+    ///
+    /// If the [`Cursor`] points to the ghost position, the instance is inserted after the last
+    /// entry. The [`Cursor`] is not moved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the supplied instance if the entry has been removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::List;
+    ///
+    /// let list: List<usize> = List::default();
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = list.cursor(&guard);
+    /// assert!(cursor.insert_before(2).is_ok());
+    /// assert!(cursor.insert_before(3).is_ok());
+    /// assert_eq!(cursor.move_next().map(|e| **e), Some(2));
+    /// assert!(cursor.insert_before(1).is_ok());
+    /// assert_eq!(list.iter(&guard).copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn insert_before(&mut self, val: T) -> Result<Shared<Entry<T>>, T> {
+        let new_entry = Shared::new(Entry::new(val, &self.list.head));
+        let _locker = Locker::lock(self.list);
+        let prev_ptr = self.current.unwrap_or(&*self.list.head).prev.load(Relaxed);
+        if prev_ptr.is_null() {
+            return Err(Self::take_instance(new_entry));
+        }
+        self.list
+            .link_after(unsafe { &*prev_ptr }, &new_entry, self.guard);
+        Ok(new_entry)
+    }
+
+    /// Extracts the instance from an entry that has never been linked.
+    fn take_instance(mut new_entry: Shared<Entry<T>>) -> T {
+        unsafe {
+            new_entry
+                .get_mut()
+                .unwrap_unchecked()
+                .instance
+                .take()
+                .unwrap_unchecked()
+        }
+    }
+}
+
+impl<'l, 'g, T: Debug> Debug for Cursor<'l, 'g, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("current", &self.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'g, T> FusedIterator for Iter<'g, T> {}
+
+impl<'g, T> Iterator for Iter<'g, T> {
+    type Item = &'g T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        self.current = current.next_linked(self.guard);
+        Some(current)
+    }
+}
+
+impl<'l, T> Locker<'l, T> {
+    /// Locks the [`List`].
+    fn lock(list: &'l List<T>) -> Self {
+        loop {
+            if let Some(locker) = Self::try_lock(list) {
+                return locker;
+            }
+            if let Ok(locker) = list.wait_queue.wait_sync(|| {
+                // Mark that there is a waiting thread.
+                list.state.fetch_or(WAITING, Release);
+                Self::try_lock(list).ok_or(())
+            }) {
+                return locker;
+            }
+        }
+    }
+
+    /// Tries to lock the [`List`].
+    fn try_lock(list: &'l List<T>) -> Option<Self> {
+        let mut current = list.state.load(Relaxed);
+        while current & LOCK == 0 {
+            match list
+                .state
+                .compare_exchange_weak(current, current | LOCK, Acquire, Relaxed)
+            {
+                Ok(_) => return Some(Self { list }),
+                Err(actual) => current = actual,
+            }
+        }
+        None
+    }
+}
+
+impl<T> Drop for Locker<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.list.state.swap(0, Release) & WAITING == WAITING {
+            self.list.wait_queue.signal();
+        }
+    }
+}
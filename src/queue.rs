@@ -1,10 +1,20 @@
 //! [`Queue`] is a lock-free concurrent first-in-first-out container.
 
+mod array_queue;
+pub use array_queue::ArrayQueue;
+
+mod segmented_queue;
+pub use segmented_queue::SegmentedQueue;
+
 use super::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
-use super::linked_list::{Entry, LinkedList};
+use super::linked_list::{Entry, EntryPool, LinkedList};
+use super::wait_queue::{AsyncWait, WaitQueue};
+use crate::atomic::AtomicUsize;
 use std::fmt::{self, Debug};
 use std::iter::FusedIterator;
-use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
+use std::pin::Pin;
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
+use std::time::{Duration, Instant};
 
 /// [`Queue`] is a lock-free concurrent first-in-first-out container.
 pub struct Queue<T> {
@@ -13,6 +23,30 @@ pub struct Queue<T> {
 
     /// `newest` *eventually* points to the newest entry in the [`Queue`].
     newest: AtomicShared<Entry<T>>,
+
+    /// `len` is the number of entries in the [`Queue`] and pushes that are in progress.
+    len: AtomicUsize,
+
+    /// `pop_waiters` holds tasks waiting for an entry to be pushed.
+    pop_waiters: WaitQueue,
+}
+
+/// [`BoundedQueue`] is a [`Queue`] that holds a limited number of entries.
+///
+/// Pushing an instance into a full [`BoundedQueue`] fails, and the instance is returned to the
+/// caller.
+pub struct BoundedQueue<T> {
+    /// The entries.
+    ///
+    /// Its `len` is reserved before an entry is pushed, so that the number of entries cannot
+    /// exceed the capacity.
+    queue: Queue<T>,
+
+    /// The maximum number of entries.
+    capacity: usize,
+
+    /// `wait_queue` holds tasks waiting for a vacant slot.
+    wait_queue: WaitQueue,
 }
 
 /// An iterator over the entries of a [`Queue`].
@@ -23,6 +57,24 @@ pub struct Iter<'g, T> {
     guard: &'g Guard,
 }
 
+/// An iterator that moves out of a [`Queue`].
+///
+/// [`IntoIter`] pops the oldest entry first.
+pub struct IntoIter<T> {
+    queue: Queue<T>,
+}
+
+/// An iterator that pops the entries of a [`Queue`].
+///
+/// [`Drain`] pops the oldest entry whenever it is advanced, therefore entries that are pushed
+/// while draining the [`Queue`] are also popped.
+pub struct Drain<'q, T> {
+    queue: &'q Queue<T>,
+}
+
+/// The maximum number of entries that [`Queue`] formats with [`Debug`].
+const DEBUG_MAX_ENTRIES: usize = 32;
+
 impl<T: 'static> Queue<T> {
     /// Pushes an instance of `T`.
     ///
@@ -74,10 +126,93 @@ impl<T: 'static> Queue<T> {
         self.push_if_internal(val, cond, &Guard::new())
     }
 
+    /// Pushes all the instances of `T` that the iterator yields.
+    ///
+    /// The instances are linked to each other in advance, and then appended to the [`Queue`] at
+    /// once, therefore other threads observe either none or all of them. Returns the number of
+    /// pushed instances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// queue.push(3);
+    /// assert_eq!(queue.push_many([5, 7, 11]), 3);
+    ///
+    /// assert_eq!(queue.pop().map(|e| **e), Some(3));
+    /// assert_eq!(queue.pop().map(|e| **e), Some(5));
+    /// assert_eq!(queue.len(), 2);
+    /// ```
+    #[inline]
+    pub fn push_many<I: IntoIterator<Item = T>>(&self, iter: I) -> usize {
+        let mut iter = iter.into_iter();
+        let Some(val) = iter.next() else {
+            return 0;
+        };
+        let first = Shared::new(Entry::new(val));
+        let mut last = first.clone();
+        let mut len = 1;
+        for val in iter {
+            let new_entry = Shared::new(Entry::new(val));
+            last.next()
+                .swap((Some(new_entry.clone()), Tag::None), Relaxed);
+            last = new_entry;
+            len += 1;
+        }
+
+        let guard = Guard::new();
+        let newest_ptr = self.newest_ptr(&guard);
+        self.len.fetch_add(len, Relaxed);
+        let pushed = self.push_chain(&first, &last, len, newest_ptr, |_| true, &guard);
+        debug_assert!(pushed);
+        len
+    }
+
+    /// Pushes an instance of `T` using a vacant entry taken from the supplied [`EntryPool`].
+    ///
+    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry. The entry is not
+    /// allocated, and popped entries can be returned to the [`EntryPool`] by
+    /// [`EntryPool::recycle`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the supplied instance if the [`EntryPool`] is empty; [`Queue::push`] can be used
+    /// to fall back to the allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::{LinkedEntryPool, Queue};
+    ///
+    /// let pool: LinkedEntryPool<usize> = LinkedEntryPool::new(1);
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// assert_eq!(queue.push_pooled(11, &pool).map(|e| **e), Ok(11));
+    /// assert_eq!(queue.push_pooled(17, &pool).map(|e| **e), Err(17));
+    /// assert_eq!(**queue.push(17), 17);
+    ///
+    /// assert_eq!(queue.pop().map(|e| **e), Some(11));
+    /// assert_eq!(queue.pop().map(|e| **e), Some(17));
+    /// ```
+    #[inline]
+    pub fn push_pooled(&self, val: T, pool: &EntryPool<T>) -> Result<Shared<Entry<T>>, T> {
+        let new_entry = pool.take(val)?;
+        let guard = Guard::new();
+        let newest_ptr = self.newest_ptr(&guard);
+        self.len.fetch_add(1, Relaxed);
+        let pushed = self.push_chain(&new_entry, &new_entry, 1, newest_ptr, |_| true, &guard);
+        debug_assert!(pushed);
+        Ok(new_entry)
+    }
+
     /// Returns a guarded reference to the oldest entry.
     ///
     /// Returns `None` if the [`Queue`] is empty. The returned reference can survive as long as the
-    /// associated [`Guard`] is alive.
+    /// associated [`Guard`] is alive. [`Queue::pop_if`] pops the oldest entry only if it still
+    /// satisfies the condition that the caller inspected.
     ///
     /// # Examples
     ///
@@ -93,6 +228,16 @@ impl<T: 'static> Queue<T> {
     /// queue.push(3);
     ///
     /// assert_eq!(**queue.peek(&Guard::new()).unwrap(), 37);
+    ///
+    /// fn oldest_name<'g>(queue: &Queue<String>, guard: &'g Guard) -> Option<&'g str> {
+    ///     queue.peek(guard).map(|e| e.as_str())
+    /// }
+    ///
+    /// let queue: Queue<String> = Queue::default();
+    /// queue.push(String::from("oldest"));
+    ///
+    /// let guard = Guard::new();
+    /// assert_eq!(oldest_name(&queue, &guard), Some("oldest"));
     /// ```
     #[inline]
     pub fn peek<'g>(&self, guard: &'g Guard) -> Option<&'g Entry<T>> {
@@ -197,9 +342,93 @@ impl<T> Queue<T> {
         }
     }
 
+    /// Pops the oldest entry, or waits until an entry is pushed if the [`Queue`] is empty.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// queue.push(37);
+    ///
+    /// let future_pop = queue.pop_async();
+    /// ```
+    #[inline]
+    pub async fn pop_async(&self) -> Shared<Entry<T>> {
+        loop {
+            if let Some(entry) = self.pop() {
+                return entry;
+            }
+            let mut popped = None;
+            let mut async_wait = AsyncWait::default();
+            let _result = self.pop_waiters.push_async_entry(&mut async_wait, || {
+                popped = self.pop();
+                if popped.is_some() {
+                    Ok(())
+                } else {
+                    Err(())
+                }
+            });
+            Pin::new(&mut async_wait).await;
+            if let Some(entry) = popped {
+                return entry;
+            }
+        }
+    }
+
+    /// Pops the oldest entry, blocking the current thread until an entry is pushed or the timeout
+    /// expires if the [`Queue`] is empty.
+    ///
+    /// Returns `None` if no entries were pushed before the timeout expired.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// let queue: Arc<Queue<usize>> = Arc::new(Queue::default());
+    ///
+    /// assert!(queue.pop_timeout(Duration::from_millis(1)).is_none());
+    ///
+    /// let queue_clone = queue.clone();
+    /// let thread = thread::spawn(move || queue_clone.pop_timeout(Duration::from_secs(60)));
+    /// queue.push(37);
+    /// assert_eq!(thread.join().unwrap().map(|e| **e), Some(37));
+    /// ```
+    #[inline]
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<Shared<Entry<T>>> {
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            if let Some(entry) = self.pop() {
+                return Some(entry);
+            }
+            if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+                return None;
+            }
+            if let Ok(entry) = self
+                .pop_waiters
+                .wait_sync_until(|| self.pop().ok_or(()), deadline)
+            {
+                return Some(entry);
+            }
+        }
+    }
+
     /// Pops the oldest entry if the entry satisfies the given condition.
     ///
-    /// Returns `None` if the [`Queue`] is empty.
+    /// Returns `None` if the [`Queue`] is empty. The condition is evaluated on the oldest entry
+    /// which is then popped in a single step, therefore an entry that is not ready to be
+    /// consumed, e.g., its scheduled time has not arrived yet, stays at the head of the [`Queue`].
+    /// A thread that loses the race for the oldest entry proceeds to the next entry rather than
+    /// starting over, therefore the number of failed attempts is bounded by the number of entries
+    /// popped by other threads in the meantime.
     ///
     /// # Errors
     ///
@@ -232,17 +461,195 @@ impl<T> Queue<T> {
                     return Err(oldest_entry);
                 }
                 if oldest_entry.delete_self(Relaxed) {
+                    self.len.fetch_sub(1, Relaxed);
                     self.cleanup_oldest(&guard);
                     return Ok(Some(oldest_entry));
                 }
+
+                // Another thread has popped the entry: move on to the next one instead of
+                // restarting from `oldest` so that the current thread is not starved.
+                current = oldest_entry.next_ptr(Acquire, &guard);
+                continue;
             }
             current = self.cleanup_oldest(&guard);
         }
         Ok(None)
     }
 
+    /// Pops at most `n` oldest entries.
+    ///
+    /// If the [`Queue`] holds more than `n` entries, the `n` oldest entries are detached from the
+    /// [`Queue`] by a single atomic operation, otherwise the entries are popped one by one. Entries
+    /// that are popped by other threads in the meantime are skipped, therefore the returned
+    /// entries may not be contiguous; use [`Queue::pop_all`] to detach all the entries at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// queue.push(37);
+    /// queue.push(3);
+    /// queue.push(1);
+    ///
+    /// assert_eq!(queue.pop_many(2).iter().map(|e| ***e).collect::<Vec<_>>(), vec![37, 3]);
+    /// assert_eq!(queue.pop_many(2).iter().map(|e| ***e).collect::<Vec<_>>(), vec![1]);
+    /// assert!(queue.pop_many(2).is_empty());
+    /// ```
+    #[inline]
+    pub fn pop_many(&self, n: usize) -> Vec<Shared<Entry<T>>> {
+        let mut popped = Vec::new();
+        if n == 0 {
+            return popped;
+        }
+        let guard = Guard::new();
+        while popped.len() < n {
+            let oldest_ptr = self.cleanup_oldest(&guard);
+            if oldest_ptr.is_null() {
+                break;
+            }
+
+            // Find the entry that becomes the oldest one after the remaining entries are detached.
+            let mut boundary = oldest_ptr;
+            let mut num_entries = 0;
+            while let Some(entry) = boundary.as_ref() {
+                if num_entries == n - popped.len() {
+                    break;
+                }
+                if !entry.is_deleted(Relaxed) {
+                    num_entries += 1;
+                }
+                boundary = entry.next_ptr(Acquire, &guard);
+            }
+
+            if boundary.is_null() {
+                // The newest entry cannot be detached without blocking pushes: pop the entries one
+                // by one, advancing from the current entry regardless of the result.
+                let mut current = oldest_ptr;
+                while let Some(entry) = current.as_ref() {
+                    if popped.len() == n {
+                        break;
+                    }
+                    if entry.delete_self(Relaxed) {
+                        if let Some(entry) = current.get_shared() {
+                            popped.push(entry);
+                        }
+                    }
+                    current = entry.next_ptr(Acquire, &guard);
+                }
+                break;
+            }
+
+            // `detached` keeps the oldest detached entry alive until it is claimed.
+            let Ok((detached, _)) = self.oldest.compare_exchange(
+                oldest_ptr,
+                (boundary.get_shared(), Tag::None),
+                AcqRel,
+                Acquire,
+                &guard,
+            ) else {
+                continue;
+            };
+
+            // Threads that had reached the detached entries before they were detached may still
+            // pop them: those entries are skipped.
+            let mut current = oldest_ptr;
+            while let Some(entry) = current.as_ref() {
+                if popped.len() == n || current.as_ptr() == boundary.as_ptr() {
+                    break;
+                }
+                if entry.delete_self(Relaxed) {
+                    if let Some(entry) = current.get_shared() {
+                        popped.push(entry);
+                    }
+                }
+                current = entry.next_ptr(Acquire, &guard);
+            }
+            drop(detached);
+        }
+        if !popped.is_empty() {
+            self.len.fetch_sub(popped.len(), Relaxed);
+            self.cleanup_oldest(&guard);
+        }
+        popped
+    }
+
+    /// Pops all the entries at once, and returns an iterator over the popped entries.
+    ///
+    /// The entries are detached from the [`Queue`] by a single atomic operation, and the iterator
+    /// yields them from the oldest. Entries that are pushed while the detached entries are being
+    /// popped may be popped as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// queue.push(37);
+    /// queue.push(3);
+    ///
+    /// let popped = queue.pop_all();
+    ///
+    /// queue.push(1);
+    ///
+    /// assert_eq!(popped.map(|e| **e).collect::<Vec<_>>(), vec![37, 3]);
+    /// assert_eq!(queue.pop().map(|e| **e), Some(1));
+    /// assert!(queue.is_empty());
+    /// ```
+    #[inline]
+    pub fn pop_all(&self) -> std::vec::IntoIter<Shared<Entry<T>>> {
+        let guard = Guard::new();
+        let detached = loop {
+            let oldest_ptr = self.oldest.load(Acquire, &guard);
+            if oldest_ptr.is_null() {
+                return Vec::new().into_iter();
+            }
+            if let Ok((detached, _)) =
+                self.oldest
+                    .compare_exchange(oldest_ptr, (None, Tag::None), AcqRel, Acquire, &guard)
+            {
+                break detached;
+            }
+        };
+
+        // Entries can be appended to the detached entries until the last one is deleted, and
+        // entries that are being popped by other threads are skipped.
+        let mut popped = Vec::new();
+        let mut last_ptr = Ptr::null();
+        let mut current = detached
+            .as_ref()
+            .map_or_else(Ptr::null, |e| e.get_guarded_ptr(&guard));
+        while let Some(entry) = current.as_ref() {
+            if entry.delete_self(Relaxed) {
+                if let Some(entry) = current.get_shared() {
+                    popped.push(entry);
+                }
+            }
+            last_ptr = current;
+            current = entry.next_ptr(Acquire, &guard);
+        }
+        self.len.fetch_sub(popped.len(), Relaxed);
+
+        // `newest` is reset only if it still points to the last detached entry.
+        let _result = self.newest.compare_exchange(
+            last_ptr.without_tag(),
+            (None, Tag::None),
+            AcqRel,
+            Relaxed,
+            &guard,
+        );
+        popped.into_iter()
+    }
+
     /// Peeks the oldest entry.
     ///
+    /// The reference passed to `reader` cannot outlive the call; use [`Queue::peek`] with a
+    /// [`Guard`] to derive a result that borrows from the oldest entry.
+    ///
     /// # Examples
     ///
     /// ```
@@ -273,8 +680,8 @@ impl<T> Queue<T> {
 
     /// Returns the number of entries in the [`Queue`].
     ///
-    /// This method iterates over all the entries in the [`Queue`] to count them, therefore its
-    /// time complexity is `O(N)`.
+    /// The number is maintained by a counter, therefore its time complexity is `O(1)`. Entries
+    /// that are being pushed are also counted.
     ///
     /// # Examples
     ///
@@ -294,7 +701,7 @@ impl<T> Queue<T> {
     /// ```
     #[inline]
     pub fn len(&self) -> usize {
-        self.iter(&Guard::new()).count()
+        self.len.load(Relaxed)
     }
 
     /// Returns `true` if the [`Queue`] is empty.
@@ -345,31 +752,84 @@ impl<T> Queue<T> {
         }
     }
 
+    /// Returns a [`Drain`] that pops the entries of the [`Queue`] in the order of insertion.
+    ///
+    /// Entries that the [`Drain`] does not reach remain in the [`Queue`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// queue.push(7);
+    /// queue.push(11);
+    /// queue.push(17);
+    ///
+    /// assert_eq!(queue.drain().next().map(|e| **e), Some(7));
+    /// assert_eq!(queue.drain().map(|e| **e).collect::<Vec<_>>(), vec![11, 17]);
+    /// assert!(queue.is_empty());
+    /// ```
+    #[inline]
+    pub fn drain(&self) -> Drain<'_, T> {
+        Drain { queue: self }
+    }
+
     /// Pushes an entry into the [`Queue`].
     fn push_if_internal<F: FnMut(Option<&Entry<T>>) -> bool>(
         &self,
         val: T,
-        mut cond: F,
+        cond: F,
         guard: &Guard,
     ) -> Result<Shared<Entry<T>>, T> {
-        let mut newest_ptr = self.newest.load(Acquire, guard);
-        if newest_ptr.is_null() {
-            // Traverse from the oldest.
-            newest_ptr = self.oldest.load(Acquire, guard);
-        }
-        newest_ptr = Self::traverse(newest_ptr, guard);
+        self.len.fetch_add(1, Relaxed);
+        self.push_if_reserved(val, cond, guard)
+    }
 
+    /// Pushes an entry into the [`Queue`] after the entry has been counted in `len`.
+    ///
+    /// The entry is uncounted if the condition is not met.
+    fn push_if_reserved<F: FnMut(Option<&Entry<T>>) -> bool>(
+        &self,
+        val: T,
+        mut cond: F,
+        guard: &Guard,
+    ) -> Result<Shared<Entry<T>>, T> {
+        let newest_ptr = self.newest_ptr(guard);
         if !cond(newest_ptr.as_ref()) {
             // The condition is not met.
+            self.len.fetch_sub(1, Relaxed);
             return Err(val);
         }
 
         let mut new_entry = unsafe { Shared::new_unchecked(Entry::new(val)) };
+        if self.push_chain(&new_entry, &new_entry, 1, newest_ptr, cond, guard) {
+            return Ok(new_entry);
+        }
+
+        // Extract the instance from the temporary entry.
+        Err(unsafe { new_entry.get_mut().unwrap_unchecked().take_inner() })
+    }
+
+    /// Appends a chain of `len` entries from `first` to `last` to the newest entry.
+    ///
+    /// The entries must have been counted in `len` of the [`Queue`], and they are uncounted if
+    /// the condition is not met. Returns `false` if the condition is not met.
+    fn push_chain<'g, F: FnMut(Option<&Entry<T>>) -> bool>(
+        &self,
+        first: &Shared<Entry<T>>,
+        last: &Shared<Entry<T>>,
+        len: usize,
+        mut newest_ptr: Ptr<'g, Entry<T>>,
+        mut cond: F,
+        guard: &'g Guard,
+    ) -> bool {
         loop {
             let result = if let Some(newest_entry) = newest_ptr.as_ref() {
                 newest_entry.next().compare_exchange(
                     Ptr::null(),
-                    (Some(new_entry.clone()), Tag::None),
+                    (Some(first.clone()), Tag::None),
                     AcqRel,
                     Acquire,
                     guard,
@@ -377,7 +837,7 @@ impl<T> Queue<T> {
             } else {
                 self.oldest.compare_exchange(
                     newest_ptr,
-                    (Some(new_entry.clone()), Tag::None),
+                    (Some(first.clone()), Tag::None),
                     AcqRel,
                     Acquire,
                     guard,
@@ -385,13 +845,20 @@ impl<T> Queue<T> {
             };
             match result {
                 Ok(_) => {
-                    self.newest
-                        .swap((Some(new_entry.clone()), Tag::None), AcqRel);
-                    if self.oldest.is_null(Relaxed) {
-                        // The `Queue` was emptied in the meantime.
-                        self.newest.swap((None, Tag::None), Release);
+                    self.newest.swap((Some(last.clone()), Tag::None), AcqRel);
+                    if self.oldest.is_null(Acquire) {
+                        // The `Queue` was emptied in the meantime; `newest` is not reset if an
+                        // entry was pushed after it.
+                        let _result = self.newest.compare_exchange(
+                            last.get_guarded_ptr(guard),
+                            (None, Tag::None),
+                            AcqRel,
+                            Relaxed,
+                            guard,
+                        );
                     }
-                    return Ok(new_entry);
+                    self.pop_waiters.signal();
+                    return true;
                 }
                 Err((_, actual_ptr)) => {
                     newest_ptr = if actual_ptr.tag() == Tag::First {
@@ -410,9 +877,18 @@ impl<T> Queue<T> {
                 }
             }
         }
+        self.len.fetch_sub(len, Relaxed);
+        false
+    }
 
-        // Extract the instance from the temporary entry.
-        Err(unsafe { new_entry.get_mut().unwrap_unchecked().take_inner() })
+    /// Returns a pointer to the newest entry.
+    fn newest_ptr<'g>(&self, guard: &'g Guard) -> Ptr<'g, Entry<T>> {
+        let mut newest_ptr = self.newest.load(Acquire, guard);
+        if newest_ptr.is_null() {
+            // Traverse from the oldest.
+            newest_ptr = self.oldest.load(Acquire, guard);
+        }
+        Self::traverse(newest_ptr, guard)
     }
 
     /// Cleans up logically removed entries that are attached to `oldest`.
@@ -432,8 +908,14 @@ impl<T> Queue<T> {
                 ) {
                     Ok((_, new_ptr)) => {
                         if new_ptr.is_null() {
-                            // Reset `newest`.
-                            self.newest.swap((None, Tag::None), Relaxed);
+                            // Reset `newest` unless an entry was pushed in the meantime.
+                            let _result = self.newest.compare_exchange(
+                                oldest_ptr,
+                                (None, Tag::None),
+                                AcqRel,
+                                Relaxed,
+                                guard,
+                            );
                         }
                         return new_ptr;
                     }
@@ -460,35 +942,401 @@ impl<T> Queue<T> {
     }
 }
 
-impl<T: Clone> Clone for Queue<T> {
+impl<T: 'static> BoundedQueue<T> {
+    /// Pushes an instance of `T` if the [`BoundedQueue`] is not full.
+    ///
+    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the supplied instance if the [`BoundedQueue`] is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(1);
+    ///
+    /// assert_eq!(queue.push(11).map(|e| **e), Ok(11));
+    /// assert_eq!(queue.push(17).map(|e| **e), Err(17));
+    /// ```
     #[inline]
-    fn clone(&self) -> Self {
-        let self_clone = Self::default();
-        let guard = Guard::new();
-        let mut current = self.oldest.load(Acquire, &guard);
-        while let Some(entry) = current.as_ref() {
-            let next = entry.next_ptr(Acquire, &guard);
-            let _result = self_clone.push_if_internal((**entry).clone(), |_| true, &guard);
-            current = next;
-        }
-        self_clone
+    pub fn push(&self, val: T) -> Result<Shared<Entry<T>>, T> {
+        self.push_if(val, |_| true)
     }
-}
 
-impl<T: Debug> Debug for Queue<T> {
-    #[inline]
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut d = f.debug_set();
-        let guard = Guard::new();
-        let mut current = self.oldest.load(Acquire, &guard);
-        while let Some(entry) = current.as_ref() {
-            let next = entry.next_ptr(Acquire, &guard);
-            d.entry(entry);
-            current = next;
-        }
-        d.finish()
-    }
-}
+    /// Pushes an instance of `T` if the [`BoundedQueue`] is not full and the newest entry
+    /// satisfies the given condition.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the supplied instance if the [`BoundedQueue`] is full or the
+    /// condition is not met.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(2);
+    ///
+    /// assert!(queue.push(11).is_ok());
+    ///
+    /// assert!(queue.push_if(17, |e| e.map_or(false, |x| **x == 11)).is_ok());
+    /// assert!(queue.push_if(29, |e| e.map_or(false, |x| **x == 17)).is_err());
+    /// ```
+    #[inline]
+    pub fn push_if<F: FnMut(Option<&Entry<T>>) -> bool>(
+        &self,
+        val: T,
+        cond: F,
+    ) -> Result<Shared<Entry<T>>, T> {
+        if !self.reserve() {
+            return Err(val);
+        }
+        self.queue.push_if_reserved(val, cond, &Guard::new())
+    }
+
+    /// Pushes an instance of `T`, or waits until a vacant slot is available if the
+    /// [`BoundedQueue`] is full.
+    ///
+    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry. It is an
+    /// asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(1);
+    ///
+    /// assert!(queue.push(11).is_ok());
+    ///
+    /// let future_push = queue.push_async(17);
+    /// ```
+    #[inline]
+    pub async fn push_async(&self, val: T) -> Shared<Entry<T>> {
+        let mut val = val;
+        loop {
+            val = match self.push(val) {
+                Ok(entry) => return entry,
+                Err(val) => val,
+            };
+            let mut async_wait = AsyncWait::default();
+            let _result = self.wait_queue.push_async_entry(&mut async_wait, || {
+                if self.is_full() {
+                    Err(())
+                } else {
+                    Ok(())
+                }
+            });
+            Pin::new(&mut async_wait).await;
+        }
+    }
+
+    /// Returns a guarded reference to the oldest entry.
+    ///
+    /// Returns `None` if the [`BoundedQueue`] is empty. The returned reference can survive as long
+    /// as the associated [`Guard`] is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(4);
+    ///
+    /// assert!(queue.peek(&Guard::new()).is_none());
+    ///
+    /// assert!(queue.push(37).is_ok());
+    /// assert!(queue.push(3).is_ok());
+    ///
+    /// assert_eq!(**queue.peek(&Guard::new()).unwrap(), 37);
+    /// ```
+    #[inline]
+    pub fn peek<'g>(&self, guard: &'g Guard) -> Option<&'g Entry<T>> {
+        self.queue.peek(guard)
+    }
+}
+
+impl<T> BoundedQueue<T> {
+    /// Creates an empty [`BoundedQueue`] that can hold at most `capacity` entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(16);
+    /// assert_eq!(queue.capacity(), 16);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: Queue::default(),
+            capacity,
+            wait_queue: WaitQueue::default(),
+        }
+    }
+
+    /// Pops the oldest entry.
+    ///
+    /// Returns `None` if the [`BoundedQueue`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(1);
+    ///
+    /// assert!(queue.push(37).is_ok());
+    /// assert!(queue.push(3).is_err());
+    ///
+    /// assert_eq!(queue.pop().map(|e| **e), Some(37));
+    /// assert!(queue.push(3).is_ok());
+    /// assert_eq!(queue.pop().map(|e| **e), Some(3));
+    /// assert!(queue.pop().is_none());
+    /// ```
+    #[inline]
+    pub fn pop(&self) -> Option<Shared<Entry<T>>> {
+        match self.pop_if(|_| true) {
+            Ok(result) => result,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Pops the oldest entry if the entry satisfies the given condition.
+    ///
+    /// Returns `None` if the [`BoundedQueue`] is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the oldest entry if the given condition is not met.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(2);
+    ///
+    /// assert!(queue.push(3).is_ok());
+    /// assert!(queue.push(1).is_ok());
+    ///
+    /// assert!(queue.pop_if(|v| **v == 1).is_err());
+    /// assert_eq!(queue.pop().map(|e| **e), Some(3));
+    /// assert_eq!(queue.pop_if(|v| **v == 1).ok().and_then(|e| e).map(|e| **e), Some(1));
+    /// ```
+    #[inline]
+    pub fn pop_if<F: FnMut(&Entry<T>) -> bool>(
+        &self,
+        cond: F,
+    ) -> Result<Option<Shared<Entry<T>>>, Shared<Entry<T>>> {
+        let result = self.queue.pop_if(cond);
+        if let Ok(Some(_)) = result.as_ref() {
+            self.wait_queue.signal();
+        }
+        result
+    }
+
+    /// Peeks the oldest entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(2);
+    ///
+    /// assert!(queue.peek_with(|v| v.is_none()));
+    ///
+    /// assert!(queue.push(37).is_ok());
+    /// assert!(queue.push(3).is_ok());
+    ///
+    /// assert_eq!(queue.peek_with(|v| **v.unwrap()), 37);
+    /// ```
+    #[inline]
+    pub fn peek_with<R, F: FnOnce(Option<&Entry<T>>) -> R>(&self, reader: F) -> R {
+        self.queue.peek_with(reader)
+    }
+
+    /// Returns the number of entries in the [`BoundedQueue`].
+    ///
+    /// Entries that are being pushed are also counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(2);
+    /// assert_eq!(queue.len(), 0);
+    ///
+    /// assert!(queue.push(7).is_ok());
+    /// assert!(queue.push(11).is_ok());
+    /// assert!(queue.push(13).is_err());
+    /// assert_eq!(queue.len(), 2);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns `true` if the [`BoundedQueue`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(2);
+    /// assert!(queue.is_empty());
+    ///
+    /// assert!(queue.push(7).is_ok());
+    /// assert!(!queue.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns `true` if the [`BoundedQueue`] is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(1);
+    /// assert!(!queue.is_full());
+    ///
+    /// assert!(queue.push(7).is_ok());
+    /// assert!(queue.is_full());
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity
+    }
+
+    /// Returns the maximum number of entries that the [`BoundedQueue`] can hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(7);
+    /// assert_eq!(queue.capacity(), 7);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns an [`Iter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::ebr::Guard;
+    /// use scc::queue::BoundedQueue;
+    ///
+    /// let queue: BoundedQueue<usize> = BoundedQueue::new(4);
+    ///
+    /// assert!(queue.push(7).is_ok());
+    /// assert!(queue.push(11).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// let mut iter = queue.iter(&guard);
+    /// assert_eq!(*iter.next().unwrap(), 7);
+    /// assert_eq!(*iter.next().unwrap(), 11);
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[inline]
+    pub fn iter<'g>(&self, guard: &'g Guard) -> Iter<'g, T> {
+        self.queue.iter(guard)
+    }
+
+    /// Reserves a slot for a new entry.
+    ///
+    /// Returns `false` if the [`BoundedQueue`] is full.
+    fn reserve(&self) -> bool {
+        self.queue
+            .len
+            .fetch_update(Relaxed, Relaxed, |len| {
+                if len < self.capacity {
+                    Some(len + 1)
+                } else {
+                    None
+                }
+            })
+            .is_ok()
+    }
+}
+
+impl<T: Debug> Debug for BoundedQueue<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedQueue")
+            .field("queue", &self.queue)
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone> Clone for Queue<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let self_clone = Self::default();
+        let guard = Guard::new();
+        let mut current = self.oldest.load(Acquire, &guard);
+        while let Some(entry) = current.as_ref() {
+            let next = entry.next_ptr(Acquire, &guard);
+            if !entry.is_deleted(Relaxed) {
+                // The oldest entry may have been popped without being unlinked.
+                let _result = self_clone.push_if_internal((**entry).clone(), |_| true, &guard);
+            }
+            current = next;
+        }
+        self_clone
+    }
+}
+
+impl<T: Debug> Debug for Queue<T> {
+    /// Formats the entries in the [`Queue`], oldest first.
+    ///
+    /// At most `32` entries are formatted, and the number of the remaining entries is appended if
+    /// there are more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::default();
+    /// (0..4).for_each(|v| { queue.push(v); });
+    /// assert_eq!(format!("{queue:?}"), "[0, 1, 2, 3]");
+    ///
+    /// (4..64).for_each(|v| { queue.push(v); });
+    /// assert!(format!("{queue:?}").ends_with("30, 31, ..: 32 more]"));
+    /// ```
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = Guard::new();
+        let mut iter = self.iter(&guard);
+        let mut debug_list = f.debug_list();
+        debug_list.entries(iter.by_ref().take(DEBUG_MAX_ENTRIES));
+        let remaining = iter.count();
+        if remaining != 0 {
+            debug_list.entry(&format_args!("..: {remaining} more"));
+        }
+        debug_list.finish()
+    }
+}
 
 impl<T> Default for Queue<T> {
     #[inline]
@@ -496,10 +1344,96 @@ impl<T> Default for Queue<T> {
         Self {
             oldest: AtomicShared::default(),
             newest: AtomicShared::default(),
+            len: AtomicUsize::new(0),
+            pop_waiters: WaitQueue::default(),
         }
     }
 }
 
+impl<T: 'static> Extend<T> for Queue<T> {
+    /// Pushes all the instances of `T` that the iterator yields.
+    ///
+    /// The entries are linked together before being appended to the [`Queue`] at once; see
+    /// [`Queue::push_many`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let mut queue: Queue<usize> = Queue::default();
+    ///
+    /// queue.extend([37, 3, 1]);
+    /// assert_eq!(queue.len(), 3);
+    /// ```
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.push_many(iter);
+    }
+}
+
+impl<T: 'static> Extend<T> for &Queue<T> {
+    /// Pushes all the instances of `T` that the iterator yields through a shared reference.
+    ///
+    /// The entries are linked together before being appended to the [`Queue`] at once, therefore
+    /// entries pushed by other threads are never interleaved with them; see [`Queue::push_many`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let queue: Arc<Queue<usize>> = Arc::new(Queue::default());
+    ///
+    /// let queue_clone = queue.clone();
+    /// let thread = thread::spawn(move || (&*queue_clone).extend([1, 2, 3]));
+    /// (&*queue).extend([4, 5, 6]);
+    /// assert!(thread.join().is_ok());
+    ///
+    /// assert_eq!(queue.len(), 6);
+    /// ```
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.push_many(iter);
+    }
+}
+
+impl<T> IntoIterator for Queue<T> {
+    type Item = Shared<Entry<T>>;
+    type IntoIter = IntoIter<T>;
+
+    /// Returns an [`IntoIter`] that pops the entries of the [`Queue`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::default();
+    ///
+    /// queue.push(37);
+    /// queue.push(3);
+    /// queue.push(1);
+    ///
+    /// assert_eq!(queue.into_iter().map(|e| **e).collect::<Vec<_>>(), vec![37, 3, 1]);
+    /// ```
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { queue: self }
+    }
+}
+
+impl<'q, T> Iterator for Drain<'q, T> {
+    type Item = Shared<Entry<T>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.pop()
+    }
+}
+
 impl<'g, T> FusedIterator for Iter<'g, T> {}
 
 impl<'g, T> Iterator for Iter<'g, T> {
@@ -515,3 +1449,14 @@ impl<'g, T> Iterator for Iter<'g, T> {
         }
     }
 }
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = Shared<Entry<T>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.pop()
+    }
+}
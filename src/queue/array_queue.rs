@@ -0,0 +1,421 @@
+//! [`ArrayQueue`] is a lock-free concurrent first-in-first-out container backed by a fixed-size
+//! ring buffer.
+
+use crate::atomic::AtomicUsize;
+use crate::wait_queue::{AsyncWait, WaitQueue};
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug};
+use std::mem::MaybeUninit;
+use std::panic::UnwindSafe;
+use std::pin::Pin;
+use std::sync::atomic::fence;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
+
+/// [`ArrayQueue`] is a lock-free concurrent first-in-first-out container backed by a fixed-size
+/// ring buffer.
+///
+/// All the slots of an [`ArrayQueue`] are allocated when it is created, and no memory is
+/// allocated afterwards; pushing an instance into a full [`ArrayQueue`] fails, and the instance is
+/// returned to the caller.
+pub struct ArrayQueue<T> {
+    /// The slots.
+    slots: Box<[Slot<T>]>,
+
+    /// The position of the next slot to pop.
+    ///
+    /// A position is composed of a lap and an index; the lower bits up to `one_lap` hold the
+    /// index of the slot, and the rest of the bits hold the lap.
+    head: AtomicUsize,
+
+    /// The position of the next slot to push.
+    tail: AtomicUsize,
+
+    /// The value that increments the lap of a position by one.
+    ///
+    /// It is the smallest power of two greater than the capacity, so that the lap can wrap
+    /// around without affecting the index.
+    one_lap: usize,
+
+    /// `push_wait_queue` holds tasks waiting for a vacant slot.
+    push_wait_queue: WaitQueue,
+
+    /// `pop_wait_queue` holds tasks waiting for an instance to be pushed.
+    pop_wait_queue: WaitQueue,
+}
+
+/// [`Slot`] stores an instance of `T` along with a stamp.
+struct Slot<T> {
+    /// The stamp.
+    ///
+    /// The slot is vacant for a pusher at `position` if it is `position`, and occupied for a
+    /// popper at `position` if it is `position + 1`.
+    stamp: AtomicUsize,
+
+    /// The instance.
+    instance: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> ArrayQueue<T> {
+    /// Creates an empty [`ArrayQueue`] that can hold at most `capacity` instances.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(16);
+    /// assert_eq!(queue.capacity(), 16);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_lap(capacity, 0)
+    }
+
+    /// Pushes an instance of `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the supplied instance if the [`ArrayQueue`] is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(1);
+    ///
+    /// assert!(queue.push(11).is_ok());
+    /// assert_eq!(queue.push(17), Err(17));
+    /// ```
+    #[inline]
+    pub fn push(&self, val: T) -> Result<(), T> {
+        let mut tail = self.tail.load(Relaxed);
+        loop {
+            let slot = &self.slots[self.index(tail)];
+            let stamp = slot.stamp.load(Acquire);
+            if stamp == tail {
+                match self
+                    .tail
+                    .compare_exchange_weak(tail, self.next(tail), Relaxed, Relaxed)
+                {
+                    Ok(_) => {
+                        unsafe {
+                            (*slot.instance.get()).as_mut_ptr().write(val);
+                        }
+                        slot.stamp.store(tail.wrapping_add(1), Release);
+                        self.pop_wait_queue.signal();
+                        return Ok(());
+                    }
+                    Err(actual) => tail = actual,
+                }
+            } else if stamp.wrapping_add(self.one_lap) == tail.wrapping_add(1) {
+                // The slot has not been popped since the previous lap.
+                fence(SeqCst);
+                if self.head.load(Relaxed).wrapping_add(self.one_lap) == tail {
+                    return Err(val);
+                }
+                tail = self.tail.load(Relaxed);
+            } else {
+                tail = self.tail.load(Relaxed);
+            }
+        }
+    }
+
+    /// Pushes an instance of `T`, or waits until a vacant slot is available if the
+    /// [`ArrayQueue`] is full.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(1);
+    ///
+    /// assert!(queue.push(11).is_ok());
+    ///
+    /// let future_push = queue.push_async(17);
+    /// ```
+    #[inline]
+    pub async fn push_async(&self, val: T) {
+        let mut val = val;
+        loop {
+            val = match self.push(val) {
+                Ok(()) => return,
+                Err(val) => val,
+            };
+            let mut async_wait = AsyncWait::default();
+            let _result = self.push_wait_queue.push_async_entry(&mut async_wait, || {
+                if self.is_full() {
+                    Err(())
+                } else {
+                    Ok(())
+                }
+            });
+            Pin::new(&mut async_wait).await;
+        }
+    }
+
+    /// Pops the oldest instance.
+    ///
+    /// Returns `None` if the [`ArrayQueue`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(2);
+    ///
+    /// assert!(queue.push(37).is_ok());
+    /// assert!(queue.push(3).is_ok());
+    ///
+    /// assert_eq!(queue.pop(), Some(37));
+    /// assert_eq!(queue.pop(), Some(3));
+    /// assert!(queue.pop().is_none());
+    /// ```
+    #[inline]
+    pub fn pop(&self) -> Option<T> {
+        let mut head = self.head.load(Relaxed);
+        loop {
+            let slot = &self.slots[self.index(head)];
+            let stamp = slot.stamp.load(Acquire);
+            if stamp == head.wrapping_add(1) {
+                match self
+                    .head
+                    .compare_exchange_weak(head, self.next(head), Relaxed, Relaxed)
+                {
+                    Ok(_) => {
+                        let val = unsafe { (*slot.instance.get()).as_ptr().read() };
+                        slot.stamp.store(head.wrapping_add(self.one_lap), Release);
+                        self.push_wait_queue.signal();
+                        return Some(val);
+                    }
+                    Err(actual) => head = actual,
+                }
+            } else if stamp == head {
+                // The slot has not been pushed in this lap.
+                fence(SeqCst);
+                if self.tail.load(Relaxed) == head {
+                    return None;
+                }
+                head = self.head.load(Relaxed);
+            } else {
+                head = self.head.load(Relaxed);
+            }
+        }
+    }
+
+    /// Pops the oldest instance, or waits until an instance is pushed if the [`ArrayQueue`] is
+    /// empty.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(1);
+    ///
+    /// let future_pop = queue.pop_async();
+    /// ```
+    #[inline]
+    pub async fn pop_async(&self) -> T {
+        loop {
+            if let Some(val) = self.pop() {
+                return val;
+            }
+            let mut async_wait = AsyncWait::default();
+            let _result = self.pop_wait_queue.push_async_entry(&mut async_wait, || {
+                if self.is_empty() {
+                    Err(())
+                } else {
+                    Ok(())
+                }
+            });
+            Pin::new(&mut async_wait).await;
+        }
+    }
+
+    /// Returns the number of instances in the [`ArrayQueue`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(4);
+    /// assert_eq!(queue.len(), 0);
+    ///
+    /// assert!(queue.push(7).is_ok());
+    /// assert!(queue.push(11).is_ok());
+    /// assert_eq!(queue.len(), 2);
+    ///
+    /// queue.pop();
+    /// assert_eq!(queue.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.load(Acquire);
+            let head = self.head.load(Acquire);
+            if self.tail.load(Acquire) == tail {
+                let head_index = self.index(head);
+                let tail_index = self.index(tail);
+                return if head_index < tail_index {
+                    tail_index - head_index
+                } else if head_index > tail_index {
+                    self.slots.len() - head_index + tail_index
+                } else if tail == head {
+                    0
+                } else {
+                    self.slots.len()
+                };
+            }
+        }
+    }
+
+    /// Returns `true` if the [`ArrayQueue`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(4);
+    /// assert!(queue.is_empty());
+    ///
+    /// assert!(queue.push(7).is_ok());
+    /// assert!(!queue.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the [`ArrayQueue`] is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(1);
+    /// assert!(!queue.is_full());
+    ///
+    /// assert!(queue.push(7).is_ok());
+    /// assert!(queue.is_full());
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.slots.len()
+    }
+
+    /// Returns the maximum number of instances that the [`ArrayQueue`] can hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::ArrayQueue;
+    ///
+    /// let queue: ArrayQueue<usize> = ArrayQueue::new(7);
+    /// assert_eq!(queue.capacity(), 7);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Creates an empty [`ArrayQueue`] of which the head and tail positions start at `lap`.
+    fn with_lap(capacity: usize, lap: usize) -> Self {
+        assert_ne!(capacity, 0, "the capacity must be greater than zero");
+        let one_lap = (capacity + 1).next_power_of_two();
+        debug_assert_eq!(lap & (one_lap - 1), 0);
+        Self {
+            slots: (0..capacity)
+                .map(|i| Slot {
+                    stamp: AtomicUsize::new(lap.wrapping_add(i)),
+                    instance: UnsafeCell::new(MaybeUninit::uninit()),
+                })
+                .collect(),
+            head: AtomicUsize::new(lap),
+            tail: AtomicUsize::new(lap),
+            one_lap,
+            push_wait_queue: WaitQueue::default(),
+            pop_wait_queue: WaitQueue::default(),
+        }
+    }
+
+    /// Returns the slot index of the position.
+    #[inline]
+    fn index(&self, position: usize) -> usize {
+        position & (self.one_lap - 1)
+    }
+
+    /// Returns the position next to the supplied one.
+    #[inline]
+    fn next(&self, position: usize) -> usize {
+        let index = self.index(position);
+        if index + 1 < self.slots.len() {
+            position + 1
+        } else {
+            (position - index).wrapping_add(self.one_lap)
+        }
+    }
+}
+
+impl<T> Debug for ArrayQueue<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayQueue")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for ArrayQueue<T> {
+    #[inline]
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T: UnwindSafe> UnwindSafe for ArrayQueue<T> {}
+
+unsafe impl<T: Send> Send for ArrayQueue<T> {}
+unsafe impl<T: Send> Sync for ArrayQueue<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lap_wrap_around() {
+        for capacity in [1_usize, 3, 4, 5, 7] {
+            let one_lap = (capacity + 1).next_power_of_two();
+            let queue: ArrayQueue<usize> =
+                ArrayQueue::with_lap(capacity, 0_usize.wrapping_sub(one_lap));
+            for round in 0..4 {
+                for i in 0..capacity {
+                    assert!(queue.push(round * capacity + i).is_ok());
+                }
+                assert!(queue.is_full());
+                assert_eq!(queue.len(), capacity);
+                assert!(queue.push(usize::MAX).is_err());
+                for i in 0..capacity {
+                    assert_eq!(queue.pop(), Some(round * capacity + i));
+                }
+                assert!(queue.is_empty());
+                assert!(queue.pop().is_none());
+            }
+        }
+    }
+}
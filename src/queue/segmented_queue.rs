@@ -0,0 +1,376 @@
+//! [`SegmentedQueue`] is a lock-free concurrent first-in-first-out container that stores
+//! instances in linked fixed-size segments.
+
+use crate::atomic::{AtomicU8, AtomicUsize};
+use crate::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug};
+use std::mem::MaybeUninit;
+use std::panic::UnwindSafe;
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
+
+/// [`SegmentedQueue`] is a lock-free concurrent first-in-first-out container that stores
+/// instances in linked fixed-size segments.
+///
+/// Unlike [`Queue`](super::Queue) which allocates an entry for each instance, [`SegmentedQueue`]
+/// allocates a segment of `SEGMENT_LEN` slots at once, and instances are moved in and out of the
+/// slots; this makes [`SegmentedQueue`] especially efficient if instances are pushed and popped
+/// frequently, while entries cannot be shared with the caller.
+pub struct SegmentedQueue<T, const SEGMENT_LEN: usize = DEFAULT_SEGMENT_LEN> {
+    /// `oldest` points to the oldest segment in the [`SegmentedQueue`].
+    oldest: AtomicShared<Segment<T, SEGMENT_LEN>>,
+
+    /// `newest` *eventually* points to the newest segment in the [`SegmentedQueue`].
+    newest: AtomicShared<Segment<T, SEGMENT_LEN>>,
+}
+
+/// The default number of slots in a segment of a [`SegmentedQueue`].
+const DEFAULT_SEGMENT_LEN: usize = 32;
+
+/// [`Segment`] is a fixed-size array of slots linked to the next segment.
+struct Segment<T, const SEGMENT_LEN: usize> {
+    /// The slots.
+    slots: [Slot<T>; SEGMENT_LEN],
+
+    /// The number of slots that have been reserved by pushers.
+    ///
+    /// It can exceed `SEGMENT_LEN` when the [`Segment`] is full.
+    push_index: AtomicUsize,
+
+    /// The number of slots that have been claimed by poppers.
+    pop_index: AtomicUsize,
+
+    /// The next segment.
+    next: AtomicShared<Segment<T, SEGMENT_LEN>>,
+}
+
+/// [`Slot`] stores an instance of `T` along with its state.
+struct Slot<T> {
+    /// The state of the slot.
+    state: AtomicU8,
+
+    /// The instance.
+    instance: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// The slot has not been written yet.
+const VACANT: u8 = 0;
+
+/// The slot holds an instance.
+const OCCUPIED: u8 = 1;
+
+/// A popper claimed the slot before the pusher wrote an instance into it.
+const ABANDONED: u8 = 2;
+
+/// The instance has been moved out of the slot.
+const CONSUMED: u8 = 3;
+
+impl<T: 'static, const SEGMENT_LEN: usize> SegmentedQueue<T, SEGMENT_LEN> {
+    /// Pushes an instance of `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::SegmentedQueue;
+    ///
+    /// let queue: SegmentedQueue<usize> = SegmentedQueue::default();
+    ///
+    /// queue.push(11);
+    /// assert_eq!(queue.pop(), Some(11));
+    /// ```
+    #[inline]
+    pub fn push(&self, mut val: T) {
+        let guard = Guard::new();
+        loop {
+            let newest_ptr = self.newest.load(Acquire, &guard);
+            let Some(segment) = newest_ptr.as_ref() else {
+                self.init(&guard);
+                continue;
+            };
+            let index = segment.push_index.fetch_add(1, Relaxed);
+            if index < SEGMENT_LEN {
+                match segment.slots[index].write(val) {
+                    Ok(()) => return,
+                    Err(returned) => {
+                        // A popper has abandoned the slot.
+                        val = returned;
+                        continue;
+                    }
+                }
+            }
+
+            // The segment is full.
+            let mut next_ptr = segment.next.load(Acquire, &guard);
+            if next_ptr.is_null() {
+                let new_segment = Shared::new(Segment::default());
+                match segment.next.compare_exchange(
+                    next_ptr,
+                    (Some(new_segment), Tag::None),
+                    AcqRel,
+                    Acquire,
+                    &guard,
+                ) {
+                    Ok((_, ptr)) | Err((_, ptr)) => next_ptr = ptr,
+                }
+            }
+            let _result = self.newest.compare_exchange(
+                newest_ptr,
+                (next_ptr.get_shared(), Tag::None),
+                AcqRel,
+                Acquire,
+                &guard,
+            );
+        }
+    }
+
+    /// Initializes the first segment.
+    fn init(&self, guard: &Guard) {
+        let mut oldest_ptr = self.oldest.load(Acquire, guard);
+        if oldest_ptr.is_null() {
+            match self.oldest.compare_exchange(
+                oldest_ptr,
+                (Some(Shared::new(Segment::default())), Tag::None),
+                AcqRel,
+                Acquire,
+                guard,
+            ) {
+                Ok((_, ptr)) | Err((_, ptr)) => oldest_ptr = ptr,
+            }
+        }
+        let _result = self.newest.compare_exchange(
+            Ptr::null(),
+            (oldest_ptr.get_shared(), Tag::None),
+            AcqRel,
+            Acquire,
+            guard,
+        );
+    }
+}
+
+impl<T, const SEGMENT_LEN: usize> SegmentedQueue<T, SEGMENT_LEN> {
+    /// Pops the oldest instance.
+    ///
+    /// Returns `None` if the [`SegmentedQueue`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::SegmentedQueue;
+    ///
+    /// let queue: SegmentedQueue<usize, 2> = SegmentedQueue::default();
+    ///
+    /// queue.push(37);
+    /// queue.push(3);
+    /// queue.push(1);
+    ///
+    /// assert_eq!(queue.pop(), Some(37));
+    /// assert_eq!(queue.pop(), Some(3));
+    /// assert_eq!(queue.pop(), Some(1));
+    /// assert!(queue.pop().is_none());
+    /// ```
+    #[inline]
+    pub fn pop(&self) -> Option<T> {
+        let guard = Guard::new();
+        let mut oldest_ptr = self.oldest.load(Acquire, &guard);
+        while let Some(segment) = oldest_ptr.as_ref() {
+            let index = segment.pop_index.load(Acquire);
+            if index < SEGMENT_LEN {
+                if index >= segment.push_index.load(Acquire) {
+                    // No slots have been reserved.
+                    return None;
+                }
+                if segment
+                    .pop_index
+                    .compare_exchange_weak(index, index + 1, AcqRel, Acquire)
+                    .is_ok()
+                {
+                    if let Some(val) = segment.slots[index].take() {
+                        return Some(val);
+                    }
+                }
+                continue;
+            }
+
+            // All the slots in the segment have been claimed.
+            let next_ptr = segment.next.load(Acquire, &guard);
+            if next_ptr.is_null() {
+                return None;
+            }
+            match self.oldest.compare_exchange(
+                oldest_ptr,
+                (next_ptr.get_shared(), Tag::None),
+                AcqRel,
+                Acquire,
+                &guard,
+            ) {
+                Ok((_, ptr)) | Err((_, ptr)) => oldest_ptr = ptr,
+            }
+        }
+        None
+    }
+
+    /// Returns the number of instances in the [`SegmentedQueue`].
+    ///
+    /// Pushes that are in progress are also counted. This method iterates over all the segments
+    /// in the [`SegmentedQueue`], therefore its time complexity is `O(N / SEGMENT_LEN)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::SegmentedQueue;
+    ///
+    /// let queue: SegmentedQueue<usize, 2> = SegmentedQueue::default();
+    /// assert_eq!(queue.len(), 0);
+    ///
+    /// queue.push(7);
+    /// queue.push(11);
+    /// queue.push(13);
+    /// assert_eq!(queue.len(), 3);
+    ///
+    /// queue.pop();
+    /// assert_eq!(queue.len(), 2);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        let guard = Guard::new();
+        let mut len = 0;
+        let mut current = self.oldest.load(Acquire, &guard);
+        while let Some(segment) = current.as_ref() {
+            let pop_index = segment.pop_index.load(Acquire);
+            let push_index = segment.push_index.load(Acquire).min(SEGMENT_LEN);
+            len += push_index.saturating_sub(pop_index);
+            current = segment.next.load(Acquire, &guard);
+        }
+        len
+    }
+
+    /// Returns `true` if the [`SegmentedQueue`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::SegmentedQueue;
+    ///
+    /// let queue: SegmentedQueue<usize> = SegmentedQueue::default();
+    /// assert!(queue.is_empty());
+    ///
+    /// queue.push(7);
+    /// assert!(!queue.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        let guard = Guard::new();
+        let mut current = self.oldest.load(Acquire, &guard);
+        while let Some(segment) = current.as_ref() {
+            let pop_index = segment.pop_index.load(Acquire);
+            if pop_index < segment.push_index.load(Acquire).min(SEGMENT_LEN) {
+                return false;
+            }
+            if pop_index < SEGMENT_LEN {
+                return true;
+            }
+            current = segment.next.load(Acquire, &guard);
+        }
+        true
+    }
+}
+
+impl<T, const SEGMENT_LEN: usize> Debug for SegmentedQueue<T, SEGMENT_LEN> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentedQueue")
+            .field("len", &self.len())
+            .field("segment_len", &SEGMENT_LEN)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, const SEGMENT_LEN: usize> Default for SegmentedQueue<T, SEGMENT_LEN> {
+    /// Creates an empty [`SegmentedQueue`].
+    ///
+    /// The first segment is allocated when the first instance is pushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::queue::SegmentedQueue;
+    ///
+    /// let queue: SegmentedQueue<usize, 4> = SegmentedQueue::default();
+    /// assert!(queue.is_empty());
+    /// ```
+    #[inline]
+    fn default() -> Self {
+        Self {
+            oldest: AtomicShared::null(),
+            newest: AtomicShared::null(),
+        }
+    }
+}
+
+impl<T: UnwindSafe, const SEGMENT_LEN: usize> UnwindSafe for SegmentedQueue<T, SEGMENT_LEN> {}
+
+impl<T, const SEGMENT_LEN: usize> Default for Segment<T, SEGMENT_LEN> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            slots: std::array::from_fn(|_| Slot {
+                state: AtomicU8::new(VACANT),
+                instance: UnsafeCell::new(MaybeUninit::uninit()),
+            }),
+            push_index: AtomicUsize::new(0),
+            pop_index: AtomicUsize::new(0),
+            next: AtomicShared::null(),
+        }
+    }
+}
+
+impl<T> Slot<T> {
+    /// Writes the instance into the slot reserved by the caller.
+    ///
+    /// Returns the instance if a popper has abandoned the slot.
+    #[inline]
+    fn write(&self, val: T) -> Result<(), T> {
+        unsafe {
+            (*self.instance.get()).as_mut_ptr().write(val);
+        }
+        if self
+            .state
+            .compare_exchange(VACANT, OCCUPIED, Release, Relaxed)
+            .is_ok()
+        {
+            Ok(())
+        } else {
+            Err(unsafe { (*self.instance.get()).as_ptr().read() })
+        }
+    }
+
+    /// Moves the instance out of the slot claimed by the caller.
+    ///
+    /// Returns `None` and abandons the slot if the instance has not been written yet.
+    #[inline]
+    fn take(&self) -> Option<T> {
+        if self
+            .state
+            .compare_exchange(VACANT, ABANDONED, Acquire, Acquire)
+            .is_ok()
+        {
+            return None;
+        }
+        self.state.store(CONSUMED, Relaxed);
+        Some(unsafe { (*self.instance.get()).as_ptr().read() })
+    }
+}
+
+impl<T> Drop for Slot<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if *self.state.get_mut() == OCCUPIED {
+            unsafe {
+                self.instance.get_mut().as_mut_ptr().drop_in_place();
+            }
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for Slot<T> {}
+unsafe impl<T: Send> Sync for Slot<T> {}
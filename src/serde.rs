@@ -1,7 +1,7 @@
 //! This module implements helper types and traits for `serde`.
 
 use super::ebr::Guard;
-use super::{HashCache, HashIndex, HashMap, HashSet, TreeIndex};
+use super::{HashCache, HashIndex, HashMap, HashSet, Queue, Stack, TreeIndex};
 use serde::de::{Deserialize, MapAccess, SeqAccess, Visitor};
 use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
 use serde::Deserializer;
@@ -240,6 +240,7 @@ where
     where
         S: Serializer,
     {
+        // Entries are serialized in ascending key order.
         let mut map = serializer.serialize_map(Some(self.len()))?;
         let mut error = None;
         self.iter(&Guard::new()).any(|(k, v)| {
@@ -374,6 +375,8 @@ where
     where
         M: MapAccess<'d>,
     {
+        // Serialized entries are sorted, and appending sorted entries to a `Leaf` makes it split
+        // at the end, so the restored `TreeIndex` is as compact as the original one.
         let treeindex = TreeIndex::default();
         while let Some((key, val)) = access.next_entry()? {
             let _result = treeindex.insert(key, val);
@@ -404,6 +407,7 @@ where
     where
         S: Serializer,
     {
+        // Entries are serialized in ascending key order.
         let mut map = serializer.serialize_map(Some(self.len()))?;
         let mut error = None;
         self.iter(&Guard::new()).any(|(k, v)| {
@@ -420,3 +424,143 @@ where
         map.end()
     }
 }
+
+/// Helper type to allow `serde` to access [`Queue`] entries.
+pub struct QueueVisitor<T> {
+    marker: PhantomData<fn() -> Queue<T>>,
+}
+
+impl<T> QueueVisitor<T> {
+    fn new() -> Self {
+        QueueVisitor {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'d, T> Visitor<'d> for QueueVisitor<T>
+where
+    T: 'static + Deserialize<'d>,
+{
+    type Value = Queue<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Queue")
+    }
+
+    fn visit_seq<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: SeqAccess<'d>,
+    {
+        let queue = Queue::default();
+        while let Some(val) = access.next_element()? {
+            queue.push(val);
+        }
+        Ok(queue)
+    }
+}
+
+impl<'d, T> Deserialize<'d> for Queue<T>
+where
+    T: 'static + Deserialize<'d>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'d>,
+    {
+        deserializer.deserialize_seq(QueueVisitor::<T>::new())
+    }
+}
+
+impl<T> Serialize for Queue<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Entries are serialized from the oldest, and the number of entries has to be known in
+        // advance.
+        let guard = Guard::new();
+        let entries: Vec<&T> = self.iter(&guard).collect();
+        let mut seq = serializer.serialize_seq(Some(entries.len()))?;
+        for val in entries {
+            seq.serialize_element(val)?;
+        }
+        seq.end()
+    }
+}
+
+/// Helper type to allow `serde` to access [`Stack`] entries.
+pub struct StackVisitor<T> {
+    marker: PhantomData<fn() -> Stack<T>>,
+}
+
+impl<T> StackVisitor<T> {
+    fn new() -> Self {
+        StackVisitor {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'d, T> Visitor<'d> for StackVisitor<T>
+where
+    T: 'static + Deserialize<'d>,
+{
+    type Value = Stack<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Stack")
+    }
+
+    fn visit_seq<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: SeqAccess<'d>,
+    {
+        // Entries are deserialized from the newest, therefore they have to be pushed in reverse
+        // order.
+        let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some(val) = access.next_element()? {
+            entries.push(val);
+        }
+        let stack = Stack::default();
+        for val in entries.into_iter().rev() {
+            stack.push(val);
+        }
+        Ok(stack)
+    }
+}
+
+impl<'d, T> Deserialize<'d> for Stack<T>
+where
+    T: 'static + Deserialize<'d>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'d>,
+    {
+        deserializer.deserialize_seq(StackVisitor::<T>::new())
+    }
+}
+
+impl<T> Serialize for Stack<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Entries are serialized from the newest, and the number of entries has to be known in
+        // advance.
+        let guard = Guard::new();
+        let entries: Vec<&T> = self.iter(&guard).collect();
+        let mut seq = serializer.serialize_seq(Some(entries.len()))?;
+        for val in entries {
+            seq.serialize_element(val)?;
+        }
+        seq.end()
+    }
+}
@@ -1,7 +1,7 @@
 //! [`Stack`] is a lock-free concurrent last-in-first-out container.
 
 use super::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
-use super::linked_list::{Entry, LinkedList};
+use super::linked_list::{Entry, EntryPool, LinkedList};
 use std::fmt::{self, Debug};
 use std::iter::FusedIterator;
 use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
@@ -20,6 +20,24 @@ pub struct Iter<'g, T> {
     guard: &'g Guard,
 }
 
+/// An iterator that moves out of a [`Stack`].
+///
+/// [`IntoIter`] pops the newest entry first.
+pub struct IntoIter<T> {
+    stack: Stack<T>,
+}
+
+/// An iterator that pops the entries of a [`Stack`].
+///
+/// [`Drain`] pops the newest entry whenever it is advanced, therefore entries that are pushed
+/// while draining the [`Stack`] are also popped.
+pub struct Drain<'s, T> {
+    stack: &'s Stack<T>,
+}
+
+/// The maximum number of entries that [`Stack`] formats with [`Debug`].
+const DEBUG_MAX_ENTRIES: usize = 32;
+
 impl<T: 'static> Stack<T> {
     /// Pushes an instance of `T`.
     ///
@@ -46,6 +64,10 @@ impl<T: 'static> Stack<T> {
 
     /// Pushes an instance of `T` if the newest entry satisfies the given condition.
     ///
+    /// The condition is evaluated again whenever another thread updates the newest entry in the
+    /// meantime, therefore the newly pushed entry is always placed on top of an entry that
+    /// satisfies the condition, e.g., a monotonic [`Stack`] can be maintained without locks.
+    ///
     /// # Errors
     ///
     /// Returns an error along with the supplied instance if the condition is not met.
@@ -71,10 +93,45 @@ impl<T: 'static> Stack<T> {
         self.push_if_internal(val, cond, &Guard::new())
     }
 
+    /// Pushes an instance of `T` using a vacant entry taken from the supplied [`EntryPool`].
+    ///
+    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry. The entry is not
+    /// allocated, and popped entries can be returned to the [`EntryPool`] by
+    /// [`EntryPool::recycle`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the supplied instance if the [`EntryPool`] is empty; [`Stack::push`] can be used
+    /// to fall back to the allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::{LinkedEntryPool, Stack};
+    ///
+    /// let pool: LinkedEntryPool<usize> = LinkedEntryPool::new(1);
+    /// let stack: Stack<usize> = Stack::default();
+    ///
+    /// assert_eq!(stack.push_pooled(11, &pool).map(|e| **e), Ok(11));
+    /// assert_eq!(stack.push_pooled(17, &pool).map(|e| **e), Err(17));
+    /// assert_eq!(stack.pop().map(|e| **e), Some(11));
+    /// ```
+    #[inline]
+    pub fn push_pooled(&self, val: T, pool: &EntryPool<T>) -> Result<Shared<Entry<T>>, T> {
+        let new_entry = pool.take(val)?;
+        let guard = Guard::new();
+        let newest_ptr = self.cleanup_newest(self.newest.load(Acquire, &guard), &guard);
+        match self.push_entry_if(new_entry, newest_ptr, |_| true, &guard) {
+            Ok(entry) => Ok(entry),
+            Err(_) => unreachable!(),
+        }
+    }
+
     /// Returns a guarded reference to the newest entry.
     ///
     /// Returns `None` if the [`Stack`] is empty. The returned reference can survive as long as the
-    /// associated [`Guard`] is alive.
+    /// associated [`Guard`] is alive. [`Stack::pop_if`] pops the newest entry only if it still
+    /// satisfies the condition that the caller inspected.
     ///
     /// # Examples
     ///
@@ -96,6 +153,53 @@ impl<T: 'static> Stack<T> {
         self.cleanup_newest(self.newest.load(Acquire, guard), guard)
             .as_ref()
     }
+
+    /// Replaces the newest entry with a new entry containing the supplied instance.
+    ///
+    /// Returns the replaced entry, or `None` if the [`Stack`] was empty or the newest entry was
+    /// popped by another thread before it could be replaced; the new entry is pushed in both
+    /// cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Stack;
+    ///
+    /// let stack: Stack<usize> = Stack::default();
+    ///
+    /// assert!(stack.swap_top(3).is_none());
+    /// assert_eq!(stack.swap_top(7).map(|e| **e), Some(3));
+    /// assert_eq!(stack.len(), 1);
+    /// assert_eq!(stack.pop().map(|e| **e), Some(7));
+    /// ```
+    #[inline]
+    pub fn swap_top(&self, val: T) -> Option<Shared<Entry<T>>> {
+        let guard = Guard::new();
+        let new_entry = Shared::new(Entry::new(val));
+        let mut newest_ptr = self.cleanup_newest(self.newest.load(Acquire, &guard), &guard);
+        loop {
+            let next = newest_ptr
+                .as_ref()
+                .and_then(|newest_entry| newest_entry.next_ptr(Acquire, &guard).get_shared());
+            new_entry.next().swap((next, Tag::None), Relaxed);
+            match self.newest.compare_exchange_weak(
+                newest_ptr,
+                (Some(new_entry.clone()), Tag::None),
+                AcqRel,
+                Acquire,
+                &guard,
+            ) {
+                Ok(_) => {
+                    // Another thread may have popped the entry in the meantime.
+                    let newest_entry = newest_ptr.get_shared()?;
+                    return newest_entry.delete_self(Relaxed).then_some(newest_entry);
+                }
+                Err((_, actual_ptr)) => {
+                    newest_ptr = self.cleanup_newest(actual_ptr, &guard);
+                }
+            }
+        }
+    }
 }
 
 impl<T> Stack<T> {
@@ -224,7 +328,9 @@ impl<T> Stack<T> {
 
     /// Pops the newest entry if the entry satisfies the given condition.
     ///
-    /// Returns `None` if the [`Stack`] is empty.
+    /// Returns `None` if the [`Stack`] is empty. The condition is evaluated on the newest entry
+    /// which is then popped in a single step, therefore an entry that does not satisfy the
+    /// condition never has to be popped and pushed back.
     ///
     /// # Errors
     ///
@@ -341,6 +447,9 @@ impl<T> Stack<T> {
 
     /// Returns an [`Iter`].
     ///
+    /// The [`Iter`] does not pop entries, and entries that are popped while iterating may not be
+    /// visited.
+    ///
     /// # Examples
     ///
     /// ```
@@ -360,6 +469,7 @@ impl<T> Stack<T> {
     /// assert_eq!(*iter.next().unwrap(), 11);
     /// assert_eq!(*iter.next().unwrap(), 7);
     /// assert!(iter.next().is_none());
+    /// assert_eq!(stack.len(), 3);
     /// ```
     #[inline]
     pub fn iter<'g>(&self, guard: &'g Guard) -> Iter<'g, T> {
@@ -369,6 +479,32 @@ impl<T> Stack<T> {
         }
     }
 
+    /// Returns a [`Drain`] that pops the entries of the [`Stack`] in the reverse order of
+    /// insertion.
+    ///
+    /// Unlike [`Stack::pop_all`], entries are popped one by one as the [`Drain`] is advanced, and
+    /// entries that the [`Drain`] does not reach remain in the [`Stack`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Stack;
+    ///
+    /// let stack: Stack<usize> = Stack::default();
+    ///
+    /// stack.push(7);
+    /// stack.push(11);
+    /// stack.push(17);
+    ///
+    /// assert_eq!(stack.drain().next().map(|e| **e), Some(17));
+    /// assert_eq!(stack.drain().map(|e| **e).collect::<Vec<_>>(), vec![11, 7]);
+    /// assert!(stack.is_empty());
+    /// ```
+    #[inline]
+    pub fn drain(&self) -> Drain<'_, T> {
+        Drain { stack: self }
+    }
+
     /// Pushes an entry into the [`Stack`].
     fn push_if_internal<F: FnMut(Option<&Entry<T>>) -> bool>(
         &self,
@@ -376,18 +512,36 @@ impl<T> Stack<T> {
         mut cond: F,
         guard: &Guard,
     ) -> Result<Shared<Entry<T>>, T> {
-        let mut newest_ptr = self.cleanup_newest(self.newest.load(Acquire, guard), guard);
+        let newest_ptr = self.cleanup_newest(self.newest.load(Acquire, guard), guard);
         if !cond(newest_ptr.as_ref()) {
             // The condition is not met.
             return Err(val);
         }
 
-        let mut new_entry = unsafe { Shared::new_unchecked(Entry::new(val)) };
+        let new_entry = unsafe { Shared::new_unchecked(Entry::new(val)) };
+        self.push_entry_if(new_entry, newest_ptr, cond, guard)
+            .map_err(|mut new_entry| {
+                // Extract the instance from the temporary entry.
+                unsafe { new_entry.get_mut().unwrap_unchecked().take_inner() }
+            })
+    }
+
+    /// Pushes the supplied entry on top of `newest_ptr`, or on top of the actual newest entry if
+    /// it satisfies the given condition.
+    ///
+    /// Returns the supplied entry if the condition is not met.
+    fn push_entry_if<'g, F: FnMut(Option<&Entry<T>>) -> bool>(
+        &self,
+        new_entry: Shared<Entry<T>>,
+        mut newest_ptr: Ptr<'g, Entry<T>>,
+        mut cond: F,
+        guard: &'g Guard,
+    ) -> Result<Shared<Entry<T>>, Shared<Entry<T>>> {
         loop {
             new_entry
                 .next()
                 .swap((newest_ptr.get_shared(), Tag::None), Relaxed);
-            let result = self.newest.compare_exchange(
+            let result = self.newest.compare_exchange_weak(
                 newest_ptr,
                 (Some(new_entry.clone()), Tag::None),
                 AcqRel,
@@ -400,14 +554,11 @@ impl<T> Stack<T> {
                     newest_ptr = self.cleanup_newest(actual_ptr, guard);
                     if !cond(newest_ptr.as_ref()) {
                         // The condition is not met.
-                        break;
+                        return Err(new_entry);
                     }
                 }
             }
         }
-
-        // Extract the instance from the temporary entry.
-        Err(unsafe { new_entry.get_mut().unwrap_unchecked().take_inner() })
     }
 
     /// Cleans up logically removed entries that are attached to `newest`.
@@ -443,7 +594,7 @@ impl<T: Clone> Clone for Stack<T> {
     fn clone(&self) -> Self {
         let self_clone = Self::default();
         let guard = Guard::new();
-        let mut current = self.newest.load(Acquire, &guard);
+        let mut current = self.cleanup_newest(self.newest.load(Acquire, &guard), &guard);
         let mut oldest: Option<Shared<Entry<T>>> = None;
         while let Some(entry) = current.as_ref() {
             let new_entry = unsafe { Shared::new_unchecked(Entry::new((**entry).clone())) };
@@ -464,17 +615,34 @@ impl<T: Clone> Clone for Stack<T> {
 }
 
 impl<T: Debug> Debug for Stack<T> {
+    /// Formats the entries in the [`Stack`], newest first.
+    ///
+    /// At most `32` entries are formatted, and the number of the remaining entries is appended if
+    /// there are more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Stack;
+    ///
+    /// let stack: Stack<usize> = Stack::default();
+    /// (0..4).for_each(|v| { stack.push(v); });
+    /// assert_eq!(format!("{stack:?}"), "[3, 2, 1, 0]");
+    ///
+    /// (4..64).for_each(|v| { stack.push(v); });
+    /// assert!(format!("{stack:?}").ends_with("33, 32, ..: 32 more]"));
+    /// ```
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut d = f.debug_set();
         let guard = Guard::new();
-        let mut current = self.newest.load(Acquire, &guard);
-        while let Some(entry) = current.as_ref() {
-            let next = entry.next_ptr(Acquire, &guard);
-            d.entry(entry);
-            current = next;
+        let mut iter = self.iter(&guard);
+        let mut debug_list = f.debug_list();
+        debug_list.entries(iter.by_ref().take(DEBUG_MAX_ENTRIES));
+        let remaining = iter.count();
+        if remaining != 0 {
+            debug_list.entry(&format_args!("..: {remaining} more"));
         }
-        d.finish()
+        debug_list.finish()
     }
 }
 
@@ -487,6 +655,61 @@ impl<T> Default for Stack<T> {
     }
 }
 
+impl<T: 'static> Extend<T> for Stack<T> {
+    /// Pushes all the instances of `T` that the iterator yields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Stack;
+    ///
+    /// let mut stack: Stack<usize> = Stack::default();
+    ///
+    /// stack.extend([37, 3, 1]);
+    /// assert_eq!(stack.len(), 3);
+    /// ```
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for val in iter {
+            self.push(val);
+        }
+    }
+}
+
+impl<T> IntoIterator for Stack<T> {
+    type Item = Shared<Entry<T>>;
+    type IntoIter = IntoIter<T>;
+
+    /// Returns an [`IntoIter`] that pops the entries of the [`Stack`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Stack;
+    ///
+    /// let stack: Stack<usize> = Stack::default();
+    ///
+    /// stack.push(37);
+    /// stack.push(3);
+    /// stack.push(1);
+    ///
+    /// assert_eq!(stack.into_iter().map(|e| **e).collect::<Vec<_>>(), vec![1, 3, 37]);
+    /// ```
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { stack: self }
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = Shared<Entry<T>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stack.pop()
+    }
+}
+
 impl<'g, T> FusedIterator for Iter<'g, T> {}
 
 impl<'g, T> Iterator for Iter<'g, T> {
@@ -502,3 +725,14 @@ impl<'g, T> Iterator for Iter<'g, T> {
         }
     }
 }
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = Shared<Entry<T>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stack.pop()
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod hashmap_test {
     use crate::hash_map::{self, Entry, Reserve};
-    use crate::HashMap;
+    use crate::{Equivalent, HashMap};
     use proptest::prelude::*;
     use proptest::strategy::ValueTree;
     use proptest::test_runner::TestRunner;
@@ -588,6 +588,32 @@ mod hashmap_test {
         assert_eq!(hashmap2.len(), 0);
     }
 
+    #[test]
+    fn equivalent_key() {
+        #[derive(Hash)]
+        struct KeyView<'a>(&'a str, u32);
+        impl Equivalent<(String, u32)> for KeyView<'_> {
+            fn equivalent(&self, key: &(String, u32)) -> bool {
+                self.0 == key.0 && self.1 == key.1
+            }
+        }
+
+        let hashmap: HashMap<(String, u32), u32> = HashMap::default();
+        for i in 0..256 {
+            assert!(hashmap.insert((i.to_string(), i), i).is_ok());
+        }
+        for i in 0..256 {
+            let name = i.to_string();
+            assert_eq!(hashmap.read(&KeyView(&name, i), |_, v| *v), Some(i));
+            assert!(!hashmap.contains(&KeyView(&name, i + 1)));
+        }
+        assert_eq!(
+            hashmap.remove(&KeyView("7", 7)),
+            Some(((String::from("7"), 7), 7))
+        );
+        assert!(!hashmap.contains(&KeyView("7", 7)));
+    }
+
     #[cfg_attr(miri, ignore)]
     #[test]
     fn iterator() {
@@ -1571,17 +1597,20 @@ mod hashcache_test {
 #[cfg(test)]
 mod treeindex_test {
     use crate::ebr::Guard;
-    use crate::tree_index::{Iter, Range};
-    use crate::TreeIndex;
+    use crate::tree_index::{Entry, IntoIter, Iter, Range, RangeStream, WatchEvent};
+    use crate::{Comparable, Equivalent, TreeIndex};
     use proptest::prelude::*;
     use proptest::strategy::ValueTree;
     use proptest::test_runner::TestRunner;
     use std::collections::BTreeSet;
-    use std::ops::RangeInclusive;
+    use std::ops::Bound::{self, Excluded, Included, Unbounded};
+    use std::ops::{ControlFlow, RangeInclusive};
     use std::panic::UnwindSafe;
+    use std::pin::Pin;
     use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
     use std::sync::atomic::{AtomicBool, AtomicUsize};
     use std::sync::{Arc, Barrier};
+    use std::task::{Context, Poll};
     use std::thread;
     use tokio::sync::Barrier as AsyncBarrier;
     use tokio::task;
@@ -1589,9 +1618,12 @@ mod treeindex_test {
     static_assertions::assert_impl_all!(TreeIndex<String, String>: Send, Sync, UnwindSafe);
     static_assertions::assert_impl_all!(Iter<'static, 'static, String, String>: UnwindSafe);
     static_assertions::assert_impl_all!(Range<'static, 'static, String, String, RangeInclusive<String>>: UnwindSafe);
+    static_assertions::assert_impl_all!(IntoIter<String, String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_impl_all!(RangeStream<'static, String, String>: Send, Sync, Unpin);
     static_assertions::assert_not_impl_all!(TreeIndex<String, *const String>: Send, Sync, UnwindSafe);
     static_assertions::assert_not_impl_all!(Iter<'static, 'static, String, *const String>: Send, Sync, UnwindSafe);
     static_assertions::assert_not_impl_all!(Range<'static, 'static, String, *const String, RangeInclusive<String>>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(RangeStream<'static, String, *const String>: Send, Sync);
 
     struct R(&'static AtomicUsize);
     impl R {
@@ -1805,6 +1837,393 @@ mod treeindex_test {
         }
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn remove_range_to() {
+        let num_tasks = 2;
+        let workload_size = 4096;
+        for _ in 0..16 {
+            let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::default());
+            let mut task_handles = Vec::with_capacity(num_tasks);
+            let barrier = Arc::new(AsyncBarrier::new(num_tasks));
+            let watermark = Arc::new(AtomicUsize::default());
+            for task_id in 0..num_tasks {
+                let barrier_clone = barrier.clone();
+                let watermark_clone = watermark.clone();
+                let tree_clone = tree.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    if task_id == 0 {
+                        for k in 1..=workload_size {
+                            assert!(tree_clone.insert_async(k, k).await.is_ok());
+                            watermark_clone.store(k, Release);
+                        }
+                    } else {
+                        loop {
+                            let watermark = watermark_clone.load(Acquire);
+                            if watermark == workload_size {
+                                break;
+                            } else if watermark <= 1 {
+                                task::yield_now().await;
+                                continue;
+                            }
+                            if watermark % 2 == 0 {
+                                tree_clone.remove_range_to(&watermark);
+                            } else {
+                                tree_clone.remove_range_to_async(&watermark).await;
+                            }
+                            assert!(!tree_clone.contains(&(watermark - 1)));
+                            assert!(tree_clone.contains(&watermark));
+                        }
+                    }
+                }));
+            }
+
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+
+            tree.remove_range_to_async(&workload_size).await;
+            assert_eq!(tree.len(), 1);
+            assert_eq!(tree.depth(), 1);
+            tree.remove_range_to_async(&(workload_size + 1)).await;
+            assert!(tree.is_empty());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn remove_range_async() {
+        let num_tasks = 2;
+        let workload_size = 4096;
+        for _ in 0..16 {
+            let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::default());
+            for k in 0..workload_size {
+                assert!(tree.insert_async(k * 2, k).await.is_ok());
+            }
+            let mut task_handles = Vec::with_capacity(num_tasks);
+            let barrier = Arc::new(AsyncBarrier::new(num_tasks));
+            for task_id in 0..num_tasks {
+                let barrier_clone = barrier.clone();
+                let tree_clone = tree.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    if task_id == 0 {
+                        for k in 0..workload_size {
+                            assert!(tree_clone.insert_async(k * 2 + 1, k).await.is_ok());
+                        }
+                    } else {
+                        tree_clone
+                            .remove_range_async(workload_size / 2..workload_size * 3 / 2)
+                            .await;
+                    }
+                }));
+            }
+
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+
+            for k in (0..workload_size / 2).chain(workload_size * 3 / 2..workload_size * 2) {
+                assert!(tree.contains(&k), "{k}");
+            }
+            for k in (workload_size / 2..workload_size * 3 / 2).filter(|k| k % 2 == 0) {
+                assert!(!tree.contains(&k), "{k}");
+            }
+
+            tree.remove_range_async(..).await;
+            assert!(tree.is_empty());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn modify() {
+        let num_tasks = 4;
+        let workload_size = 256;
+        for _ in 0..16 {
+            let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::default());
+            for k in 0..workload_size {
+                assert!(tree.insert(k, 0).is_ok());
+            }
+            let mut task_handles = Vec::with_capacity(num_tasks);
+            let barrier = Arc::new(AsyncBarrier::new(num_tasks + 1));
+            for task_id in 0..num_tasks {
+                let barrier_clone = barrier.clone();
+                let tree_clone = tree.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    for k in 0..workload_size {
+                        if task_id % 2 == 0 {
+                            assert!(tree_clone.modify(&k, |v| *v + 1));
+                        } else {
+                            assert!(tree_clone.modify_async(&k, |v| *v + 1).await);
+                        }
+                    }
+                    assert!(!tree_clone.modify(&workload_size, |v| *v + 1));
+                }));
+            }
+
+            barrier.wait().await;
+            for _ in 0..num_tasks {
+                assert_eq!(tree.range(.., &Guard::new()).count(), workload_size);
+                task::yield_now().await;
+            }
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+            for (_, v) in tree.iter(&Guard::new()) {
+                assert_eq!(*v, num_tasks);
+            }
+            assert_eq!(tree.len(), workload_size);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn compare_exchange() {
+        let num_tasks = 4;
+        let workload_size = 256;
+        for _ in 0..16 {
+            let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::default());
+            for k in 0..workload_size {
+                assert!(tree.insert(k, 0).is_ok());
+            }
+            let mut task_handles = Vec::with_capacity(num_tasks);
+            let barrier = Arc::new(AsyncBarrier::new(num_tasks));
+            for task_id in 0..num_tasks {
+                let barrier_clone = barrier.clone();
+                let tree_clone = tree.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    for k in 0..workload_size {
+                        let mut expected = tree_clone.peek_with(&k, |_, v| *v).unwrap();
+                        loop {
+                            let result = if task_id % 2 == 0 {
+                                tree_clone.compare_exchange(&k, &expected, expected + 1)
+                            } else {
+                                tree_clone
+                                    .compare_exchange_async(&k, &expected, expected + 1)
+                                    .await
+                            };
+                            match result {
+                                Ok(()) => break,
+                                Err(current) => expected = current.unwrap(),
+                            }
+                        }
+                    }
+                    assert_eq!(
+                        tree_clone.compare_exchange(&workload_size, &0, 1),
+                        Err(None)
+                    );
+                }));
+            }
+
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+            for (_, v) in tree.iter(&Guard::new()) {
+                assert_eq!(*v, num_tasks);
+            }
+            assert_eq!(tree.len(), workload_size);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn upsert() {
+        let num_tasks = 4;
+        let workload_size = 256;
+        for _ in 0..16 {
+            let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::default());
+            let num_inserted = Arc::new(AtomicUsize::new(0));
+            let num_events = Arc::new([AtomicUsize::new(0), AtomicUsize::new(0)]);
+            let num_events_clone = num_events.clone();
+            tree.watch(.., move |event| match event {
+                WatchEvent::Inserted(_, v) => {
+                    assert_eq!(**v, 1);
+                    num_events_clone[0].fetch_add(1, Relaxed);
+                }
+                WatchEvent::Modified(_, v) => {
+                    assert!(**v > 1);
+                    num_events_clone[1].fetch_add(1, Relaxed);
+                }
+                _ => unreachable!(),
+            });
+            let mut task_handles = Vec::with_capacity(num_tasks);
+            let barrier = Arc::new(AsyncBarrier::new(num_tasks));
+            for task_id in 0..num_tasks {
+                let barrier_clone = barrier.clone();
+                let num_inserted_clone = num_inserted.clone();
+                let tree_clone = tree.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    for k in 0..workload_size {
+                        let inserted = if task_id % 2 == 0 {
+                            tree_clone.upsert(k, || 1, |v| *v + 1)
+                        } else {
+                            tree_clone.upsert_async(k, || 1, |v| *v + 1).await
+                        };
+                        if inserted {
+                            num_inserted_clone.fetch_add(1, Relaxed);
+                        }
+                    }
+                }));
+            }
+
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+            assert_eq!(num_inserted.load(Relaxed), workload_size);
+            assert_eq!(num_events[0].load(Relaxed), workload_size);
+            assert_eq!(num_events[1].load(Relaxed), workload_size * (num_tasks - 1));
+            for (_, v) in tree.iter(&Guard::new()) {
+                assert_eq!(*v, num_tasks);
+            }
+            assert_eq!(tree.len(), workload_size);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn entry() {
+        let num_tasks = 4;
+        let workload_size = 256;
+        for _ in 0..16 {
+            let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::default());
+            let mut task_handles = Vec::with_capacity(num_tasks);
+            let barrier = Arc::new(AsyncBarrier::new(num_tasks));
+            for task_id in 0..num_tasks {
+                let barrier_clone = barrier.clone();
+                let tree_clone = tree.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    for k in 0..workload_size {
+                        let guard = Guard::new();
+                        let v = *tree_clone
+                            .entry(k, &guard)
+                            .or_insert(k * num_tasks + task_id);
+                        assert_eq!(v / num_tasks, k);
+                        assert_eq!(tree_clone.peek(&k, &guard), Some(&v));
+                    }
+                }));
+            }
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+            assert_eq!(tree.len(), workload_size);
+        }
+    }
+
+    #[test]
+    fn vacant_entry() {
+        let workload_size = 4096;
+        let tree: TreeIndex<usize, usize> = TreeIndex::default();
+        for k in 0..workload_size {
+            assert!(tree.insert(k * 2, k).is_ok());
+        }
+
+        // The leaves found by the entries are split in the meantime.
+        let guard = Guard::new();
+        let entries = (0..workload_size / 4)
+            .map(|k| match tree.entry(k * 8 + 1, &guard) {
+                Entry::Vacant(entry) => entry,
+                Entry::Occupied(_) => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        for k in 0..workload_size {
+            if k % 4 != 0 {
+                assert!(tree.insert(k * 2 + 1, k).is_ok());
+            }
+        }
+        for entry in entries {
+            let k = *entry.key();
+            assert_eq!(entry.insert(k), Ok(&k));
+            assert_eq!(tree.peek(&k, &guard), Some(&k));
+        }
+        assert_eq!(tree.len(), workload_size * 2);
+
+        // The leaves found by the entries are detached in the meantime.
+        let Entry::Vacant(entry) = tree.entry(workload_size * 4, &guard) else {
+            unreachable!();
+        };
+        tree.clear();
+        assert_eq!(entry.insert(1), Ok(&1));
+        assert_eq!(tree.peek(&(workload_size * 4), &guard), Some(&1));
+        for k in 0..workload_size {
+            assert!(tree.insert(k * 2, k).is_ok());
+        }
+        let Entry::Vacant(entry) = tree.entry(workload_size + 1, &guard) else {
+            unreachable!();
+        };
+        tree.remove_range(workload_size / 2..workload_size * 3 / 2);
+        assert_eq!(entry.insert(1), Ok(&1));
+        assert_eq!(tree.peek(&(workload_size + 1), &guard), Some(&1));
+    }
+
+    #[test]
+    fn range_stream() {
+        let tree: TreeIndex<usize, usize> = TreeIndex::default();
+        for k in 0..256 {
+            assert!(tree.insert(k, k).is_ok());
+        }
+        let mut stream = tree.range_async(16..);
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        let mut entries = Vec::new();
+        let mut num_yields = 0;
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(entry)) => entries.push(entry),
+                Poll::Ready(None) => break,
+                Poll::Pending => num_yields += 1,
+            }
+        }
+        assert_eq!(entries, (16..256).map(|k| (k, k)).collect::<Vec<_>>());
+        assert!(num_yields > 1);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn range_async() {
+        let num_tasks = 4;
+        let workload_size = 4096;
+        let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::default());
+        for k in 0..workload_size {
+            assert!(tree.insert(k * 2, k).is_ok());
+        }
+        let mut task_handles = Vec::with_capacity(num_tasks);
+        let barrier = Arc::new(AsyncBarrier::new(num_tasks));
+        for task_id in 0..num_tasks {
+            let barrier_clone = barrier.clone();
+            let tree_clone = tree.clone();
+            task_handles.push(tokio::task::spawn(async move {
+                barrier_clone.wait().await;
+                if task_id == 0 {
+                    for k in 0..workload_size {
+                        assert!(tree_clone.insert_async(k * 2 + 1, k).await.is_ok());
+                    }
+                } else {
+                    let mut prev = None;
+                    let mut num_even = 0;
+                    let mut stream = tree_clone.range_async(task_id..workload_size * 2 - task_id);
+                    while let Some((k, v)) = stream.next().await {
+                        assert!(prev.map_or(true, |prev| prev < k));
+                        prev.replace(k);
+                        if k % 2 == 0 {
+                            assert_eq!(k / 2, v);
+                            num_even += 1;
+                        }
+                    }
+                    assert!(stream.next().await.is_none());
+                    assert_eq!(num_even, workload_size - task_id);
+                }
+            }));
+        }
+        for r in futures::future::join_all(task_handles).await {
+            assert!(r.is_ok());
+        }
+    }
+
     #[cfg_attr(miri, ignore)]
     #[test]
     fn reclaim() {
@@ -1943,96 +2362,59 @@ mod treeindex_test {
 
     #[cfg_attr(miri, ignore)]
     #[test]
-    fn complex() {
-        let range = 4096;
-        let num_threads = 16;
+    fn cursor() {
+        let workload_size = 1024;
+        let num_threads = 4;
         let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::new());
-        for t in 0..num_threads {
-            // insert markers
-            assert!(tree.insert(t * range, t * range).is_ok());
+        for k in 0..workload_size {
+            assert!(tree.insert(k * 2, k).is_ok());
         }
-        let stopped: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
-        let barrier = Arc::new(Barrier::new(num_threads + 1));
+        let barrier = Arc::new(Barrier::new(num_threads));
         let mut thread_handles = Vec::with_capacity(num_threads);
         for thread_id in 0..num_threads {
             let tree_copied = tree.clone();
-            let stopped_copied = stopped.clone();
             let barrier_copied = barrier.clone();
             thread_handles.push(thread::spawn(move || {
-                let first_key = thread_id * range;
                 barrier_copied.wait();
-                while !stopped_copied.load(Relaxed) {
-                    for key in (first_key + 1)..(first_key + range) {
-                        assert!(tree_copied.insert(key, key).is_ok());
+                if thread_id == 0 {
+                    for k in 0..workload_size {
+                        assert!(tree_copied.insert(k * 2 + 1, k).is_ok());
                     }
-                    for key in (first_key + 1)..(first_key + range) {
-                        assert!(tree_copied
-                            .peek_with(&key, |key, val| assert_eq!(key, val))
-                            .is_some());
-                    }
-                    {
-                        let guard = Guard::new();
-                        let mut range_scanner = tree_copied.range(first_key.., &guard);
-                        let mut entry = range_scanner.next().unwrap();
-                        assert_eq!(entry, (&first_key, &first_key));
-                        entry = range_scanner.next().unwrap();
-                        assert_eq!(entry, (&(first_key + 1), &(first_key + 1)));
-                        entry = range_scanner.next().unwrap();
-                        assert_eq!(entry, (&(first_key + 2), &(first_key + 2)));
-                        entry = range_scanner.next().unwrap();
-                        assert_eq!(entry, (&(first_key + 3), &(first_key + 3)));
-                    }
-
-                    let key_at_halfway = first_key + range / 2;
-                    for key in (first_key + 1)..(first_key + range) {
-                        if key == key_at_halfway {
-                            let guard = Guard::new();
-                            let mut range_scanner = tree_copied.range((first_key + 1).., &guard);
-                            let entry = range_scanner.next().unwrap();
-                            assert_eq!(entry, (&key_at_halfway, &key_at_halfway));
-                            let entry = range_scanner.next().unwrap();
-                            assert_eq!(entry, (&(key_at_halfway + 1), &(key_at_halfway + 1)));
-                        }
-                        assert!(tree_copied.remove(&key));
-                        assert!(!tree_copied.remove(&key));
-                        assert!(tree_copied.peek_with(&(first_key + 1), |_, _| ()).is_none());
-                        assert!(tree_copied.peek_with(&key, |_, _| ()).is_none());
-                    }
-                    for key in (first_key + 1)..(first_key + range) {
-                        assert!(tree_copied
-                            .peek_with(&key, |key, val| assert_eq!(key, val))
-                            .is_none());
+                    for k in 0..workload_size {
+                        assert!(tree_copied.remove(&(k * 2 + 1)));
                     }
+                    return;
                 }
-            }));
-        }
-        barrier.wait();
-        for _ in 0..512 {
-            let mut found_0 = false;
-            let mut found_markers = 0;
-            let mut prev_marker = 0;
-            let mut prev = 0;
-            let guard = Guard::new();
-            for iter in tree.iter(&guard) {
-                let current = *iter.0;
-                if current % range == 0 {
-                    found_markers += 1;
-                    if current == 0 {
-                        found_0 = true;
+                let guard = Guard::new();
+                let mut cursor = tree_copied.lower_bound(&0, &guard);
+                let mut num_even = usize::from(cursor.get().is_some());
+                let mut prev = *cursor.get().unwrap().0;
+                while let Some((k, _)) = cursor.move_next() {
+                    assert!(prev < *k);
+                    prev = *k;
+                    if k % 2 == 0 {
+                        num_even += 1;
                     }
-                    if current > 0 {
-                        assert_eq!(prev_marker + range, current);
+                }
+                assert_eq!(num_even, workload_size);
+
+                let mut num_even = 0;
+                let mut prev = usize::MAX;
+                while let Some((k, _)) = cursor.move_prev() {
+                    assert!(prev > *k);
+                    prev = *k;
+                    if k % 2 == 0 {
+                        num_even += 1;
                     }
-                    prev_marker = current;
                 }
-                assert!(prev == 0 || prev < current);
-                prev = current;
-            }
-            assert!(found_0);
-            assert_eq!(found_markers, num_threads);
-        }
+                assert_eq!(num_even, workload_size);
 
-        stopped.store(true, Release);
+                let cursor = tree_copied.lower_bound(&(thread_id * 2), &guard);
+                assert_eq!(cursor.get(), Some((&(thread_id * 2), &thread_id)));
+                let cursor = tree_copied.upper_bound(&(workload_size * 2), &guard);
+                assert!(cursor.get().is_none());
+            }));
+        }
         for handle in thread_handles {
             handle.join().unwrap();
         }
@@ -2040,186 +2422,1123 @@ mod treeindex_test {
 
     #[cfg_attr(miri, ignore)]
     #[test]
-    fn remove() {
-        let num_threads = 16;
-        let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::new());
-        let barrier = Arc::new(Barrier::new(num_threads));
-        let mut thread_handles = Vec::with_capacity(num_threads);
-        for thread_id in 0..num_threads {
-            let tree_copied = tree.clone();
-            let barrier_copied = barrier.clone();
-            thread_handles.push(thread::spawn(move || {
-                barrier_copied.wait();
-                for _ in 0..4096 {
-                    let range = 0..32;
-                    let inserted = range
-                        .clone()
-                        .filter(|i| tree_copied.insert(*i, thread_id).is_ok())
-                        .count();
-                    let found = range
-                        .clone()
-                        .filter(|i| {
-                            tree_copied
-                                .peek_with(i, |_, v| *v == thread_id)
-                                .map_or(false, |t| t)
-                        })
-                        .count();
-                    let removed = range
-                        .clone()
-                        .filter(|i| tree_copied.remove_if(i, |v| *v == thread_id))
-                        .count();
-                    let removed_again = range
-                        .clone()
-                        .filter(|i| tree_copied.remove_if(i, |v| *v == thread_id))
-                        .count();
-                    assert_eq!(removed_again, 0);
-                    assert_eq!(found, removed, "{inserted} {found} {removed}");
-                    assert_eq!(inserted, found, "{inserted} {found} {removed}");
-                }
-            }));
+    fn count_range() {
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        for k in 0..4096 {
+            assert!(tree.insert(k * 3, k).is_ok());
         }
-        for handle in thread_handles {
-            handle.join().unwrap();
+        for k in (0..4096).step_by(7) {
+            assert!(tree.remove(&(k * 3)));
         }
-        assert_eq!(tree.len(), 0);
-        assert_eq!(tree.depth(), 0);
+        let guard = Guard::new();
+        for start in (0..4096 * 3).step_by(97) {
+            for len in [0, 1, 2, 13, 14, 15, 64, 1024, 8192] {
+                assert_eq!(
+                    tree.count_range(start..start + len),
+                    tree.range(start..start + len, &guard).count()
+                );
+                assert_eq!(
+                    tree.count_range(start..=start + len),
+                    tree.range(start..=start + len, &guard).count()
+                );
+            }
+            assert_eq!(
+                tree.count_range(start..),
+                tree.range(start.., &guard).count()
+            );
+            assert_eq!(
+                tree.count_range(..start),
+                tree.range(..start, &guard).count()
+            );
+            assert_eq!(
+                tree.count_range((Excluded(start), Unbounded)),
+                tree.range((Excluded(start), Unbounded), &guard).count()
+            );
+        }
+        assert_eq!(tree.count_range(..), tree.len());
     }
 
-    #[cfg_attr(miri, ignore)]
     #[test]
-    fn string_key() {
-        let tree1: TreeIndex<String, u32> = TreeIndex::default();
-        let tree2: TreeIndex<u32, String> = TreeIndex::default();
-        let mut checker1 = BTreeSet::new();
-        let mut checker2 = BTreeSet::new();
-        let mut runner = TestRunner::default();
-        let test_size = 4096;
-        for i in 0..test_size {
-            let prop_str = "[a-z]{1,16}".new_tree(&mut runner).unwrap();
-            let str_val = prop_str.current();
-            if tree1.insert(str_val.clone(), i).is_ok() {
-                checker1.insert((str_val.clone(), i));
-            }
-            let str_borrowed = str_val.as_str();
-            assert!(tree1.peek_with(str_borrowed, |_, _| ()).is_some());
+    fn into_iter() {
+        let workload_size = 1024;
+        let tree: TreeIndex<usize, String> = TreeIndex::new();
+        assert!(tree.clone().into_iter().next().is_none());
+        for k in (0..workload_size).rev() {
+            assert!(tree.insert(k, k.to_string()).is_ok());
+        }
+        for k in (0..workload_size).step_by(3) {
+            assert!(tree.remove(&k));
+        }
+        let mut into_iter = tree.clone().into_iter();
+        for k in (0..workload_size / 2).filter(|k| k % 3 != 0) {
+            assert_eq!(into_iter.next(), Some((k, k.to_string())));
+        }
+        drop(into_iter);
 
-            if tree2.insert(i, str_val.clone()).is_ok() {
-                checker2.insert((i, str_val.clone()));
-            }
+        let expected = (0..workload_size)
+            .filter(|k| k % 3 != 0)
+            .map(|k| (k, k.to_string()))
+            .collect::<Vec<_>>();
+        let guard = Guard::new();
+        let peeked = tree.peek(&1, &guard).unwrap();
+        assert_eq!(tree.into_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(peeked, "1");
+    }
+
+    #[test]
+    fn read_with() {
+        let tree: TreeIndex<String, String> = TreeIndex::new();
+        let guard = Guard::new();
+        assert!(tree.read_with("1", |_, v| v.as_str(), &guard).is_none());
+        for k in 0..64 {
+            assert!(tree.insert(k.to_string(), format!("v{k}")).is_ok());
         }
-        for iter in &checker1 {
-            let v = tree1.peek_with(iter.0.as_str(), |_, v| *v);
-            assert_eq!(v.unwrap(), iter.1);
+        let vals = (0..64)
+            .filter_map(|k| tree.read_with(k.to_string().as_str(), |_, v| v.as_str(), &guard))
+            .collect::<Vec<&str>>();
+        for k in 0..64 {
+            assert!(tree.remove(k.to_string().as_str()));
         }
-        for iter in &checker2 {
-            let v = tree2.peek_with(&iter.0, |_, v| v.clone());
-            assert_eq!(v.unwrap(), iter.1);
+        assert!(tree.is_empty());
+        for (k, v) in vals.iter().enumerate() {
+            assert_eq!(*v, format!("v{k}"));
         }
     }
 
     #[cfg_attr(miri, ignore)]
     #[test]
-    fn scanner() {
-        let data_size = 4096;
-        for _ in 0..64 {
-            let tree: Arc<TreeIndex<usize, u64>> = Arc::new(TreeIndex::default());
-            let barrier = Arc::new(Barrier::new(3));
-            let inserted = Arc::new(AtomicUsize::new(0));
-            let removed = Arc::new(AtomicUsize::new(data_size));
-            let mut thread_handles = Vec::new();
-            for _ in 0..2 {
-                let tree_copied = tree.clone();
-                let barrier_copied = barrier.clone();
-                let inserted_copied = inserted.clone();
-                let removed_copied = removed.clone();
-                let thread_handle = thread::spawn(move || {
-                    // test insert
-                    for _ in 0..2 {
-                        barrier_copied.wait();
-                        let max = inserted_copied.load(Acquire);
-                        let mut prev = 0;
-                        let mut iterated = 0;
-                        let guard = Guard::new();
-                        for iter in tree_copied.iter(&guard) {
-                            assert!(
-                                prev == 0
-                                    || (*iter.0 <= max && prev + 1 == *iter.0)
-                                    || *iter.0 > prev
-                            );
-                            prev = *iter.0;
-                            iterated += 1;
-                        }
-                        assert!(iterated >= max);
-                    }
-                    // test remove
-                    for _ in 0..2 {
-                        barrier_copied.wait();
-                        let mut prev = 0;
-                        let max = removed_copied.load(Acquire);
-                        let guard = Guard::new();
-                        for iter in tree_copied.iter(&guard) {
-                            let current = *iter.0;
-                            assert!(current < max);
-                            assert!(prev + 1 == current || prev == 0);
-                            prev = current;
-                        }
-                    }
-                });
-                thread_handles.push(thread_handle);
+    fn remove_range_inner() {
+        let workload_size = 4096;
+        for (start, end) in [(2048, 6144), (1000, 7000), (100, 200), (7000, 9000)] {
+            let tree: TreeIndex<usize, usize> = TreeIndex::new();
+            for k in 0..workload_size {
+                assert!(tree.insert(k * 2, k).is_ok());
             }
-            // insert
-            barrier.wait();
-            for i in 0..data_size {
-                if i == data_size / 2 {
-                    barrier.wait();
-                }
-                assert!(tree.insert(i, 0).is_ok());
-                inserted.store(i, Release);
+            tree.remove_range(start..end);
+            let expected = (0..workload_size)
+                .map(|k| k * 2)
+                .filter(|k| !(start..end).contains(k))
+                .collect::<Vec<_>>();
+            let guard = Guard::new();
+            assert_eq!(
+                tree.iter(&guard).map(|(k, _)| *k).collect::<Vec<_>>(),
+                expected
+            );
+            for k in (0..workload_size * 2).step_by(2) {
+                assert_eq!(tree.contains(&k), !(start..end).contains(&k), "{k}");
             }
-            // remove
-            barrier.wait();
-            for i in (0..data_size).rev() {
-                if i == data_size / 2 {
-                    barrier.wait();
-                }
-                assert!(tree.remove(&i));
-                removed.store(i, Release);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn remove_range_to_border() {
+        let workload_size = 65536;
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        let removed = Arc::new(AtomicUsize::new(0));
+        let removed_copied = removed.clone();
+        tree.watch(.., move |event| {
+            if let WatchEvent::Removed(_, _) = event {
+                removed_copied.fetch_add(1, Relaxed);
             }
-            thread_handles.into_iter().for_each(|t| t.join().unwrap());
+        });
+        for k in 0..workload_size {
+            assert!(tree.insert(k, k).is_ok());
+        }
+        for key in (1..16).map(|i| i * workload_size / 16 + i) {
+            // Only the entries in the leaf containing `key` are removed one by one.
+            let removed_before = removed.load(Relaxed);
+            tree.remove_range_to(&key);
+            assert!(removed.load(Relaxed) - removed_before <= 16);
+            assert!(!tree.contains(&(key - 1)));
+            assert!(tree.contains(&key));
+            assert_eq!(tree.len(), workload_size - key);
         }
     }
 
     #[cfg_attr(miri, ignore)]
     #[test]
-    fn range() {
-        let tree: TreeIndex<String, usize> = TreeIndex::default();
-        assert!(tree.insert("Ape".to_owned(), 0).is_ok());
-        assert!(tree.insert("Apple".to_owned(), 1).is_ok());
-        assert!(tree.insert("Banana".to_owned(), 3).is_ok());
-        assert!(tree.insert("Badezimmer".to_owned(), 2).is_ok());
-        assert_eq!(tree.range(..="Ball".to_owned(), &Guard::new()).count(), 3);
-        assert_eq!(
-            tree.range("Ape".to_owned()..="Ball".to_owned(), &Guard::new())
-                .count(),
-            3
-        );
-        assert_eq!(
-            tree.range("Apex".to_owned()..="Ball".to_owned(), &Guard::new())
-                .count(),
-            2
-        );
-        assert_eq!(
-            tree.range("Ace".to_owned()..="Ball".to_owned(), &Guard::new())
-                .count(),
-            3
-        );
-        assert_eq!(tree.range(..="Z".to_owned(), &Guard::new()).count(), 4);
+    fn remove_range_if() {
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        assert_eq!(tree.remove_range_if(.., |_, _| true), 0);
+        for k in 0..4096 {
+            assert!(tree.insert(k, k).is_ok());
+        }
+        assert_eq!(tree.remove_range_if(1024..=3072, |k, _| k % 3 == 0), 683);
         assert_eq!(
-            tree.range("Ape".to_owned()..="Z".to_owned(), &Guard::new())
-                .count(),
-            4
+            tree.remove_range_if((Excluded(8), Unbounded), |_, v| *v < 16),
+            7
         );
-        assert_eq!(
+        for k in 0..4096 {
+            let removed = ((1024..=3072).contains(&k) && k % 3 == 0) || (9..16).contains(&k);
+            assert_eq!(tree.contains(&k), !removed, "{k}");
+        }
+        assert_eq!(tree.len(), 4096 - 683 - 7);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn sample() {
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        let guard = Guard::new();
+        assert!(tree
+            .sample(8, |n| rand::random::<usize>() % n, &guard)
+            .is_empty());
+        for k in 0..4096 {
+            assert!(tree.insert(k, k).is_ok());
+        }
+        for k in 0..2048 {
+            assert!(tree.remove(&k));
+        }
+        let samples = tree.sample(1024, |n| rand::random::<usize>() % n, &guard);
+        assert_eq!(samples.len(), 1024);
+        assert!(samples.iter().all(|(k, v)| **k >= 2048 && k == v));
+        assert!(samples.iter().any(|(k, _)| **k < 3072));
+        assert!(samples.iter().any(|(k, _)| **k >= 3072));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn reduce_range() {
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        assert_eq!(tree.reduce_range(.., 0, |sum, _, v| sum + v), 0);
+        for k in 0..4096 {
+            assert!(tree.insert(k * 3, k).is_ok());
+        }
+        for k in (0..4096).step_by(5) {
+            assert!(tree.remove(&(k * 3)));
+        }
+        let guard = Guard::new();
+        for start in (0..4096 * 3).step_by(89) {
+            for len in [0, 1, 13, 14, 15, 1024, 8192] {
+                let range = start..start + len;
+                assert_eq!(
+                    tree.reduce_range(range.clone(), 0, |sum, _, v| sum + v),
+                    tree.range(range.clone(), &guard).map(|(_, v)| v).sum()
+                );
+                assert_eq!(
+                    tree.reduce_range(range.clone(), None, |_, k, _| Some(*k)),
+                    tree.range(range.clone(), &guard).last().map(|(k, _)| *k)
+                );
+            }
+            assert_eq!(
+                tree.reduce_range((Excluded(start), Unbounded), usize::MAX, |min, _, v| min
+                    .min(*v)),
+                tree.range((Excluded(start), Unbounded), &guard)
+                    .map(|(_, v)| *v)
+                    .min()
+                    .unwrap_or(usize::MAX)
+            );
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn scan_prefix() {
+        let tree: TreeIndex<Vec<u8>, usize> = TreeIndex::new();
+        for i in 0..16_u8 {
+            for j in 0..=255_u8 {
+                assert!(tree.insert(vec![i, j, 255], usize::from(j)).is_ok());
+            }
+        }
+        assert!(tree.insert(vec![255], 0).is_ok());
+        assert!(tree.insert(vec![255, 255], 0).is_ok());
+        assert!(tree.insert(Vec::new(), 0).is_ok());
+
+        let guard = Guard::new();
+        for prefix in [
+            &[][..],
+            &[0],
+            &[1, 255],
+            &[7, 3],
+            &[15, 255, 255],
+            &[16],
+            &[255],
+            &[255, 255],
+        ] {
+            assert!(tree
+                .scan_prefix(prefix, &guard)
+                .eq(tree.iter(&guard).filter(|(k, _)| k.starts_with(prefix))));
+        }
+        assert_eq!(tree.scan_prefix(&[3_u8][..], &guard).count(), 256);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn append() {
+        let workload_size = 4096;
+        let num_threads = 4;
+        let mut trees: Vec<TreeIndex<usize, usize>> =
+            (0..num_threads).map(|_| TreeIndex::new()).collect();
+        thread::scope(|s| {
+            for (thread_id, tree) in trees.iter().enumerate() {
+                s.spawn(move || {
+                    for k in 0..workload_size {
+                        assert!(tree.insert(k * num_threads + thread_id, thread_id).is_ok());
+                    }
+                });
+            }
+        });
+        let mut merged: TreeIndex<usize, usize> = TreeIndex::new();
+        for k in (0..workload_size * num_threads).step_by(2) {
+            assert!(merged.insert(k, usize::MAX).is_ok());
+        }
+        for tree in &mut trees {
+            merged.append(tree);
+        }
+        assert!(trees.iter().all(TreeIndex::is_empty));
+        assert_eq!(merged.len(), workload_size * num_threads);
+        for (k, v) in merged.iter(&Guard::new()) {
+            assert_eq!(k % num_threads, *v);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn append_disjoint() {
+        let sizes = [4096, 1, 65536, 15, 300];
+        let offsets: Vec<usize> = sizes
+            .iter()
+            .scan(0, |offset, size| {
+                *offset += size;
+                Some(*offset - size)
+            })
+            .collect();
+        let mut trees: Vec<TreeIndex<usize, usize>> =
+            sizes.iter().map(|_| TreeIndex::new()).collect();
+        thread::scope(|s| {
+            for ((tree, size), offset) in trees.iter().zip(sizes).zip(offsets.iter().copied()) {
+                s.spawn(move || {
+                    for k in offset..offset + size {
+                        assert!(tree.insert(k, k).is_ok());
+                    }
+                });
+            }
+        });
+        let removed = Arc::new(AtomicUsize::new(0));
+        for tree in &trees {
+            let removed_copied = removed.clone();
+            tree.watch(.., move |event| {
+                if let WatchEvent::Removed(_, _) = event {
+                    removed_copied.fetch_add(1, Relaxed);
+                }
+            });
+        }
+
+        // Each partition is either below or above `merged`, and either taller or shorter.
+        let mut merged: TreeIndex<usize, usize> = TreeIndex::new();
+        for index in [1, 0, 2, 3, 4] {
+            merged.append(&mut trees[index]);
+            assert!(trees[index].is_empty());
+        }
+        assert_eq!(removed.load(Relaxed), 0);
+
+        let workload_size = sizes.iter().sum::<usize>();
+        assert_eq!(merged.len(), workload_size);
+        assert!(merged
+            .iter(&Guard::new())
+            .map(|(k, _)| *k)
+            .eq(0..workload_size));
+        for k in 0..workload_size {
+            assert_eq!(merged.peek_with(&k, |_, v| *v), Some(k));
+        }
+        for k in workload_size..workload_size * 2 {
+            assert!(merged.insert(k, k).is_ok());
+        }
+        for k in (0..workload_size * 2).step_by(3) {
+            assert!(merged.remove(&k));
+        }
+        assert!(merged
+            .iter(&Guard::new())
+            .map(|(k, _)| *k)
+            .eq((0..workload_size * 2).filter(|k| k % 3 != 0)));
+
+        // Appending small trees does not make the tree deeper than needed.
+        let mut merged: TreeIndex<usize, usize> = TreeIndex::new();
+        for chunk in (2048..4096).chain((0..2048).rev()) {
+            let mut tree = TreeIndex::new();
+            for k in chunk * 4..chunk * 4 + 4 {
+                assert!(tree.insert(k, k).is_ok());
+            }
+            let removed_copied = removed.clone();
+            tree.watch(.., move |event| {
+                if let WatchEvent::Removed(_, _) = event {
+                    removed_copied.fetch_add(1, Relaxed);
+                }
+            });
+            merged.append(&mut tree);
+        }
+        assert_eq!(removed.load(Relaxed), 0);
+        assert!(merged.depth() <= 5);
+        assert!(merged.iter(&Guard::new()).map(|(k, _)| *k).eq(0..4096 * 4));
+        thread::scope(|s| {
+            for thread_id in 0..4 {
+                let merged = &merged;
+                s.spawn(move || {
+                    for k in (thread_id..4096 * 4).step_by(4) {
+                        assert!(merged.remove(&k));
+                        assert!(merged.insert(k + 4096 * 4, k).is_ok());
+                    }
+                });
+            }
+        });
+        assert!(merged
+            .iter(&Guard::new())
+            .map(|(k, _)| *k)
+            .eq(4096 * 4..4096 * 8));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn par_range() {
+        let workload_size = 65536;
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        for k in 0..workload_size {
+            assert!(tree.insert(k, k).is_ok());
+        }
+        thread::scope(|s| {
+            s.spawn(|| {
+                for k in workload_size..workload_size * 2 {
+                    assert!(tree.insert(k, k).is_ok());
+                }
+            });
+            for range in [0..workload_size, 7..workload_size - 7, 1024..1025] {
+                let visited = AtomicUsize::new(0);
+                let sum = AtomicUsize::new(0);
+                tree.par_range(range.clone(), |k, v| {
+                    assert_eq!(k, v);
+                    assert!(range.contains(k));
+                    visited.fetch_add(1, Relaxed);
+                    sum.fetch_add(*k, Relaxed);
+                });
+                assert_eq!(visited.load(Relaxed), range.len());
+                assert_eq!(sum.load(Relaxed), range.sum());
+            }
+        });
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn statistics() {
+        let workload_size = 65536;
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        assert_eq!(tree.statistics(), tree.statistics());
+        assert_eq!(tree.statistics().depth(), 0);
+        for k in 0..workload_size {
+            assert!(tree.insert(k, k).is_ok());
+        }
+        let statistics = tree.statistics();
+        assert_eq!(statistics.depth(), tree.depth());
+        assert_eq!(statistics.num_nodes()[0], 1);
+        assert!(statistics.num_nodes().windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(statistics.num_entries(), workload_size);
+        assert_eq!(statistics.num_retired_leaves(), 0);
+
+        for k in (0..workload_size).filter(|k| k % 8 != 0) {
+            assert!(tree.remove(&k));
+        }
+        let statistics = tree.statistics();
+        assert_eq!(statistics.num_entries(), workload_size / 8);
+        assert_eq!(statistics.num_entries(), tree.len());
+        assert!(statistics.num_leaves() * 14 >= workload_size / 8);
+    }
+
+    #[test]
+    fn peek_entry() {
+        let tree: TreeIndex<String, usize> = TreeIndex::new();
+        let guard = Guard::new();
+        assert!(tree.peek_entry("0", &guard).is_none());
+        for i in 0..256 {
+            assert!(tree.insert(i.to_string(), i).is_ok());
+        }
+        for i in 0..256 {
+            let key = i.to_string();
+            let (k, v) = tree.peek_entry(key.as_str(), &guard).unwrap();
+            assert_eq!(*k, key);
+            assert_eq!(*v, i);
+            assert_ne!(k.as_ptr(), key.as_ptr());
+            let (k_again, _) = tree.peek_entry(&key, &guard).unwrap();
+            assert_eq!(k.as_ptr(), k_again.as_ptr());
+        }
+        assert!(tree.remove("1"));
+        assert!(tree.peek_entry("1", &guard).is_none());
+    }
+
+    #[test]
+    fn scan() {
+        let workload_size = 1024;
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        assert!(tree.scan(.., |_, _| ControlFlow::Break(())).is_none());
+        for k in 0..workload_size {
+            assert!(tree.insert(k, k * 3).is_ok());
+        }
+        for start in [0, 1, workload_size / 2, workload_size - 1] {
+            let mut visited = 0;
+            let found = tree.scan(start.., |k, v| {
+                visited += 1;
+                if *v % 2 == 1 {
+                    ControlFlow::Break(*k)
+                } else {
+                    ControlFlow::Continue(())
+                }
+            });
+            let expected = if start % 2 == 1 { start } else { start + 1 };
+            assert_eq!(found, (expected < workload_size).then_some(expected));
+            assert_eq!(visited, expected.min(workload_size - 1) - start + 1);
+        }
+        let mut visited = 0;
+        assert!(tree
+            .scan(4..8, |_, _| {
+                visited += 1;
+                ControlFlow::<()>::Continue(())
+            })
+            .is_none());
+        assert_eq!(visited, 4);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn watch() {
+        let workload_size = 1024;
+        let num_threads = 4;
+        let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::new());
+        let events = Arc::new([
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+        ]);
+        let events_copied = events.clone();
+        let watched = workload_size / 4..workload_size / 2;
+        let watched_copied = watched.clone();
+        let id = tree.watch(watched.clone(), move |event| {
+            let (index, k) = match event {
+                WatchEvent::Inserted(k, v) => {
+                    assert_eq!(**k, **v);
+                    (0, **k)
+                }
+                WatchEvent::Modified(k, v) => {
+                    assert_eq!(**k + 1, **v);
+                    (1, **k)
+                }
+                WatchEvent::Removed(k, _) => (2, **k),
+                WatchEvent::RangeRemoved(_, _) => unreachable!(),
+            };
+            assert!(watched_copied.contains(&(k % workload_size)));
+            events_copied[index].fetch_add(1, Relaxed);
+        });
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let tree_copied = tree.clone();
+            let barrier_copied = barrier.clone();
+            thread_handles.push(thread::spawn(move || {
+                barrier_copied.wait();
+                let range = (thread_id * workload_size)..((thread_id + 1) * workload_size);
+                for k in range.clone() {
+                    assert!(tree_copied.insert(k, k).is_ok());
+                }
+                for k in range.clone() {
+                    assert!(tree_copied.modify(&k, |v| *v + 1));
+                }
+                for k in range {
+                    assert!(tree_copied.remove(&k));
+                }
+            }));
+        }
+        for handle in thread_handles {
+            handle.join().unwrap();
+        }
+        let expected = watched.len();
+        assert!(events.iter().all(|e| e.load(Relaxed) == expected));
+
+        assert!(tree.unwatch(id));
+        assert!(!tree.unwatch(id));
+        assert!(tree.insert(workload_size / 4, 0).is_ok());
+        assert_eq!(events[0].load(Relaxed), expected);
+    }
+
+    #[test]
+    fn watch_bulk_remove() {
+        let workload_size = 4096;
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        let removed = Arc::new(AtomicUsize::new(0));
+        let removed_copied = removed.clone();
+        let range_removed = Arc::new(AtomicUsize::new(0));
+        let range_removed_copied = range_removed.clone();
+        let watched = workload_size / 4..workload_size / 2;
+        let watched_copied = watched.clone();
+        tree.watch(watched, move |event| match event {
+            WatchEvent::Removed(k, _) => {
+                assert!(watched_copied.contains(*k));
+                removed_copied.fetch_add(1, Relaxed);
+            }
+            WatchEvent::RangeRemoved(_, _) => {
+                range_removed_copied.fetch_add(1, Relaxed);
+            }
+            WatchEvent::Inserted(_, _) | WatchEvent::Modified(_, _) => (),
+        });
+        let populate = || {
+            for k in 0..workload_size {
+                assert!(tree.insert(k, k).is_ok());
+            }
+        };
+
+        populate();
+        tree.remove_range(..workload_size * 3 / 8);
+        assert_eq!(range_removed.load(Relaxed), 1);
+        assert!(removed.load(Relaxed) <= workload_size / 8);
+        tree.remove_range_to(&(workload_size * 7 / 16));
+        assert_eq!(range_removed.load(Relaxed), 2);
+        assert!(removed.load(Relaxed) <= workload_size * 3 / 16);
+        tree.remove_range(workload_size * 3 / 4..);
+        assert_eq!(range_removed.load(Relaxed), 2);
+        assert!(!tree.contains(&(workload_size * 7 / 16 - 1)));
+        assert!(tree.contains(&(workload_size * 7 / 16)));
+        assert!(tree.contains(&(workload_size * 3 / 4 - 1)));
+        assert!(!tree.contains(&(workload_size * 3 / 4)));
+
+        let removed_before = removed.load(Relaxed);
+        assert_eq!(
+            tree.remove_range_if(.., |k, _| k % 2 == 0),
+            workload_size * 5 / 32
+        );
+        assert_eq!(removed.load(Relaxed), removed_before + workload_size / 32);
+        tree.clear();
+        assert_eq!(range_removed.load(Relaxed), 3);
+        assert!(tree.is_empty());
+
+        removed.store(0, Relaxed);
+        populate();
+        tree.clear();
+        assert_eq!(removed.load(Relaxed), 0);
+        assert_eq!(range_removed.load(Relaxed), 4);
+        tree.clear();
+        assert_eq!(range_removed.load(Relaxed), 4);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn skewed_insert() {
+        let workload_size = 4096;
+        let num_threads = 32;
+        for _ in 0..4 {
+            let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::new());
+            let barrier = Arc::new(Barrier::new(num_threads));
+            let mut thread_handles = Vec::with_capacity(num_threads);
+            for thread_id in 0..num_threads {
+                let tree_copied = tree.clone();
+                let barrier_copied = barrier.clone();
+                thread_handles.push(thread::spawn(move || {
+                    barrier_copied.wait();
+                    // All the threads insert keys into the same leaves.
+                    for k in 0..workload_size {
+                        let key = k * num_threads + thread_id;
+                        assert!(tree_copied.insert(key, key).is_ok());
+                    }
+                }));
+            }
+            for handle in thread_handles {
+                handle.join().unwrap();
+            }
+            assert_eq!(tree.len(), workload_size * num_threads);
+            let guard = Guard::new();
+            for (expected, (k, v)) in tree.iter(&guard).enumerate() {
+                assert_eq!(expected, *k);
+                assert_eq!(k, v);
+            }
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn retain() {
+        let workload_size = 4096;
+        let num_threads = 4;
+        for _ in 0..4 {
+            let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::new());
+            for k in 0..workload_size {
+                assert!(tree.insert(k, k).is_ok());
+            }
+            let barrier = Arc::new(Barrier::new(num_threads));
+            let mut thread_handles = Vec::with_capacity(num_threads);
+            for thread_id in 0..num_threads {
+                let tree_copied = tree.clone();
+                let barrier_copied = barrier.clone();
+                thread_handles.push(thread::spawn(move || {
+                    barrier_copied.wait();
+                    if thread_id == 0 {
+                        tree_copied.retain(|k, _| k % 2 == 0 || *k >= workload_size);
+                    } else if thread_id == 1 {
+                        let extracted =
+                            tree_copied.extract_if(|k, _| k % 4 == 0 && *k < workload_size);
+                        let mut prev = None;
+                        for (k, v) in extracted {
+                            assert_eq!(k, v);
+                            assert_eq!(k % 4, 0);
+                            assert!(prev.map_or(true, |prev| prev < k));
+                            prev.replace(k);
+                        }
+                    } else {
+                        let base = workload_size * thread_id;
+                        for k in base..base + workload_size {
+                            assert!(tree_copied.insert(k, k).is_ok());
+                        }
+                    }
+                }));
+            }
+            for handle in thread_handles {
+                handle.join().unwrap();
+            }
+            for k in 0..workload_size {
+                assert_eq!(tree.contains(&k), k % 4 == 2, "{k}");
+            }
+            assert_eq!(
+                tree.len(),
+                workload_size / 4 + (num_threads - 2) * workload_size
+            );
+        }
+
+        // The predicate is evaluated once for each entry, and emptied leaves are cleaned up.
+        let tree: TreeIndex<usize, usize> = TreeIndex::new();
+        for k in 0..workload_size {
+            assert!(tree.insert(k, k).is_ok());
+        }
+        let mut num_calls = 0;
+        tree.retain(|_, _| {
+            num_calls += 1;
+            false
+        });
+        assert_eq!(num_calls, workload_size);
+        assert!(tree.is_empty());
+        assert_eq!(tree.depth(), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn complex() {
+        let range = 4096;
+        let num_threads = 16;
+        let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::new());
+        for t in 0..num_threads {
+            // insert markers
+            assert!(tree.insert(t * range, t * range).is_ok());
+        }
+        let stopped: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let barrier = Arc::new(Barrier::new(num_threads + 1));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let tree_copied = tree.clone();
+            let stopped_copied = stopped.clone();
+            let barrier_copied = barrier.clone();
+            thread_handles.push(thread::spawn(move || {
+                let first_key = thread_id * range;
+                barrier_copied.wait();
+                while !stopped_copied.load(Relaxed) {
+                    for key in (first_key + 1)..(first_key + range) {
+                        assert!(tree_copied.insert(key, key).is_ok());
+                    }
+                    for key in (first_key + 1)..(first_key + range) {
+                        assert!(tree_copied
+                            .peek_with(&key, |key, val| assert_eq!(key, val))
+                            .is_some());
+                    }
+                    {
+                        let guard = Guard::new();
+                        let mut range_scanner = tree_copied.range(first_key.., &guard);
+                        let mut entry = range_scanner.next().unwrap();
+                        assert_eq!(entry, (&first_key, &first_key));
+                        entry = range_scanner.next().unwrap();
+                        assert_eq!(entry, (&(first_key + 1), &(first_key + 1)));
+                        entry = range_scanner.next().unwrap();
+                        assert_eq!(entry, (&(first_key + 2), &(first_key + 2)));
+                        entry = range_scanner.next().unwrap();
+                        assert_eq!(entry, (&(first_key + 3), &(first_key + 3)));
+                    }
+
+                    let key_at_halfway = first_key + range / 2;
+                    for key in (first_key + 1)..(first_key + range) {
+                        if key == key_at_halfway {
+                            let guard = Guard::new();
+                            let mut range_scanner = tree_copied.range((first_key + 1).., &guard);
+                            let entry = range_scanner.next().unwrap();
+                            assert_eq!(entry, (&key_at_halfway, &key_at_halfway));
+                            let entry = range_scanner.next().unwrap();
+                            assert_eq!(entry, (&(key_at_halfway + 1), &(key_at_halfway + 1)));
+                        }
+                        assert!(tree_copied.remove(&key));
+                        assert!(!tree_copied.remove(&key));
+                        assert!(tree_copied.peek_with(&(first_key + 1), |_, _| ()).is_none());
+                        assert!(tree_copied.peek_with(&key, |_, _| ()).is_none());
+                    }
+                    for key in (first_key + 1)..(first_key + range) {
+                        assert!(tree_copied
+                            .peek_with(&key, |key, val| assert_eq!(key, val))
+                            .is_none());
+                    }
+                }
+            }));
+        }
+        barrier.wait();
+        for _ in 0..512 {
+            let mut found_0 = false;
+            let mut found_markers = 0;
+            let mut prev_marker = 0;
+            let mut prev = 0;
+            let guard = Guard::new();
+            for iter in tree.iter(&guard) {
+                let current = *iter.0;
+                if current % range == 0 {
+                    found_markers += 1;
+                    if current == 0 {
+                        found_0 = true;
+                    }
+                    if current > 0 {
+                        assert_eq!(prev_marker + range, current);
+                    }
+                    prev_marker = current;
+                }
+                assert!(prev == 0 || prev < current);
+                prev = current;
+            }
+            assert!(found_0);
+            assert_eq!(found_markers, num_threads);
+        }
+
+        stopped.store(true, Release);
+        for handle in thread_handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn remove() {
+        let num_threads = 16;
+        let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::new());
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let tree_copied = tree.clone();
+            let barrier_copied = barrier.clone();
+            thread_handles.push(thread::spawn(move || {
+                barrier_copied.wait();
+                for _ in 0..4096 {
+                    let range = 0..32;
+                    let inserted = range
+                        .clone()
+                        .filter(|i| tree_copied.insert(*i, thread_id).is_ok())
+                        .count();
+                    let found = range
+                        .clone()
+                        .filter(|i| {
+                            tree_copied
+                                .peek_with(i, |_, v| *v == thread_id)
+                                .map_or(false, |t| t)
+                        })
+                        .count();
+                    let removed = range
+                        .clone()
+                        .filter(|i| tree_copied.remove_if(i, |v| *v == thread_id))
+                        .count();
+                    let removed_again = range
+                        .clone()
+                        .filter(|i| tree_copied.remove_if(i, |v| *v == thread_id))
+                        .count();
+                    assert_eq!(removed_again, 0);
+                    assert_eq!(found, removed, "{inserted} {found} {removed}");
+                    assert_eq!(inserted, found, "{inserted} {found} {removed}");
+                }
+            }));
+        }
+        for handle in thread_handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.depth(), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn string_key() {
+        let tree1: TreeIndex<String, u32> = TreeIndex::default();
+        let tree2: TreeIndex<u32, String> = TreeIndex::default();
+        let mut checker1 = BTreeSet::new();
+        let mut checker2 = BTreeSet::new();
+        let mut runner = TestRunner::default();
+        let test_size = 4096;
+        for i in 0..test_size {
+            let prop_str = "[a-z]{1,16}".new_tree(&mut runner).unwrap();
+            let str_val = prop_str.current();
+            if tree1.insert(str_val.clone(), i).is_ok() {
+                checker1.insert((str_val.clone(), i));
+            }
+            let str_borrowed = str_val.as_str();
+            assert!(tree1.peek_with(str_borrowed, |_, _| ()).is_some());
+
+            if tree2.insert(i, str_val.clone()).is_ok() {
+                checker2.insert((i, str_val.clone()));
+            }
+        }
+        for iter in &checker1 {
+            let v = tree1.peek_with(iter.0.as_str(), |_, v| *v);
+            assert_eq!(v.unwrap(), iter.1);
+        }
+        for iter in &checker2 {
+            let v = tree2.peek_with(&iter.0, |_, v| v.clone());
+            assert_eq!(v.unwrap(), iter.1);
+        }
+    }
+
+    #[test]
+    fn comparable_key() {
+        struct KeyView<'a>(&'a str, u32);
+        impl Equivalent<(String, u32)> for KeyView<'_> {
+            fn equivalent(&self, key: &(String, u32)) -> bool {
+                self.0 == key.0 && self.1 == key.1
+            }
+        }
+        impl Comparable<(String, u32)> for KeyView<'_> {
+            fn compare(&self, key: &(String, u32)) -> std::cmp::Ordering {
+                self.0.cmp(&key.0).then(self.1.cmp(&key.1))
+            }
+        }
+
+        let tree: TreeIndex<(String, u32), u32> = TreeIndex::default();
+        for i in 0..256 {
+            assert!(tree.insert((format!("{i:03}"), i), i).is_ok());
+        }
+        for i in 0..256 {
+            let name = format!("{i:03}");
+            assert_eq!(tree.peek_with(&KeyView(&name, i), |_, v| *v), Some(i));
+            assert!(!tree.contains(&KeyView(&name, i + 1)));
+        }
+        assert!(tree.remove(&KeyView("007", 7)));
+        assert!(!tree.contains(&KeyView("007", 7)));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn scanner() {
+        let data_size = 4096;
+        for _ in 0..64 {
+            let tree: Arc<TreeIndex<usize, u64>> = Arc::new(TreeIndex::default());
+            let barrier = Arc::new(Barrier::new(3));
+            let inserted = Arc::new(AtomicUsize::new(0));
+            let removed = Arc::new(AtomicUsize::new(data_size));
+            let mut thread_handles = Vec::new();
+            for _ in 0..2 {
+                let tree_copied = tree.clone();
+                let barrier_copied = barrier.clone();
+                let inserted_copied = inserted.clone();
+                let removed_copied = removed.clone();
+                let thread_handle = thread::spawn(move || {
+                    // test insert
+                    for _ in 0..2 {
+                        barrier_copied.wait();
+                        let max = inserted_copied.load(Acquire);
+                        let mut prev = 0;
+                        let mut iterated = 0;
+                        let guard = Guard::new();
+                        for iter in tree_copied.iter(&guard) {
+                            assert!(
+                                prev == 0
+                                    || (*iter.0 <= max && prev + 1 == *iter.0)
+                                    || *iter.0 > prev
+                            );
+                            prev = *iter.0;
+                            iterated += 1;
+                        }
+                        assert!(iterated >= max);
+                    }
+                    // test remove
+                    for _ in 0..2 {
+                        barrier_copied.wait();
+                        let mut prev = 0;
+                        let max = removed_copied.load(Acquire);
+                        let guard = Guard::new();
+                        for iter in tree_copied.iter(&guard) {
+                            let current = *iter.0;
+                            assert!(current < max);
+                            assert!(prev + 1 == current || prev == 0);
+                            prev = current;
+                        }
+                    }
+                });
+                thread_handles.push(thread_handle);
+            }
+            // insert
+            barrier.wait();
+            for i in 0..data_size {
+                if i == data_size / 2 {
+                    barrier.wait();
+                }
+                assert!(tree.insert(i, 0).is_ok());
+                inserted.store(i, Release);
+            }
+            // remove
+            barrier.wait();
+            for i in (0..data_size).rev() {
+                if i == data_size / 2 {
+                    barrier.wait();
+                }
+                assert!(tree.remove(&i));
+                removed.store(i, Release);
+            }
+            thread_handles.into_iter().for_each(|t| t.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn extend_from_iter() {
+        let workload_size = 1024;
+        let mut tree: TreeIndex<usize, usize> = (0..workload_size).map(|k| (k, k)).collect();
+        assert_eq!(tree.len(), workload_size);
+        tree.extend(
+            (workload_size / 2..workload_size * 2)
+                .rev()
+                .map(|k| (k, k + 1)),
+        );
+        assert_eq!(tree.len(), workload_size * 2);
+        for (k, v) in tree.iter(&Guard::new()) {
+            assert_eq!(*v, if *k < workload_size / 2 { *k } else { *k + 1 });
+        }
+        let tree: TreeIndex<usize, usize> = [(1, 1), (0, 0), (1, 2)].into_iter().collect();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.peek_with(&1, |_, v| *v), Some(2));
+
+        // Sorted input is packed into full leaves.
+        let workload_size = 65536;
+        let mut tree: TreeIndex<usize, usize> = (0..workload_size).map(|k| (k * 4, k)).collect();
+        assert_eq!(tree.statistics().num_leaves(), workload_size / 14 + 1);
+        assert_eq!(tree.len(), workload_size);
+        let inserted = Arc::new(AtomicUsize::new(0));
+        let inserted_copied = inserted.clone();
+        tree.watch(.., move |event| {
+            if let WatchEvent::Inserted(_, _) = event {
+                inserted_copied.fetch_add(1, Relaxed);
+            }
+        });
+        tree.extend((workload_size * 4..workload_size * 5).map(|k| (k, k)));
+        assert_eq!(inserted.load(Relaxed), workload_size);
+        let mut below = vec![(0, 0); 3];
+        below.extend((0..4).flat_map(|k| [(k, k), (k, k + 1)]));
+        tree.remove_range(..4);
+        tree.extend(below.into_iter().chain([(5, 5), (7, 7), (3, 3)]));
+        assert_eq!(inserted.load(Relaxed), workload_size + 6);
+        assert_eq!(tree.len(), workload_size * 2 + 5);
+        for k in 0..3 {
+            assert_eq!(tree.peek_with(&k, |_, v| *v), Some(k + 1));
+        }
+        assert_eq!(tree.peek_with(&3, |_, v| *v), Some(3));
+        assert!(tree.iter(&Guard::new()).map(|(k, _)| *k).eq((0..4)
+            .chain([4, 5, 7])
+            .chain((8..workload_size * 4).step_by(4))
+            .chain(workload_size * 4..workload_size * 5)));
+        tree.extend([(8, 0), (9, 9)]);
+        assert_eq!(tree.peek_with(&8, |_, v| *v), Some(0));
+        assert_eq!(tree.peek_with(&9, |_, v| *v), Some(9));
+    }
+
+    #[test]
+    fn debug_eq() {
+        let tree: TreeIndex<usize, usize> = (0..40).map(|k| (k, k)).collect();
+        let debug_str = format!("{tree:?}");
+        assert!(debug_str.starts_with("{0: 0, 1: 1, "));
+        assert!(debug_str.ends_with("30: 30, 31: 31, ..: 8 more}"));
+        let guard = Guard::new();
+        let mut iter = tree.iter(&guard);
+        assert_eq!(iter.by_ref().count(), 40);
+        assert!(iter.next().is_none());
+        let cloned = tree.clone();
+        assert_eq!(tree, cloned);
+        assert_eq!(format!("{cloned:?}"), debug_str);
+        assert!(cloned.remove(&39));
+        assert_ne!(tree, cloned);
+        assert!(cloned.insert(39, 40).is_ok());
+        assert_ne!(tree, cloned);
+        cloned.clear();
+        assert_eq!(format!("{cloned:?}"), "{}");
+        assert_eq!(cloned, TreeIndex::default());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn range_seek() {
+        let tree: TreeIndex<usize, usize> = TreeIndex::default();
+        let mut model = BTreeSet::new();
+        for k in 0..4096 {
+            if rand::random::<u8>() % 3 != 0 {
+                assert!(tree.insert(k, k).is_ok());
+                model.insert(k);
+            }
+        }
+        let guard = Guard::new();
+        for _ in 0..64 {
+            let start = rand::random::<usize>() % 4096;
+            let end = start + rand::random::<usize>() % 1024;
+            let mut range = tree.range(start..end, &guard);
+            let mut expected = model.range(start..end).copied().peekable();
+            let mut target = start;
+            loop {
+                target += rand::random::<usize>() % 64;
+                range.seek(&target);
+                while expected.peek().map_or(false, |k| *k < target) {
+                    expected.next();
+                }
+                let next = range.next().map(|(k, _)| *k);
+                assert_eq!(next, expected.next());
+                if let Some(k) = next {
+                    target = target.max(k);
+                } else {
+                    break;
+                }
+            }
+            assert!(range.next().is_none());
+            range.seek(&0);
+            assert!(range.next().is_none());
+        }
+
+        let mut range = tree.range(.., &guard);
+        range.seek(&4096);
+        assert!(range.next().is_none());
+        assert!(range.next().is_none());
+        let mut iter = tree.range(4000.., &guard);
+        assert_eq!(iter.by_ref().count(), model.range(4000..).count());
+        assert!(iter.next().is_none());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn range() {
+        let tree: TreeIndex<String, usize> = TreeIndex::default();
+        assert!(tree.insert("Ape".to_owned(), 0).is_ok());
+        assert!(tree.insert("Apple".to_owned(), 1).is_ok());
+        assert!(tree.insert("Banana".to_owned(), 3).is_ok());
+        assert!(tree.insert("Badezimmer".to_owned(), 2).is_ok());
+        assert_eq!(tree.range(..="Ball".to_owned(), &Guard::new()).count(), 3);
+        assert_eq!(
+            tree.range("Ape".to_owned()..="Ball".to_owned(), &Guard::new())
+                .count(),
+            3
+        );
+        assert_eq!(
+            tree.range("Apex".to_owned()..="Ball".to_owned(), &Guard::new())
+                .count(),
+            2
+        );
+        assert_eq!(
+            tree.range("Ace".to_owned()..="Ball".to_owned(), &Guard::new())
+                .count(),
+            3
+        );
+        assert_eq!(tree.range(..="Z".to_owned(), &Guard::new()).count(), 4);
+        assert_eq!(
+            tree.range("Ape".to_owned()..="Z".to_owned(), &Guard::new())
+                .count(),
+            4
+        );
+        assert_eq!(
             tree.range("Apex".to_owned()..="Z".to_owned(), &Guard::new())
                 .count(),
             3
@@ -2241,148 +3560,1046 @@ mod treeindex_test {
             2
         );
         assert_eq!(
-            tree.range("Ace".to_owned().."Banana".to_owned(), &Guard::new())
-                .count(),
-            3
+            tree.range("Ace".to_owned().."Banana".to_owned(), &Guard::new())
+                .count(),
+            3
+        );
+
+        let guard = Guard::new();
+        let borrowed = |lower: Bound<&'static str>, upper: Bound<&'static str>| {
+            tree.range::<str, _>((lower, upper), &guard)
+                .map(|(_, v)| *v)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(borrowed(Excluded("Ape"), Included("Banana")), vec![1, 2, 3]);
+        assert_eq!(borrowed(Excluded("Ape"), Excluded("Banana")), vec![1, 2]);
+        assert_eq!(
+            borrowed(Included("Ape"), Excluded("Badezimmer")),
+            vec![0, 1]
+        );
+        assert_eq!(borrowed(Excluded("Apple"), Unbounded), vec![2, 3]);
+        assert_eq!(borrowed(Unbounded, Excluded("Ape")), Vec::<usize>::new());
+        assert_eq!(borrowed(Excluded("Banana"), Unbounded), Vec::<usize>::new());
+        assert_eq!(borrowed(Excluded("B"), Excluded("Bb")), vec![2, 3]);
+    }
+
+    proptest! {
+        #[cfg_attr(miri, ignore)]
+        #[test]
+        fn prop_remove_range(lower in 0_usize..4096_usize, range in 0_usize..1024_usize) {
+            let remove_range = lower..lower + range;
+            let tree = TreeIndex::default();
+            for k in 256_usize..1024_usize {
+                assert!(tree.insert(k, k).is_ok());
+            }
+            assert_eq!(tree.depth(), 2);
+            tree.remove_range(remove_range.clone());
+            if remove_range.contains(&256) && remove_range.contains(&1023) {
+                assert!(tree.is_empty());
+            }
+            for (k, v) in tree.iter(&Guard::new()) {
+                assert_eq!(k, v);
+                assert!(!remove_range.contains(k), "{k}");
+            }
+            for k in 0_usize..4096_usize {
+                if tree.peek_with(&k, |_, _|()).is_some() {
+                    assert!(!remove_range.contains(&k), "{k}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod treemultiindex_test {
+    use crate::ebr::Guard;
+    use crate::TreeMultiIndex;
+    use std::sync::Arc;
+    use tokio::sync::Barrier as AsyncBarrier;
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn insert_remove() {
+        let num_tasks = 4;
+        let workload_size = 256;
+        let index: Arc<TreeMultiIndex<usize, usize>> = Arc::new(TreeMultiIndex::default());
+        let mut task_handles = Vec::with_capacity(num_tasks);
+        let barrier = Arc::new(AsyncBarrier::new(num_tasks));
+        for task_id in 0..num_tasks {
+            let barrier_clone = barrier.clone();
+            let index_clone = index.clone();
+            task_handles.push(tokio::task::spawn(async move {
+                barrier_clone.wait().await;
+                for i in 0..workload_size {
+                    if task_id % 2 == 0 {
+                        index_clone.insert(i % 16, task_id * workload_size + i);
+                    } else {
+                        index_clone
+                            .insert_async(i % 16, task_id * workload_size + i)
+                            .await;
+                    }
+                }
+            }));
+        }
+        for r in futures::future::join_all(task_handles).await {
+            assert!(r.is_ok());
+        }
+        assert_eq!(index.len(), num_tasks * workload_size);
+
+        let guard = Guard::new();
+        for k in 0..16 {
+            assert_eq!(index.count(&k), num_tasks * workload_size / 16);
+            let mut prev = vec![None; num_tasks];
+            for v in index.peek(&k, &guard) {
+                assert_eq!(v % 16, k);
+                let task_id = v / workload_size;
+                assert!(prev[task_id].map_or(true, |prev| prev < *v));
+                prev[task_id].replace(*v);
+            }
+        }
+
+        let num_removed = index.remove_if(&3, |v| v / workload_size == 0);
+        assert_eq!(num_removed, workload_size / 16);
+        assert_eq!(
+            index.remove_async(&3).await,
+            (num_tasks - 1) * workload_size / 16
         );
+        assert!(!index.contains(&3));
+        assert_eq!(index.remove(&4), num_tasks * workload_size / 16);
+        assert_eq!(index.len(), (num_tasks * workload_size) / 16 * 14);
+    }
+}
+
+#[cfg(test)]
+mod bag_test {
+    use crate::bag::IterMut;
+    use crate::Bag;
+    use std::panic::UnwindSafe;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::Arc;
+    use tokio::sync::Barrier as AsyncBarrier;
+
+    static_assertions::assert_impl_all!(Bag<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_impl_all!(IterMut<'static, String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(Bag<*const String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(IterMut<'static, *const String>: Send, Sync, UnwindSafe);
+
+    struct R(&'static AtomicUsize);
+    impl R {
+        fn new(cnt: &'static AtomicUsize) -> R {
+            cnt.fetch_add(1, Relaxed);
+            R(cnt)
+        }
+    }
+    impl Clone for R {
+        fn clone(&self) -> Self {
+            self.0.fetch_add(1, Relaxed);
+            R(self.0)
+        }
+    }
+    impl Drop for R {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Relaxed);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn reclaim() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+        for workload_size in [2, 18, 32, 40, 120] {
+            let mut bag: Bag<R> = Bag::default();
+            for _ in 0..workload_size {
+                bag.push(R::new(&INST_CNT));
+            }
+            assert_eq!(INST_CNT.load(Relaxed), workload_size);
+            assert_eq!(bag.iter_mut().count(), workload_size);
+            bag.iter_mut().for_each(|e| {
+                *e = R::new(&INST_CNT);
+            });
+
+            for _ in 0..workload_size / 2 {
+                bag.pop();
+            }
+            assert_eq!(INST_CNT.load(Relaxed), workload_size / 2);
+            drop(bag);
+            assert_eq!(INST_CNT.load(Relaxed), 0);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn into_iter() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+        for workload_size in [2, 18, 32, 40, 120] {
+            let mut bag: Bag<R> = Bag::default();
+            for _ in 0..workload_size {
+                bag.push(R::new(&INST_CNT));
+            }
+            assert_eq!(INST_CNT.load(Relaxed), workload_size);
+            assert_eq!(bag.len(), workload_size);
+            assert_eq!(bag.iter_mut().count(), workload_size);
+
+            for v in &mut bag {
+                assert_eq!(v.0.load(Relaxed), INST_CNT.load(Relaxed));
+            }
+            assert_eq!(INST_CNT.load(Relaxed), workload_size);
+
+            for v in bag {
+                assert_eq!(v.0.load(Relaxed), INST_CNT.load(Relaxed));
+            }
+            assert_eq!(INST_CNT.load(Relaxed), 0);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
+    async fn mpmc() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+        const NUM_TASKS: usize = 6;
+        let workload_size = 256;
+        let bag32: Arc<Bag<R>> = Arc::new(Bag::default());
+        let bag17: Arc<Bag<R, 17>> = Arc::new(Bag::new());
+        for _ in 0..256 {
+            let mut task_handles = Vec::with_capacity(NUM_TASKS);
+            let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
+            for _ in 0..NUM_TASKS {
+                let barrier_clone = barrier.clone();
+                let bag32_clone = bag32.clone();
+                let bag17_clone = bag17.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    for _ in 0..workload_size {
+                        bag32_clone.push(R::new(&INST_CNT));
+                        bag17_clone.push(R::new(&INST_CNT));
+                    }
+                    for _ in 0..workload_size {
+                        assert!(!bag32_clone.is_empty());
+                        assert!(bag32_clone.pop().is_some());
+                        assert!(!bag17_clone.is_empty());
+                        assert!(bag17_clone.pop().is_some());
+                    }
+                }));
+            }
+
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+            assert!(bag32.pop().is_none());
+            assert!(bag32.is_empty());
+            assert!(bag17.pop().is_none());
+            assert!(bag17.is_empty());
+        }
+        assert_eq!(INST_CNT.load(Relaxed), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
+    async fn mpsc() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+        const NUM_TASKS: usize = 6;
+        let workload_size = 256;
+        let bag32: Arc<Bag<R>> = Arc::new(Bag::default());
+        let bag7: Arc<Bag<R, 7>> = Arc::new(Bag::new());
+        for _ in 0..256 {
+            let mut task_handles = Vec::with_capacity(NUM_TASKS);
+            let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
+            for task_id in 0..NUM_TASKS {
+                let barrier_clone = barrier.clone();
+                let bag32_clone = bag32.clone();
+                let bag7_clone = bag7.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    let mut cnt = 0;
+                    while task_id == 0 && cnt < workload_size * (NUM_TASKS - 1) * 2 {
+                        cnt += bag32_clone.pop_all(0, |a, _| a + 1);
+                        cnt += bag7_clone.pop_all(0, |a, _| a + 1);
+                        tokio::task::yield_now().await;
+                    }
+                    if task_id != 0 {
+                        for _ in 0..workload_size {
+                            bag32_clone.push(R::new(&INST_CNT));
+                            bag7_clone.push(R::new(&INST_CNT));
+                        }
+                        for _ in 0..workload_size / 16 {
+                            if bag32_clone.pop().is_some() {
+                                bag32_clone.push(R::new(&INST_CNT));
+                            }
+                            if bag7_clone.pop().is_some() {
+                                bag7_clone.push(R::new(&INST_CNT));
+                            }
+                        }
+                    }
+                }));
+            }
+
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+            assert!(bag32.pop().is_none());
+            assert!(bag32.is_empty());
+            assert!(bag7.pop().is_none());
+            assert!(bag7.is_empty());
+        }
+        assert_eq!(INST_CNT.load(Relaxed), 0);
+    }
+}
+
+#[cfg(test)]
+mod queue_test {
+    use crate::ebr::Guard;
+    use crate::queue::{ArrayQueue, BoundedQueue, SegmentedQueue};
+    use crate::{LinkedList, Queue};
+    use std::panic::UnwindSafe;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::{Arc, Barrier};
+    use std::time::Duration;
+    use tokio::sync::Barrier as AsyncBarrier;
+
+    static_assertions::assert_impl_all!(Queue<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(Queue<*const String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_impl_all!(ArrayQueue<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(ArrayQueue<*const String>: Send, Sync);
+    static_assertions::assert_impl_all!(SegmentedQueue<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(SegmentedQueue<*const String>: Send, Sync);
+    static_assertions::assert_impl_all!(BoundedQueue<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(BoundedQueue<*const String>: Send, Sync, UnwindSafe);
+
+    struct R(usize, usize);
+    impl R {
+        fn new(task_id: usize, seq: usize) -> R {
+            R(task_id, seq)
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn clone() {
+        let queue = Queue::default();
+        queue.push(37);
+        queue.push(3);
+        queue.push(1);
+
+        let queue_clone = queue.clone();
+
+        assert_eq!(queue.pop().map(|e| **e), Some(37));
+        assert_eq!(queue.pop().map(|e| **e), Some(3));
+        assert_eq!(queue.pop().map(|e| **e), Some(1));
+        assert!(queue.pop().is_none());
+
+        assert_eq!(queue_clone.pop().map(|e| **e), Some(37));
+        assert_eq!(queue_clone.pop().map(|e| **e), Some(3));
+        assert_eq!(queue_clone.pop().map(|e| **e), Some(1));
+        assert!(queue_clone.pop().is_none());
+    }
+
+    #[test]
+    fn array_drop() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+        struct D;
+        impl Drop for D {
+            fn drop(&mut self) {
+                INST_CNT.fetch_add(1, Relaxed);
+            }
+        }
+        let queue: ArrayQueue<D> = ArrayQueue::new(4);
+        for _ in 0..8 {
+            assert!(queue.push(D).is_ok());
+            assert!(queue.pop().is_some());
+        }
+        (0..4).for_each(|_| assert!(queue.push(D).is_ok()));
+        assert!(queue.is_full());
+        assert!(queue.push(D).is_err());
+        assert_eq!(INST_CNT.load(Relaxed), 9);
+        drop(queue);
+        assert_eq!(INST_CNT.load(Relaxed), 13);
+
+        let queue: ArrayQueue<usize> = ArrayQueue::new(1);
+        for v in 0..4 {
+            assert!(queue.push(v).is_ok());
+            assert_eq!(queue.push(v + 1), Err(v + 1));
+            assert_eq!(queue.pop(), Some(v));
+            assert!(queue.pop().is_none());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn array_mpmc_async() {
+        const NUM_TASKS: usize = 4;
+        let queue: Arc<ArrayQueue<usize>> = Arc::new(ArrayQueue::new(4));
+        let workload_size = 1024;
+        let mut task_handles = Vec::with_capacity(NUM_TASKS * 2);
+        for task_id in 0..NUM_TASKS {
+            let queue_clone = queue.clone();
+            task_handles.push(tokio::spawn(async move {
+                for seq in 0..workload_size {
+                    queue_clone.push_async(task_id * workload_size + seq).await;
+                }
+                Vec::new()
+            }));
+        }
+        for _ in 0..NUM_TASKS {
+            let queue_clone = queue.clone();
+            task_handles.push(tokio::spawn(async move {
+                let mut popped = Vec::with_capacity(workload_size);
+                let mut last = [None; NUM_TASKS];
+                for _ in 0..workload_size {
+                    let v = queue_clone.pop_async().await;
+                    let task_id = v / workload_size;
+                    assert!(last[task_id].map_or(true, |l| l < v));
+                    last[task_id].replace(v);
+                    popped.push(v);
+                }
+                popped
+            }));
+        }
+        let mut all = Vec::new();
+        for handle in futures::future::join_all(task_handles).await {
+            all.append(&mut handle.unwrap());
+        }
+        assert!(queue.is_empty());
+        all.sort_unstable();
+        assert_eq!(all, (0..NUM_TASKS * workload_size).collect::<Vec<_>>());
     }
 
-    proptest! {
-        #[cfg_attr(miri, ignore)]
-        #[test]
-        fn prop_remove_range(lower in 0_usize..4096_usize, range in 0_usize..1024_usize) {
-            let remove_range = lower..lower + range;
-            let tree = TreeIndex::default();
-            for k in 256_usize..1024_usize {
-                assert!(tree.insert(k, k).is_ok());
+    #[test]
+    fn segmented_drop() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+        struct D;
+        impl Drop for D {
+            fn drop(&mut self) {
+                INST_CNT.fetch_add(1, Relaxed);
             }
-            assert_eq!(tree.depth(), 2);
-            tree.remove_range(remove_range.clone());
-            if remove_range.contains(&256) && remove_range.contains(&1023) {
-                assert!(tree.is_empty());
+        }
+        let queue: SegmentedQueue<D, 4> = SegmentedQueue::default();
+        (0..10).for_each(|_| queue.push(D));
+        assert_eq!(queue.len(), 10);
+        assert!(queue.pop().is_some());
+        assert_eq!(INST_CNT.load(Relaxed), 1);
+        drop(queue);
+        while INST_CNT.load(Relaxed) != 10 {
+            drop(Guard::new());
+            std::thread::yield_now();
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn segmented_mpmc() {
+        const NUM_THREADS: usize = 4;
+        let queue: Arc<SegmentedQueue<usize, 8>> = Arc::new(SegmentedQueue::default());
+        let workload_size = 4096;
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS * 2);
+        for task_id in 0..NUM_THREADS {
+            let queue_clone = queue.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                for seq in 0..workload_size {
+                    queue_clone.push(task_id * workload_size + seq);
+                }
+                Vec::new()
+            }));
+        }
+        for _ in 0..NUM_THREADS {
+            let queue_clone = queue.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                let mut popped = Vec::new();
+                let mut last = [None; NUM_THREADS];
+                for _ in 0..workload_size {
+                    if let Some(v) = queue_clone.pop() {
+                        let task_id = v / workload_size;
+                        assert!(last[task_id].map_or(true, |l| l < v));
+                        last[task_id].replace(v);
+                        popped.push(v);
+                    }
+                }
+                popped
+            }));
+        }
+        let mut all = Vec::new();
+        for handle in thread_handles {
+            all.append(&mut handle.join().unwrap());
+        }
+        while let Some(v) = queue.pop() {
+            all.push(v);
+        }
+        assert!(queue.is_empty());
+        all.sort_unstable();
+        assert_eq!(all, (0..NUM_THREADS * workload_size).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn debug() {
+        let queue: Queue<usize> = Queue::default();
+        assert_eq!(format!("{queue:?}"), "[]");
+        queue.push_many(0..40);
+        assert_eq!(queue.pop().map(|e| **e), Some(0));
+        let debug_str = format!("{queue:?}");
+        assert!(debug_str.starts_with("[1, 2, "));
+        assert!(debug_str.ends_with("31, 32, ..: 7 more]"));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn clone_popped() {
+        let queue: Arc<Queue<usize>> = Arc::new(Queue::default());
+        let workload_size = 1024;
+        for _ in 0..4 {
+            queue.push_many(0..workload_size);
+            let queue_clone = queue.clone();
+            let thread_handle = std::thread::spawn(move || while queue_clone.pop().is_some() {});
+            loop {
+                let cloned = (*queue).clone();
+                let guard = Guard::new();
+                let mut last = None;
+                for entry in cloned.iter(&guard) {
+                    assert!(last.map_or(true, |last| last < *entry));
+                    last.replace(*entry);
+                }
+                if cloned.is_empty() {
+                    break;
+                }
             }
-            for (k, v) in tree.iter(&Guard::new()) {
-                assert_eq!(k, v);
-                assert!(!remove_range.contains(k), "{k}");
+            assert!(thread_handle.join().is_ok());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn into_iter_extend() {
+        let workload_size = 256;
+        let mut queue: Queue<usize> = Queue::default();
+        queue.extend(0..workload_size / 2);
+        queue.extend((workload_size / 2..workload_size).collect::<Vec<_>>());
+        assert_eq!(queue.len(), workload_size);
+        let popped = queue.pop();
+        assert_eq!(popped.as_deref().map(|e| **e), Some(0));
+        let mut expected = 1;
+        for entry in queue {
+            assert_eq!(**entry, expected);
+            expected += 1;
+        }
+        assert_eq!(expected, workload_size);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn drain() {
+        const NUM_THREADS: usize = 4;
+        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
+        let workload_size = 256;
+        for _ in 0..4 {
+            let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+            for task_id in 0..NUM_THREADS {
+                let queue_clone = queue.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    for seq in 1..=workload_size {
+                        queue_clone.push(R::new(task_id, seq));
+                    }
+                }));
             }
-            for k in 0_usize..4096_usize {
-                if tree.peek_with(&k, |_, _|()).is_some() {
-                    assert!(!remove_range.contains(&k), "{k}");
+            let mut popped_acc: [usize; NUM_THREADS] = Default::default();
+            let mut num_drained = 0;
+            while num_drained != workload_size * NUM_THREADS {
+                for popped in queue.drain() {
+                    assert_eq!(popped_acc[popped.0] + 1, popped.1);
+                    popped_acc[popped.0] = popped.1;
+                    num_drained += 1;
                 }
             }
+            for handle in thread_handles {
+                assert!(handle.join().is_ok());
+            }
+            assert!(queue.drain().next().is_none());
+            assert_eq!(queue.len(), 0);
         }
     }
-}
-
-#[cfg(test)]
-mod bag_test {
-    use crate::bag::IterMut;
-    use crate::Bag;
-    use std::panic::UnwindSafe;
-    use std::sync::atomic::AtomicUsize;
-    use std::sync::atomic::Ordering::Relaxed;
-    use std::sync::Arc;
-    use tokio::sync::Barrier as AsyncBarrier;
 
-    static_assertions::assert_impl_all!(Bag<String>: Send, Sync, UnwindSafe);
-    static_assertions::assert_impl_all!(IterMut<'static, String>: Send, Sync, UnwindSafe);
-    static_assertions::assert_not_impl_all!(Bag<*const String>: Send, Sync, UnwindSafe);
-    static_assertions::assert_not_impl_all!(IterMut<'static, *const String>: Send, Sync, UnwindSafe);
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn push_pop_many() {
+        const NUM_THREADS: usize = 4;
+        const BATCH_SIZE: usize = 8;
+        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
+        let workload_size = 256;
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for task_id in 0..NUM_THREADS {
+            let queue_clone = queue.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                for batch in 0..workload_size / BATCH_SIZE {
+                    let start = batch * BATCH_SIZE;
+                    let iter = (start..start + BATCH_SIZE).map(|seq| R::new(task_id, seq));
+                    if task_id % 2 == 0 {
+                        assert_eq!(queue_clone.push_many(iter), BATCH_SIZE);
+                    } else {
+                        (&*queue_clone).extend(iter);
+                    }
+                }
+            }));
+        }
+        let mut popped = Vec::new();
+        while popped.len() != workload_size * NUM_THREADS {
+            let batch = queue.pop_many(5);
+            assert!(batch.len() <= 5);
+            popped.extend(batch.iter().map(|e| (e.0, e.1)));
+        }
+        for handle in thread_handles {
+            assert!(handle.join().is_ok());
+        }
+        for (i, (task_id, seq)) in popped.iter().enumerate() {
+            if seq % BATCH_SIZE != 0 {
+                assert_eq!(popped[i - 1], (*task_id, seq - 1));
+            }
+        }
+        assert!(queue.pop_many(1).is_empty());
+        assert_eq!(queue.push_many(std::iter::empty()), 0);
+        assert_eq!(queue.len(), 0);
+    }
 
-    struct R(&'static AtomicUsize);
-    impl R {
-        fn new(cnt: &'static AtomicUsize) -> R {
-            cnt.fetch_add(1, Relaxed);
-            R(cnt)
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn pop_pop_many() {
+        const NUM_THREADS: usize = 4;
+        let workload_size = 4096;
+        for _ in 0..16 {
+            let queue: Arc<Queue<usize>> = Arc::new(Queue::default());
+            assert_eq!(queue.push_many(0..workload_size), workload_size);
+            let barrier = Arc::new(Barrier::new(NUM_THREADS));
+            let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+            for task_id in 0..NUM_THREADS {
+                let barrier_clone = barrier.clone();
+                let queue_clone = queue.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    barrier_clone.wait();
+                    let mut popped = Vec::new();
+                    loop {
+                        let batch = if task_id % 2 == 0 {
+                            queue_clone.pop().into_iter().collect()
+                        } else {
+                            queue_clone.pop_many(task_id + 1)
+                        };
+                        if batch.is_empty() {
+                            break;
+                        }
+                        assert!(batch.len() <= task_id + 1);
+                        popped.extend(batch.iter().map(|e| ***e));
+                    }
+                    popped
+                }));
+            }
+            let mut all = Vec::new();
+            for handle in thread_handles {
+                let popped = handle.join().unwrap();
+                assert!(popped.windows(2).all(|w| w[0] < w[1]));
+                all.extend(popped);
+            }
+            all.sort_unstable();
+            assert_eq!(all, (0..workload_size).collect::<Vec<_>>());
+            assert_eq!(queue.len(), 0);
         }
     }
-    impl Clone for R {
-        fn clone(&self) -> Self {
-            self.0.fetch_add(1, Relaxed);
-            R(self.0)
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn peek_pop_if() {
+        const NUM_THREADS: usize = 4;
+        let queue: Arc<Queue<usize>> = Arc::new(Queue::default());
+        let workload_size = 1024;
+        for seq in 0..workload_size {
+            queue.push(seq);
+        }
+        assert_eq!(queue.len(), workload_size);
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for _ in 0..NUM_THREADS {
+            let queue_clone = queue.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                let mut popped = Vec::new();
+                loop {
+                    let guard = Guard::new();
+                    let Some(oldest) = queue_clone.peek(&guard) else {
+                        break;
+                    };
+                    let oldest_val = **oldest;
+                    assert!(queue_clone.peek_with(|e| e.map_or(true, |e| **e >= oldest_val)));
+                    if let Ok(Some(entry)) = queue_clone.pop_if(|e| **e == oldest_val) {
+                        assert_eq!(**entry, oldest_val);
+                        assert!(queue_clone.len() < workload_size - oldest_val);
+                        popped.push(oldest_val);
+                    }
+                }
+                popped
+            }));
         }
+        let mut popped = Vec::new();
+        for handle in thread_handles {
+            let result = handle.join();
+            assert!(result.is_ok());
+            popped.extend(result.unwrap_or_default());
+        }
+        popped.sort_unstable();
+        assert_eq!(popped, (0..workload_size).collect::<Vec<_>>());
+        assert!(queue.peek(&Guard::new()).is_none());
+        assert!(queue.peek_with(|e| e.is_none()));
+        assert_eq!(queue.len(), 0);
     }
-    impl Drop for R {
-        fn drop(&mut self) {
-            self.0.fetch_sub(1, Relaxed);
+
+    #[test]
+    fn pop_if_scheduled() {
+        let queue: Queue<(usize, usize)> = Queue::default();
+        for seq in 0..16 {
+            queue.push((seq, seq * 2));
+        }
+        for now in 0..32 {
+            while let Ok(Some(entry)) = queue.pop_if(|e| e.1 <= now) {
+                assert!(entry.1 <= now);
+            }
+            let len = queue.len();
+            if let Err(entry) = queue.pop_if(|e| e.1 <= now) {
+                assert!(entry.1 > now);
+                assert_eq!(queue.len(), len);
+                assert_eq!(queue.peek_with(|e| e.map(|e| e.0)), Some(entry.0));
+            }
         }
+        assert!(queue.is_empty());
     }
 
     #[cfg_attr(miri, ignore)]
     #[test]
-    fn reclaim() {
-        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
-        for workload_size in [2, 18, 32, 40, 120] {
-            let mut bag: Bag<R> = Bag::default();
-            for _ in 0..workload_size {
-                bag.push(R::new(&INST_CNT));
+    fn pop_contention() {
+        const NUM_THREADS: usize = 8;
+        let queue: Arc<Queue<usize>> = Arc::new(Queue::default());
+        let workload_size = 4096;
+        for _ in 0..4 {
+            queue.push_many(0..workload_size);
+            let barrier = Arc::new(std::sync::Barrier::new(NUM_THREADS));
+            let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+            for _ in 0..NUM_THREADS {
+                let barrier_clone = barrier.clone();
+                let queue_clone = queue.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    barrier_clone.wait();
+                    let mut popped = Vec::new();
+                    while let Some(entry) = queue_clone.pop() {
+                        assert!(popped.last().map_or(true, |last| *last < **entry));
+                        popped.push(**entry);
+                    }
+                    popped
+                }));
             }
-            assert_eq!(INST_CNT.load(Relaxed), workload_size);
-            assert_eq!(bag.iter_mut().count(), workload_size);
-            bag.iter_mut().for_each(|e| {
-                *e = R::new(&INST_CNT);
-            });
-
-            for _ in 0..workload_size / 2 {
-                bag.pop();
+            let mut popped = Vec::new();
+            for handle in thread_handles {
+                let result = handle.join();
+                assert!(result.is_ok());
+                popped.extend(result.unwrap_or_default());
             }
-            assert_eq!(INST_CNT.load(Relaxed), workload_size / 2);
-            drop(bag);
-            assert_eq!(INST_CNT.load(Relaxed), 0);
+            popped.sort_unstable();
+            assert_eq!(popped, (0..workload_size).collect::<Vec<_>>());
+            assert!(queue.is_empty());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn pop_async() {
+        const NUM_TASKS: usize = 4;
+        const NUM_PRODUCERS: usize = NUM_TASKS / 2;
+        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
+        let workload_size = 256;
+        let mut task_handles = Vec::with_capacity(NUM_TASKS);
+        for task_id in 0..NUM_TASKS {
+            let queue_clone = queue.clone();
+            task_handles.push(tokio::task::spawn(async move {
+                if task_id < NUM_PRODUCERS {
+                    for seq in 1..=workload_size {
+                        queue_clone.push(R::new(task_id, seq));
+                        if seq % 16 == 0 {
+                            tokio::task::yield_now().await;
+                        }
+                    }
+                } else {
+                    let mut popped_acc: [usize; NUM_PRODUCERS] = Default::default();
+                    for _ in 0..workload_size {
+                        let popped = queue_clone.pop_async().await;
+                        assert!(popped_acc[popped.0] < popped.1);
+                        popped_acc[popped.0] = popped.1;
+                    }
+                }
+            }));
+        }
+        for r in futures::future::join_all(task_handles).await {
+            assert!(r.is_ok());
         }
+        assert!(queue.pop().is_none());
+
+        let pending = {
+            let queue_clone = queue.clone();
+            tokio::task::spawn(async move { queue_clone.pop_async().await.0 })
+        };
+        tokio::task::yield_now().await;
+        queue.push(R::new(0, 0));
+        queue.push(R::new(1, 1));
+        assert_eq!(pending.await.ok(), Some(0));
+        assert_eq!(queue.pop().map(|e| e.0), Some(1));
     }
 
     #[cfg_attr(miri, ignore)]
     #[test]
-    fn into_iter() {
-        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
-        for workload_size in [2, 18, 32, 40, 120] {
-            let mut bag: Bag<R> = Bag::default();
-            for _ in 0..workload_size {
-                bag.push(R::new(&INST_CNT));
-            }
-            assert_eq!(INST_CNT.load(Relaxed), workload_size);
-            assert_eq!(bag.len(), workload_size);
-            assert_eq!(bag.iter_mut().count(), workload_size);
+    fn pop_all() {
+        const NUM_THREADS: usize = 4;
+        const NUM_PRODUCERS: usize = NUM_THREADS / 2;
+        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
+        let num_popped = Arc::new(AtomicUsize::new(0));
+        let workload_size = 1024;
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for task_id in 0..NUM_THREADS {
+            let queue_clone = queue.clone();
+            let num_popped_clone = num_popped.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                if task_id < NUM_PRODUCERS {
+                    for seq in 1..=workload_size {
+                        queue_clone.push(R::new(task_id, seq));
+                    }
+                } else {
+                    let mut popped_acc: [usize; NUM_PRODUCERS] = Default::default();
+                    while num_popped_clone.load(Relaxed) != workload_size * NUM_PRODUCERS {
+                        let mut cnt = 0;
+                        if task_id % 2 == 0 {
+                            for popped in queue_clone.pop_all() {
+                                assert!(popped_acc[popped.0] < popped.1);
+                                popped_acc[popped.0] = popped.1;
+                                cnt += 1;
+                            }
+                        } else if let Some(popped) = queue_clone.pop() {
+                            assert!(popped_acc[popped.0] < popped.1);
+                            popped_acc[popped.0] = popped.1;
+                            cnt += 1;
+                        }
+                        num_popped_clone.fetch_add(cnt, Relaxed);
+                    }
+                }
+            }));
+        }
+        for handle in thread_handles {
+            assert!(handle.join().is_ok());
+        }
+        assert!(queue.is_empty());
+        assert_eq!(queue.len(), 0);
+        queue.push(R::new(0, 0));
+        assert_eq!(queue.pop_all().map(|e| e.1).collect::<Vec<_>>(), vec![0]);
+        assert!(queue.pop_all().next().is_none());
+
+        // Entries marked by the user are popped as well.
+        let oldest = queue.push(R::new(0, 1));
+        let newest = queue.push(R::new(0, 2));
+        assert!(oldest.mark(Relaxed));
+        assert!(newest.mark(Relaxed));
+        assert_eq!(queue.pop_all().map(|e| e.1).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(queue.is_empty());
+    }
 
-            for v in &mut bag {
-                assert_eq!(v.0.load(Relaxed), INST_CNT.load(Relaxed));
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn pop_all_push() {
+        const NUM_PRODUCERS: usize = 4;
+        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
+        let workload_size = 4096;
+        for _ in 0..4 {
+            let finished = Arc::new(AtomicUsize::new(0));
+            let mut thread_handles = Vec::with_capacity(NUM_PRODUCERS);
+            for task_id in 0..NUM_PRODUCERS {
+                let queue_clone = queue.clone();
+                let finished_clone = finished.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    for seq in 1..=workload_size {
+                        queue_clone.push(R::new(task_id, seq));
+                    }
+                    finished_clone.fetch_add(1, Relaxed);
+                }));
             }
-            assert_eq!(INST_CNT.load(Relaxed), workload_size);
+            let mut popped_acc: [usize; NUM_PRODUCERS] = Default::default();
+            let mut num_popped = 0;
+            loop {
+                let producers_finished = finished.load(Relaxed) == NUM_PRODUCERS;
+                for popped in queue.pop_all() {
+                    assert_eq!(popped_acc[popped.0] + 1, popped.1);
+                    popped_acc[popped.0] = popped.1;
+                    num_popped += 1;
+                }
+                if producers_finished {
+                    break;
+                }
+            }
+            for handle in thread_handles {
+                assert!(handle.join().is_ok());
+            }
+            assert_eq!(num_popped, workload_size * NUM_PRODUCERS);
+            assert!(queue.is_empty());
+            assert_eq!(queue.len(), 0);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn pop_timeout() {
+        const NUM_THREADS: usize = 4;
+        const NUM_PRODUCERS: usize = NUM_THREADS / 2;
+        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
+        let num_popped = Arc::new(AtomicUsize::new(0));
+        let workload_size = 256;
+        assert!(queue.pop_timeout(Duration::from_millis(1)).is_none());
+
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for task_id in 0..NUM_THREADS {
+            let queue_clone = queue.clone();
+            let num_popped_clone = num_popped.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                if task_id < NUM_PRODUCERS {
+                    for seq in 1..=workload_size {
+                        queue_clone.push(R::new(task_id, seq));
+                        if seq % 16 == 0 {
+                            std::thread::sleep(Duration::from_micros(100));
+                        }
+                    }
+                } else {
+                    let mut popped_acc: [usize; NUM_PRODUCERS] = Default::default();
+                    while num_popped_clone.load(Relaxed) != workload_size * NUM_PRODUCERS {
+                        if let Some(popped) = queue_clone.pop_timeout(Duration::from_millis(1)) {
+                            assert!(popped_acc[popped.0] < popped.1);
+                            popped_acc[popped.0] = popped.1;
+                            num_popped_clone.fetch_add(1, Relaxed);
+                        }
+                    }
+                }
+            }));
+        }
+        for handle in thread_handles {
+            assert!(handle.join().is_ok());
+        }
+        assert!(queue.is_empty());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bounded() {
+        const NUM_THREADS: usize = 4;
+        let capacity = 7;
+        let queue: Arc<BoundedQueue<usize>> = Arc::new(BoundedQueue::new(capacity));
+        let num_popped = Arc::new(AtomicUsize::new(0));
+        let workload_size = 256;
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for _ in 0..NUM_THREADS {
+            let queue_clone = queue.clone();
+            let num_popped_clone = num_popped.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                let mut num_pushed = 0;
+                while num_pushed != workload_size {
+                    match queue_clone.push(num_pushed) {
+                        Ok(_) => num_pushed += 1,
+                        Err(val) => {
+                            assert_eq!(val, num_pushed);
+                            if queue_clone.pop().is_some() {
+                                num_popped_clone.fetch_add(1, Relaxed);
+                            }
+                        }
+                    }
+                    assert!(queue_clone.len() <= capacity);
+                }
+            }));
+        }
+        for handle in thread_handles {
+            assert!(handle.join().is_ok());
+        }
+        assert_eq!(
+            queue.len() + num_popped.load(Relaxed),
+            workload_size * NUM_THREADS
+        );
+        let len = queue.len();
+        for _ in 0..len {
+            assert!(queue.pop().is_some());
+        }
+        assert!(queue.pop().is_none());
+        assert!(queue.is_empty());
+        assert_eq!(queue.len(), 0);
+    }
 
-            for v in bag {
-                assert_eq!(v.0.load(Relaxed), INST_CNT.load(Relaxed));
-            }
-            assert_eq!(INST_CNT.load(Relaxed), 0);
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn bounded_push_async() {
+        const NUM_TASKS: usize = 4;
+        const NUM_PRODUCERS: usize = NUM_TASKS - 1;
+        let capacity = 3;
+        let queue: Arc<BoundedQueue<R>> = Arc::new(BoundedQueue::new(capacity));
+        let workload_size = 256;
+        let mut task_handles = Vec::with_capacity(NUM_TASKS);
+        for task_id in 0..NUM_TASKS {
+            let queue_clone = queue.clone();
+            task_handles.push(tokio::task::spawn(async move {
+                if task_id < NUM_PRODUCERS {
+                    for seq in 1..=workload_size {
+                        let entry = queue_clone.push_async(R::new(task_id, seq)).await;
+                        assert_eq!(entry.1, seq);
+                        assert!(queue_clone.len() <= capacity);
+                    }
+                } else {
+                    let mut popped_acc: [usize; NUM_PRODUCERS] = Default::default();
+                    let mut num_popped = 0;
+                    while num_popped != workload_size * NUM_PRODUCERS {
+                        if let Some(popped) = queue_clone.pop() {
+                            assert!(popped_acc[popped.0] < popped.1);
+                            popped_acc[popped.0] = popped.1;
+                            num_popped += 1;
+                        } else {
+                            tokio::task::yield_now().await;
+                        }
+                    }
+                }
+            }));
+        }
+        for r in futures::future::join_all(task_handles).await {
+            assert!(r.is_ok());
         }
+        assert!(queue.is_empty());
     }
 
     #[cfg_attr(miri, ignore)]
-    #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
-    async fn mpmc() {
-        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
-        const NUM_TASKS: usize = 6;
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn iterator() {
+        const NUM_TASKS: usize = 2;
+        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
         let workload_size = 256;
-        let bag32: Arc<Bag<R>> = Arc::new(Bag::default());
-        let bag17: Arc<Bag<R, 17>> = Arc::new(Bag::new());
-        for _ in 0..256 {
+        for _ in 0..16 {
             let mut task_handles = Vec::with_capacity(NUM_TASKS);
             let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
-            for _ in 0..NUM_TASKS {
+            for task_id in 0..NUM_TASKS {
                 let barrier_clone = barrier.clone();
-                let bag32_clone = bag32.clone();
-                let bag17_clone = bag17.clone();
+                let queue_clone = queue.clone();
                 task_handles.push(tokio::task::spawn(async move {
-                    barrier_clone.wait().await;
-                    for _ in 0..workload_size {
-                        bag32_clone.push(R::new(&INST_CNT));
-                        bag17_clone.push(R::new(&INST_CNT));
-                    }
-                    for _ in 0..workload_size {
-                        assert!(!bag32_clone.is_empty());
-                        assert!(bag32_clone.pop().is_some());
-                        assert!(!bag17_clone.is_empty());
-                        assert!(bag17_clone.pop().is_some());
+                    if task_id == 0 {
+                        for seq in 0..workload_size {
+                            if seq == workload_size / 2 {
+                                barrier_clone.wait().await;
+                            }
+                            assert_eq!(queue_clone.push(R::new(task_id, seq)).1, seq);
+                        }
+                        let mut last = 0;
+                        while let Some(popped) = queue_clone.pop() {
+                            let current = popped.1;
+                            assert!(last == 0 || last + 1 == current);
+                            last = current;
+                        }
+                    } else {
+                        let mut last = 0;
+
+                        barrier_clone.wait().await;
+                        let guard = Guard::new();
+                        let iter = queue_clone.iter(&guard);
+                        for current in iter {
+                            let current = current.1;
+                            assert!(current == 0 || last + 1 == current);
+                            last = current;
+                        }
                     }
                 }));
             }
@@ -2390,49 +4607,47 @@ mod bag_test {
             for r in futures::future::join_all(task_handles).await {
                 assert!(r.is_ok());
             }
-            assert!(bag32.pop().is_none());
-            assert!(bag32.is_empty());
-            assert!(bag17.pop().is_none());
-            assert!(bag17.is_empty());
         }
-        assert_eq!(INST_CNT.load(Relaxed), 0);
+        assert!(queue.is_empty());
+        assert_eq!(queue.len(), 0);
     }
 
     #[cfg_attr(miri, ignore)]
     #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
-    async fn mpsc() {
-        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
-        const NUM_TASKS: usize = 6;
+    async fn mpmc() {
+        const NUM_TASKS: usize = 12;
+        const NUM_PRODUCERS: usize = NUM_TASKS / 2;
+        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
         let workload_size = 256;
-        let bag32: Arc<Bag<R>> = Arc::new(Bag::default());
-        let bag7: Arc<Bag<R, 7>> = Arc::new(Bag::new());
-        for _ in 0..256 {
+        for _ in 0..16 {
+            let num_popped: Arc<AtomicUsize> = Arc::new(AtomicUsize::default());
             let mut task_handles = Vec::with_capacity(NUM_TASKS);
             let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
             for task_id in 0..NUM_TASKS {
                 let barrier_clone = barrier.clone();
-                let bag32_clone = bag32.clone();
-                let bag7_clone = bag7.clone();
+                let queue_clone = queue.clone();
+                let num_popped_clone = num_popped.clone();
                 task_handles.push(tokio::task::spawn(async move {
                     barrier_clone.wait().await;
-                    let mut cnt = 0;
-                    while task_id == 0 && cnt < workload_size * (NUM_TASKS - 1) * 2 {
-                        cnt += bag32_clone.pop_all(0, |a, _| a + 1);
-                        cnt += bag7_clone.pop_all(0, |a, _| a + 1);
-                        tokio::task::yield_now().await;
-                    }
-                    if task_id != 0 {
-                        for _ in 0..workload_size {
-                            bag32_clone.push(R::new(&INST_CNT));
-                            bag7_clone.push(R::new(&INST_CNT));
+                    if task_id < NUM_PRODUCERS {
+                        for seq in 1..=workload_size {
+                            assert_eq!(queue_clone.push(R::new(task_id, seq)).1, seq);
                         }
-                        for _ in 0..workload_size / 16 {
-                            if bag32_clone.pop().is_some() {
-                                bag32_clone.push(R::new(&INST_CNT));
+                    } else {
+                        let mut popped_acc: [usize; NUM_PRODUCERS] = Default::default();
+                        loop {
+                            let mut cnt = 0;
+                            while let Some(popped) = queue_clone.pop() {
+                                cnt += 1;
+                                assert!(popped_acc[popped.0] < popped.1);
+                                popped_acc[popped.0] = popped.1;
                             }
-                            if bag7_clone.pop().is_some() {
-                                bag7_clone.push(R::new(&INST_CNT));
+                            if num_popped_clone.fetch_add(cnt, Relaxed) + cnt
+                                == workload_size * NUM_PRODUCERS
+                            {
+                                break;
                             }
+                            tokio::task::yield_now().await;
                         }
                     }
                 }));
@@ -2441,28 +4656,23 @@ mod bag_test {
             for r in futures::future::join_all(task_handles).await {
                 assert!(r.is_ok());
             }
-            assert!(bag32.pop().is_none());
-            assert!(bag32.is_empty());
-            assert!(bag7.pop().is_none());
-            assert!(bag7.is_empty());
         }
-        assert_eq!(INST_CNT.load(Relaxed), 0);
+        assert!(queue.is_empty());
+        assert_eq!(queue.len(), 0);
     }
 }
 
 #[cfg(test)]
-mod queue_test {
+mod stack_test {
     use crate::ebr::Guard;
-    use crate::Queue;
-    use std::panic::UnwindSafe;
-    use std::sync::atomic::AtomicUsize;
-    use std::sync::atomic::Ordering::Relaxed;
-    use std::sync::Arc;
+    use crate::{LinkedEntryPool, Stack};
+    use std::{panic::UnwindSafe, sync::Arc};
     use tokio::sync::Barrier as AsyncBarrier;
 
-    static_assertions::assert_impl_all!(Queue<String>: Send, Sync, UnwindSafe);
-    static_assertions::assert_not_impl_all!(Queue<*const String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_impl_all!(Stack<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(Stack<*const String>: Send, Sync, UnwindSafe);
 
+    #[derive(Debug)]
     struct R(usize, usize);
     impl R {
         fn new(task_id: usize, seq: usize) -> R {
@@ -2473,59 +4683,301 @@ mod queue_test {
     #[cfg_attr(miri, ignore)]
     #[test]
     fn clone() {
-        let queue = Queue::default();
-        queue.push(37);
-        queue.push(3);
-        queue.push(1);
+        let stack = Stack::default();
+        stack.push(37);
+        stack.push(3);
+        stack.push(1);
 
-        let queue_clone = queue.clone();
+        let stack_clone = stack.clone();
 
-        assert_eq!(queue.pop().map(|e| **e), Some(37));
-        assert_eq!(queue.pop().map(|e| **e), Some(3));
-        assert_eq!(queue.pop().map(|e| **e), Some(1));
-        assert!(queue.pop().is_none());
+        assert_eq!(stack.pop().map(|e| **e), Some(1));
+        assert_eq!(stack.pop().map(|e| **e), Some(3));
+        assert_eq!(stack.pop().map(|e| **e), Some(37));
+        assert!(stack.pop().is_none());
+
+        assert_eq!(stack_clone.pop().map(|e| **e), Some(1));
+        assert_eq!(stack_clone.pop().map(|e| **e), Some(3));
+        assert_eq!(stack_clone.pop().map(|e| **e), Some(37));
+        assert!(stack_clone.pop().is_none());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn debug() {
+        let stack: Stack<usize> = Stack::default();
+        assert_eq!(format!("{stack:?}"), "[]");
+        (0..40).for_each(|v| {
+            stack.push(v);
+        });
+        assert_eq!(stack.pop().map(|e| **e), Some(39));
+        let debug_str = format!("{stack:?}");
+        assert!(debug_str.starts_with("[38, 37, "));
+        assert!(debug_str.ends_with("8, 7, ..: 7 more]"));
+    }
+
+    #[test]
+    fn clone_popped() {
+        let stack = Stack::default();
+        stack.push(37);
+        stack.push(3);
+        stack.push(1);
+        assert_eq!(stack.pop().map(|e| **e), Some(1));
+
+        let stack_clone = stack.clone();
+
+        assert_eq!(stack.pop().map(|e| **e), Some(3));
+        assert_eq!(stack.pop().map(|e| **e), Some(37));
+        assert!(stack.pop().is_none());
+
+        assert_eq!(stack_clone.len(), 2);
+        assert_eq!(stack_clone.pop().map(|e| **e), Some(3));
+        assert_eq!(stack_clone.pop().map(|e| **e), Some(37));
+        assert!(stack_clone.pop().is_none());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn into_iter_extend() {
+        let workload_size = 256;
+        let mut stack: Stack<usize> = Stack::default();
+        stack.extend(0..workload_size);
+        assert_eq!(stack.len(), workload_size);
+        let popped = stack.pop();
+        assert_eq!(popped.as_deref().map(|e| **e), Some(workload_size - 1));
+        let mut expected = workload_size - 1;
+        for entry in stack {
+            expected -= 1;
+            assert_eq!(**entry, expected);
+        }
+        assert_eq!(expected, 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn push_if_monotonic() {
+        const NUM_THREADS: usize = 4;
+        let stack: Arc<Stack<usize>> = Arc::new(Stack::default());
+        let workload_size = 1024;
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for task_id in 0..NUM_THREADS {
+            let stack_clone = stack.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                for seq in 0..workload_size {
+                    let watermark = seq * NUM_THREADS + task_id;
+                    let _result =
+                        stack_clone.push_if(watermark, |e| e.map_or(true, |e| **e < watermark));
+                }
+            }));
+        }
+        for handle in thread_handles {
+            assert!(handle.join().is_ok());
+        }
+        let mut last = usize::MAX;
+        while let Some(entry) = stack.pop() {
+            assert!(**entry < last);
+            last = **entry;
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn drain() {
+        const NUM_THREADS: usize = 4;
+        let stack: Arc<Stack<R>> = Arc::new(Stack::default());
+        let workload_size = 256;
+        for _ in 0..4 {
+            let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+            for task_id in 0..NUM_THREADS {
+                let stack_clone = stack.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    for seq in 1..=workload_size {
+                        stack_clone.push(R::new(task_id, seq));
+                    }
+                }));
+            }
+            let mut num_drained = 0;
+            while num_drained != workload_size * NUM_THREADS {
+                let mut last_popped: [usize; NUM_THREADS] = [usize::MAX; NUM_THREADS];
+                for popped in stack.drain().take(workload_size) {
+                    assert!(popped.1 < last_popped[popped.0]);
+                    last_popped[popped.0] = popped.1;
+                    num_drained += 1;
+                }
+            }
+            for handle in thread_handles {
+                assert!(handle.join().is_ok());
+            }
+            assert!(stack.drain().next().is_none());
+            assert!(stack.is_empty());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn push_pooled() {
+        const NUM_THREADS: usize = 4;
+        let workload_size = 256;
+        let pool: Arc<LinkedEntryPool<usize>> =
+            Arc::new(LinkedEntryPool::new(NUM_THREADS * workload_size));
+        let stack: Arc<Stack<usize>> = Arc::new(Stack::default());
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for task_id in 0..NUM_THREADS {
+            let pool_clone = pool.clone();
+            let stack_clone = stack.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                for seq in 0..workload_size * 2 {
+                    let val = task_id * workload_size * 2 + seq;
+                    if let Err(val) = stack_clone.push_pooled(val, &pool_clone) {
+                        stack_clone.push(val);
+                    }
+                }
+            }));
+        }
+        for handle in thread_handles {
+            assert!(handle.join().is_ok());
+        }
+        assert!(pool.is_empty());
+        assert_eq!(stack.len(), NUM_THREADS * workload_size * 2);
+        pool.reserve(1);
+        assert_eq!(stack.push_pooled(1, &pool).map(|e| **e), Ok(1));
+        assert_eq!(stack.pop().map(|e| **e), Some(1));
+
+        // Popped entries are reused once no other thread can read them.
+        while let Some(entry) = stack.pop() {
+            pool.recycle(entry);
+        }
+        assert_eq!(pool.len(), NUM_THREADS * workload_size * 2);
+        let mut num_pushed = 0;
+        while num_pushed < NUM_THREADS * workload_size * 2 {
+            if stack.push_pooled(num_pushed, &pool).is_ok() {
+                num_pushed += 1;
+            } else {
+                let guard = Guard::new();
+                guard.accelerate();
+            }
+        }
+        assert!(pool.is_empty());
+        assert_eq!(stack.len(), num_pushed);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn swap_top_pop() {
+        const NUM_THREADS: usize = 4;
+        let stack: Arc<Stack<usize>> = Arc::new(Stack::default());
+        let workload_size = 1024;
+        for seq in 0..workload_size {
+            stack.push(seq);
+        }
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for task_id in 0..NUM_THREADS {
+            let stack_clone = stack.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                let mut removed = Vec::new();
+                for seq in 0..workload_size {
+                    let entry = if task_id % 2 == 0 {
+                        stack_clone.swap_top((task_id + 1) * workload_size + seq)
+                    } else {
+                        stack_clone.pop()
+                    };
+                    if let Some(entry) = entry {
+                        removed.push(**entry);
+                    }
+                }
+                removed
+            }));
+        }
+        let mut all = Vec::new();
+        for handle in thread_handles {
+            all.append(&mut handle.join().unwrap());
+        }
+        while let Some(entry) = stack.pop() {
+            all.push(**entry);
+        }
+        all.sort_unstable();
+        let mut expected: Vec<usize> = (0..workload_size).collect();
+        for task_id in (0..NUM_THREADS).filter(|t| t % 2 == 0) {
+            expected.extend((task_id + 1) * workload_size..(task_id + 2) * workload_size);
+        }
+        assert_eq!(all, expected);
+    }
 
-        assert_eq!(queue_clone.pop().map(|e| **e), Some(37));
-        assert_eq!(queue_clone.pop().map(|e| **e), Some(3));
-        assert_eq!(queue_clone.pop().map(|e| **e), Some(1));
-        assert!(queue_clone.pop().is_none());
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn peek_pop_if() {
+        const NUM_THREADS: usize = 4;
+        let stack: Arc<Stack<usize>> = Arc::new(Stack::default());
+        let workload_size = 1024;
+        for seq in 0..workload_size {
+            stack.push(seq);
+        }
+        let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+        for _ in 0..NUM_THREADS {
+            let stack_clone = stack.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                let mut popped = Vec::new();
+                loop {
+                    let guard = Guard::new();
+                    let Some(newest) = stack_clone.peek(&guard) else {
+                        break;
+                    };
+                    let newest_val = **newest;
+                    assert!(stack_clone.peek_with(|e| e.map_or(true, |e| **e <= newest_val)));
+                    if let Ok(Some(entry)) = stack_clone.pop_if(|e| **e == newest_val) {
+                        assert_eq!(**entry, newest_val);
+                        popped.push(newest_val);
+                    }
+                }
+                popped
+            }));
+        }
+        let mut popped = Vec::new();
+        for handle in thread_handles {
+            let result = handle.join();
+            assert!(result.is_ok());
+            popped.extend(result.unwrap_or_default());
+        }
+        popped.sort_unstable();
+        assert_eq!(popped, (0..workload_size).collect::<Vec<_>>());
+        assert!(stack.peek(&Guard::new()).is_none());
+        assert!(stack.peek_with(|e| e.is_none()));
     }
 
     #[cfg_attr(miri, ignore)]
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn iterator() {
         const NUM_TASKS: usize = 2;
-        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
+        let stack: Arc<Stack<R>> = Arc::new(Stack::default());
         let workload_size = 256;
         for _ in 0..16 {
             let mut task_handles = Vec::with_capacity(NUM_TASKS);
             let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
             for task_id in 0..NUM_TASKS {
                 let barrier_clone = barrier.clone();
-                let queue_clone = queue.clone();
+                let stack_clone = stack.clone();
                 task_handles.push(tokio::task::spawn(async move {
                     if task_id == 0 {
                         for seq in 0..workload_size {
                             if seq == workload_size / 2 {
                                 barrier_clone.wait().await;
                             }
-                            assert_eq!(queue_clone.push(R::new(task_id, seq)).1, seq);
+                            assert_eq!(stack_clone.push(R::new(task_id, seq)).1, seq);
                         }
-                        let mut last = 0;
-                        while let Some(popped) = queue_clone.pop() {
+                        let mut last = workload_size;
+                        while let Some(popped) = stack_clone.pop() {
                             let current = popped.1;
-                            assert!(last == 0 || last + 1 == current);
+                            assert_eq!(current + 1, last);
                             last = current;
                         }
                     } else {
-                        let mut last = 0;
+                        let mut last = workload_size;
 
                         barrier_clone.wait().await;
                         let guard = Guard::new();
-                        let iter = queue_clone.iter(&guard);
+                        let iter = stack_clone.iter(&guard);
                         for current in iter {
                             let current = current.1;
-                            assert!(current == 0 || last + 1 == current);
+                            assert!(last == workload_size || last - 1 == current);
                             last = current;
                         }
                     }
@@ -2536,45 +4988,79 @@ mod queue_test {
                 assert!(r.is_ok());
             }
         }
-        assert!(queue.is_empty());
+        assert!(stack.is_empty());
     }
 
     #[cfg_attr(miri, ignore)]
     #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
     async fn mpmc() {
         const NUM_TASKS: usize = 12;
-        const NUM_PRODUCERS: usize = NUM_TASKS / 2;
-        let queue: Arc<Queue<R>> = Arc::new(Queue::default());
+        let stack: Arc<Stack<R>> = Arc::new(Stack::default());
         let workload_size = 256;
         for _ in 0..16 {
-            let num_popped: Arc<AtomicUsize> = Arc::new(AtomicUsize::default());
             let mut task_handles = Vec::with_capacity(NUM_TASKS);
             let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
             for task_id in 0..NUM_TASKS {
                 let barrier_clone = barrier.clone();
-                let queue_clone = queue.clone();
-                let num_popped_clone = num_popped.clone();
+                let stack_clone = stack.clone();
                 task_handles.push(tokio::task::spawn(async move {
                     barrier_clone.wait().await;
-                    if task_id < NUM_PRODUCERS {
-                        for seq in 1..=workload_size {
-                            assert_eq!(queue_clone.push(R::new(task_id, seq)).1, seq);
+                    for seq in 0..workload_size {
+                        assert_eq!(stack_clone.push(R::new(task_id, seq)).1, seq);
+                    }
+                    let mut last_popped = usize::MAX;
+                    let mut cnt = 0;
+                    while cnt < workload_size {
+                        while let Ok(Some(popped)) = stack_clone.pop_if(|e| e.0 == task_id) {
+                            assert_eq!(popped.0, task_id);
+                            assert!(last_popped > popped.1);
+                            last_popped = popped.1;
+                            cnt += 1;
                         }
-                    } else {
-                        let mut popped_acc: [usize; NUM_PRODUCERS] = Default::default();
-                        loop {
-                            let mut cnt = 0;
-                            while let Some(popped) = queue_clone.pop() {
-                                cnt += 1;
-                                assert!(popped_acc[popped.0] < popped.1);
-                                popped_acc[popped.0] = popped.1;
-                            }
-                            if num_popped_clone.fetch_add(cnt, Relaxed) + cnt
-                                == workload_size * NUM_PRODUCERS
-                            {
-                                break;
+                        tokio::task::yield_now().await;
+                    }
+                }));
+            }
+
+            for r in futures::future::join_all(task_handles).await {
+                assert!(r.is_ok());
+            }
+        }
+        assert!(stack.is_empty());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
+    async fn mpsc() {
+        const NUM_TASKS: usize = 12;
+        let stack: Arc<Stack<R>> = Arc::new(Stack::default());
+        let workload_size = 256;
+        for _ in 0..16 {
+            let mut task_handles = Vec::with_capacity(NUM_TASKS);
+            let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
+            for task_id in 0..NUM_TASKS {
+                let barrier_clone = barrier.clone();
+                let stack_clone = stack.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    barrier_clone.wait().await;
+                    let mut cnt = 0;
+                    while task_id == 0 && cnt < workload_size * (NUM_TASKS - 1) {
+                        // Consumer.
+                        let popped = stack_clone.pop_all();
+                        while let Some(e) = popped.pop() {
+                            assert_ne!(e.0, 0);
+                            cnt += 1;
+                        }
+                        tokio::task::yield_now().await;
+                    }
+                    if task_id != 0 {
+                        for seq in 0..workload_size {
+                            assert_eq!(stack_clone.push(R::new(task_id, seq)).1, seq);
+                        }
+                        for seq in 0..workload_size / 16 {
+                            if stack_clone.pop().is_some() {
+                                assert_eq!(stack_clone.push(R::new(task_id, seq)).1, seq);
                             }
-                            tokio::task::yield_now().await;
                         }
                     }
                 }));
@@ -2584,187 +5070,457 @@ mod queue_test {
                 assert!(r.is_ok());
             }
         }
-        assert!(queue.is_empty());
+        assert!(stack.is_empty());
     }
 }
 
 #[cfg(test)]
-mod stack_test {
-    use crate::ebr::Guard;
-    use crate::Stack;
-    use std::{panic::UnwindSafe, sync::Arc};
-    use tokio::sync::Barrier as AsyncBarrier;
-
-    static_assertions::assert_impl_all!(Stack<String>: Send, Sync, UnwindSafe);
-    static_assertions::assert_not_impl_all!(Stack<*const String>: Send, Sync, UnwindSafe);
+mod linked_list_test {
+    use crate::ebr::{AtomicShared, Guard, Shared};
+    use crate::LinkedList;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::{Arc, Barrier};
 
-    #[derive(Debug)]
-    struct R(usize, usize);
-    impl R {
-        fn new(task_id: usize, seq: usize) -> R {
-            R(task_id, seq)
+    #[derive(Default)]
+    struct L(AtomicShared<L>, usize);
+    impl LinkedList for L {
+        fn link_ref(&self) -> &AtomicShared<L> {
+            &self.0
         }
     }
 
     #[cfg_attr(miri, ignore)]
     #[test]
-    fn clone() {
-        let stack = Stack::default();
-        stack.push(37);
-        stack.push(3);
-        stack.push(1);
+    fn cursor_remove_insert() {
+        let num_threads = 4;
+        let total = 1024;
+        let head: Arc<L> = Arc::new(L::default());
+        let guard = Guard::new();
+        for v in (0..total).rev() {
+            assert!(head
+                .push_back(
+                    Shared::new(L(AtomicShared::null(), v)),
+                    false,
+                    Relaxed,
+                    &guard
+                )
+                .is_ok());
+        }
+        drop(guard);
 
-        let stack_clone = stack.clone();
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for task_id in 0..num_threads {
+            let barrier_clone = barrier.clone();
+            let head_clone = head.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                barrier_clone.wait();
+                let guard = Guard::new();
+                let mut cursor = head_clone.cursor(&guard);
+                while let Some(entry) = cursor.get() {
+                    if entry.1 >= total || entry.1 % num_threads != task_id {
+                        cursor.move_next();
+                    } else if task_id % 2 == 0 {
+                        assert!(cursor.remove());
+                    } else {
+                        let before = L(AtomicShared::null(), total + entry.1 * 3);
+                        assert!(cursor.insert_before(Shared::new(before)).is_ok());
+                        let after = L(AtomicShared::null(), total + entry.1 * 3 + 2);
+                        assert!(cursor.insert_after(Shared::new(after)).is_ok());
+                        cursor.move_next();
+                        cursor.move_next();
+                    }
+                }
+            }));
+        }
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
 
-        assert_eq!(stack.pop().map(|e| **e), Some(1));
-        assert_eq!(stack.pop().map(|e| **e), Some(3));
-        assert_eq!(stack.pop().map(|e| **e), Some(37));
-        assert!(stack.pop().is_none());
+        let guard = Guard::new();
+        let mut cursor = head.cursor(&guard);
+        let mut count = 0;
+        for v in (0..total).filter(|v| v % 2 == 1) {
+            for expected in [total + v * 3, v, total + v * 3 + 2] {
+                assert_eq!(cursor.get().map(|e| e.1), Some(expected));
+                cursor.move_next();
+                count += 1;
+            }
+        }
+        assert!(cursor.get().is_none());
+        assert_eq!(count, total * 3 / 2);
+    }
+}
 
-        assert_eq!(stack_clone.pop().map(|e| **e), Some(1));
-        assert_eq!(stack_clone.pop().map(|e| **e), Some(3));
-        assert_eq!(stack_clone.pop().map(|e| **e), Some(37));
-        assert!(stack_clone.pop().is_none());
+#[cfg(test)]
+mod list_test {
+    use crate::ebr::Guard;
+    use crate::List;
+    use std::panic::UnwindSafe;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::{Arc, Barrier};
+
+    static_assertions::assert_impl_all!(List<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(List<*const String>: Send, Sync, UnwindSafe);
+
+    #[test]
+    fn cursor() {
+        let list: List<usize> = List::default();
+        let guard = Guard::new();
+        let mut cursor = list.cursor(&guard);
+        assert!(cursor.move_next().is_none());
+        assert!(cursor.unlink().is_none());
+
+        assert!(cursor.insert_before(3).is_ok());
+        assert!(cursor.insert_after(1).is_ok());
+        assert_eq!(cursor.move_next().map(|e| **e), Some(1));
+        assert!(cursor.insert_after(2).is_ok());
+        assert_eq!(cursor.move_next().map(|e| **e), Some(2));
+        assert_eq!(cursor.move_next().map(|e| **e), Some(3));
+        assert!(cursor.insert_before(0).is_ok());
+        assert_eq!(
+            list.iter(&guard).copied().collect::<Vec<_>>(),
+            vec![1, 2, 0, 3]
+        );
+
+        let mut cursor = list.cursor(&guard);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.move_next().map(|e| **e), Some(0));
+        assert_eq!(cursor.unlink().map(|e| **e), Some(0));
+        assert_eq!(cursor.get().map(|e| **e), Some(3));
+        assert_eq!(cursor.unlink().map(|e| **e), Some(3));
+        assert!(cursor.get().is_none());
+        assert_eq!(list.len(), 2);
+
+        let mut other = list.cursor(&guard);
+        assert_eq!(other.move_next().map(|e| **e), Some(1));
+        assert_eq!(cursor.move_next().map(|e| **e), Some(1));
+        assert_eq!(cursor.unlink().map(|e| **e), Some(1));
+        assert!(other.unlink().is_none());
+        assert_eq!(other.insert_after(4).err(), Some(4));
+        assert_eq!(other.insert_before(5).err(), Some(5));
+        assert_eq!(list.pop_front().map(|e| **e), Some(2));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn prev_link() {
+        let list: List<usize> = List::default();
+        let entries = (0..8).map(|i| list.push_back(i)).collect::<Vec<_>>();
+        assert_eq!(list.back(&Guard::new()).map(|e| **e), Some(7));
+
+        assert_eq!(list.unlink(&entries[3]).map(|e| **e), Some(3));
+        assert!(list.unlink(&entries[3]).is_none());
+        assert!(!entries[3].is_linked());
+        assert_eq!(list.pop_back().map(|e| **e), Some(7));
+        assert_eq!(list.unlink(&entries[0]).map(|e| **e), Some(0));
+
+        let guard = Guard::new();
+        let mut cursor = list.cursor(&guard);
+        let mut backward = Vec::new();
+        while let Some(entry) = cursor.move_prev() {
+            backward.push(**entry);
+        }
+        assert_eq!(backward, vec![6, 5, 4, 2, 1]);
+
+        assert_eq!(cursor.move_prev().map(|e| **e), Some(6));
+        assert_eq!(cursor.move_prev().map(|e| **e), Some(5));
+        assert_eq!(list.unlink(&entries[5]).map(|e| **e), Some(5));
+        assert!(cursor.move_prev().is_none());
+        assert_eq!(cursor.move_prev().map(|e| **e), Some(6));
+        assert!(cursor.insert_before(5).is_ok());
+        assert_eq!(cursor.move_prev().map(|e| **e), Some(5));
+        assert_eq!(cursor.move_prev().map(|e| **e), Some(4));
+
+        let other: List<usize> = List::default();
+        let entry = other.push_back(9);
+        assert!(list.unlink(&entry).is_none());
+        assert!(entry.is_linked());
+        std::mem::drop(other);
+        assert!(!entry.is_linked());
+        assert_eq!(
+            list.iter(&guard).copied().collect::<Vec<_>>(),
+            vec![1, 2, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn drop() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+        struct R;
+        impl R {
+            fn new() -> R {
+                INST_CNT.fetch_add(1, Relaxed);
+                R
+            }
+        }
+        impl Drop for R {
+            fn drop(&mut self) {
+                INST_CNT.fetch_sub(1, Relaxed);
+            }
+        }
+
+        let list: List<R> = List::default();
+        for _ in 0..16 {
+            list.push_back(R::new());
+            list.push_front(R::new());
+        }
+        assert!(list.pop_front().is_some());
+        std::mem::drop(list);
+        while INST_CNT.load(Relaxed) != 0 {
+            std::mem::drop(Guard::new());
+            std::thread::yield_now();
+        }
     }
 
     #[cfg_attr(miri, ignore)]
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn iterator() {
-        const NUM_TASKS: usize = 2;
-        let stack: Arc<Stack<R>> = Arc::new(Stack::default());
+    #[test]
+    fn insert_unlink() {
+        const NUM_THREADS: usize = 4;
+        let list: Arc<List<(usize, usize)>> = Arc::new(List::default());
         let workload_size = 256;
-        for _ in 0..16 {
-            let mut task_handles = Vec::with_capacity(NUM_TASKS);
-            let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
-            for task_id in 0..NUM_TASKS {
+        for _ in 0..4 {
+            let barrier = Arc::new(Barrier::new(NUM_THREADS));
+            let mut thread_handles = Vec::with_capacity(NUM_THREADS);
+            for task_id in 0..NUM_THREADS {
                 let barrier_clone = barrier.clone();
-                let stack_clone = stack.clone();
-                task_handles.push(tokio::task::spawn(async move {
-                    if task_id == 0 {
-                        for seq in 0..workload_size {
-                            if seq == workload_size / 2 {
-                                barrier_clone.wait().await;
-                            }
-                            assert_eq!(stack_clone.push(R::new(task_id, seq)).1, seq);
-                        }
-                        let mut last = workload_size;
-                        while let Some(popped) = stack_clone.pop() {
-                            let current = popped.1;
-                            assert_eq!(current + 1, last);
-                            last = current;
-                        }
-                    } else {
-                        let mut last = workload_size;
-
-                        barrier_clone.wait().await;
-                        let guard = Guard::new();
-                        let iter = stack_clone.iter(&guard);
-                        for current in iter {
-                            let current = current.1;
-                            assert!(last == workload_size || last - 1 == current);
-                            last = current;
+                let list_clone = list.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    barrier_clone.wait();
+                    for seq in 0..workload_size {
+                        list_clone.push_back((task_id, seq * 2));
+                    }
+                    let guard = Guard::new();
+                    let mut cursor = list_clone.cursor(&guard);
+                    let mut current = cursor.move_next();
+                    while let Some(entry) = current {
+                        if entry.0 != task_id {
+                            current = cursor.move_next();
+                        } else if entry.1 % 4 == 0 {
+                            assert_eq!(cursor.unlink().map(|e| **e), Some((task_id, entry.1)));
+                            current = cursor.get();
+                        } else {
+                            assert!(cursor.insert_before((task_id, entry.1 - 1)).is_ok());
+                            assert!(cursor.insert_after((task_id, entry.1 + 1)).is_ok());
+                            cursor.move_next();
+                            current = cursor.move_next();
                         }
                     }
                 }));
             }
+            for handle in thread_handles {
+                assert!(handle.join().is_ok());
+            }
+
+            let guard = Guard::new();
+            let mut remaining: [Vec<usize>; NUM_THREADS] = Default::default();
+            for entry in list.iter(&guard) {
+                remaining[entry.0].push(entry.1);
+            }
+            let expected = (0..workload_size / 2)
+                .flat_map(|k| [k * 4 + 1, k * 4 + 2, k * 4 + 3])
+                .collect::<Vec<_>>();
+            for remaining in remaining {
+                assert_eq!(remaining, expected);
+            }
+            let mut cursor = list.cursor(&guard);
+            let mut backward = Vec::new();
+            while let Some(entry) = cursor.move_prev() {
+                backward.push(**entry);
+            }
+            backward.reverse();
+            assert_eq!(backward, list.iter(&guard).copied().collect::<Vec<_>>());
+            while list.pop_front().is_some() {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod channel_test {
+    use crate::channel::{self, Receiver, Sender};
+    use std::panic::UnwindSafe;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::Arc;
 
-            for r in futures::future::join_all(task_handles).await {
-                assert!(r.is_ok());
+    static_assertions::assert_impl_all!(Sender<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_impl_all!(Receiver<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(Sender<*const String>: Send, Sync);
+    static_assertions::assert_not_impl_all!(Receiver<*const String>: Send, Sync);
+
+    #[test]
+    fn disconnect() {
+        let (sender, receiver) = channel::unbounded();
+        let receiver_clone = receiver.clone();
+        assert!(sender.send(7).is_ok());
+        drop(receiver);
+        assert!(!sender.is_disconnected());
+        assert!(sender.send(11).is_ok());
+        drop(receiver_clone);
+        assert!(sender.is_disconnected());
+        assert_eq!(sender.send(13), Err(13));
+        assert_eq!(sender.try_send(13), Err(13));
+
+        let (sender, receiver) = channel::bounded(1);
+        let sender_clone = sender.clone();
+        assert!(sender.send(17).is_ok());
+        drop(sender);
+        assert!(!receiver.is_disconnected());
+        drop(sender_clone);
+        assert!(receiver.is_disconnected());
+        assert_eq!(receiver.recv(), Some(17));
+        assert!(receiver.recv().is_none());
+    }
+
+    #[test]
+    fn drop_unreceived() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+        struct R;
+        impl R {
+            fn new() -> R {
+                INST_CNT.fetch_add(1, Relaxed);
+                R
             }
         }
-        assert!(stack.is_empty());
+        impl Drop for R {
+            fn drop(&mut self) {
+                INST_CNT.fetch_sub(1, Relaxed);
+            }
+        }
+
+        let (sender, receiver) = channel::unbounded();
+        for _ in 0..16 {
+            assert!(sender.send(R::new()).is_ok());
+        }
+        assert!(receiver.recv().is_some());
+        drop(sender);
+        drop(receiver);
+        while INST_CNT.load(Relaxed) != 0 {
+            drop(crate::ebr::Guard::new());
+            std::thread::yield_now();
+        }
     }
 
     #[cfg_attr(miri, ignore)]
-    #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
-    async fn mpmc() {
-        const NUM_TASKS: usize = 12;
-        let stack: Arc<Stack<R>> = Arc::new(Stack::default());
+    #[test]
+    fn mpmc_sync() {
+        const NUM_THREADS: usize = 4;
         let workload_size = 256;
-        for _ in 0..16 {
-            let mut task_handles = Vec::with_capacity(NUM_TASKS);
-            let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
-            for task_id in 0..NUM_TASKS {
-                let barrier_clone = barrier.clone();
-                let stack_clone = stack.clone();
-                task_handles.push(tokio::task::spawn(async move {
-                    barrier_clone.wait().await;
-                    for seq in 0..workload_size {
-                        assert_eq!(stack_clone.push(R::new(task_id, seq)).1, seq);
+        for capacity in [1, 7, usize::MAX] {
+            let (sender, receiver) = channel::bounded(capacity);
+            let num_received = Arc::new(AtomicUsize::new(0));
+            let mut thread_handles = Vec::with_capacity(NUM_THREADS * 2);
+            for task_id in 0..NUM_THREADS {
+                let sender_clone = sender.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    for seq in 1..=workload_size {
+                        assert!(sender_clone.send((task_id, seq)).is_ok());
                     }
-                    let mut last_popped = usize::MAX;
-                    let mut cnt = 0;
-                    while cnt < workload_size {
-                        while let Ok(Some(popped)) = stack_clone.pop_if(|e| e.0 == task_id) {
-                            assert_eq!(popped.0, task_id);
-                            assert!(last_popped > popped.1);
-                            last_popped = popped.1;
-                            cnt += 1;
-                        }
-                        tokio::task::yield_now().await;
+                }));
+                let receiver_clone = receiver.clone();
+                let num_received_clone = num_received.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    let mut received_acc: [usize; NUM_THREADS] = Default::default();
+                    while let Some((task_id, seq)) = receiver_clone.recv() {
+                        assert!(received_acc[task_id] < seq);
+                        received_acc[task_id] = seq;
+                        num_received_clone.fetch_add(1, Relaxed);
                     }
                 }));
             }
-
-            for r in futures::future::join_all(task_handles).await {
-                assert!(r.is_ok());
+            drop(sender);
+            drop(receiver);
+            for handle in thread_handles {
+                handle.join().unwrap();
             }
+            assert_eq!(num_received.load(Relaxed), workload_size * NUM_THREADS);
         }
-        assert!(stack.is_empty());
     }
 
     #[cfg_attr(miri, ignore)]
-    #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
-    async fn mpsc() {
-        const NUM_TASKS: usize = 12;
-        let stack: Arc<Stack<R>> = Arc::new(Stack::default());
+    #[test]
+    fn many_senders() {
+        const NUM_SENDERS: usize = 8;
+        let workload_size = 1024;
+        let (sender, receiver) = channel::unbounded();
+        let mut thread_handles = Vec::with_capacity(NUM_SENDERS);
+        for task_id in 0..NUM_SENDERS {
+            let sender_clone = sender.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                for seq in 1..=workload_size {
+                    assert!(sender_clone.send((task_id, seq)).is_ok());
+                    if seq % 64 == 0 {
+                        std::thread::yield_now();
+                    }
+                }
+            }));
+        }
+
+        // `sender` is alive, therefore `recv` blocks forever if a wakeup is lost.
+        let mut received_acc: [usize; NUM_SENDERS] = Default::default();
+        for _ in 0..workload_size * NUM_SENDERS {
+            let (task_id, seq) = receiver.recv().unwrap();
+            assert_eq!(received_acc[task_id] + 1, seq);
+            received_acc[task_id] = seq;
+        }
+        for handle in thread_handles {
+            handle.join().unwrap();
+        }
+        assert!(receiver.try_recv().is_none());
+        drop(sender);
+        assert!(receiver.recv().is_none());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn mpmc_async() {
+        const NUM_TASKS: usize = 4;
         let workload_size = 256;
-        for _ in 0..16 {
-            let mut task_handles = Vec::with_capacity(NUM_TASKS);
-            let barrier = Arc::new(AsyncBarrier::new(NUM_TASKS));
+        for capacity in [1, 7, usize::MAX] {
+            let (sender, receiver) = channel::bounded(capacity);
+            let num_received = Arc::new(AtomicUsize::new(0));
+            let mut task_handles = Vec::with_capacity(NUM_TASKS * 2);
             for task_id in 0..NUM_TASKS {
-                let barrier_clone = barrier.clone();
-                let stack_clone = stack.clone();
+                let sender_clone = sender.clone();
                 task_handles.push(tokio::task::spawn(async move {
-                    barrier_clone.wait().await;
-                    let mut cnt = 0;
-                    while task_id == 0 && cnt < workload_size * (NUM_TASKS - 1) {
-                        // Consumer.
-                        let popped = stack_clone.pop_all();
-                        while let Some(e) = popped.pop() {
-                            assert_ne!(e.0, 0);
-                            cnt += 1;
-                        }
-                        tokio::task::yield_now().await;
+                    for seq in 1..=workload_size {
+                        assert!(sender_clone.send_async((task_id, seq)).await.is_ok());
                     }
-                    if task_id != 0 {
-                        for seq in 0..workload_size {
-                            assert_eq!(stack_clone.push(R::new(task_id, seq)).1, seq);
-                        }
-                        for seq in 0..workload_size / 16 {
-                            if stack_clone.pop().is_some() {
-                                assert_eq!(stack_clone.push(R::new(task_id, seq)).1, seq);
-                            }
-                        }
+                }));
+                let receiver_clone = receiver.clone();
+                let num_received_clone = num_received.clone();
+                task_handles.push(tokio::task::spawn(async move {
+                    let mut received_acc: [usize; NUM_TASKS] = Default::default();
+                    while let Some((task_id, seq)) = receiver_clone.recv_async().await {
+                        assert!(received_acc[task_id] < seq);
+                        received_acc[task_id] = seq;
+                        num_received_clone.fetch_add(1, Relaxed);
                     }
                 }));
             }
-
+            drop(sender);
+            drop(receiver);
             for r in futures::future::join_all(task_handles).await {
                 assert!(r.is_ok());
             }
+            assert_eq!(num_received.load(Relaxed), workload_size * NUM_TASKS);
         }
-        assert!(stack.is_empty());
     }
 }
 
 #[cfg(test)]
 mod ebr_test {
-    use crate::ebr::{suspend, AtomicOwned, AtomicShared, Guard, Owned, Ptr, Shared, Tag};
+    use crate::ebr::{
+        detach, suspend, AtomicArc, AtomicOwned, AtomicOwnedCell, AtomicShared, Guard, Owned, Ptr,
+        Shared, Tag, Weak,
+    };
     use std::ops::Deref;
     use std::panic::UnwindSafe;
-    use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+    use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
     use std::sync::atomic::{AtomicBool, AtomicUsize};
+    use std::sync::{Arc, Barrier};
 
     static_assertions::assert_impl_all!(Shared<String>: Send, Sync, UnwindSafe);
     static_assertions::assert_impl_all!(AtomicShared<String>: Send, Sync, UnwindSafe);
@@ -2773,6 +5529,10 @@ mod ebr_test {
     static_assertions::assert_not_impl_all!(AtomicShared<*const u8>: Send, Sync, UnwindSafe);
     static_assertions::assert_not_impl_all!(Ptr<String>: Send, Sync);
     static_assertions::assert_not_impl_all!(Ptr<*const u8>: Send, Sync, UnwindSafe);
+    static_assertions::assert_impl_all!(Weak<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_impl_all!(AtomicArc<String>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(AtomicArc<*const u8>: Send, Sync, UnwindSafe);
+    static_assertions::assert_not_impl_all!(Weak<*const u8>: Send, Sync, UnwindSafe);
     static_assertions::assert_impl_all!(Guard: UnwindSafe);
     static_assertions::assert_not_impl_all!(Guard: Send, Sync);
 
@@ -2785,18 +5545,403 @@ mod ebr_test {
 
     #[cfg_attr(miri, ignore)]
     #[test]
-    fn deferred() {
-        static EXECUTED: AtomicBool = AtomicBool::new(false);
+    fn suspend_parked() {
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        let thread_handle = std::thread::spawn(move || {
+            let shared = Shared::new(A(AtomicUsize::new(0), 0, &DROPPED));
+            let guard = Guard::new();
+            assert!(shared.release(&guard));
+            drop(guard);
+            assert!(suspend());
+
+            // The thread stays idle until the garbage is reclaimed by another thread.
+            assert!(rx.recv().is_ok());
+        });
+        while !DROPPED.load(Relaxed) {
+            drop(Guard::new());
+            std::thread::yield_now();
+        }
+        assert!(tx.send(()).is_ok());
+        assert!(thread_handle.join().is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn deferred() {
+        static EXECUTED: AtomicBool = AtomicBool::new(false);
+
+        let guard = Guard::new();
+        guard.defer_execute(|| EXECUTED.store(true, Relaxed));
+        drop(guard);
+
+        while !EXECUTED.load(Relaxed) {
+            drop(Guard::new());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn deferred_after_readers() {
+        static EXECUTED: AtomicBool = AtomicBool::new(false);
+
+        let barrier = Arc::new(Barrier::new(2));
+        let barrier_clone = barrier.clone();
+        let thread_handle = std::thread::spawn(move || {
+            let guard = Guard::new();
+            barrier_clone.wait();
+            barrier_clone.wait();
+            drop(guard);
+        });
+        barrier.wait();
+
+        let guard = Guard::new();
+        guard.defer_execute(|| EXECUTED.store(true, Relaxed));
+        drop(guard);
+
+        // The closure cannot be executed while the reader in the other thread is alive.
+        for _ in 0..4096 {
+            drop(Guard::new());
+            assert!(!EXECUTED.load(Relaxed));
+        }
+
+        barrier.wait();
+        assert!(thread_handle.join().is_ok());
+        while !EXECUTED.load(Relaxed) {
+            drop(Guard::new());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn detach_parked() {
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        let thread_handle = std::thread::spawn(move || {
+            let shared = Shared::new(A(AtomicUsize::new(0), 0, &DROPPED));
+            let guard = Guard::new();
+            assert!(shared.release(&guard));
+            assert!(!detach());
+            drop(guard);
+            assert!(detach());
+            assert!(detach());
+
+            assert!(rx.recv().is_ok());
+
+            // The thread can be attached again.
+            let guard = Guard::new();
+            assert!(Shared::new(1).release(&guard));
+        });
+        while !DROPPED.load(Relaxed) {
+            drop(Guard::new());
+            std::thread::yield_now();
+        }
+        assert!(tx.send(()).is_ok());
+        assert!(thread_handle.join().is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn flush() {
+        static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+        let shared = Shared::new(A(AtomicUsize::new(0), 0, &DESTROYED));
+        let guard = Guard::new();
+        assert!(shared.release(&guard));
+        assert!(!crate::ebr::flush());
+        drop(guard);
+
+        while !DESTROYED.load(Relaxed) {
+            assert!(crate::ebr::flush());
+            std::thread::yield_now();
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn synchronize() {
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+
+        let shared = Shared::new(A(AtomicUsize::new(0), 0, &DROPPED));
+        let guard = Guard::new();
+        assert!(shared.release(&guard));
+        assert!(!crate::ebr::synchronize());
+        drop(guard);
+
+        assert!(crate::ebr::synchronize());
+        assert!(DROPPED.load(Relaxed));
+        assert!(crate::ebr::synchronize());
+
+        // It waits for `Guard` instances in other threads.
+        let barrier = Arc::new(Barrier::new(2));
+        let synchronized = Arc::new(AtomicBool::new(false));
+        let barrier_clone = barrier.clone();
+        let reader = std::thread::spawn(move || {
+            let guard = Guard::new();
+            barrier_clone.wait();
+            barrier_clone.wait();
+            drop(guard);
+        });
+        barrier.wait();
+        let synchronized_clone = synchronized.clone();
+        let synchronizer = std::thread::spawn(move || {
+            assert!(crate::ebr::synchronize());
+            synchronized_clone.store(true, Relaxed);
+        });
+        std::thread::sleep(std::time::Duration::from_millis(16));
+        assert!(!synchronized.load(Relaxed));
+        barrier.wait();
+        assert!(reader.join().is_ok());
+        assert!(synchronizer.join().is_ok());
+        assert!(synchronized.load(Relaxed));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "current_thread")]
+    async fn synchronize_async() {
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+
+        let shared = Shared::new(A(AtomicUsize::new(0), 0, &DROPPED));
+        assert!(shared.release(&Guard::new()));
+
+        assert!(crate::ebr::synchronize_async().await);
+        assert!(DROPPED.load(Relaxed));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn guard_try_new() {
+        static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+        let thread_handle = std::thread::spawn(|| {
+            assert!(Guard::try_new().is_none());
+
+            let shared = Shared::new(A(AtomicUsize::new(0), 0, &DESTROYED));
+            drop(Guard::new());
+
+            let guard = Guard::try_new().unwrap();
+            assert!(Guard::try_reuse().is_some());
+            let ptr = shared.get_guarded_ptr(&guard);
+            assert!(shared.release(&guard));
+            assert_eq!(ptr.as_ref().map(|a| a.1), Some(0));
+            drop(guard);
+
+            assert!(detach());
+            assert!(Guard::try_new().is_none());
+        });
+        assert!(thread_handle.join().is_ok());
+        while !DESTROYED.load(Relaxed) {
+            drop(Guard::new());
+            std::thread::yield_now();
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn guard_epoch() {
+        let guard = Guard::new();
+        let epoch = guard.epoch();
+        assert!(u8::from(epoch) < 3);
+        assert_eq!(Guard::try_reuse().unwrap().epoch(), epoch);
+        drop(guard);
+
+        loop {
+            let guard = Guard::new();
+            if guard.epoch() != epoch {
+                break;
+            }
+            guard.accelerate();
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn defer_drop_on() {
+        struct D(std::sync::mpsc::Sender<Option<String>>);
+        impl Drop for D {
+            fn drop(&mut self) {
+                let name = std::thread::current().name().map(String::from);
+                assert!(self.0.send(name).is_ok());
+            }
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (executor_tx, executor_rx) = std::sync::mpsc::channel::<D>();
+        let executor = std::thread::Builder::new()
+            .name(String::from("executor"))
+            .spawn(move || {
+                while let Ok(d) = executor_rx.recv() {
+                    drop(d);
+                }
+            })
+            .unwrap();
+
+        let guard = Guard::new();
+        let executor_tx = std::sync::Mutex::new(executor_tx);
+        guard.defer_drop_on(
+            move |d| assert!(executor_tx.lock().unwrap().send(d).is_ok()),
+            D(tx),
+        );
+        drop(guard);
+
+        loop {
+            let guard = Guard::new();
+            guard.accelerate();
+            drop(guard);
+            if let Ok(name) = rx.try_recv() {
+                assert_eq!(name.as_deref(), Some("executor"));
+                break;
+            }
+            std::thread::yield_now();
+        }
+        assert!(executor.join().is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn accelerate() {
+        static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+        let shared = Shared::new(A(AtomicUsize::new(0), 0, &DESTROYED));
+        let guard = Guard::new();
+        assert!(shared.release(&guard));
+        drop(guard);
+
+        while !DESTROYED.load(Relaxed) {
+            let guard = Guard::new();
+            guard.accelerate();
+            drop(guard);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn statistics() {
+        static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+        let barrier = Arc::new(Barrier::new(2));
+        let barrier_clone = barrier.clone();
+        let thread_handle = std::thread::spawn(move || {
+            let guard = Guard::new();
+            barrier_clone.wait();
+            barrier_clone.wait();
+            drop(guard);
+        });
+        barrier.wait();
 
+        let shared = Shared::new((A(AtomicUsize::new(0), 0, &DESTROYED), [0_u8; 256]));
         let guard = Guard::new();
-        guard.defer_execute(|| EXECUTED.store(true, Relaxed));
+        assert!(shared.release(&guard));
         drop(guard);
 
-        while !EXECUTED.load(Relaxed) {
+        let statistics = loop {
+            if let Some(statistics) = crate::ebr::statistics() {
+                break statistics;
+            }
+        };
+        assert!(statistics.epoch() < 3);
+        assert!(statistics.num_threads() >= 2);
+        #[cfg(feature = "ebr-stats")]
+        {
+            assert!(statistics.num_garbage() >= 1);
+            assert!(statistics.garbage_bytes() >= 256);
+        }
+
+        // The other thread eventually lags behind the global epoch.
+        while crate::ebr::statistics().map_or(0, |s| s.num_lagging_threads()) == 0 {
+            let guard = Guard::new();
+            guard.accelerate();
+            drop(guard);
+        }
+        assert!(!DESTROYED.load(Relaxed));
+
+        barrier.wait();
+        assert!(thread_handle.join().is_ok());
+        while !DESTROYED.load(Relaxed) {
             drop(Guard::new());
         }
     }
 
+    #[cfg(feature = "ebr-stats")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn collector_statistics() {
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel::<()>();
+        let thread_handle = std::thread::spawn(move || {
+            let guard = Guard::new();
+            for _ in 0..16 {
+                guard.defer_execute(|| ());
+            }
+            assert!(ready_tx.send(()).is_ok());
+            assert!(rx.recv().is_ok());
+        });
+        assert!(ready_rx.recv().is_ok());
+
+        // The thread holds a `Guard`, therefore none of the closures can be executed.
+        let statistics = loop {
+            if let Some(statistics) = crate::ebr::collector_statistics() {
+                break statistics;
+            }
+        };
+        assert!(statistics
+            .iter()
+            .any(|s| s.is_active() && s.num_garbage() >= 16 && s.num_retired() >= 16));
+        assert!(tx.send(()).is_ok());
+        assert!(thread_handle.join().is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn stalled_threads() {
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        let thread_handle = std::thread::spawn(move || {
+            let guard = Guard::new();
+            let nested = Guard::new();
+            assert!(ready_tx.send(std::thread::current().id()).is_ok());
+            assert!(rx.recv().is_ok());
+            drop(nested);
+            drop(guard);
+        });
+        let thread_id = ready_rx.recv().unwrap();
+
+        // The global epoch advances at most once while the thread holds the `Guard`.
+        let first = loop {
+            let guard = Guard::new();
+            guard.accelerate();
+            drop(guard);
+            if let Some(s) = crate::ebr::stalled_threads()
+                .unwrap_or_default()
+                .into_iter()
+                .find(|s| s.thread_id() == thread_id)
+            {
+                break s;
+            }
+            std::thread::yield_now();
+        };
+        assert_eq!(first.num_guards(), 2);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let second = loop {
+            if let Some(stalled_threads) = crate::ebr::stalled_threads() {
+                break stalled_threads
+                    .into_iter()
+                    .find(|s| s.thread_id() == thread_id)
+                    .unwrap();
+            }
+        };
+        assert!(second.duration() >= first.duration() + std::time::Duration::from_millis(10));
+
+        assert!(tx.send(()).is_ok());
+        assert!(thread_handle.join().is_ok());
+        assert!(crate::ebr::stalled_threads()
+            .unwrap_or_default()
+            .iter()
+            .all(|s| s.thread_id() != thread_id));
+    }
+
     #[cfg_attr(miri, ignore)]
     #[test]
     fn shared() {
@@ -2862,6 +6007,235 @@ mod ebr_test {
         }
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn owned_shared() {
+        static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+        let owned = Owned::new(A(AtomicUsize::new(10), 10, &DESTROYED));
+        let shared = Shared::from(owned);
+        let atomic_shared = AtomicShared::from(shared.clone());
+
+        let guard = Guard::new();
+        let ptr = atomic_shared.load(Acquire, &guard);
+        let shared = Owned::try_from(shared).err().unwrap();
+        assert_eq!(shared.1, 10);
+
+        let (taken, _) = atomic_shared.swap((None, Tag::None), Acquire);
+        assert!(taken.is_some_and(|s| !s.release(&guard)));
+
+        let mut owned = Owned::try_from(shared).ok().unwrap();
+        assert!(ptr.get_shared().is_none());
+        unsafe {
+            owned.get_mut().1 += 1;
+        }
+        assert_eq!(ptr.as_ref().unwrap().1, 11);
+
+        drop(owned);
+        drop(guard);
+
+        while !DESTROYED.load(Relaxed) {
+            drop(Guard::new());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn try_get_mut() {
+        let mut shared = Shared::new(AtomicUsize::new(0));
+        for _ in 0..16 {
+            let shared_clone = shared.clone();
+            assert!(shared.try_get_mut().is_none());
+            std::thread::spawn(move || {
+                shared_clone.fetch_add(1, Relaxed);
+            })
+            .join()
+            .unwrap();
+            *shared.try_get_mut().unwrap().get_mut() += 1;
+        }
+        assert_eq!(shared.load(Relaxed), 32);
+
+        let weak = shared.downgrade();
+        assert!(shared.try_get_mut().is_none());
+        drop(weak);
+        assert!(shared.try_get_mut().is_some());
+
+        let guard = Guard::new();
+        let atomic_shared = AtomicShared::from(shared);
+        let (shared, _) = atomic_shared.swap((None, Tag::None), AcqRel);
+        let mut shared = shared.unwrap();
+        assert!(shared.try_get_mut().is_none());
+
+        let mut owned = Owned::new(1);
+        *owned.try_get_mut().unwrap() += 1;
+        let atomic_owned = AtomicOwned::from(owned);
+        let (owned, _) = atomic_owned.swap((None, Tag::None), AcqRel);
+        let mut owned = owned.unwrap();
+        assert_eq!(*owned, 2);
+        assert!(owned.try_get_mut().is_none());
+        drop(guard);
+
+        // The instances become exclusive once the global epoch has been updated twice.
+        while shared.try_get_mut().is_none() || owned.try_get_mut().is_none() {
+            let guard = Guard::new();
+            guard.accelerate();
+            drop(guard);
+        }
+    }
+
+    #[test]
+    fn guard_reuse() {
+        std::thread::spawn(|| {
+            assert!(Guard::try_reuse().is_none());
+
+            let atomic_shared = AtomicShared::new(11);
+            let guard = Guard::new();
+            let nested = Guard::try_reuse().unwrap();
+            drop(guard);
+
+            // The nested `Guard` keeps the instance alive.
+            let ptr = atomic_shared.load(Acquire, &nested);
+            drop(atomic_shared);
+            assert_eq!(ptr.as_ref(), Some(&11));
+            drop(nested);
+            assert!(Guard::try_reuse().is_none());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn static_atomic_shared() {
+        static GLOBAL: AtomicShared<usize> = AtomicShared::null();
+
+        let thread_handles: Vec<_> = (0..4)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    let (prev, _) = GLOBAL.swap((Some(Shared::new(i)), Tag::None), AcqRel);
+                    prev.map_or(0, |s| *s + 1)
+                })
+            })
+            .collect();
+        let mut sum = 0;
+        for thread_handle in thread_handles {
+            sum += thread_handle.join().unwrap();
+        }
+        let (last, _) = GLOBAL.swap((None, Tag::None), AcqRel);
+        sum += last.map_or(0, |s| *s + 1);
+        assert_eq!(sum, 1 + 2 + 3 + 4);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn atomic_arc() {
+        static DESTROYED: AtomicBool = AtomicBool::new(false);
+        static REPLACED: AtomicBool = AtomicBool::new(false);
+
+        let arc = Arc::new(A(AtomicUsize::new(10), 10, &DESTROYED));
+        let atomic_arc = Arc::new(AtomicArc::new(arc.clone()));
+        let guard = Guard::new();
+        let published = atomic_arc.load(Acquire, &guard).unwrap();
+        assert!(std::ptr::eq(published, &*arc));
+        published.0.fetch_add(1, Relaxed);
+        assert_eq!(arc.0.load(Relaxed), 11);
+
+        let num_threads = 4;
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let thread_handles = (0..num_threads)
+            .map(|_| {
+                let atomic_arc = atomic_arc.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    for _ in 0..256 {
+                        let guard = Guard::new();
+                        let current = atomic_arc.load(Acquire, &guard);
+                        let next = current.map_or(0, |a| a.1 + 1);
+                        let new = Arc::new(A(AtomicUsize::new(next), next, &REPLACED));
+                        let _result = atomic_arc.compare_exchange(
+                            current,
+                            Some(new),
+                            AcqRel,
+                            Acquire,
+                            &guard,
+                        );
+                        assert!(atomic_arc.get_arc(Acquire, &guard).unwrap().1 >= next);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        thread_handles.into_iter().for_each(|t| t.join().unwrap());
+
+        // The original instance is kept alive as long as `guard` is alive.
+        drop(arc);
+        for _ in 0..16 {
+            Guard::new().accelerate();
+        }
+        assert!(!DESTROYED.load(Relaxed));
+        assert_eq!(published.0.load(Relaxed), 11);
+        drop(guard);
+
+        let last = atomic_arc.swap(None, AcqRel).unwrap();
+        assert!(last.1 >= 256);
+        drop(last);
+        while !DESTROYED.load(Relaxed) {
+            drop(Guard::new());
+        }
+    }
+
+    #[test]
+    fn weak() {
+        static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+        let shared = Shared::new(A(AtomicUsize::new(10), 10, &DESTROYED));
+        let weak = shared.downgrade();
+        let weak_clone = weak.clone();
+        assert!(weak.is_alive());
+        assert_eq!(weak.upgrade().map(|s| s.1), Some(10));
+
+        drop(shared);
+        assert!(!weak.is_alive());
+        assert!(weak_clone.upgrade().is_none());
+        drop(weak_clone);
+
+        while !DESTROYED.load(Relaxed) {
+            drop(Guard::new());
+        }
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn weak_upgrade_race() {
+        for _ in 0..64 {
+            let shared = Shared::new(AtomicUsize::new(0));
+            let weak = shared.downgrade();
+            let barrier = Arc::new(Barrier::new(2));
+            let barrier_clone = barrier.clone();
+            let thread_handle = std::thread::spawn(move || {
+                barrier_clone.wait();
+                while let Some(upgraded) = weak.upgrade() {
+                    upgraded.fetch_add(1, Relaxed);
+                }
+                // The instance is being reclaimed by the other thread.
+                barrier_clone.wait();
+                for _ in 0..1024 {
+                    assert!(!weak.is_alive());
+                    assert!(weak.upgrade().is_none());
+                }
+            });
+            barrier.wait();
+            std::thread::yield_now();
+            drop(shared);
+            barrier.wait();
+            for _ in 0..4 {
+                let _: bool = crate::ebr::flush();
+            }
+            assert!(thread_handle.join().is_ok());
+        }
+    }
+
     #[cfg_attr(miri, ignore)]
     #[test]
     fn sendable() {
@@ -2971,6 +6345,260 @@ mod ebr_test {
         }
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn compare_exchange_weak() {
+        let num_threads = 4;
+        let num_iters = 256;
+        let atomic_shared: Arc<AtomicShared<usize>> = Arc::new(AtomicShared::new(0));
+        let thread_handles: Vec<_> = (0..num_threads)
+            .map(|_| {
+                let atomic_shared = atomic_shared.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..num_iters {
+                        let guard = Guard::new();
+                        let mut current = atomic_shared.load(Acquire, &guard);
+                        loop {
+                            let new = Shared::new(*current.as_ref().unwrap() + 1);
+                            match atomic_shared.compare_exchange_weak(
+                                current,
+                                (Some(new), Tag::None),
+                                AcqRel,
+                                Acquire,
+                                &guard,
+                            ) {
+                                Ok(_) => break,
+                                Err((_, actual)) => current = actual,
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+
+        let guard = Guard::new();
+        assert_eq!(
+            atomic_shared.load(Acquire, &guard).as_ref(),
+            Some(&(num_threads * num_iters))
+        );
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn shared_uninit() {
+        const LEN: usize = 1 << 21;
+
+        // The instance is larger than the stack of the thread.
+        let thread = std::thread::Builder::new()
+            .stack_size(1 << 16)
+            .spawn(|| {
+                let mut shared = Shared::<[u64; LEN]>::new_uninit();
+                let uninit = shared.try_get_mut().unwrap();
+                unsafe {
+                    let ptr = uninit.as_mut_ptr().cast::<u64>();
+                    (0..LEN).for_each(|i| ptr.add(i).write(i as u64));
+                }
+                let shared = unsafe { shared.assume_init() };
+                assert_eq!(shared[LEN - 1], (LEN - 1) as u64);
+
+                let cloned = shared.clone();
+                assert!(!shared.release(&Guard::new()));
+                assert_eq!(cloned.iter().sum::<u64>(), (LEN * (LEN - 1) / 2) as u64);
+                assert!(cloned.release(&Guard::new()));
+            })
+            .unwrap();
+        assert!(thread.join().is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn swap_preserving_tag() {
+        let atomic_shared: Arc<AtomicShared<usize>> = Arc::new(AtomicShared::new(0));
+        let barrier = Arc::new(Barrier::new(2));
+        let thread_handle = {
+            let atomic_shared = atomic_shared.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                for i in 1..=64 {
+                    let (old, _) = atomic_shared.swap_preserving_tag(Some(Shared::new(i)), AcqRel);
+                    assert_eq!(*old.unwrap(), i - 1);
+                }
+            })
+        };
+        barrier.wait();
+        let guard = Guard::new();
+        while atomic_shared.tag(Relaxed) == Tag::None {
+            let ptr = atomic_shared.load(Acquire, &guard);
+            let _result =
+                atomic_shared.compare_exchange_tag(ptr, Tag::Second, AcqRel, Acquire, &guard);
+        }
+        assert!(thread_handle.join().is_ok());
+        assert_eq!(atomic_shared.tag(Relaxed), Tag::Second);
+        assert_eq!(atomic_shared.load(Acquire, &guard).as_ref(), Some(&64));
+
+        let atomic_owned = AtomicOwned::new(1);
+        assert!(atomic_owned.update_tag_if(Tag::Both, |_| true, Relaxed, Relaxed));
+        let (old, tag) = atomic_owned.swap_preserving_tag(None, Relaxed);
+        assert_eq!((old.map(|o| *o), tag), (Some(1), Tag::Both));
+        assert_eq!(atomic_owned.tag(Relaxed), Tag::Both);
+        assert!(atomic_owned.is_null(Relaxed));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn atomic_owned_cell() {
+        let cell: Arc<AtomicOwnedCell<(usize, usize)>> = Arc::new(AtomicOwnedCell::new((0, 0)));
+        let barrier = Arc::new(Barrier::new(4));
+        let thread_handles: Vec<_> = (0..4)
+            .map(|t| {
+                let cell = cell.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..256 {
+                        if t == 0 {
+                            cell.store((i, i));
+                        } else {
+                            let guard = Guard::new();
+                            let snapshot = cell.load(&guard);
+                            assert_eq!(snapshot.0, snapshot.1);
+                        }
+                    }
+                })
+            })
+            .collect();
+        thread_handles
+            .into_iter()
+            .for_each(|t| assert!(t.join().is_ok()));
+
+        let old = cell.swap((7, 7));
+        assert_eq!(*old, (255, 255));
+        assert_eq!(format!("{cell:?}"), "AtomicOwnedCell((7, 7))");
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn ptr_map() {
+        let atomic_shared = AtomicShared::new((String::from("scc"), 11_usize));
+        let guard = Guard::new();
+        let ptr = atomic_shared.load(Acquire, &guard);
+        let name = ptr.map(|t| t.0.as_str());
+        let count = ptr.map(|t| &t.1);
+
+        // The references are valid as long as the `Guard` is alive.
+        drop(atomic_shared.swap((None, Tag::None), AcqRel));
+        assert_eq!(name, Some("scc"));
+        assert_eq!(count, Some(&11));
+        assert!(atomic_shared.load(Acquire, &guard).map(|t| &t.1).is_none());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn try_unwrap() {
+        static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+        let shared = Shared::new(A(AtomicUsize::new(0), 5, &DESTROYED));
+        let weak = shared.downgrade();
+        let shared = shared.try_unwrap().err().unwrap();
+        drop(weak);
+        let a = shared.try_unwrap().ok().unwrap();
+        assert_eq!(a.1, 5);
+        assert!(!DESTROYED.load(Relaxed));
+        drop(a);
+        assert!(DESTROYED.load(Relaxed));
+
+        let atomic_shared = AtomicShared::new(String::from("scc"));
+        let (shared, _) = atomic_shared.swap((None, Tag::None), Relaxed);
+        assert!(shared.unwrap().try_unwrap().is_err());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn compare_exchange_tag() {
+        let atomic_shared: Arc<AtomicShared<usize>> = Arc::new(AtomicShared::new(3));
+        let barrier = Arc::new(Barrier::new(4));
+        let thread_handles: Vec<_> = (0..4)
+            .map(|_| {
+                let atomic_shared = atomic_shared.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    let guard = Guard::new();
+                    let ptr = atomic_shared.load(Acquire, &guard);
+                    barrier.wait();
+
+                    // Only one thread can logically delete the instance.
+                    atomic_shared
+                        .compare_exchange_tag(ptr, Tag::First, AcqRel, Acquire, &guard)
+                        .is_ok()
+                })
+            })
+            .collect();
+        let num_marked = thread_handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|marked| *marked)
+            .count();
+        assert_eq!(num_marked, 1);
+
+        let guard = Guard::new();
+        let ptr = atomic_shared.load(Acquire, &guard);
+        assert_eq!(ptr.tag(), Tag::First);
+        assert_eq!(ptr.as_ref(), Some(&3));
+
+        let atomic_owned = AtomicOwned::new(5);
+        let ptr = atomic_owned.load(Acquire, &guard);
+        assert!(atomic_owned
+            .compare_exchange_tag(
+                ptr.with_tag(Tag::Second),
+                Tag::Both,
+                AcqRel,
+                Acquire,
+                &guard
+            )
+            .is_err());
+        assert!(atomic_owned
+            .compare_exchange_tag(ptr, Tag::Both, AcqRel, Acquire, &guard)
+            .is_ok());
+        assert_eq!(atomic_owned.tag(Relaxed), Tag::Both);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn tag_flags() {
+        let atomic_shared: Arc<AtomicShared<usize>> = Arc::new(AtomicShared::new(7));
+        let thread_handles = [Tag::First, Tag::Second].map(|flag| {
+            let atomic_shared = atomic_shared.clone();
+            std::thread::spawn(move || {
+                let guard = Guard::new();
+                loop {
+                    let current = atomic_shared.load(Relaxed, &guard).tag();
+                    if atomic_shared.update_tag_if(
+                        current | flag,
+                        |p| p.tag() == current,
+                        Relaxed,
+                        Relaxed,
+                    ) {
+                        break;
+                    }
+                }
+            })
+        });
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        assert_eq!(atomic_shared.tag(Relaxed), Tag::Both);
+
+        let guard = Guard::new();
+        let ptr = atomic_shared.load(Relaxed, &guard);
+        assert_eq!(ptr.tag() & Tag::First, Tag::First);
+        assert_eq!(ptr.with_tag(ptr.tag() ^ Tag::First).tag(), Tag::Second);
+        assert_eq!(ptr.as_ref(), Some(&7));
+    }
+
     #[cfg_attr(miri, ignore)]
     #[test]
     fn atomic_shared_send() {
@@ -3335,9 +6963,11 @@ mod random_failure_test {
 #[cfg(feature = "serde")]
 #[cfg(test)]
 mod serde_test {
-    use crate::{HashCache, HashIndex, HashMap, HashSet, TreeIndex};
+    use crate::{HashCache, HashIndex, HashMap, HashSet, Queue, Stack, TreeIndex};
 
-    use serde_test::{assert_tokens, Token};
+    use serde::de::value::{Error, SeqDeserializer};
+    use serde::Deserialize;
+    use serde_test::{assert_de_tokens, assert_ser_tokens, assert_tokens, Token};
 
     #[test]
     fn hashmap() {
@@ -3417,4 +7047,79 @@ mod serde_test {
             ],
         );
     }
+
+    #[test]
+    fn treeindex_unordered() {
+        let treeindex: TreeIndex<u64, i16> = TreeIndex::new();
+        assert!(treeindex.insert(2, -6).is_ok());
+        assert!(treeindex.insert(3, -5).is_ok());
+        assert!(treeindex.insert(4, -4).is_ok());
+        assert_de_tokens(
+            &treeindex,
+            &[
+                Token::Map { len: Some(3) },
+                Token::U64(4),
+                Token::I16(-4),
+                Token::U64(2),
+                Token::I16(-6),
+                Token::U64(3),
+                Token::I16(-5),
+                Token::MapEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn queue() {
+        let queue: Queue<u64> = Queue::default();
+        queue.push(4);
+        queue.push(2);
+        queue.push(3);
+        assert_ser_tokens(
+            &queue,
+            &[
+                Token::Seq { len: Some(3) },
+                Token::U64(4),
+                Token::U64(2),
+                Token::U64(3),
+                Token::SeqEnd,
+            ],
+        );
+
+        let deserializer = SeqDeserializer::<_, Error>::new([4_u64, 2, 3].into_iter());
+        let result = Queue::<u64>::deserialize(deserializer);
+        assert_eq!(
+            result
+                .ok()
+                .map(|q| q.into_iter().map(|e| **e).collect::<Vec<_>>()),
+            Some(vec![4, 2, 3])
+        );
+    }
+
+    #[test]
+    fn stack() {
+        let stack: Stack<u64> = Stack::default();
+        stack.push(4);
+        stack.push(2);
+        stack.push(3);
+        assert_ser_tokens(
+            &stack,
+            &[
+                Token::Seq { len: Some(3) },
+                Token::U64(3),
+                Token::U64(2),
+                Token::U64(4),
+                Token::SeqEnd,
+            ],
+        );
+
+        let deserializer = SeqDeserializer::<_, Error>::new([3_u64, 2, 4].into_iter());
+        let result = Stack::<u64>::deserialize(deserializer);
+        assert_eq!(
+            result
+                .ok()
+                .map(|s| s.into_iter().map(|e| **e).collect::<Vec<_>>()),
+            Some(vec![3, 2, 4])
+        );
+    }
 }
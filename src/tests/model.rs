@@ -1,3 +1,218 @@
+#[cfg(test)]
+mod bucket_lock_model {
+    use loom::cell::UnsafeCell;
+    use loom::sync::atomic::AtomicU32;
+    use loom::sync::{Condvar, Mutex};
+    use loom::thread::spawn;
+    use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+    use std::sync::Arc;
+
+    const KILLED: u32 = 1_u32 << 31;
+    const WAITING: u32 = 1_u32 << 30;
+    const LOCK: u32 = 1_u32 << 29;
+    const SLOCK_MAX: u32 = LOCK - 1;
+    const LOCK_MASK: u32 = LOCK | SLOCK_MAX;
+
+    /// Mirrors the `state` word protocol of `hash_table::bucket::Bucket`.
+    ///
+    /// Bucket arrays are allocated as zero-initialized memory which `loom` atomics cannot live
+    /// in, therefore the protocol is replicated here with the same constants and orderings.
+    #[derive(Default)]
+    struct ModelBucket {
+        state: AtomicU32,
+        data: UnsafeCell<usize>,
+        signaled: Mutex<bool>,
+        condvar: Condvar,
+    }
+
+    impl ModelBucket {
+        fn try_lock(&self) -> Result<bool, ()> {
+            let current = self.state.load(Relaxed) & (!LOCK_MASK);
+            if (current & KILLED) == KILLED {
+                return Ok(false);
+            }
+            if self
+                .state
+                .compare_exchange(current, current | LOCK, Acquire, Relaxed)
+                .is_ok()
+            {
+                Ok(true)
+            } else {
+                Err(())
+            }
+        }
+
+        fn unlock(&self) {
+            let mut current = self.state.load(Relaxed);
+            while let Err(result) = self.state.compare_exchange_weak(
+                current,
+                current & (!(WAITING | LOCK)),
+                Release,
+                Relaxed,
+            ) {
+                current = result;
+            }
+            if (current & WAITING) == WAITING {
+                self.signal();
+            }
+        }
+
+        fn try_read(&self) -> Result<bool, ()> {
+            let current = self.state.load(Relaxed);
+            if (current & LOCK_MASK) >= SLOCK_MAX {
+                return Err(());
+            }
+            if (current & KILLED) == KILLED {
+                return Ok(false);
+            }
+            if self
+                .state
+                .compare_exchange(current, current + 1, Acquire, Relaxed)
+                .is_ok()
+            {
+                Ok(true)
+            } else {
+                Err(())
+            }
+        }
+
+        fn release_read(&self) {
+            let mut current = self.state.load(Relaxed);
+            loop {
+                let wakeup = (current & WAITING) == WAITING;
+                let next = (current - 1) & !(WAITING);
+                match self
+                    .state
+                    .compare_exchange_weak(current, next, Release, Relaxed)
+                {
+                    Ok(_) => {
+                        if wakeup {
+                            self.signal();
+                        }
+                        break;
+                    }
+                    Err(result) => current = result,
+                }
+            }
+        }
+
+        /// Acquires the exclusive lock the way `Locker::lock` does: a failed attempt marks
+        /// `WAITING` and retries once before sleeping until signaled.
+        fn lock(&self) -> bool {
+            loop {
+                if let Ok(locked) = self.try_lock() {
+                    return locked;
+                }
+                self.state.fetch_or(WAITING, Release);
+                if let Ok(locked) = self.try_lock() {
+                    return locked;
+                }
+                self.wait();
+            }
+        }
+
+        fn read(&self) -> bool {
+            loop {
+                if let Ok(locked) = self.try_read() {
+                    return locked;
+                }
+                self.state.fetch_or(WAITING, Release);
+                if let Ok(locked) = self.try_read() {
+                    return locked;
+                }
+                self.wait();
+            }
+        }
+
+        fn signal(&self) {
+            *self.signaled.lock().unwrap() = true;
+            self.condvar.notify_all();
+        }
+
+        fn wait(&self) {
+            let mut signaled = self.signaled.lock().unwrap();
+            while !*signaled {
+                signaled = self.condvar.wait(signaled).unwrap();
+            }
+            *signaled = false;
+        }
+
+        fn kill(&self) {
+            self.state.fetch_or(KILLED, Release);
+        }
+    }
+
+    fn check<F: Fn() + Sync + Send + 'static>(f: F) {
+        let mut model = loom::model::Builder::new();
+        model.preemption_bound = Some(3);
+        model.check(f);
+    }
+
+    #[test]
+    fn exclusive() {
+        check(|| {
+            let bucket: Arc<ModelBucket> = Arc::default();
+            let thread = {
+                let bucket = bucket.clone();
+                spawn(move || {
+                    assert!(bucket.lock());
+                    bucket.data.with_mut(|data| unsafe { *data += 1 });
+                    bucket.unlock();
+                })
+            };
+            assert!(bucket.lock());
+            bucket.data.with_mut(|data| unsafe { *data += 1 });
+            bucket.unlock();
+            drop(thread.join());
+            assert_eq!(bucket.data.with(|data| unsafe { *data }), 2);
+            assert_eq!(bucket.state.load(Relaxed), 0);
+        });
+    }
+
+    #[test]
+    fn shared_exclusive() {
+        check(|| {
+            let bucket: Arc<ModelBucket> = Arc::default();
+            let thread = {
+                let bucket = bucket.clone();
+                spawn(move || {
+                    assert!(bucket.read());
+                    let data = bucket.data.with(|data| unsafe { *data });
+                    assert!(data <= 1);
+                    bucket.release_read();
+                })
+            };
+            assert!(bucket.lock());
+            bucket.data.with_mut(|data| unsafe { *data += 1 });
+            bucket.unlock();
+            drop(thread.join());
+            assert_eq!(bucket.state.load(Relaxed), 0);
+        });
+    }
+
+    #[test]
+    fn killed() {
+        check(|| {
+            let bucket: Arc<ModelBucket> = Arc::default();
+            let thread = {
+                let bucket = bucket.clone();
+                spawn(move || {
+                    if bucket.lock() {
+                        bucket.data.with_mut(|data| unsafe { *data += 1 });
+                        bucket.unlock();
+                    }
+                })
+            };
+            assert!(bucket.lock());
+            bucket.kill();
+            bucket.unlock();
+            assert!(!bucket.read());
+            drop(thread.join());
+            assert_eq!(bucket.state.load(Relaxed) & LOCK_MASK, 0);
+        });
+    }
+}
+
 #[cfg(test)]
 mod ebr_model {
     use loom::sync::atomic::fence;
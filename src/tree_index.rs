@@ -5,19 +5,27 @@ mod leaf;
 mod leaf_node;
 mod node;
 
+use crate::atomic::AtomicUsize;
 use crate::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
 use crate::wait_queue::AsyncWait;
-use leaf::{InsertResult, Leaf, RemoveResult, Scanner};
+use crate::{Comparable, LinkedList};
+use leaf::{InsertResult, Leaf, ModifyResult, RemoveResult, Scanner};
 use node::Node;
 use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::fmt::{self, Debug};
+use std::future::poll_fn;
 use std::iter::FusedIterator;
-use std::ops::Bound::{Excluded, Included, Unbounded};
-use std::ops::RangeBounds;
+use std::marker::PhantomData;
+use std::mem::replace;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+use std::ops::{ControlFlow, RangeBounds};
 use std::panic::UnwindSafe;
 use std::pin::Pin;
+use std::ptr;
 use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
+use std::sync::Arc;
+use std::task::{Context, Poll};
 
 /// Scalable concurrent B-plus tree.
 ///
@@ -37,7 +45,10 @@ use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
 /// * Lock-free-read: read and scan operations do not modify shared data and are never blocked.
 /// * Near lock-free write: write operations do not block unless a structural change is needed.
 /// * No busy waiting: each node has a wait queue to avoid spinning.
-/// * Immutability: the data in the container is immutable until it becomes unreachable.
+/// * Immutability: entries are never updated in place; [`TreeIndex::modify`] writes a new value
+///   next to the old one which stays intact until it becomes unreachable.
+/// * Flexible lookups: keys can be looked up by any type implementing
+///   [`Comparable`], e.g., a borrowed view of a composite key.
 ///
 /// ## The key statistics for [`TreeIndex`]
 ///
@@ -61,6 +72,9 @@ where
     V: 'static + Clone,
 {
     root: AtomicShared<Node<K, V>>,
+    /// The number of times nodes have been detached from the tree without being retired.
+    detach_count: AtomicUsize,
+    watchers: AtomicShared<Vec<Watcher<K, V>>>,
 }
 
 /// An iterator over the entries of a [`TreeIndex`].
@@ -74,24 +88,182 @@ where
 {
     root: &'t AtomicShared<Node<K, V>>,
     leaf_scanner: Option<Scanner<'g, K, V>>,
+    started: bool,
     guard: &'g Guard,
 }
 
 /// An iterator over a sub-range of entries in a [`TreeIndex`].
-pub struct Range<'t, 'g, K, V, R>
+///
+/// The bounds of the range can be of any type that the keys can be borrowed as.
+#[allow(clippy::struct_excessive_bools)]
+pub struct Range<'t, 'g, K, V, R, Q = K>
 where
     K: 'static + Clone + Ord,
     V: 'static + Clone,
-    R: RangeBounds<K>,
+    R: RangeBounds<Q>,
+    Q: ?Sized,
 {
     root: &'t AtomicShared<Node<K, V>>,
     leaf_scanner: Option<Scanner<'g, K, V>>,
     range: R,
     check_lower_bound: bool,
     check_upper_bound: bool,
+    started: bool,
+    pending: bool,
+    guard: &'g Guard,
+    query: PhantomData<fn(&Q)>,
+}
+
+/// An iterator that moves out of a [`TreeIndex`].
+///
+/// Entries are yielded in ascending key order by following the links between leaves; the entries
+/// of a leaf are cloned at once, and the leaf is released when the iterator moves past it.
+pub struct IntoIter<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    leaf: Option<Shared<Leaf<K, V>>>,
+    buffer: std::vec::IntoIter<(K, V)>,
+}
+
+/// An asynchronous stream over a sub-range of entries in a [`TreeIndex`].
+///
+/// Entries of a leaf are cloned at once, and control is yielded to the async executor before
+/// the next leaf is read; the stream does not hold a [`Guard`] across polls.
+pub struct RangeStream<'t, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    tree_index: &'t TreeIndex<K, V>,
+    lower_bound: Bound<K>,
+    upper_bound: Bound<K>,
+    buffer: std::vec::IntoIter<(K, V)>,
+    yield_now: bool,
+    finished: bool,
+}
+
+/// Tree shape statistics of a [`TreeIndex`].
+///
+/// The statistics are collected without blocking other threads, therefore they may not
+/// correspond to a consistent snapshot of the [`TreeIndex`] when it is being modified.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Statistics {
+    num_nodes: Vec<usize>,
+    leaf_occupancy: Vec<usize>,
+    retired_leaves: usize,
+}
+
+/// An iterator over the entries having keys starting with a byte prefix in a [`TreeIndex`].
+pub struct Prefix<'g, 'p, K, V, Q>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+    Q: ?Sized,
+{
+    leaf_scanner: Option<Scanner<'g, K, V>>,
+    bytes: &'p Q,
+    first: bool,
+    guard: &'g Guard,
+}
+
+/// [`Cursor`] points to an entry in a [`TreeIndex`], and can move forward or backward.
+///
+/// A [`Cursor`] has a ghost position after the last entry and before the first entry, which is
+/// represented as `None`.
+pub struct Cursor<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    tree_index: &'t TreeIndex<K, V>,
+    leaf_scanner: Option<Scanner<'g, K, V>>,
+    guard: &'g Guard,
+}
+
+/// [`Entry`] represents a single entry in a [`TreeIndex`].
+pub enum Entry<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'g, K, V>),
+
+    /// A vacant entry.
+    Vacant(VacantEntry<'t, 'g, K, V>),
+}
+
+/// [`OccupiedEntry`] is a view into an occupied entry in a [`TreeIndex`].
+pub struct OccupiedEntry<'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    key: K,
+    val: &'g V,
+}
+
+/// [`VacantEntry`] is a view into a vacant entry in a [`TreeIndex`].
+pub struct VacantEntry<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    tree_index: &'t TreeIndex<K, V>,
+    key: K,
+    leaf: Option<&'g Leaf<K, V>>,
+    detach_count: usize,
     guard: &'g Guard,
 }
 
+/// [`WatchEvent`] describes a change made to an entry in a watched key range.
+#[derive(Debug)]
+pub enum WatchEvent<'e, K, V> {
+    /// A new entry was inserted.
+    Inserted(&'e K, &'e V),
+
+    /// The value associated with the key was replaced with a new one.
+    Modified(&'e K, &'e V),
+
+    /// The entry was removed; the removed value is supplied.
+    Removed(&'e K, &'e V),
+
+    /// Entries in the key range were removed at once by [`TreeIndex::remove_range`] or
+    /// [`TreeIndex::clear`].
+    ///
+    /// The removed entries are not visited, therefore the event is delivered even if the range
+    /// did not contain any entries, and the range may exceed the range of the watcher.
+    RangeRemoved(Bound<&'e K>, Bound<&'e K>),
+}
+
+/// A callback registered with [`TreeIndex::watch`].
+type WatchCallback<K, V> = dyn Fn(&WatchEvent<'_, K, V>) + Send + Sync;
+
+/// [`Watcher`] is a key range registered with [`TreeIndex::watch`].
+struct Watcher<K, V> {
+    id: usize,
+    range: (Bound<K>, Bound<K>),
+    callback: Arc<WatchCallback<K, V>>,
+}
+
+/// The identifier of the next [`Watcher`].
+static WATCHER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A [`Leaf`] or the key of an entry visited by [`TreeIndex::walk_leaves`].
+enum LeafVisit<'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    Leaf(&'g Leaf<K, V>),
+    Key(&'g K),
+}
+
+/// The maximum number of entries that [`TreeIndex`] formats with [`Debug`].
+const DEBUG_MAX_ENTRIES: usize = 32;
+
 impl<K, V> TreeIndex<K, V>
 where
     K: 'static + Clone + Ord,
@@ -111,6 +283,8 @@ where
     pub const fn new() -> Self {
         Self {
             root: AtomicShared::null(),
+            detach_count: AtomicUsize::new(0),
+            watchers: AtomicShared::null(),
         }
     }
 
@@ -132,54 +306,8 @@ where
     /// assert_eq!(treeindex.peek_with(&1, |k, v| *v).unwrap(), 10);
     /// ```
     #[inline]
-    pub fn insert(&self, mut key: K, mut val: V) -> Result<(), (K, V)> {
-        let mut new_root = None;
-        loop {
-            let guard = Guard::new();
-            if let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
-                match root_ref.insert(key, val, &mut (), &guard) {
-                    Ok(r) => match r {
-                        InsertResult::Success => return Ok(()),
-                        InsertResult::Frozen(k, v) | InsertResult::Retry(k, v) => {
-                            key = k;
-                            val = v;
-                            root_ref.cleanup_link(&key, false, &guard);
-                        }
-                        InsertResult::Duplicate(k, v) => return Err((k, v)),
-                        InsertResult::Full(k, v) => {
-                            let (k, v) = Node::split_root(k, v, &self.root, &guard);
-                            key = k;
-                            val = v;
-                            continue;
-                        }
-                        InsertResult::Retired(k, v) => {
-                            key = k;
-                            val = v;
-                            let _result = Node::cleanup_root(&self.root, &mut (), &guard);
-                        }
-                    },
-                    Err((k, v)) => {
-                        key = k;
-                        val = v;
-                    }
-                }
-            }
-
-            let node = if let Some(new_root) = new_root.take() {
-                new_root
-            } else {
-                Shared::new(Node::new_leaf_node())
-            };
-            if let Err((node, _)) = self.root.compare_exchange(
-                Ptr::null(),
-                (Some(node), Tag::None),
-                AcqRel,
-                Acquire,
-                &guard,
-            ) {
-                new_root = node;
-            }
-        }
+    pub fn insert(&self, key: K, val: V) -> Result<(), (K, V)> {
+        self.insert_with_guard(key, val, &Guard::new()).map(|_| ())
     }
 
     /// Inserts a key-value pair.
@@ -200,6 +328,7 @@ where
     /// ```
     #[inline]
     pub async fn insert_async(&self, mut key: K, mut val: V) -> Result<(), (K, V)> {
+        let watched_key = self.is_watched(&key).then(|| key.clone());
         let mut new_root = None;
         loop {
             let mut async_wait = AsyncWait::default();
@@ -210,7 +339,13 @@ where
                 if let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
                     match root_ref.insert(key, val, &mut async_wait_pinned, &guard) {
                         Ok(r) => match r {
-                            InsertResult::Success => return Ok(()),
+                            InsertResult::Success(val_ptr) => {
+                                if let Some(key) = watched_key.as_ref() {
+                                    let val = unsafe { &*val_ptr };
+                                    self.notify(&WatchEvent::Inserted(key, val));
+                                }
+                                return Ok(());
+                            }
                             InsertResult::Frozen(k, v) | InsertResult::Retry(k, v) => {
                                 key = k;
                                 val = v;
@@ -262,6 +397,44 @@ where
         }
     }
 
+    /// Gets the entry associated with the given key for in-place manipulation.
+    ///
+    /// The returned [`Entry`] and references derived from it can survive as long as the
+    /// associated [`Guard`] is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// assert_eq!(*treeindex.entry(1, &guard).or_insert(10), 10);
+    /// assert_eq!(*treeindex.entry(1, &guard).or_insert(11), 10);
+    /// ```
+    #[inline]
+    pub fn entry<'t, 'g>(&'t self, key: K, guard: &'g Guard) -> Entry<'t, 'g, K, V> {
+        let detach_count = self.detach_count.load(Acquire);
+        let leaf = self
+            .root
+            .load(Acquire, guard)
+            .as_ref()
+            .and_then(|root_ref| root_ref.search_leaf(&key, guard).as_ref());
+        if let Some((_, val)) = leaf.and_then(|leaf| leaf.search_entry(&key)) {
+            Entry::Occupied(OccupiedEntry { key, val })
+        } else {
+            Entry::Vacant(VacantEntry {
+                tree_index: self,
+                key,
+                leaf,
+                detach_count,
+                guard,
+            })
+        }
+    }
+
     /// Removes a key-value pair.
     ///
     /// Returns `false` if the key does not exist.
@@ -283,8 +456,7 @@ where
     #[inline]
     pub fn remove<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         self.remove_if(key, |_| true)
     }
@@ -308,8 +480,7 @@ where
     #[inline]
     pub async fn remove_async<Q>(&self, key: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         self.remove_if_async(key, |_| true).await
     }
@@ -335,11 +506,19 @@ where
     #[inline]
     pub fn remove_if<Q, F: FnMut(&V) -> bool>(&self, key: &Q, mut condition: F) -> bool
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
+        let watched_key = self.watched_key(key);
+        let mut removed_val = None;
+        let mut condition = |v: &V| {
+            let result = condition(v);
+            if result && watched_key.is_some() {
+                removed_val.replace(v.clone());
+            }
+            result
+        };
         let mut removed = false;
-        loop {
+        let result = loop {
             let guard = Guard::new();
             if let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
                 if let Ok(result) =
@@ -349,29 +528,33 @@ where
                         root_ref.cleanup_link(key, false, &guard);
                     }
                     match result {
-                        RemoveResult::Success => return true,
+                        RemoveResult::Success => break true,
                         RemoveResult::Cleanup | RemoveResult::Retired => {
                             if Node::cleanup_root(&self.root, &mut (), &guard) {
-                                return true;
+                                break true;
                             }
                             removed = true;
                         }
                         RemoveResult::Fail => {
                             if removed {
                                 if Node::cleanup_root(&self.root, &mut (), &guard) {
-                                    return true;
+                                    break true;
                                 }
                             } else {
-                                return false;
+                                break false;
                             }
                         }
                         RemoveResult::Frozen => (),
                     }
                 }
             } else {
-                return removed;
+                break removed;
             }
+        };
+        if let (true, Some(key), Some(val)) = (result, watched_key.as_ref(), removed_val.as_ref()) {
+            self.notify(&WatchEvent::Removed(key, val));
         }
+        result
     }
 
     /// Removes a key-value pair if the given condition is met.
@@ -393,11 +576,19 @@ where
     #[inline]
     pub async fn remove_if_async<Q, F: FnMut(&V) -> bool>(&self, key: &Q, mut condition: F) -> bool
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
+        let watched_key = self.watched_key(key);
+        let mut removed_val = None;
+        let mut condition = |v: &V| {
+            let result = condition(v);
+            if result && watched_key.is_some() {
+                removed_val.replace(v.clone());
+            }
+            result
+        };
         let mut removed = false;
-        loop {
+        let result = loop {
             let mut async_wait = AsyncWait::default();
             let mut async_wait_pinned = Pin::new(&mut async_wait);
             {
@@ -413,10 +604,10 @@ where
                             root_ref.cleanup_link(key, false, &guard);
                         }
                         match result {
-                            RemoveResult::Success => return true,
+                            RemoveResult::Success => break true,
                             RemoveResult::Cleanup | RemoveResult::Retired => {
                                 if Node::cleanup_root(&self.root, &mut async_wait_pinned, &guard) {
-                                    return true;
+                                    break true;
                                 }
                                 removed = true;
                             }
@@ -427,35 +618,34 @@ where
                                         &mut async_wait_pinned,
                                         &guard,
                                     ) {
-                                        return true;
+                                        break true;
                                     }
                                 } else {
-                                    return false;
+                                    break false;
                                 }
                             }
                             RemoveResult::Frozen => (),
                         }
                     }
                 } else {
-                    return removed;
+                    break removed;
                 }
             }
             async_wait_pinned.await;
+        };
+        if let (true, Some(key), Some(val)) = (result, watched_key.as_ref(), removed_val.as_ref()) {
+            self.notify(&WatchEvent::Removed(key, val));
         }
+        result
     }
 
-    /// Removes keys in the specified range.
-    ///
-    /// This method removes internal nodes that are definitely contained in the specified range
-    /// first, and then removes remaining entries individually.
+    /// Replaces the value associated with the key with a new one derived from the current value.
     ///
-    /// # Notes
+    /// Returns `false` if the key does not exist.
     ///
-    /// Internally, multiple internal node locks need to be acquired, thus making this method
-    /// susceptible to lock starvation. Also, no asynchronous version of this method is provided
-    /// which will be added once
-    /// [`Issue 123`](https://github.com/wvwwvwwv/scalable-concurrent-containers/issues/123) is
-    /// resolved.
+    /// The key stays visible throughout the operation; readers see either the old or the new
+    /// value, and references to the old value remain valid as long as the associated [`Guard`] is
+    /// alive. `updater` may be invoked more than once if the entry is concurrently updated.
     ///
     /// # Examples
     ///
@@ -464,73 +654,50 @@ where
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
     ///
-    /// for k in 2..8 {
-    ///     assert!(treeindex.insert(k, 1).is_ok());
-    /// }
-    ///
-    /// treeindex.remove_range(3..8);
-    ///
-    /// assert!(treeindex.contains(&2));
-    /// assert!(!treeindex.contains(&3));
+    /// assert!(!treeindex.modify(&1, |v| *v + 1));
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    /// assert!(treeindex.modify(&1, |v| *v + 1));
+    /// assert_eq!(treeindex.peek_with(&1, |_, v| *v).unwrap(), 11);
     /// ```
     #[inline]
-    pub fn remove_range<R: RangeBounds<K>>(&self, range: R) {
-        let start_unbounded = matches!(range.start_bound(), Unbounded);
-        let guard = Guard::new();
-
-        // Remove internal nodes.
-        //
-        // It takes O(N) to traverse sub-trees on the range border.
-        while let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
-            if let Ok(num_children) =
-                root_ref.remove_range(&range, start_unbounded, None, None, &mut (), &guard)
-            {
-                if num_children < 2 && !Node::cleanup_root(&self.root, &mut (), &guard) {
-                    continue;
-                }
-                break;
-            }
-        }
-
-        // Remove individual entries in leaves on the border.
-        //
-        // The expected number of calls to `remove` is `14`: the number of entry slots in a leaf.
-        for (k, _) in self.range(range, &guard) {
-            self.remove(k);
-        }
+    pub fn modify<Q, F: FnMut(&V) -> V>(&self, key: &Q, mut updater: F) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.modify_if(key, |v| Some(updater(v)))
     }
 
-    /// Returns a guarded reference to the value for the specified key without acquiring locks.
+    /// Replaces the value associated with the key with a new one derived from the current value.
     ///
-    /// Returns `None` if the key does not exist. The returned reference can survive as long as the
-    /// associated [`Guard`] is alive.
+    /// Returns `false` if the key does not exist. It is an asynchronous method returning an
+    /// `impl Future` for the caller to await.
     ///
     /// # Examples
     ///
     /// ```
     /// use scc::TreeIndex;
-    /// use scc::ebr::Guard;
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
-    ///
-    /// let guard = Guard::new();
-    /// assert!(treeindex.peek(&1, &guard).is_none());
+    /// let future_modify = treeindex.modify_async(&1, |v| *v + 1);
     /// ```
     #[inline]
-    pub fn peek<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<&'g V>
+    pub async fn modify_async<Q, F: FnMut(&V) -> V>(&self, key: &Q, mut updater: F) -> bool
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
-        if let Some(root_ref) = self.root.load(Acquire, guard).as_ref() {
-            return root_ref.search(key, guard);
-        }
-        None
+        self.modify_if_async(key, |v| Some(updater(v))).await
     }
 
-    /// Peeks a key-value pair without acquiring locks.
+    /// Replaces the value associated with the key with `new` if the current value is equal to
+    /// `expected`.
     ///
-    /// Returns `None` if the key does not exist.
+    /// The value is atomically replaced, therefore a lock-free reader sees either the old or the
+    /// new value, and concurrent writers that started from the same value cannot both succeed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the current value if it is not equal to `expected`, or `None` if the key does not
+    /// exist.
     ///
     /// # Examples
     ///
@@ -538,41 +705,78 @@ where
     /// use scc::TreeIndex;
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
-    /// assert!(treeindex.peek_with(&1, |k, v| *v).is_none());
+    ///
+    /// assert_eq!(treeindex.compare_exchange(&1, &0, 1), Err(None));
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// assert_eq!(treeindex.compare_exchange(&1, &0, 1), Ok(()));
+    /// assert_eq!(treeindex.compare_exchange(&1, &0, 2), Err(Some(1)));
     /// ```
+    #[allow(clippy::needless_pass_by_value)]
     #[inline]
-    pub fn peek_with<Q, R, F: FnOnce(&Q, &V) -> R>(&self, key: &Q, reader: F) -> Option<R>
+    pub fn compare_exchange<Q>(&self, key: &Q, expected: &V, new: V) -> Result<(), Option<V>>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
+        V: PartialEq,
     {
-        let guard = Guard::new();
-        self.peek(key, &guard).map(|v| reader(key, v))
+        let mut current = None;
+        if self.modify_if(key, |v| {
+            Self::exchange_if_equal(v, expected, &new, &mut current)
+        }) {
+            Ok(())
+        } else {
+            Err(current)
+        }
     }
 
-    /// Returns `true` if the [`TreeIndex`] contains the key.
+    /// Replaces the value associated with the key with `new` if the current value is equal to
+    /// `expected`.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Errors
+    ///
+    /// Returns the current value if it is not equal to `expected`, or `None` if the key does not
+    /// exist.
     ///
     /// # Examples
     ///
     /// ```
     /// use scc::TreeIndex;
     ///
-    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::default();
-    ///
-    /// assert!(!treeindex.contains(&1));
-    /// assert!(treeindex.insert(1, 0).is_ok());
-    /// assert!(treeindex.contains(&1));
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// let future_compare_exchange = treeindex.compare_exchange_async(&1, &0, 1);
     /// ```
     #[inline]
-    pub fn contains<Q>(&self, key: &Q) -> bool
+    pub async fn compare_exchange_async<Q>(
+        &self,
+        key: &Q,
+        expected: &V,
+        new: V,
+    ) -> Result<(), Option<V>>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
+        V: PartialEq,
     {
-        self.peek(key, &Guard::new()).is_some()
+        let mut current = None;
+        if self
+            .modify_if_async(key, |v| {
+                Self::exchange_if_equal(v, expected, &new, &mut current)
+            })
+            .await
+        {
+            Ok(())
+        } else {
+            Err(current)
+        }
     }
 
-    /// Clears the [`TreeIndex`].
+    /// Inserts a new value constructed by `constructor` if the key does not exist, otherwise
+    /// replaces the value with a new one derived from the current value.
+    ///
+    /// Returns `true` if a new entry was inserted. The operation is retried if the entry is
+    /// concurrently inserted or removed, therefore there is no window in which both the insertion
+    /// and the update fail. `constructor` is invoked at most once, and `updater` may be invoked
+    /// more than once if the entry is concurrently updated.
     ///
     /// # Examples
     ///
@@ -581,17 +785,50 @@ where
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
     ///
-    /// treeindex.clear();
-    /// assert_eq!(treeindex.len(), 0);
+    /// assert!(treeindex.upsert(1, || 0, |v| *v + 1));
+    /// assert!(!treeindex.upsert(1, || 0, |v| *v + 1));
+    /// assert_eq!(treeindex.peek_with(&1, |_, v| *v).unwrap(), 1);
     /// ```
     #[inline]
-    pub fn clear(&self) {
-        self.root.swap((None, Tag::None), Relaxed);
+    pub fn upsert<C: FnOnce() -> V, F: FnMut(&V) -> V>(
+        &self,
+        mut key: K,
+        constructor: C,
+        mut updater: F,
+    ) -> bool {
+        let mut val = Err(constructor);
+        loop {
+            match self.upsert_in_leaf(key, val, &mut updater, &Guard::new()) {
+                Ok(inserted) => return inserted,
+                Err((k, v)) => {
+                    key = k;
+                    val = v;
+                }
+            }
+
+            // The `Leaf` is being split or retired: let the tree restructure itself.
+            if self.modify(&key, &mut updater) {
+                return false;
+            }
+            let v = match val {
+                Ok(v) => v,
+                Err(constructor) => constructor(),
+            };
+            match self.insert(key, v) {
+                Ok(()) => return true,
+                Err((k, v)) => {
+                    key = k;
+                    val = Ok(v);
+                }
+            }
+        }
     }
 
-    /// Returns the size of the [`TreeIndex`].
+    /// Inserts a new value constructed by `constructor` if the key does not exist, otherwise
+    /// replaces the value with a new one derived from the current value.
     ///
-    /// It internally scans all the leaf nodes, and therefore the time complexity is O(N).
+    /// Returns `true` if a new entry was inserted. It is an asynchronous method returning an
+    /// `impl Future` for the caller to await.
     ///
     /// # Examples
     ///
@@ -599,174 +836,2946 @@ where
     /// use scc::TreeIndex;
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
-    /// assert_eq!(treeindex.len(), 0);
+    /// let future_upsert = treeindex.upsert_async(1, || 0, |v| *v + 1);
     /// ```
     #[inline]
-    pub fn len(&self) -> usize {
-        let guard = Guard::new();
-        self.iter(&guard).count()
+    pub async fn upsert_async<C: FnOnce() -> V, F: FnMut(&V) -> V>(
+        &self,
+        mut key: K,
+        constructor: C,
+        mut updater: F,
+    ) -> bool {
+        let mut val = Err(constructor);
+        loop {
+            match self.upsert_in_leaf(key, val, &mut updater, &Guard::new()) {
+                Ok(inserted) => return inserted,
+                Err((k, v)) => {
+                    key = k;
+                    val = v;
+                }
+            }
+
+            // The `Leaf` is being split or retired: let the tree restructure itself.
+            if self.modify_async(&key, &mut updater).await {
+                return false;
+            }
+            let v = match val {
+                Ok(v) => v,
+                Err(constructor) => constructor(),
+            };
+            match self.insert_async(key, v).await {
+                Ok(()) => return true,
+                Err((k, v)) => {
+                    key = k;
+                    val = Ok(v);
+                }
+            }
+        }
+    }
+
+    /// Removes keys in the specified range.
+    ///
+    /// This method removes internal nodes that are definitely contained in the specified range
+    /// first, and then removes remaining entries individually. Callbacks registered with
+    /// [`TreeIndex::watch`] are notified of the detached nodes by a single
+    /// [`WatchEvent::RangeRemoved`] event, and of the remaining entries individually.
+    ///
+    /// # Notes
+    ///
+    /// Internally, multiple internal node locks need to be acquired, thus making this method
+    /// susceptible to lock starvation. See [`TreeIndex::remove_range_async`] for the asynchronous
+    /// version of this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// for k in 2..8 {
+    ///     assert!(treeindex.insert(k, 1).is_ok());
+    /// }
+    ///
+    /// treeindex.remove_range(3..8);
+    ///
+    /// assert!(treeindex.contains(&2));
+    /// assert!(!treeindex.contains(&3));
+    /// ```
+    #[inline]
+    pub fn remove_range<R: RangeBounds<K>>(&self, range: R) {
+        let start_unbounded = matches!(range.start_bound(), Unbounded);
+        let guard = Guard::new();
+        self.detach_count.fetch_add(1, AcqRel);
+
+        // Remove internal nodes.
+        //
+        // Only the nodes on the range border are traversed.
+        while let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
+            if let Ok(num_children) =
+                root_ref.remove_range(&range, start_unbounded, None, None, &mut (), &guard)
+            {
+                if num_children < 2 && !Node::cleanup_root(&self.root, &mut (), &guard) {
+                    continue;
+                }
+                break;
+            }
+        }
+        self.notify(&WatchEvent::RangeRemoved(
+            range.start_bound(),
+            range.end_bound(),
+        ));
+
+        // Remove individual entries in leaves on the border.
+        //
+        // The expected number of calls to `remove` is `14`: the number of entry slots in a leaf.
+        for (k, _) in self.range(range, &guard) {
+            self.remove(k);
+        }
+    }
+
+    /// Removes keys in the specified range.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await. Node
+    /// latches are awaited instead of blocking the thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// let future_remove_range = treeindex.remove_range_async(3..8);
+    /// ```
+    #[inline]
+    pub async fn remove_range_async<R: RangeBounds<K>>(&self, range: R) {
+        let start_unbounded = matches!(range.start_bound(), Unbounded);
+        self.detach_count.fetch_add(1, AcqRel);
+
+        // Remove internal nodes.
+        loop {
+            let mut async_wait = AsyncWait::default();
+            let mut async_wait_pinned = Pin::new(&mut async_wait);
+            {
+                let guard = Guard::new();
+                let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() else {
+                    break;
+                };
+                if let Ok(num_children) = root_ref.remove_range(
+                    &range,
+                    start_unbounded,
+                    None,
+                    None,
+                    &mut async_wait_pinned,
+                    &guard,
+                ) {
+                    if num_children >= 2
+                        || Node::cleanup_root(&self.root, &mut async_wait_pinned, &guard)
+                    {
+                        break;
+                    }
+                }
+            }
+            async_wait_pinned.await;
+        }
+        self.notify(&WatchEvent::RangeRemoved(
+            range.start_bound(),
+            range.end_bound(),
+        ));
+
+        // Remove individual entries in leaves on the border.
+        let mut prev_key: Option<K> = None;
+        loop {
+            let next_key = {
+                let guard = Guard::new();
+                let start = prev_key.as_ref().map_or(range.start_bound(), Excluded);
+                self.range((start, range.end_bound()), &guard)
+                    .next()
+                    .map(|(k, _)| k.clone())
+            };
+            if let Some(next_key) = next_key {
+                self.remove_async(&next_key).await;
+                prev_key.replace(next_key);
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Removes all the keys less than the given key.
+    ///
+    /// Sub-trees that only contain keys below `key` are detached from the tree as a whole without
+    /// visiting their entries, therefore the cost of the operation is proportional to the depth
+    /// of the tree rather than the number of removed entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// for k in 0..1024 {
+    ///     assert!(treeindex.insert(k, 1).is_ok());
+    /// }
+    ///
+    /// treeindex.remove_range_to(&1000);
+    ///
+    /// assert_eq!(treeindex.len(), 24);
+    /// assert!(!treeindex.contains(&999));
+    /// assert!(treeindex.contains(&1000));
+    /// ```
+    #[inline]
+    pub fn remove_range_to(&self, key: &K) {
+        self.remove_range((Unbounded, Excluded(key)));
+    }
+
+    /// Removes all the keys less than the given key.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// let future_remove_range_to = treeindex.remove_range_to_async(&1000);
+    /// ```
+    #[inline]
+    pub async fn remove_range_to_async(&self, key: &K) {
+        self.remove_range_async((Unbounded, Excluded(key))).await;
+    }
+
+    /// Moves all the entries in `other` into `self`.
+    ///
+    /// If the keys in `other` are all less than or all greater than those in `self`, the two trees
+    /// are linked without moving the entries, and only the nodes on the border are visited.
+    /// Otherwise, the entries are moved one by one in ascending key order, and if a key exists in
+    /// both, the value in `other` replaces the value in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let mut treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// let mut other: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    /// assert!(treeindex.insert(2, 20).is_ok());
+    /// assert!(other.insert(2, 21).is_ok());
+    /// assert!(other.insert(3, 31).is_ok());
+    ///
+    /// treeindex.append(&mut other);
+    ///
+    /// assert!(other.is_empty());
+    /// assert_eq!(treeindex.len(), 3);
+    /// assert_eq!(treeindex.peek_with(&2, |_, v| *v), Some(21));
+    ///
+    /// assert!(other.insert(4, 40).is_ok());
+    /// treeindex.append(&mut other);
+    /// assert_eq!(treeindex.len(), 4);
+    /// ```
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        if self.graft(other) {
+            return;
+        }
+        for (key, val) in other.extract_if(|_, _| true) {
+            self.insert_or_replace(key, val);
+        }
+    }
+
+    /// Retains the entries specified by the predicate.
+    ///
+    /// Entries are visited in ascending key order, and those that do not satisfy the predicate
+    /// are removed from their leaves without searching the tree for each of them. Entries that
+    /// are inserted during the scan may not be visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// assert!(treeindex.insert(2, 1).is_ok());
+    /// assert!(treeindex.insert(3, 2).is_ok());
+    ///
+    /// treeindex.retain(|k, v| *k == 1 && *v == 0);
+    ///
+    /// assert!(treeindex.contains(&1));
+    /// assert!(!treeindex.contains(&2));
+    /// assert!(!treeindex.contains(&3));
+    /// ```
+    #[inline]
+    pub fn retain<F: FnMut(&K, &V) -> bool>(&self, mut pred: F) {
+        self.remove_entries(&.., |k, v| !pred(k, v), |_, _| ());
+    }
+
+    /// Removes the entries in the range satisfying the predicate, and returns the number of
+    /// removed entries.
+    ///
+    /// The range is traversed only once, and entries are removed from their leaves without
+    /// searching the tree for each of them. Entries that are inserted during the scan may not be
+    /// visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// for k in 0..16 {
+    ///     assert!(treeindex.insert(k, 0).is_ok());
+    /// }
+    ///
+    /// assert_eq!(treeindex.remove_range_if(4..12, |k, _| k % 2 == 0), 4);
+    /// assert!(treeindex.contains(&2));
+    /// assert!(!treeindex.contains(&4));
+    /// assert!(treeindex.contains(&5));
+    /// assert!(treeindex.contains(&12));
+    /// ```
+    #[inline]
+    pub fn remove_range_if<R: RangeBounds<K>, F: FnMut(&K, &V) -> bool>(
+        &self,
+        range: R,
+        pred: F,
+    ) -> usize {
+        let mut num_removed = 0;
+        self.remove_entries(&range, pred, |_, _| num_removed += 1);
+        num_removed
+    }
+
+    /// Removes the entries satisfying the predicate, and returns them in ascending key order.
+    ///
+    /// Entries are removed from their leaves without searching the tree for each of them.
+    /// Entries that are inserted during the scan may not be visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// assert!(treeindex.insert(2, 1).is_ok());
+    /// assert!(treeindex.insert(3, 2).is_ok());
+    ///
+    /// assert_eq!(treeindex.extract_if(|k, _| *k >= 2), vec![(2, 1), (3, 2)]);
+    /// assert_eq!(treeindex.len(), 1);
+    /// ```
+    #[inline]
+    pub fn extract_if<F: FnMut(&K, &V) -> bool>(&self, pred: F) -> Vec<(K, V)> {
+        let mut extracted = Vec::new();
+        self.remove_entries(&.., pred, |k, v| extracted.push((k.clone(), v.clone())));
+        extracted
+    }
+
+    /// Registers a callback that is invoked whenever an entry in the key range is inserted,
+    /// modified, or removed.
+    ///
+    /// Returns an identifier that can be passed to [`TreeIndex::unwatch`].
+    ///
+    /// The callback is invoked by the thread that made the change right after the change became
+    /// visible, therefore events for different keys may be observed out of order.
+    ///
+    /// The callback is invoked synchronously, therefore its execution time is added to the
+    /// latency of every modification in the key range; expensive work should be handed over to
+    /// another thread, e.g., through a channel. Bulk removals, such as [`TreeIndex::remove_range`]
+    /// and [`TreeIndex::clear`], notify each watcher of a single [`WatchEvent::RangeRemoved`]
+    /// event instead of visiting the removed entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::tree_index::WatchEvent;
+    /// use std::sync::atomic::AtomicUsize;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    /// use std::sync::Arc;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// let inserted = Arc::new(AtomicUsize::new(0));
+    /// let inserted_clone = inserted.clone();
+    ///
+    /// let id = treeindex.watch(10..20, move |event| {
+    ///     if let WatchEvent::Inserted(_, _) = event {
+    ///         inserted_clone.fetch_add(1, Relaxed);
+    ///     }
+    /// });
+    ///
+    /// assert!(treeindex.insert(5, 0).is_ok());
+    /// assert!(treeindex.insert(15, 0).is_ok());
+    /// assert_eq!(inserted.load(Relaxed), 1);
+    /// assert!(treeindex.unwatch(id));
+    /// ```
+    #[inline]
+    pub fn watch<R, F>(&self, range: R, callback: F) -> usize
+    where
+        R: RangeBounds<K>,
+        F: Fn(&WatchEvent<'_, K, V>) + Send + Sync + 'static,
+    {
+        let watcher = Watcher {
+            id: WATCHER_ID.fetch_add(1, Relaxed),
+            range: (range.start_bound().cloned(), range.end_bound().cloned()),
+            callback: Arc::new(callback),
+        };
+        self.update_watchers(|watchers| {
+            let mut new_watchers = watchers.to_vec();
+            new_watchers.push(watcher.clone());
+            Some(new_watchers)
+        });
+        watcher.id
+    }
+
+    /// Unregisters the callback associated with the identifier.
+    ///
+    /// Returns `false` if no callback is associated with the identifier. The callback may still
+    /// be invoked by threads that started notifying it before it was unregistered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let id = treeindex.watch(.., |_| ());
+    /// assert!(treeindex.unwatch(id));
+    /// assert!(!treeindex.unwatch(id));
+    /// ```
+    #[inline]
+    pub fn unwatch(&self, id: usize) -> bool {
+        self.update_watchers(|watchers| {
+            watchers
+                .iter()
+                .any(|w| w.id == id)
+                .then(|| watchers.iter().filter(|w| w.id != id).cloned().collect())
+        })
+    }
+
+    /// Returns a guarded reference to the value for the specified key without acquiring locks.
+    ///
+    /// Returns `None` if the key does not exist. The returned reference can survive as long as the
+    /// associated [`Guard`] is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// assert!(treeindex.peek(&1, &guard).is_none());
+    /// ```
+    #[inline]
+    pub fn peek<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<&'g V>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        if let Some(root_ref) = self.root.load(Acquire, guard).as_ref() {
+            return root_ref.search(key, guard);
+        }
+        None
+    }
+
+    /// Returns guarded references to the stored key and the value for the specified key without
+    /// acquiring locks.
+    ///
+    /// Returns `None` if the key does not exist. The returned references can survive as long as
+    /// the associated [`Guard`] is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<String, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert("1".to_string(), 1).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// let (k, v) = treeindex.peek_entry("1", &guard).unwrap();
+    /// assert_eq!(k, "1");
+    /// assert_eq!(*v, 1);
+    /// assert!(treeindex.peek_entry("2", &guard).is_none());
+    /// ```
+    #[inline]
+    pub fn peek_entry<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<(&'g K, &'g V)>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        if let Some(root_ref) = self.root.load(Acquire, guard).as_ref() {
+            return root_ref.search_entry(key, guard);
+        }
+        None
+    }
+
+    /// Peeks a key-value pair without acquiring locks.
+    ///
+    /// Returns `None` if the key does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert!(treeindex.peek_with(&1, |k, v| *v).is_none());
+    /// ```
+    #[inline]
+    pub fn peek_with<Q, R, F: FnOnce(&Q, &V) -> R>(&self, key: &Q, reader: F) -> Option<R>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        let guard = Guard::new();
+        self.peek(key, &guard).map(|v| reader(key, v))
+    }
+
+    /// Reads a key-value pair under the supplied [`Guard`] without acquiring locks.
+    ///
+    /// Returns `None` if the key does not exist. The references passed to `reader` can survive as
+    /// long as the [`Guard`] is alive, therefore `reader` is allowed to return them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, String> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, "one".to_string()).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// let val: &str = treeindex.read_with(&1, |_, v| v.as_str(), &guard).unwrap();
+    /// assert_eq!(val, "one");
+    /// assert!(treeindex.read_with(&2, |_, v| v.len(), &guard).is_none());
+    /// ```
+    #[inline]
+    pub fn read_with<'g, Q, R, F: FnOnce(&'g K, &'g V) -> R>(
+        &self,
+        key: &Q,
+        reader: F,
+        guard: &'g Guard,
+    ) -> Option<R>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.peek_entry(key, guard).map(|(k, v)| reader(k, v))
+    }
+
+    /// Returns `true` if the [`TreeIndex`] contains the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::default();
+    ///
+    /// assert!(!treeindex.contains(&1));
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// assert!(treeindex.contains(&1));
+    /// ```
+    #[inline]
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.peek(key, &Guard::new()).is_some()
+    }
+
+    /// Clears the [`TreeIndex`].
+    ///
+    /// The whole tree is detached at once, and then callbacks registered with
+    /// [`TreeIndex::watch`] are notified by a single [`WatchEvent::RangeRemoved`] event without
+    /// visiting the entries in the detached tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// treeindex.clear();
+    /// assert_eq!(treeindex.len(), 0);
+    /// ```
+    #[inline]
+    pub fn clear(&self) {
+        self.detach_count.fetch_add(1, AcqRel);
+        if self.root.swap((None, Tag::None), AcqRel).0.is_some() {
+            self.notify(&WatchEvent::RangeRemoved(Unbounded, Unbounded));
+        }
+    }
+
+    /// Returns the size of the [`TreeIndex`].
+    ///
+    /// It internally sums up the number of entries in each leaf without visiting the entries, and
+    /// therefore the time complexity is proportional to the number of leaves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert_eq!(treeindex.len(), 0);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        let guard = Guard::new();
+        let Some(leaf) = self.min_leaf(&guard) else {
+            return 0;
+        };
+        let mut len = 0;
+        let _: Option<()> = Self::walk_leaves(leaf, None, &guard, |visit| {
+            match visit {
+                LeafVisit::Leaf(leaf) => len += leaf.len(),
+                LeafVisit::Key(..) => len += 1,
+            }
+            ControlFlow::Continue(true)
+        });
+        len
+    }
+
+    /// Returns the number of entries in the specified range.
+    ///
+    /// Leaves that are entirely contained in the range are counted without visiting the
+    /// entries, and therefore only the leaves on the range border are scanned entry by entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// for k in 0..64 {
+    ///     assert!(treeindex.insert(k, 0).is_ok());
+    /// }
+    ///
+    /// assert_eq!(treeindex.count_range(..), 64);
+    /// assert_eq!(treeindex.count_range(4..=8), 5);
+    /// assert_eq!(treeindex.count_range(60..), 4);
+    /// assert_eq!(treeindex.count_range(70..), 0);
+    /// ```
+    #[inline]
+    pub fn count_range<R: RangeBounds<K>>(&self, range: R) -> usize {
+        let guard = Guard::new();
+        let Some(mut scanner) = self.range_start(range.start_bound(), &guard) else {
+            return 0;
+        };
+        let contains = |key: &K| match range.end_bound() {
+            Included(end) => key <= end,
+            Excluded(end) => key < end,
+            Unbounded => true,
+        };
+
+        // The first leaf is scanned from the first entry in the range.
+        let mut count = 0;
+        let mut prev_max_key = None;
+        while let Some((k, _)) = scanner.get() {
+            if !contains(k) {
+                return count;
+            }
+            count += 1;
+            prev_max_key.replace(k);
+            scanner.next();
+        }
+
+        let Some(next_leaf) = scanner.leaf().next_ptr(Acquire, &guard).as_ref() else {
+            return count;
+        };
+        let _: Option<()> = Self::walk_leaves(next_leaf, prev_max_key, &guard, |visit| {
+            match visit {
+                LeafVisit::Leaf(leaf) => {
+                    // Only leaves that are entirely contained in the range are counted as a whole.
+                    if !leaf.max_key().map_or(false, contains) {
+                        return ControlFlow::Continue(false);
+                    }
+                    count += leaf.len();
+                }
+                LeafVisit::Key(k) => {
+                    if !contains(k) {
+                        return ControlFlow::Break(());
+                    }
+                    count += 1;
+                }
+            }
+            ControlFlow::Continue(true)
+        });
+        count
+    }
+
+    /// Returns randomly chosen entries.
+    ///
+    /// The tree is descended from the root by choosing a random child at each level, and then a
+    /// random entry is chosen in the leaf reached, therefore the entries are approximately
+    /// uniformly distributed as long as the leaves are evenly filled. `rng` is supplied with the
+    /// number of candidates, and is expected to return a random number less than it. Entries may
+    /// be returned more than once, and no entries are returned if the [`TreeIndex`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// assert!(treeindex.sample(4, |n| n / 2, &guard).is_empty());
+    ///
+    /// for k in 0..1024 {
+    ///     assert!(treeindex.insert(k, 0).is_ok());
+    /// }
+    ///
+    /// let samples = treeindex.sample(4, |n| n / 2, &guard);
+    /// assert_eq!(samples.len(), 4);
+    /// assert!(samples.iter().all(|(k, v)| **k < 1024 && **v == 0));
+    /// ```
+    #[inline]
+    pub fn sample<'g, R: FnMut(usize) -> usize>(
+        &self,
+        n: usize,
+        mut rng: R,
+        guard: &'g Guard,
+    ) -> Vec<(&'g K, &'g V)> {
+        let mut samples = Vec::with_capacity(n);
+        while samples.len() < n {
+            let Some(scanner) = self
+                .root
+                .load(Acquire, guard)
+                .as_ref()
+                .and_then(|root_ref| root_ref.random(&mut rng, guard))
+            else {
+                break;
+            };
+            let len = scanner.leaf().len();
+            let entry = if len == 0 {
+                // All the entries in the leaf have been removed, so take the next one.
+                Self::first_entry(scanner, guard)
+                    .or_else(|| self.range_start(Unbounded, guard))
+                    .and_then(|scanner| scanner.get())
+            } else {
+                Scanner::new(scanner.leaf()).nth(rng(len) % len)
+            };
+            if let Some(entry) = entry {
+                samples.push(entry);
+            } else {
+                break;
+            }
+        }
+        samples
+    }
+
+    /// Folds the entries in the specified range into a single value in ascending key order.
+    ///
+    /// Leaves are scanned directly, so that aggregates such as the minimum, maximum, or sum of
+    /// the values in a range are computed without creating an iterator. Entries that are inserted
+    /// or removed during the operation may or may not be visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// for k in 0..16 {
+    ///     assert!(treeindex.insert(k, 16 - k as u32).is_ok());
+    /// }
+    ///
+    /// assert_eq!(treeindex.reduce_range(4..8, 0, |sum, _, v| sum + v), 12 + 11 + 10 + 9);
+    /// assert_eq!(treeindex.reduce_range(4.., u32::MAX, |min, _, v| min.min(*v)), 1);
+    /// assert_eq!(treeindex.reduce_range(16.., 0, |max, _, v| max.max(*v)), 0);
+    /// ```
+    #[inline]
+    pub fn reduce_range<R: RangeBounds<K>, B, F: FnMut(B, &K, &V) -> B>(
+        &self,
+        range: R,
+        init: B,
+        mut reducer: F,
+    ) -> B {
+        let guard = Guard::new();
+        let Some(mut scanner) = self.range_start(range.start_bound(), &guard) else {
+            return init;
+        };
+        let contains = |key: &K| match range.end_bound() {
+            Included(end) => key <= end,
+            Excluded(end) => key < end,
+            Unbounded => true,
+        };
+
+        let mut accumulated = init;
+        let mut prev_max_key = None;
+        while let Some((k, v)) = scanner.get() {
+            if !contains(k) {
+                return accumulated;
+            }
+            accumulated = reducer(accumulated, k, v);
+            prev_max_key.replace(k);
+            scanner.next();
+        }
+
+        let mut leaf = scanner.leaf();
+        while let Some(next_leaf) = leaf.next_ptr(Acquire, &guard).as_ref() {
+            leaf = next_leaf;
+            for (k, v) in Scanner::new(leaf) {
+                // A split leaf may contain entries that have been visited.
+                if prev_max_key.map_or(false, |prev_max_key| k <= prev_max_key) {
+                    continue;
+                }
+                if !contains(k) {
+                    return accumulated;
+                }
+                accumulated = reducer(accumulated, k, v);
+                prev_max_key.replace(k);
+            }
+        }
+        accumulated
+    }
+
+    /// Returns `true` if the [`TreeIndex`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        let guard = Guard::new();
+        !self.iter(&guard).any(|_| true)
+    }
+
+    /// Returns the depth of the [`TreeIndex`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert_eq!(treeindex.depth(), 0);
+    /// ```
+    #[inline]
+    pub fn depth(&self) -> usize {
+        let guard = Guard::new();
+        self.root
+            .load(Acquire, &guard)
+            .as_ref()
+            .map_or(0, |root_ref| root_ref.depth(1, &guard))
+    }
+
+    /// Returns the tree shape [`Statistics`] of the [`TreeIndex`].
+    ///
+    /// It internally visits all the nodes and leaves, and therefore the time complexity is
+    /// proportional to the number of leaves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// for k in 0..1024 {
+    ///     assert!(treeindex.insert(k, 0).is_ok());
+    /// }
+    ///
+    /// let statistics = treeindex.statistics();
+    /// assert_eq!(statistics.depth(), treeindex.depth());
+    /// assert_eq!(statistics.num_entries(), 1024);
+    /// assert_eq!(statistics.num_retired_leaves(), 0);
+    /// ```
+    #[inline]
+    pub fn statistics(&self) -> Statistics {
+        let guard = Guard::new();
+        let mut statistics = Statistics {
+            num_nodes: Vec::new(),
+            leaf_occupancy: vec![0; leaf::DIMENSION.num_entries + 1],
+            retired_leaves: 0,
+        };
+        if let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
+            root_ref.collect_statistics(0, &mut statistics, &guard);
+        }
+        statistics
+    }
+
+    /// Returns an [`Iter`].
+    ///
+    /// The returned [`Iter`] starts scanning from the minimum key-value pair. Key-value pairs
+    /// are scanned in ascending order, and key-value pairs that have existed since the invocation
+    /// of the method are guaranteed to be visited if they are not removed. However, it is possible
+    /// to visit removed key-value pairs momentarily.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// let mut iter = treeindex.iter(&guard);
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[inline]
+    pub fn iter<'t, 'g>(&'t self, guard: &'g Guard) -> Iter<'t, 'g, K, V> {
+        Iter::new(&self.root, guard)
+    }
+
+    /// Returns a [`Range`] that scans keys in the given range.
+    ///
+    /// Key-value pairs in the range are scanned in ascending order, and key-value pairs that have
+    /// existed since the invocation of the method are guaranteed to be visited if they are not
+    /// removed. However, it is possible to visit removed key-value pairs momentarily.
+    ///
+    /// The bounds of the range can be borrowed forms of the key type, e.g.,
+    /// `(Bound<&str>, Bound<&str>)` for `String` keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    /// use std::ops::Bound::{Excluded, Included};
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// assert_eq!(treeindex.range(4..=8, &guard).count(), 0);
+    ///
+    /// let treeindex: TreeIndex<String, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert("a".to_string(), 0).is_ok());
+    /// assert!(treeindex.insert("b".to_string(), 1).is_ok());
+    /// assert!(treeindex.insert("c".to_string(), 2).is_ok());
+    ///
+    /// let range = (Excluded("a"), Included("c"));
+    /// assert_eq!(treeindex.range::<str, _>(range, &guard).count(), 2);
+    /// ```
+    #[inline]
+    pub fn range<'t, 'g, Q, R>(&'t self, range: R, guard: &'g Guard) -> Range<'t, 'g, K, V, R, Q>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range::new(&self.root, range, guard)
+    }
+
+    /// Returns a [`RangeStream`] over the entries in the specified range.
+    ///
+    /// The [`RangeStream`] yields clones of the entries in ascending key order, and yields control
+    /// to the async executor whenever it crosses a leaf boundary. Entries that are inserted or
+    /// removed during the scan may or may not be visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    /// assert!(treeindex.insert(4, 40).is_ok());
+    /// assert!(treeindex.insert(9, 90).is_ok());
+    ///
+    /// let mut stream = treeindex.range_async(2..=9);
+    /// let future_next = stream.next();
+    /// ```
+    #[inline]
+    pub fn range_async<R: RangeBounds<K>>(&self, range: R) -> RangeStream<'_, K, V> {
+        RangeStream {
+            tree_index: self,
+            lower_bound: range.start_bound().cloned(),
+            upper_bound: range.end_bound().cloned(),
+            buffer: Vec::new().into_iter(),
+            yield_now: false,
+            finished: false,
+        }
+    }
+
+    /// Scans the entries in the specified range until the visitor breaks.
+    ///
+    /// The visitor is invoked for each entry in ascending key order; returns the value the
+    /// visitor broke with, or `None` if the visitor never broke.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    /// assert!(treeindex.insert(4, 41).is_ok());
+    /// assert!(treeindex.insert(9, 91).is_ok());
+    ///
+    /// let first_odd = treeindex.scan(2.., |k, v| {
+    ///     if *v % 2 == 1 {
+    ///         ControlFlow::Break(*k)
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// });
+    /// assert_eq!(first_odd, Some(4));
+    /// assert_eq!(treeindex.scan(..4, |_, v| ControlFlow::Break(*v)), Some(10));
+    /// assert_eq!(treeindex.scan(10.., |_, v| ControlFlow::Break(*v)), None);
+    /// ```
+    #[inline]
+    pub fn scan<R: RangeBounds<K>, B, F: FnMut(&K, &V) -> ControlFlow<B>>(
+        &self,
+        range: R,
+        mut visitor: F,
+    ) -> Option<B> {
+        let guard = Guard::new();
+        for (k, v) in self.range(range, &guard) {
+            if let ControlFlow::Break(result) = visitor(k, v) {
+                return Some(result);
+            }
+        }
+        None
+    }
+
+    /// Scans the entries in the specified range in parallel.
+    ///
+    /// The range is split at the keys stored in the upper levels of the tree, and the sub-ranges
+    /// are visited on the `rayon` thread pool; entries in each sub-range are visited in ascending
+    /// key order, but the order across sub-ranges is unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use std::sync::atomic::AtomicU64;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// let treeindex: TreeIndex<u64, u64> = TreeIndex::new();
+    ///
+    /// for k in 0..1024 {
+    ///     assert!(treeindex.insert(k, k).is_ok());
+    /// }
+    ///
+    /// let sum = AtomicU64::new(0);
+    /// treeindex.par_range(8..16, |_, v| {
+    ///     sum.fetch_add(*v, Relaxed);
+    /// });
+    /// assert_eq!(sum.load(Relaxed), (8..16).sum());
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn par_range<R, F>(&self, range: R, visitor: F)
+    where
+        K: Sync,
+        V: Sync,
+        R: RangeBounds<K>,
+        F: Fn(&K, &V) + Send + Sync,
+    {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        // Each sub-tree below the top two levels roughly corresponds to a leaf node.
+        const SPLIT_DEPTH: usize = 2;
+
+        let guard = Guard::new();
+        let mut separators = Vec::new();
+        if let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
+            root_ref.collect_separators(SPLIT_DEPTH, &mut separators, &guard);
+        }
+        // Concurrent structural changes may render the separators out of order.
+        separators.sort_unstable();
+        separators.dedup();
+        separators.retain(|key| range.contains(*key));
+
+        let mut sub_ranges = Vec::with_capacity(separators.len() + 1);
+        let mut lower_bound = range.start_bound();
+        for key in separators {
+            sub_ranges.push((lower_bound, Included(key)));
+            lower_bound = Excluded(key);
+        }
+        sub_ranges.push((lower_bound, range.end_bound()));
+
+        sub_ranges.into_par_iter().for_each(|sub_range| {
+            let guard = Guard::new();
+            for (k, v) in self.range(sub_range, &guard) {
+                visitor(k, v);
+            }
+        });
+    }
+
+    /// Returns a [`Prefix`] that iterates over the entries having keys starting with the given
+    /// byte prefix.
+    ///
+    /// The order of the keys must be consistent with the lexicographical order of their byte
+    /// representations, e.g., [`String`] or [`Vec<u8>`], so that the iteration stops at the first
+    /// key without the prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<String, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert("user/1".to_string(), 1).is_ok());
+    /// assert!(treeindex.insert("user/2".to_string(), 2).is_ok());
+    /// assert!(treeindex.insert("user0".to_string(), 3).is_ok());
+    /// assert!(treeindex.insert("group/1".to_string(), 4).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// let mut prefix = treeindex.scan_prefix("user/", &guard);
+    /// assert_eq!(prefix.next(), Some((&"user/1".to_string(), &1)));
+    /// assert_eq!(prefix.next(), Some((&"user/2".to_string(), &2)));
+    /// assert!(prefix.next().is_none());
+    /// ```
+    #[inline]
+    pub fn scan_prefix<'g, 'p, Q>(&self, prefix: &'p Q, guard: &'g Guard) -> Prefix<'g, 'p, K, V, Q>
+    where
+        K: 'g + Borrow<Q>,
+        Q: AsRef<[u8]> + Ord + ?Sized,
+    {
+        Prefix {
+            leaf_scanner: Self::min_greater(&self.root, prefix, true, guard),
+            bytes: prefix,
+            first: true,
+            guard,
+        }
+    }
+
+    /// Returns a [`Cursor`] pointing to the first entry with a key greater than or equal to the
+    /// given key.
+    ///
+    /// The [`Cursor`] points to the ghost position if no such entry exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    /// assert!(treeindex.insert(3, 30).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// assert_eq!(treeindex.lower_bound(&1, &guard).get(), Some((&1, &10)));
+    /// assert_eq!(treeindex.lower_bound(&2, &guard).get(), Some((&3, &30)));
+    /// assert!(treeindex.lower_bound(&4, &guard).get().is_none());
+    /// ```
+    #[inline]
+    pub fn lower_bound<'t, 'g, Q>(&'t self, key: &Q, guard: &'g Guard) -> Cursor<'t, 'g, K, V>
+    where
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
+    {
+        Cursor {
+            tree_index: self,
+            leaf_scanner: Self::min_greater(&self.root, key, true, guard),
+            guard,
+        }
+    }
+
+    /// Returns a [`Cursor`] pointing to the first entry with a key greater than the given key.
+    ///
+    /// The [`Cursor`] points to the ghost position if no such entry exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    /// assert!(treeindex.insert(3, 30).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// assert_eq!(treeindex.upper_bound(&1, &guard).get(), Some((&3, &30)));
+    /// assert!(treeindex.upper_bound(&3, &guard).get().is_none());
+    /// ```
+    #[inline]
+    pub fn upper_bound<'t, 'g, Q>(&'t self, key: &Q, guard: &'g Guard) -> Cursor<'t, 'g, K, V>
+    where
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
+    {
+        Cursor {
+            tree_index: self,
+            leaf_scanner: Self::min_greater(&self.root, key, false, guard),
+            guard,
+        }
+    }
+
+    /// Returns a [`Scanner`] pointing to the first entry with a key greater than the given key,
+    /// or greater than or equal to the given key if `inclusive` is `true`.
+    fn min_greater<'g, Q>(
+        root: &AtomicShared<Node<K, V>>,
+        key: &Q,
+        inclusive: bool,
+        guard: &'g Guard,
+    ) -> Option<Scanner<'g, K, V>>
+    where
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
+    {
+        let root_ref = root.load(Acquire, guard).as_ref()?;
+        let mut scanner = if let Some(scanner) = root_ref.max_le_appr(key, guard) {
+            scanner
+        } else {
+            Self::first_entry(root_ref.min(guard)?, guard)?
+        };
+        loop {
+            let current_key = scanner.get().map(|(k, _)| k);
+            if let Some(k) = current_key {
+                match key.compare(k).reverse() {
+                    Ordering::Greater => return Some(scanner),
+                    Ordering::Equal if inclusive => return Some(scanner),
+                    _ => (),
+                }
+            }
+            if scanner.next().is_none() {
+                scanner = scanner.jump::<K>(current_key, guard)?;
+            }
+        }
+    }
+
+    /// Returns a [`Scanner`] pointing to the last entry with a key less than the given key, or
+    /// the last entry in the [`TreeIndex`] if no key is given.
+    fn max_less<'g>(&self, key: Option<&K>, guard: &'g Guard) -> Option<Scanner<'g, K, V>> {
+        let root_ref = self.root.load(Acquire, guard).as_ref()?;
+        let mut scanner = if let Some(key) = key {
+            root_ref.max_le_appr(key, guard)?
+        } else {
+            let mut max_scanner = root_ref.max(guard)?;
+            if max_scanner.next().is_some() {
+                max_scanner
+            } else {
+                // All the entries in the last leaf have been removed.
+                Self::first_entry(root_ref.min(guard)?, guard)?
+            }
+        };
+        let mut result = None;
+        loop {
+            let current_key = scanner.get().map(|(k, _)| k);
+            if let Some(k) = current_key {
+                if key.map_or(false, |key| k.cmp(key) != Ordering::Less) {
+                    return result;
+                }
+                result.replace(scanner.clone());
+            }
+            if scanner.next().is_none() {
+                if let Some(next_scanner) = scanner.jump(current_key, guard) {
+                    scanner = next_scanner;
+                } else {
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Links the tree of `other` to the tree of `self` if their key ranges do not overlap.
+    ///
+    /// Returns `false` if the key ranges overlap, or the trees cannot be linked.
+    fn graft(&mut self, other: &mut Self) -> bool {
+        let guard = Guard::new();
+        let Some(other_min) = other
+            .range_start(Unbounded, &guard)
+            .and_then(|s| s.get().map(|(k, _)| k))
+        else {
+            other.clear();
+            return true;
+        };
+        let Some(other_max) = other
+            .max_less(None, &guard)
+            .and_then(|s| s.get().map(|(k, _)| k))
+        else {
+            return false;
+        };
+        let (root, moved) = if let Some((self_min, self_max)) = self
+            .range_start(Unbounded, &guard)
+            .and_then(|s| s.get().map(|(k, _)| k))
+            .zip(
+                self.max_less(None, &guard)
+                    .and_then(|s| s.get().map(|(k, _)| k)),
+            ) {
+            let (left, right, max_key, moved) = if self_max < other_min {
+                (
+                    &self.root,
+                    &other.root,
+                    self_max,
+                    (Excluded(self_max), Unbounded),
+                )
+            } else if other_max < self_min {
+                (
+                    &other.root,
+                    &self.root,
+                    other_max,
+                    (Unbounded, Included(other_max)),
+                )
+            } else {
+                return false;
+            };
+            let linked = left
+                .load(Acquire, &guard)
+                .as_ref()
+                .zip(right.load(Acquire, &guard).as_ref())
+                .map_or(false, |(l, r)| l.link_leaves(r, &guard));
+            if !linked {
+                return false;
+            }
+            let (Some(left_root), Some(right_root)) = (
+                left.swap((None, Tag::None), Relaxed).0,
+                right.swap((None, Tag::None), Relaxed).0,
+            ) else {
+                unreachable!()
+            };
+            (Node::graft(left_root, right_root, max_key, &guard), moved)
+        } else {
+            let Some(root) = other.root.swap((None, Tag::None), Relaxed).0 else {
+                unreachable!()
+            };
+            (root, (Unbounded, Unbounded))
+        };
+        if let Some(old_root) = self.root.swap((Some(root), Tag::None), AcqRel).0 {
+            let _: bool = old_root.release(&guard);
+        }
+
+        other.notify(&WatchEvent::RangeRemoved(Unbounded, Unbounded));
+        if !self.watchers.is_null(Acquire) {
+            for (k, v) in self.range(moved, &guard) {
+                self.notify(&WatchEvent::Inserted(k, v));
+            }
+        }
+        true
+    }
+
+    /// Loads entries into the empty [`TreeIndex`] while the keys are in ascending order and less
+    /// than `upper_bound`, and returns the first entry that is not loaded.
+    ///
+    /// The entries are packed into new leaves, and the leaves are attached to the tree without
+    /// searching the tree for each entry. If a key appears more than once in a row, the last value
+    /// is kept.
+    fn bulk_load<I: Iterator<Item = (K, V)>>(
+        &mut self,
+        first: (K, V),
+        iter: &mut I,
+        upper_bound: Option<&K>,
+    ) -> Option<(K, V)> {
+        let guard = Guard::new();
+        let mut leaf_node = Shared::new(Node::new_leaf_node());
+        let mut leaf = Shared::new(Leaf::new());
+        let (mut last_key, mut last_val) = first;
+        let rest = loop {
+            match iter.next() {
+                Some((key, val)) if key == last_key => last_val = val,
+                Some((key, val))
+                    if key > last_key && upper_bound.map_or(true, |bound| key < *bound) =>
+                {
+                    let key = replace(&mut last_key, key);
+                    let val = replace(&mut last_val, val);
+                    if let InsertResult::Full(key, val) = leaf.insert(key, val) {
+                        let full_leaf = replace(&mut leaf, Shared::new(Leaf::new()));
+                        self.push_leaf(&mut leaf_node, full_leaf, &guard);
+                        let result = leaf.insert(key, val);
+                        debug_assert!(matches!(result, InsertResult::Success(..)));
+                    }
+                }
+                rest => break rest,
+            }
+        };
+        if let InsertResult::Full(key, val) = leaf.insert(last_key, last_val) {
+            let full_leaf = replace(&mut leaf, Shared::new(Leaf::new()));
+            self.push_leaf(&mut leaf_node, full_leaf, &guard);
+            let result = leaf.insert(key, val);
+            debug_assert!(matches!(result, InsertResult::Success(..)));
+        }
+        self.push_leaf(&mut leaf_node, leaf, &guard);
+        self.attach(leaf_node, &guard);
+        rest
+    }
+
+    /// Pushes a full [`Leaf`] to the [`LeafNode`] being built by [`TreeIndex::bulk_load`], and
+    /// attaches the [`LeafNode`] to the tree if it cannot accommodate the [`Leaf`].
+    fn push_leaf(
+        &self,
+        leaf_node: &mut Shared<Node<K, V>>,
+        leaf: Shared<Leaf<K, V>>,
+        guard: &Guard,
+    ) {
+        if let Err(leaf) = leaf_node.push_leaf(leaf, guard) {
+            let full_leaf_node = replace(leaf_node, Shared::new(Node::new_leaf_node()));
+            self.attach(full_leaf_node, guard);
+            let result = leaf_node.push_leaf(leaf, guard);
+            debug_assert!(result.is_ok());
+        }
+    }
+
+    /// Attaches a [`LeafNode`] containing keys greater than those in the tree to the tree.
+    fn attach(&self, leaf_node: Shared<Node<K, V>>, guard: &Guard) {
+        let root = if let Some(root) = self.root.swap((None, Tag::None), Relaxed).0 {
+            let max_key = root.max(guard).and_then(|s| s.max_key()).unwrap().clone();
+            let linked = root.link_leaves(&leaf_node, guard);
+            debug_assert!(linked);
+            Node::graft(root, leaf_node, &max_key, guard)
+        } else {
+            leaf_node
+        };
+        self.root.swap((Some(root), Tag::None), Relaxed);
+    }
+
+    /// Returns the [`Leaf`] containing the minimum key.
+    fn min_leaf<'g>(&self, guard: &'g Guard) -> Option<&'g Leaf<K, V>> {
+        self.root
+            .load(Acquire, guard)
+            .as_ref()
+            .and_then(|root_ref| root_ref.min(guard))
+            .map(|scanner| scanner.leaf())
+    }
+
+    /// Visits the leaves in ascending key order starting from `leaf`.
+    ///
+    /// `visitor` is given each [`Leaf`] that does not overlap with the keys visited so far, and
+    /// returns `true` if the [`Leaf`] is accounted as a whole; otherwise, or if the [`Leaf`] may
+    /// contain entries that have been visited due to a concurrent split or merge, `visitor` is
+    /// given the key of each entry greater than `prev_max_key`. The walk stops when `visitor`
+    /// returns [`ControlFlow::Break`], and the value is returned.
+    fn walk_leaves<'g, B, F>(
+        mut leaf: &'g Leaf<K, V>,
+        mut prev_max_key: Option<&'g K>,
+        guard: &'g Guard,
+        mut visitor: F,
+    ) -> Option<B>
+    where
+        F: FnMut(LeafVisit<'g, K, V>) -> ControlFlow<B, bool>,
+    {
+        loop {
+            let overlaps = prev_max_key.map_or(false, |prev_max_key| {
+                Scanner::new(leaf)
+                    .next()
+                    .map_or(false, |(min_key, _)| min_key <= prev_max_key)
+            });
+            let as_whole = !overlaps
+                && match visitor(LeafVisit::Leaf(leaf)) {
+                    ControlFlow::Continue(as_whole) => as_whole,
+                    ControlFlow::Break(result) => return Some(result),
+                };
+            if as_whole {
+                if let Some(max_key) = leaf.max_key() {
+                    if prev_max_key.map_or(true, |prev_max_key| max_key > prev_max_key) {
+                        prev_max_key.replace(max_key);
+                    }
+                }
+            } else {
+                for (k, _) in Scanner::new(leaf) {
+                    if prev_max_key.map_or(false, |prev_max_key| k <= prev_max_key) {
+                        continue;
+                    }
+                    if let ControlFlow::Break(result) = visitor(LeafVisit::Key(k)) {
+                        return Some(result);
+                    }
+                    prev_max_key.replace(k);
+                }
+            }
+            leaf = leaf.next_ptr(Acquire, guard).as_ref()?;
+        }
+    }
+
+    /// Returns a [`Scanner`] pointing to the first entry satisfying the start bound.
+    fn range_start<'g>(&self, start: Bound<&K>, guard: &'g Guard) -> Option<Scanner<'g, K, V>> {
+        match start {
+            Included(key) => Self::min_greater(&self.root, key, true, guard),
+            Excluded(key) => Self::min_greater(&self.root, key, false, guard),
+            Unbounded => self
+                .root
+                .load(Acquire, guard)
+                .as_ref()
+                .and_then(|root_ref| root_ref.min(guard))
+                .and_then(|scanner| Self::first_entry(scanner, guard)),
+        }
+    }
+
+    /// Moves the [`Scanner`] to the first valid entry.
+    fn first_entry<'g>(
+        mut scanner: Scanner<'g, K, V>,
+        guard: &'g Guard,
+    ) -> Option<Scanner<'g, K, V>> {
+        // It's possible that the leaf has just been emptied, so go to the next.
+        scanner.next();
+        while scanner.get().is_none() {
+            scanner = scanner.jump::<K>(None, guard)?;
+        }
+        Some(scanner)
+    }
+
+    /// Removes the entries in the range satisfying the predicate, and passes the removed entries
+    /// to `removed`.
+    ///
+    /// Entries are directly removed from the [`Leaf`] unless the [`Leaf`] is frozen or the entry
+    /// is the last one in the [`Leaf`]; those entries are removed through [`TreeIndex::remove_if`],
+    /// since an emptied [`Leaf`] has to be cleaned up by its parent node. The predicate is
+    /// evaluated once for each entry, and an entry is only removed if it has not been replaced
+    /// after the predicate was evaluated.
+    fn remove_entries<B: RangeBounds<K>, P: FnMut(&K, &V) -> bool, R: FnMut(&K, &V)>(
+        &self,
+        range: &B,
+        mut pred: P,
+        mut removed: R,
+    ) {
+        let guard = Guard::new();
+        let Some(mut leaf) = self
+            .range_start(range.start_bound(), &guard)
+            .map(|scanner| scanner.leaf())
+        else {
+            return;
+        };
+        loop {
+            for (k, v) in Scanner::new(leaf) {
+                if !range.contains(k) {
+                    match range.end_bound() {
+                        Included(key) if k.cmp(key) == Ordering::Greater => return,
+                        Excluded(key) if k.cmp(key) != Ordering::Less => return,
+                        _ => continue,
+                    }
+                }
+                if !pred(k, v) {
+                    continue;
+                }
+                let mut unchanged = |current: &V| ptr::eq(current, v);
+                match leaf.remove_unless_last(k, &mut unchanged) {
+                    RemoveResult::Success => {
+                        self.notify(&WatchEvent::Removed(k, v));
+                        removed(k, v);
+                    }
+                    RemoveResult::Fail => (),
+                    RemoveResult::Frozen => {
+                        if self.remove_if(k, unchanged) {
+                            removed(k, v);
+                        }
+                    }
+                    RemoveResult::Cleanup | RemoveResult::Retired => unreachable!(),
+                }
+            }
+            if let Some(next_leaf) = leaf.next_ptr(Acquire, &guard).as_ref() {
+                leaf = next_leaf;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Replaces the value associated with the key with the one returned by `updater`.
+    ///
+    /// Returns `false` if the key does not exist or `updater` returns `None`.
+    fn modify_if<Q, F: FnMut(&V) -> Option<V>>(&self, key: &Q, mut updater: F) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        let watched_key = self.watched_key(key);
+        let mut new_val = None;
+        let mut updater = |v: &V| {
+            let new_v = updater(v);
+            if watched_key.is_some() {
+                new_val.clone_from(&new_v);
+            }
+            new_v
+        };
+        let result = loop {
+            let guard = Guard::new();
+            if let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
+                match root_ref.modify(key, &mut updater, &mut (), &guard) {
+                    Ok(ModifyResult::Success) => break true,
+                    Ok(ModifyResult::Fail) => break false,
+                    Ok(ModifyResult::Frozen) => {
+                        root_ref.cleanup_link(key, false, &guard);
+                    }
+                    Ok(ModifyResult::Full(k, v)) => {
+                        Node::split_root(k, v, &self.root, &guard);
+                    }
+                    Ok(ModifyResult::Retired) => {
+                        let _result = Node::cleanup_root(&self.root, &mut (), &guard);
+                    }
+                    Err(()) => (),
+                }
+            } else {
+                break false;
+            }
+        };
+        if let (true, Some(key), Some(val)) = (result, watched_key.as_ref(), new_val.as_ref()) {
+            self.notify(&WatchEvent::Modified(key, val));
+        }
+        result
+    }
+
+    /// Replaces the value associated with the key with the one returned by `updater`.
+    ///
+    /// Returns `false` if the key does not exist or `updater` returns `None`.
+    async fn modify_if_async<Q, F: FnMut(&V) -> Option<V>>(&self, key: &Q, mut updater: F) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        let watched_key = self.watched_key(key);
+        let mut new_val = None;
+        let mut updater = |v: &V| {
+            let new_v = updater(v);
+            if watched_key.is_some() {
+                new_val.clone_from(&new_v);
+            }
+            new_v
+        };
+        let result = loop {
+            let mut async_wait = AsyncWait::default();
+            let mut async_wait_pinned = Pin::new(&mut async_wait);
+            {
+                let guard = Guard::new();
+                if let Some(root_ref) = self.root.load(Acquire, &guard).as_ref() {
+                    match root_ref.modify(key, &mut updater, &mut async_wait_pinned, &guard) {
+                        Ok(ModifyResult::Success) => break true,
+                        Ok(ModifyResult::Fail) => break false,
+                        Ok(ModifyResult::Frozen) => {
+                            root_ref.cleanup_link(key, false, &guard);
+                        }
+                        Ok(ModifyResult::Full(k, v)) => {
+                            Node::split_root(k, v, &self.root, &guard);
+                            continue;
+                        }
+                        Ok(ModifyResult::Retired) => {
+                            if Node::cleanup_root(&self.root, &mut async_wait_pinned, &guard) {
+                                continue;
+                            }
+                        }
+                        Err(()) => (),
+                    }
+                } else {
+                    break false;
+                }
+            }
+            async_wait_pinned.await;
+        };
+        if let (true, Some(key), Some(val)) = (result, watched_key.as_ref(), new_val.as_ref()) {
+            self.notify(&WatchEvent::Modified(key, val));
+        }
+        result
+    }
+
+    /// Inserts a new value or replaces the value in the [`Leaf`] found by a single descent.
+    ///
+    /// Returns the key and the value, or the constructor if it has not been invoked, if the
+    /// [`Leaf`] is full, frozen, or retired.
+    fn upsert_in_leaf<C: FnOnce() -> V, F: FnMut(&V) -> V>(
+        &self,
+        mut key: K,
+        mut val: Result<V, C>,
+        updater: &mut F,
+        guard: &Guard,
+    ) -> Result<bool, (K, Result<V, C>)> {
+        let leaf_ptr = self
+            .root
+            .load(Acquire, guard)
+            .as_ref()
+            .map_or_else(Ptr::null, |root_ref| root_ref.search_leaf(&key, guard));
+        let Some(leaf) = leaf_ptr.as_ref() else {
+            return Err((key, val));
+        };
+        let watched = self.is_watched(&key);
+        let mut new_val = None;
+        let mut updater = |v: &V| {
+            let new_v = updater(v);
+            if watched {
+                new_val = Some(new_v.clone());
+            }
+            Some(new_v)
+        };
+        loop {
+            match Leaf::modify(leaf_ptr, &key, &mut updater, guard) {
+                ModifyResult::Success => {
+                    if let Some(val) = new_val.as_ref() {
+                        self.notify(&WatchEvent::Modified(&key, val));
+                    }
+                    return Ok(false);
+                }
+                ModifyResult::Fail => (),
+                ModifyResult::Full(..) | ModifyResult::Frozen | ModifyResult::Retired => {
+                    return Err((key, val));
+                }
+            }
+            let v = match val {
+                Ok(v) => v,
+                Err(constructor) => constructor(),
+            };
+            let watched_key = watched.then(|| key.clone());
+            match leaf.insert(key, v) {
+                InsertResult::Success(val_ptr) => {
+                    if let Some(key) = watched_key.as_ref() {
+                        self.notify(&WatchEvent::Inserted(key, unsafe { &*val_ptr }));
+                    }
+                    return Ok(true);
+                }
+                InsertResult::Duplicate(k, v) => {
+                    // The key has been inserted in the meantime: retry on the same `Leaf`.
+                    key = k;
+                    val = Ok(v);
+                }
+                InsertResult::Full(k, v)
+                | InsertResult::Frozen(k, v)
+                | InsertResult::Retired(k, v)
+                | InsertResult::Retry(k, v) => return Err((k, Ok(v))),
+            }
+        }
+    }
+
+    /// Returns a clone of `new` if `current` is equal to `expected`, otherwise stores a clone of
+    /// `current` in `actual`.
+    fn exchange_if_equal(current: &V, expected: &V, new: &V, actual: &mut Option<V>) -> Option<V>
+    where
+        V: PartialEq,
+    {
+        if current == expected {
+            Some(new.clone())
+        } else {
+            actual.replace(current.clone());
+            None
+        }
+    }
+
+    /// Inserts the key-value pair, or replaces the value if the key exists.
+    fn insert_or_replace(&self, mut key: K, mut val: V) {
+        while let Err((k, v)) = self.insert(key, val) {
+            if self.modify(&k, |_| v.clone()) {
+                break;
+            }
+            // The entry has been removed in the meantime.
+            key = k;
+            val = v;
+        }
+    }
+
+    /// Replaces the registered watchers with the ones returned by `updater`.
+    ///
+    /// Returns `false` if `updater` returns `None`.
+    fn update_watchers<F: FnMut(&[Watcher<K, V>]) -> Option<Vec<Watcher<K, V>>>>(
+        &self,
+        mut updater: F,
+    ) -> bool {
+        let guard = Guard::new();
+        let mut current = self.watchers.load(Acquire, &guard);
+        loop {
+            let watchers = current.as_ref().map_or(&[][..], Vec::as_slice);
+            let Some(new_watchers) = updater(watchers) else {
+                return false;
+            };
+            let new_watchers = (!new_watchers.is_empty()).then(|| Shared::new(new_watchers));
+            match self.watchers.compare_exchange(
+                current,
+                (new_watchers, Tag::None),
+                AcqRel,
+                Acquire,
+                &guard,
+            ) {
+                Ok(_) => return true,
+                Err((_, actual)) => current = actual,
+            }
+        }
+    }
+
+    /// Returns `true` if the key is in the range of any registered watcher.
+    fn is_watched<Q>(&self, key: &Q) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        if self.watchers.is_null(Acquire) {
+            return false;
+        }
+        let guard = Guard::new();
+        self.watchers
+            .load(Acquire, &guard)
+            .as_ref()
+            .map_or(false, |watchers| watchers.iter().any(|w| w.contains(key)))
+    }
+
+    /// Returns a clone of the stored key if the key exists and is being watched.
+    fn watched_key<Q>(&self, key: &Q) -> Option<K>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        if self.watchers.is_null(Acquire) {
+            return None;
+        }
+        let guard = Guard::new();
+        self.peek_entry(key, &guard)
+            .filter(|_| self.is_watched(key))
+            .map(|(k, _)| k.clone())
+    }
+
+    /// Passes the event to the watchers of the key.
+    fn notify(&self, event: &WatchEvent<'_, K, V>) {
+        if self.watchers.is_null(Acquire) {
+            return;
+        }
+        let guard = Guard::new();
+        if let Some(watchers) = self.watchers.load(Acquire, &guard).as_ref() {
+            for watcher in watchers.iter().filter(|w| match event {
+                WatchEvent::Inserted(k, _)
+                | WatchEvent::Modified(k, _)
+                | WatchEvent::Removed(k, _) => w.contains(*k),
+                WatchEvent::RangeRemoved(start, end) => w.overlaps(*start, *end),
+            }) {
+                (watcher.callback)(event);
+            }
+        }
+    }
+
+    /// Inserts a key-value pair, and returns a reference to the inserted value.
+    ///
+    /// The supplied [`Guard`] is kept throughout the operation, so that the [`Leaf`] containing
+    /// the value cannot be reclaimed while the [`Guard`] is alive.
+    fn insert_with_guard<'g>(
+        &self,
+        mut key: K,
+        mut val: V,
+        guard: &'g Guard,
+    ) -> Result<&'g V, (K, V)> {
+        let watched_key = self.is_watched(&key).then(|| key.clone());
+        let mut new_root = None;
+        loop {
+            if let Some(root_ref) = self.root.load(Acquire, guard).as_ref() {
+                match root_ref.insert(key, val, &mut (), guard) {
+                    Ok(r) => match r {
+                        InsertResult::Success(val_ptr) => {
+                            let val = unsafe { &*val_ptr };
+                            if let Some(key) = watched_key.as_ref() {
+                                self.notify(&WatchEvent::Inserted(key, val));
+                            }
+                            return Ok(val);
+                        }
+                        InsertResult::Frozen(k, v) | InsertResult::Retry(k, v) => {
+                            key = k;
+                            val = v;
+                            root_ref.cleanup_link(&key, false, guard);
+                        }
+                        InsertResult::Duplicate(k, v) => return Err((k, v)),
+                        InsertResult::Full(k, v) => {
+                            let (k, v) = Node::split_root(k, v, &self.root, guard);
+                            key = k;
+                            val = v;
+                            continue;
+                        }
+                        InsertResult::Retired(k, v) => {
+                            key = k;
+                            val = v;
+                            let _result = Node::cleanup_root(&self.root, &mut (), guard);
+                        }
+                    },
+                    Err((k, v)) => {
+                        key = k;
+                        val = v;
+                    }
+                }
+            }
+
+            let node = if let Some(new_root) = new_root.take() {
+                new_root
+            } else {
+                Shared::new(Node::new_leaf_node())
+            };
+            if let Err((node, _)) = self.root.compare_exchange(
+                Ptr::null(),
+                (Some(node), Tag::None),
+                AcqRel,
+                Acquire,
+                guard,
+            ) {
+                new_root = node;
+            }
+        }
+    }
+}
+
+impl<K, V> Clone for TreeIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        let self_clone = Self::default();
+        for (k, v) in self.iter(&Guard::new()) {
+            let _reuslt = self_clone.insert(k.clone(), v.clone());
+        }
+        self_clone
+    }
+}
+
+impl<K, V> Debug for TreeIndex<K, V>
+where
+    K: 'static + Clone + Debug + Ord,
+    V: 'static + Clone + Debug,
+{
+    /// Formats the entries in ascending key order.
+    ///
+    /// At most `32` entries are formatted, and the number of the remaining entries is appended if
+    /// there are more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = (0..4).map(|k| (k, k as u32)).collect();
+    /// assert_eq!(format!("{treeindex:?}"), "{0: 0, 1: 1, 2: 2, 3: 3}");
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = (0..64).map(|k| (k, k as u32)).collect();
+    /// assert!(format!("{treeindex:?}").ends_with("31: 31, ..: 32 more}"));
+    /// ```
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = Guard::new();
+        let mut iter = self.iter(&guard);
+        let mut debug_map = f.debug_map();
+        debug_map.entries(iter.by_ref().take(DEBUG_MAX_ENTRIES));
+        let remaining = iter.count();
+        if remaining != 0 {
+            debug_map.entry(&format_args!(".."), &format_args!("{remaining} more"));
+        }
+        debug_map.finish()
+    }
+}
+
+impl<K, V> Default for TreeIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Creates a [`TreeIndex`] with the default parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::default();
+    /// ```
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Extend<(K, V)> for TreeIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Inserts the key-value pairs, replacing the values of existing keys.
+    ///
+    /// Leading key-value pairs in ascending key order are packed into new leaves without searching
+    /// the tree for each of them if the keys are all less than or all greater than those in the
+    /// [`TreeIndex`], and the new leaves are linked to the tree at once; the remaining key-value
+    /// pairs are inserted one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let mut treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// treeindex.extend([(1, 10), (2, 20)]);
+    /// assert_eq!(treeindex.peek_with(&1, |_, v| *v), Some(10));
+    /// assert_eq!(treeindex.len(), 2);
+    /// ```
+    #[inline]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        let mut iter = iter.into_iter();
+        let mut next = iter.next();
+        if let Some((key, _)) = next.as_ref() {
+            let guard = Guard::new();
+            let min_key = self
+                .range_start(Unbounded, &guard)
+                .and_then(|s| s.get().map(|(k, _)| k));
+            let max_key = self
+                .max_less(None, &guard)
+                .and_then(|s| s.get().map(|(k, _)| k));
+            let upper_bound = match (min_key, max_key) {
+                (Some(min_key), Some(max_key)) => {
+                    if key > max_key {
+                        Some(None)
+                    } else if key < min_key {
+                        Some(Some(min_key))
+                    } else {
+                        None
+                    }
+                }
+                _ => Some(None),
+            };
+            if let Some(upper_bound) = upper_bound {
+                let mut sorted = Self::new();
+                next = next.and_then(|first| sorted.bulk_load(first, &mut iter, upper_bound));
+                self.append(&mut sorted);
+            }
+        }
+        while let Some((key, val)) = next {
+            self.insert_or_replace(key, val);
+            next = iter.next();
+        }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for TreeIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Creates a [`TreeIndex`] from the key-value pairs.
+    ///
+    /// If a key appears more than once, the last value is kept. Leading key-value pairs in
+    /// ascending key order are packed into leaves without searching the tree for each of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = (0..16).map(|k| (k, k as u32)).collect();
+    /// assert_eq!(treeindex.len(), 16);
+    /// ```
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut tree_index = Self::new();
+        tree_index.extend(iter);
+        tree_index
+    }
+}
+
+impl<K, V> IntoIterator for TreeIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let guard = Guard::new();
+        let leaf = self
+            .range_start(Unbounded, &guard)
+            .and_then(|scanner| scanner.get().map(|(k, _)| k))
+            .and_then(|min_key| {
+                self.root
+                    .load(Acquire, &guard)
+                    .as_ref()
+                    .and_then(|root_ref| root_ref.search_leaf(min_key, &guard).get_shared())
+            });
+        if leaf.is_some() {
+            self.notify(&WatchEvent::RangeRemoved(Unbounded, Unbounded));
+        }
+
+        // The leaves are kept alive by the links between them after the nodes are dropped.
+        IntoIter {
+            leaf,
+            buffer: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<K, V> PartialEq for TreeIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone + PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        // The key order is preserved, therefore comparing iterators suffices.
+        let guard = Guard::new();
+        Iterator::eq(self.iter(&guard), other.iter(&guard))
+    }
+}
+
+impl<K, V> Watcher<K, V>
+where
+    K: Ord,
+{
+    /// Returns `true` if the key is in the range of the [`Watcher`].
+    fn contains<Q>(&self, key: &Q) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        range_contains(&self.range, key)
+    }
+
+    /// Returns `true` if the range of the [`Watcher`] overlaps with the given range.
+    fn overlaps(&self, start: Bound<&K>, end: Bound<&K>) -> bool {
+        ranges_overlap((self.range.0.as_ref(), self.range.1.as_ref()), (start, end))
+    }
+}
+
+impl<K: Clone, V> Clone for Watcher<K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            range: self.range.clone(),
+            callback: self.callback.clone(),
+        }
+    }
+}
+
+// Callbacks are invoked after the change is complete, therefore a panic in a callback cannot
+// leave the `TreeIndex` in an inconsistent state.
+impl<K: UnwindSafe, V: UnwindSafe> UnwindSafe for Watcher<K, V> {}
+
+/// Returns `true` if the key is in the range.
+fn range_contains<K, Q>(range: &(Bound<K>, Bound<K>), key: &Q) -> bool
+where
+    Q: Comparable<K> + ?Sized,
+{
+    let after_start = match &range.0 {
+        Included(k) => key.compare(k).is_ge(),
+        Excluded(k) => key.compare(k).is_gt(),
+        Unbounded => true,
+    };
+    let before_end = match &range.1 {
+        Included(k) => key.compare(k).is_le(),
+        Excluded(k) => key.compare(k).is_lt(),
+        Unbounded => true,
+    };
+    after_start && before_end
+}
+
+/// Returns `true` if the two ranges overlap.
+fn ranges_overlap<K: Ord>(a: (Bound<&K>, Bound<&K>), b: (Bound<&K>, Bound<&K>)) -> bool {
+    let ends_before = |end: Bound<&K>, start: Bound<&K>| match (end, start) {
+        (Unbounded, _) | (_, Unbounded) => false,
+        (Included(end), Included(start)) => end < start,
+        (Included(end) | Excluded(end), Included(start) | Excluded(start)) => end <= start,
+    };
+    !ends_before(a.1, b.0) && !ends_before(b.1, a.0)
+}
+
+impl Statistics {
+    /// Returns the depth of the [`TreeIndex`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert_eq!(treeindex.statistics().depth(), 0);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.num_nodes.len()
+    }
+
+    /// Returns the number of nodes at each level from the root, excluding leaves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// assert_eq!(treeindex.statistics().num_nodes(), &[1]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_nodes(&self) -> &[usize] {
+        &self.num_nodes
+    }
+
+    /// Returns the number of leaves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// assert_eq!(treeindex.statistics().num_leaves(), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_leaves(&self) -> usize {
+        self.leaf_occupancy.iter().sum()
+    }
+
+    /// Returns the number of reachable entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// assert_eq!(treeindex.statistics().num_entries(), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_entries(&self) -> usize {
+        self.leaf_occupancy
+            .iter()
+            .enumerate()
+            .map(|(len, num_leaves)| len * num_leaves)
+            .sum()
+    }
+
+    /// Returns the leaf occupancy distribution.
+    ///
+    /// The `i`-th element of the returned slice is the number of leaves containing `i` entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert!(treeindex.insert(1, 0).is_ok());
+    /// assert_eq!(treeindex.statistics().leaf_occupancy()[1], 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn leaf_occupancy(&self) -> &[usize] {
+        &self.leaf_occupancy
+    }
+
+    /// Returns the number of retired leaves that are still reachable.
+    ///
+    /// Retired leaves do not contain any usable entries, and they are removed from the tree when
+    /// the parent node is restructured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert_eq!(treeindex.statistics().num_retired_leaves(), 0);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn num_retired_leaves(&self) -> usize {
+        self.retired_leaves
+    }
+
+    /// Adds a [`Leaf`] to the statistics.
+    fn add_leaf<K, V>(&mut self, leaf: &Leaf<K, V>)
+    where
+        K: 'static + Clone + Ord,
+        V: 'static + Clone,
+    {
+        self.leaf_occupancy[leaf.len()] += 1;
+        if leaf.is_retired() {
+            self.retired_leaves += 1;
+        }
+    }
+}
+
+impl<'g, 'p, K, V, Q> Debug for Prefix<'g, 'p, K, V, Q>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+    Q: ?Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prefix")
+            .field("leaf_scanner", &self.leaf_scanner)
+            .field("first", &self.first)
+            .finish()
+    }
+}
+
+impl<'g, 'p, K, V, Q> Iterator for Prefix<'g, 'p, K, V, Q>
+where
+    K: 'static + Borrow<Q> + Clone + Ord,
+    V: 'static + Clone,
+    Q: AsRef<[u8]> + Ord + ?Sized,
+{
+    type Item = (&'g K, &'g V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut leaf_scanner = self.leaf_scanner.take()?;
+        if self.first {
+            self.first = false;
+        } else {
+            let current_key = leaf_scanner.get().map(|(k, _)| k);
+            if leaf_scanner.next().is_none() {
+                leaf_scanner = leaf_scanner.jump::<K>(current_key, self.guard)?;
+            }
+        }
+        let (k, v) = leaf_scanner.get()?;
+        if !k.borrow().as_ref().starts_with(self.bytes.as_ref()) {
+            return None;
+        }
+        self.leaf_scanner.replace(leaf_scanner);
+        Some((k, v))
+    }
+}
+
+impl<'g, 'p, K, V, Q> FusedIterator for Prefix<'g, 'p, K, V, Q>
+where
+    K: 'static + Borrow<Q> + Clone + Ord,
+    V: 'static + Clone,
+    Q: AsRef<[u8]> + Ord + ?Sized,
+{
+}
+
+impl<'g, 'p, K, V, Q> UnwindSafe for Prefix<'g, 'p, K, V, Q>
+where
+    K: 'static + Clone + Ord + UnwindSafe,
+    V: 'static + Clone + UnwindSafe,
+    Q: ?Sized,
+{
+}
+
+impl<'t, 'g, K, V> Cursor<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Returns a reference to the entry that the [`Cursor`] is pointing to.
+    ///
+    /// Returns `None` if the [`Cursor`] points to the ghost position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(2, 20).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// assert_eq!(treeindex.lower_bound(&0, &guard).get(), Some((&2, &20)));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<(&'g K, &'g V)> {
+        self.leaf_scanner.as_ref().and_then(Scanner::get)
+    }
+
+    /// Moves the [`Cursor`] to the next entry, and returns a reference to it.
+    ///
+    /// If the [`Cursor`] points to the ghost position, it moves to the first entry; if the
+    /// [`Cursor`] points to the last entry, it moves to the ghost position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    /// assert!(treeindex.insert(2, 20).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = treeindex.lower_bound(&1, &guard);
+    /// assert_eq!(cursor.move_next(), Some((&2, &20)));
+    /// assert!(cursor.move_next().is_none());
+    /// assert_eq!(cursor.move_next(), Some((&1, &10)));
+    /// ```
+    #[inline]
+    pub fn move_next(&mut self) -> Option<(&'g K, &'g V)> {
+        if let Some(mut leaf_scanner) = self.leaf_scanner.take() {
+            let current_key = leaf_scanner.get().map(|(k, _)| k);
+            if leaf_scanner.next().is_some() {
+                self.leaf_scanner.replace(leaf_scanner);
+            } else if let Some(current_key) = current_key {
+                self.leaf_scanner = leaf_scanner.jump(Some(current_key), self.guard);
+            }
+        } else {
+            let root_ref = self.tree_index.root.load(Acquire, self.guard).as_ref();
+            self.leaf_scanner = root_ref
+                .and_then(|root_ref| root_ref.min(self.guard))
+                .and_then(|scanner| TreeIndex::first_entry(scanner, self.guard));
+        }
+        self.get()
+    }
+
+    /// Moves the [`Cursor`] to the previous entry, and returns a reference to it.
+    ///
+    /// If the [`Cursor`] points to the ghost position, it moves to the last entry; if the
+    /// [`Cursor`] points to the first entry, it moves to the ghost position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    /// assert!(treeindex.insert(2, 20).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// let mut cursor = treeindex.upper_bound(&1, &guard);
+    /// assert_eq!(cursor.move_prev(), Some((&1, &10)));
+    /// assert!(cursor.move_prev().is_none());
+    /// assert_eq!(cursor.move_prev(), Some((&2, &20)));
+    /// ```
+    #[inline]
+    pub fn move_prev(&mut self) -> Option<(&'g K, &'g V)> {
+        let current_key = self.get().map(|(k, _)| k);
+        self.leaf_scanner = self.tree_index.max_less(current_key, self.guard);
+        self.get()
+    }
+}
+
+impl<'t, 'g, K, V> Debug for Cursor<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("leaf_scanner", &self.leaf_scanner)
+            .finish()
+    }
+}
+
+impl<'t, 'g, K, V> Entry<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Ensures a value is in the entry by inserting the supplied instance if empty.
+    ///
+    /// Returns a reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// treeindex.entry(3, &guard).or_insert(7);
+    /// assert_eq!(treeindex.peek_with(&3, |_, v| *v), Some(7));
+    /// ```
+    #[inline]
+    pub fn or_insert(self, val: V) -> &'g V {
+        self.or_insert_with(|| val)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the supplied closure if empty.
+    ///
+    /// Returns a reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// treeindex.entry(19, &guard).or_insert_with(|| 5);
+    /// assert_eq!(treeindex.peek_with(&19, |_, v| *v), Some(5));
+    /// ```
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, constructor: F) -> &'g V {
+        self.or_insert_with_key(|_| constructor())
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the supplied closure if empty.
+    ///
+    /// The reference to the moved key is provided, therefore cloning or copying the key is
+    /// unnecessary.
+    ///
+    /// Returns a reference to the value in the entry; if another thread inserts the key in the
+    /// meantime, the value inserted by the other thread is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// treeindex.entry(11, &guard).or_insert_with_key(|k| if *k == 11 { 7 } else { 3 });
+    /// assert_eq!(treeindex.peek_with(&11, |_, v| *v), Some(7));
+    /// ```
+    #[inline]
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, constructor: F) -> &'g V {
+        match self {
+            Self::Occupied(o) => o.get(),
+            Self::Vacant(v) => {
+                let val = constructor(v.key());
+                let (tree_index, mut key, mut val, guard) = (v.tree_index, v.key, val, v.guard);
+                loop {
+                    match tree_index.insert_with_guard(key, val, guard) {
+                        Ok(val) => return val,
+                        Err((k, v)) => {
+                            if let Some(val) = tree_index.peek(&k, guard) {
+                                return val;
+                            }
+                            // The entry has been removed in the meantime.
+                            key = k;
+                            val = v;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns a reference to the key of this entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// assert_eq!(treeindex.entry(31, &guard).key(), &31);
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Self::Occupied(o) => o.key(),
+            Self::Vacant(v) => v.key(),
+        }
     }
+}
 
-    /// Returns `true` if the [`TreeIndex`] is empty.
+impl<'t, 'g, K, V> Entry<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone + Default,
+{
+    /// Ensures a value is in the entry by inserting the default value if empty.
+    ///
+    /// Returns a reference to the value in the entry.
     ///
     /// # Examples
     ///
     /// ```
     /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
     ///
-    /// assert!(treeindex.is_empty());
+    /// let guard = Guard::new();
+    /// treeindex.entry(11, &guard).or_default();
+    /// assert_eq!(treeindex.peek_with(&11, |_, v| *v), Some(0));
     /// ```
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        let guard = Guard::new();
-        !self.iter(&guard).any(|_| true)
+    pub fn or_default(self) -> &'g V {
+        self.or_insert_with(Default::default)
     }
+}
 
-    /// Returns the depth of the [`TreeIndex`].
+impl<'t, 'g, K, V> Debug for Entry<'t, 'g, K, V>
+where
+    K: 'static + Clone + Debug + Ord,
+    V: 'static + Clone + Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Vacant(v) => f.debug_tuple("Entry").field(v).finish(),
+            Self::Occupied(o) => f.debug_tuple("Entry").field(o).finish(),
+        }
+    }
+}
+
+impl<'g, K, V> OccupiedEntry<'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Gets a reference to the key in the entry.
     ///
     /// # Examples
     ///
     /// ```
     /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    /// use scc::tree_index::Entry;
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
-    /// assert_eq!(treeindex.depth(), 0);
+    /// assert!(treeindex.insert(29, 1).is_ok());
+    ///
+    /// let guard = Guard::new();
+    /// if let Entry::Occupied(o) = treeindex.entry(29, &guard) {
+    ///     assert_eq!(o.key(), &29);
+    /// };
     /// ```
     #[inline]
-    pub fn depth(&self) -> usize {
-        let guard = Guard::new();
-        self.root
-            .load(Acquire, &guard)
-            .as_ref()
-            .map_or(0, |root_ref| root_ref.depth(1, &guard))
+    #[must_use]
+    pub fn key(&self) -> &K {
+        &self.key
     }
 
-    /// Returns an [`Iter`].
+    /// Gets a reference to the value in the entry.
     ///
-    /// The returned [`Iter`] starts scanning from the minimum key-value pair. Key-value pairs
-    /// are scanned in ascending order, and key-value pairs that have existed since the invocation
-    /// of the method are guaranteed to be visited if they are not removed. However, it is possible
-    /// to visit removed key-value pairs momentarily.
+    /// The returned reference can survive as long as the associated [`Guard`] is alive.
     ///
     /// # Examples
     ///
     /// ```
     /// use scc::TreeIndex;
     /// use scc::ebr::Guard;
+    /// use scc::tree_index::Entry;
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    /// assert!(treeindex.insert(11, 17).is_ok());
     ///
     /// let guard = Guard::new();
-    /// let mut iter = treeindex.iter(&guard);
-    /// assert!(iter.next().is_none());
+    /// if let Entry::Occupied(o) = treeindex.entry(11, &guard) {
+    ///     assert_eq!(o.get(), &17);
+    /// };
     /// ```
     #[inline]
-    pub fn iter<'t, 'g>(&'t self, guard: &'g Guard) -> Iter<'t, 'g, K, V> {
-        Iter::new(&self.root, guard)
+    #[must_use]
+    pub fn get(&self) -> &'g V {
+        self.val
     }
+}
 
-    /// Returns a [`Range`] that scans keys in the given range.
+impl<'g, K, V> Debug for OccupiedEntry<'g, K, V>
+where
+    K: 'static + Clone + Debug + Ord,
+    V: 'static + Clone + Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+impl<'t, 'g, K, V> VacantEntry<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Gets a reference to the key.
     ///
-    /// Key-value pairs in the range are scanned in ascending order, and key-value pairs that have
-    /// existed since the invocation of the method are guaranteed to be visited if they are not
-    /// removed. However, it is possible to visit removed key-value pairs momentarily.
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// assert_eq!(treeindex.entry(11, &guard).key(), &11);
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
     ///
     /// # Examples
     ///
     /// ```
     /// use scc::TreeIndex;
     /// use scc::ebr::Guard;
+    /// use scc::tree_index::Entry;
     ///
     /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
     ///
     /// let guard = Guard::new();
-    /// assert_eq!(treeindex.range(4..=8, &guard).count(), 0);
+    /// if let Entry::Vacant(v) = treeindex.entry(17, &guard) {
+    ///     assert_eq!(v.into_key(), 17);
+    /// };
     /// ```
     #[inline]
-    pub fn range<'t, 'g, R: RangeBounds<K>>(
-        &'t self,
-        range: R,
-        guard: &'g Guard,
-    ) -> Range<'t, 'g, K, V, R> {
-        Range::new(&self.root, range, guard)
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the supplied value with the key of the entry, and returns a reference to it.
+    ///
+    /// The value is inserted into the leaf found by [`TreeIndex::entry`] without descending the
+    /// tree again, unless the leaf has been split or removed in the meantime. The returned
+    /// reference can survive as long as the associated [`Guard`] is alive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the key and the supplied value if the key has been inserted by
+    /// another thread in the meantime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    /// use scc::tree_index::Entry;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// let guard = Guard::new();
+    /// if let Entry::Vacant(v) = treeindex.entry(19, &guard) {
+    ///     assert_eq!(v.insert(29), Ok(&29));
+    /// }
+    ///
+    /// assert_eq!(treeindex.peek_with(&19, |_, v| *v), Some(29));
+    /// ```
+    #[inline]
+    pub fn insert(self, mut val: V) -> Result<&'g V, (K, V)> {
+        let mut key = self.key;
+        let tree_index = self.tree_index;
+        if let Some(leaf) = self.leaf {
+            // The leaf stays in the tree until it is frozen or retired, unless nodes have been
+            // detached by `clear` or `remove_range`.
+            if tree_index.detach_count.load(Acquire) == self.detach_count {
+                let watched_key = tree_index.is_watched(&key).then(|| key.clone());
+                match leaf.insert(key, val) {
+                    InsertResult::Success(val_ptr) => {
+                        let val = unsafe { &*val_ptr };
+                        if let Some(key) = watched_key.as_ref() {
+                            tree_index.notify(&WatchEvent::Inserted(key, val));
+                        }
+                        return Ok(val);
+                    }
+                    InsertResult::Duplicate(k, v) => return Err((k, v)),
+                    InsertResult::Full(k, v)
+                    | InsertResult::Frozen(k, v)
+                    | InsertResult::Retired(k, v)
+                    | InsertResult::Retry(k, v) => {
+                        key = k;
+                        val = v;
+                    }
+                }
+            }
+        }
+        tree_index.insert_with_guard(key, val, self.guard)
     }
 }
 
-impl<K, V> Clone for TreeIndex<K, V>
+impl<'t, 'g, K, V> Debug for VacantEntry<'t, 'g, K, V>
+where
+    K: 'static + Clone + Debug + Ord,
+    V: 'static + Clone + Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(self.key()).finish()
+    }
+}
+
+impl<'t, 'g, K, V> Iter<'t, 'g, K, V>
 where
     K: 'static + Clone + Ord,
     V: 'static + Clone,
 {
     #[inline]
-    fn clone(&self) -> Self {
-        let self_clone = Self::default();
-        for (k, v) in self.iter(&Guard::new()) {
-            let _reuslt = self_clone.insert(k.clone(), v.clone());
+    fn new(root: &'t AtomicShared<Node<K, V>>, guard: &'g Guard) -> Iter<'t, 'g, K, V> {
+        Iter::<'t, 'g, K, V> {
+            root,
+            leaf_scanner: None,
+            started: false,
+            guard,
         }
-        self_clone
     }
 }
 
-impl<K, V> Debug for TreeIndex<K, V>
+impl<K, V> Debug for IntoIter<K, V>
 where
-    K: 'static + Clone + Debug + Ord,
-    V: 'static + Clone + Debug,
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
 {
-    #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let guard = Guard::new();
-        f.debug_map().entries(self.iter(&guard)).finish()
+        f.debug_struct("IntoIter")
+            .field("finished", &self.leaf.is_none())
+            .field("buffered", &self.buffer.len())
+            .finish()
     }
 }
 
-impl<K, V> Default for TreeIndex<K, V>
+impl<K, V> Iterator for IntoIter<K, V>
 where
     K: 'static + Clone + Ord,
     V: 'static + Clone,
 {
-    /// Creates a [`TreeIndex`] with the default parameters.
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.buffer.next() {
+                return Some(entry);
+            }
+
+            let leaf = self.leaf.take()?;
+            self.buffer = Scanner::new(&leaf)
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<Vec<_>>()
+                .into_iter();
+            self.leaf = leaf.next_ptr(Acquire, &Guard::new()).get_shared();
+        }
+    }
+}
+
+impl<K, V> FusedIterator for IntoIter<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+}
+
+impl<K, V> UnwindSafe for IntoIter<K, V>
+where
+    K: 'static + Clone + Ord + UnwindSafe,
+    V: 'static + Clone + UnwindSafe,
+{
+}
+
+impl<K, V> RangeStream<'_, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Attempts to pull out the next entry.
+    ///
+    /// The signature is identical to that of `futures::Stream::poll_next`, so the
+    /// [`RangeStream`] can be adapted into a `Stream`, e.g., with `futures::stream::poll_fn`.
     ///
     /// # Examples
     ///
     /// ```
     /// use scc::TreeIndex;
+    /// use std::future::poll_fn;
+    /// use std::pin::Pin;
     ///
-    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::default();
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    ///
+    /// let mut stream = treeindex.range_async(..);
+    /// let future_next = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx));
     /// ```
     #[inline]
-    fn default() -> Self {
-        Self::new()
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<(K, V)>> {
+        let this = self.get_mut();
+        if let Some(entry) = this.buffer.next() {
+            return Poll::Ready(Some(entry));
+        } else if this.finished {
+            return Poll::Ready(None);
+        } else if this.yield_now {
+            this.yield_now = false;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        this.read_leaf();
+        this.yield_now = true;
+        Poll::Ready(this.buffer.next())
+    }
+
+    /// Returns the next entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// assert!(treeindex.insert(1, 10).is_ok());
+    ///
+    /// let mut stream = treeindex.range_async(..);
+    /// let future_next = stream.next();
+    /// ```
+    #[inline]
+    pub async fn next(&mut self) -> Option<(K, V)> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    /// Clones the entries in the next leaf into the buffer.
+    fn read_leaf(&mut self) {
+        let guard = Guard::new();
+        let mut range_iter = self.tree_index.range(
+            (self.lower_bound.as_ref(), self.upper_bound.as_ref()),
+            &guard,
+        );
+        let mut entries = Vec::new();
+        let mut current_leaf = None;
+        self.finished = true;
+        while let Some((k, v)) = range_iter.next() {
+            let leaf_ptr = range_iter.leaf_ptr();
+            if current_leaf.map_or(false, |current_leaf| current_leaf != leaf_ptr) {
+                // The entry belongs to the next leaf.
+                self.finished = false;
+                break;
+            }
+            current_leaf.replace(leaf_ptr);
+            entries.push((k.clone(), v.clone()));
+        }
+        if let Some((k, _)) = entries.last() {
+            self.lower_bound = Excluded(k.clone());
+        }
+        self.buffer = entries.into_iter();
     }
 }
 
-impl<K, V> PartialEq for TreeIndex<K, V>
+impl<K, V> Debug for RangeStream<'_, K, V>
 where
     K: 'static + Clone + Ord,
-    V: 'static + Clone + PartialEq,
+    V: 'static + Clone,
 {
-    #[inline]
-    fn eq(&self, other: &Self) -> bool {
-        // The key order is preserved, therefore comparing iterators suffices.
-        let guard = Guard::new();
-        Iterator::eq(self.iter(&guard), other.iter(&guard))
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RangeStream")
+            .field("root", &self.tree_index.root)
+            .field("buffered", &self.buffer.len())
+            .field("finished", &self.finished)
+            .finish()
     }
 }
 
-impl<'t, 'g, K, V> Iter<'t, 'g, K, V>
+impl<K, V> Unpin for RangeStream<'_, K, V>
 where
     K: 'static + Clone + Ord,
     V: 'static + Clone,
 {
-    #[inline]
-    fn new(root: &'t AtomicShared<Node<K, V>>, guard: &'g Guard) -> Iter<'t, 'g, K, V> {
-        Iter::<'t, 'g, K, V> {
-            root,
-            leaf_scanner: None,
-            guard,
-        }
-    }
 }
 
 impl<'t, 'g, K, V> Debug for Iter<'t, 'g, K, V>
@@ -792,7 +3801,8 @@ where
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         // Starts scanning.
-        if self.leaf_scanner.is_none() {
+        if !self.started {
+            self.started = true;
             let root_ptr = self.root.load(Acquire, self.guard);
             if let Some(root_ref) = root_ptr.as_ref() {
                 if let Some(scanner) = root_ref.min(self.guard) {
@@ -836,32 +3846,102 @@ where
 {
 }
 
-impl<'t, 'g, K, V, R> Range<'t, 'g, K, V, R>
+impl<'t, 'g, K, V, R, Q> Range<'t, 'g, K, V, R, Q>
 where
-    K: 'static + Clone + Ord,
+    K: 'static + Clone + Ord + Borrow<Q>,
     V: 'static + Clone,
-    R: RangeBounds<K>,
+    R: RangeBounds<Q>,
+    Q: Ord + ?Sized,
 {
     #[inline]
     fn new(
         root: &'t AtomicShared<Node<K, V>>,
         range: R,
         guard: &'g Guard,
-    ) -> Range<'t, 'g, K, V, R> {
-        Range::<'t, 'g, K, V, R> {
+    ) -> Range<'t, 'g, K, V, R, Q> {
+        Range::<'t, 'g, K, V, R, Q> {
             root,
             leaf_scanner: None,
             range,
             check_lower_bound: true,
             check_upper_bound: false,
+            started: false,
+            pending: false,
             guard,
+            query: PhantomData,
+        }
+    }
+
+    /// Moves the [`Range`] forward so that the next entry is the first one with a key greater
+    /// than or equal to the given key.
+    ///
+    /// It does nothing if the next entry already has a key greater than or equal to the given
+    /// key. The [`Range`] is repositioned within the current leaf if possible, otherwise the tree
+    /// is searched from the root without visiting the skipped entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treeindex: TreeIndex<u64, u32> = TreeIndex::new();
+    ///
+    /// for k in 0..1024 {
+    ///     assert!(treeindex.insert(k, 0).is_ok());
+    /// }
+    ///
+    /// let guard = Guard::new();
+    /// let mut range = treeindex.range(4..1000, &guard);
+    /// assert_eq!(range.next().map(|(k, _)| *k), Some(4));
+    /// range.seek(&8);
+    /// assert_eq!(range.next().map(|(k, _)| *k), Some(8));
+    /// range.seek(&512);
+    /// assert_eq!(range.next().map(|(k, _)| *k), Some(512));
+    /// range.seek(&256);
+    /// assert_eq!(range.next().map(|(k, _)| *k), Some(513));
+    /// range.seek(&1000);
+    /// assert!(range.next().is_none());
+    /// ```
+    #[inline]
+    pub fn seek(&mut self, key: &Q) {
+        if let Some(leaf_scanner) = self.leaf_scanner.as_mut() {
+            if leaf_scanner
+                .get()
+                .map_or(false, |(k, _)| key.compare(k).reverse() != Ordering::Less)
+            {
+                return;
+            }
+            if leaf_scanner.max_key().map_or(false, |max_key| {
+                key.compare(max_key).reverse() != Ordering::Less
+            }) {
+                for (k, _) in leaf_scanner.by_ref() {
+                    if key.compare(k).reverse() != Ordering::Less {
+                        self.pending = true;
+                        return;
+                    }
+                }
+            }
+        } else if self.started {
+            return;
+        }
+
+        // Search the tree for the key.
+        self.started = true;
+        self.pending = false;
+        self.leaf_scanner = TreeIndex::min_greater(self.root, key, true, self.guard);
+        if let Some(leaf_scanner) = self.leaf_scanner.take() {
+            self.set_check_upper_bound(&leaf_scanner);
+            self.leaf_scanner.replace(leaf_scanner);
+            self.pending = true;
         }
     }
 
     #[inline]
     fn next_unbounded(&mut self) -> Option<(&'g K, &'g V)> {
-        if self.leaf_scanner.is_none() {
+        if !self.started {
             // Start scanning.
+            self.started = true;
             let root_ptr = self.root.load(Acquire, self.guard);
             if let Some(root_ref) = root_ptr.as_ref() {
                 let min_allowed_key = match self.range.start_bound() {
@@ -880,7 +3960,7 @@ where
                         // It's possible that the leaf has just been emptied, so go to the next.
                         scanner.next();
                         while scanner.get().is_none() {
-                            scanner = scanner.jump(None, self.guard)?;
+                            scanner = scanner.jump::<K>(None, self.guard)?;
                         }
                         leaf_scanner.replace(scanner);
                     }
@@ -897,13 +3977,21 @@ where
 
         // Go to the next entry.
         if let Some(mut leaf_scanner) = self.leaf_scanner.take() {
+            if self.pending {
+                // The entry has been found by `seek`.
+                self.pending = false;
+                if let Some(result) = leaf_scanner.get() {
+                    self.leaf_scanner.replace(leaf_scanner);
+                    return Some(result);
+                }
+            }
             let min_allowed_key = leaf_scanner.get().map(|(key, _)| key);
             if let Some(result) = leaf_scanner.next() {
                 self.leaf_scanner.replace(leaf_scanner);
                 return Some(result);
             }
             // Go to the next leaf node.
-            if let Some(new_scanner) = leaf_scanner.jump(min_allowed_key, self.guard) {
+            if let Some(new_scanner) = leaf_scanner.jump::<K>(min_allowed_key, self.guard) {
                 if let Some(entry) = new_scanner.get() {
                     self.set_check_upper_bound(&new_scanner);
                     self.leaf_scanner.replace(new_scanner);
@@ -915,25 +4003,33 @@ where
         None
     }
 
+    #[inline]
+    fn leaf_ptr(&self) -> *const Leaf<K, V> {
+        self.leaf_scanner
+            .as_ref()
+            .map_or(ptr::null(), |leaf_scanner| leaf_scanner.leaf())
+    }
+
     #[inline]
     fn set_check_upper_bound(&mut self, scanner: &Scanner<K, V>) {
         self.check_upper_bound = match self.range.end_bound() {
-            Excluded(key) => scanner
-                .max_key()
-                .map_or(false, |max_key| max_key.cmp(key) != Ordering::Less),
-            Included(key) => scanner
-                .max_key()
-                .map_or(false, |max_key| max_key.cmp(key) == Ordering::Greater),
+            Excluded(key) => scanner.max_key().map_or(false, |max_key| {
+                key.compare(max_key).reverse() != Ordering::Less
+            }),
+            Included(key) => scanner.max_key().map_or(false, |max_key| {
+                key.compare(max_key).reverse() == Ordering::Greater
+            }),
             Unbounded => false,
         };
     }
 }
 
-impl<'t, 'g, K, V, R> Debug for Range<'t, 'g, K, V, R>
+impl<'t, 'g, K, V, R, Q> Debug for Range<'t, 'g, K, V, R, Q>
 where
-    K: 'static + Clone + Ord,
+    K: 'static + Clone + Ord + Borrow<Q>,
     V: 'static + Clone,
-    R: RangeBounds<K>,
+    R: RangeBounds<Q>,
+    Q: Ord + ?Sized,
 {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -946,11 +4042,12 @@ where
     }
 }
 
-impl<'t, 'g, K, V, R> Iterator for Range<'t, 'g, K, V, R>
+impl<'t, 'g, K, V, R, Q> Iterator for Range<'t, 'g, K, V, R, Q>
 where
-    K: 'static + Clone + Ord,
+    K: 'static + Clone + Ord + Borrow<Q>,
     V: 'static + Clone,
-    R: RangeBounds<K>,
+    R: RangeBounds<Q>,
+    Q: Ord + ?Sized,
 {
     type Item = (&'g K, &'g V);
 
@@ -960,12 +4057,12 @@ where
             if self.check_lower_bound {
                 match self.range.start_bound() {
                     Excluded(key) => {
-                        if k.cmp(key) != Ordering::Greater {
+                        if key.compare(k).reverse() != Ordering::Greater {
                             continue;
                         }
                     }
                     Included(key) => {
-                        if k.cmp(key) == Ordering::Less {
+                        if key.compare(k).reverse() == Ordering::Less {
                             continue;
                         }
                     }
@@ -976,12 +4073,12 @@ where
             if self.check_upper_bound {
                 match self.range.end_bound() {
                     Excluded(key) => {
-                        if k.cmp(key) == Ordering::Less {
+                        if key.compare(k).reverse() == Ordering::Less {
                             return Some((k, v));
                         }
                     }
                     Included(key) => {
-                        if k.cmp(key) != Ordering::Greater {
+                        if key.compare(k).reverse() != Ordering::Greater {
                             return Some((k, v));
                         }
                     }
@@ -997,18 +4094,20 @@ where
     }
 }
 
-impl<'t, 'g, K, V, R> FusedIterator for Range<'t, 'g, K, V, R>
+impl<'t, 'g, K, V, R, Q> FusedIterator for Range<'t, 'g, K, V, R, Q>
 where
-    K: 'static + Clone + Ord,
+    K: 'static + Clone + Ord + Borrow<Q>,
     V: 'static + Clone,
-    R: RangeBounds<K>,
+    R: RangeBounds<Q>,
+    Q: Ord + ?Sized,
 {
 }
 
-impl<'t, 'g, K, V, R> UnwindSafe for Range<'t, 'g, K, V, R>
+impl<'t, 'g, K, V, R, Q> UnwindSafe for Range<'t, 'g, K, V, R, Q>
 where
     K: 'static + Clone + Ord + UnwindSafe,
     V: 'static + Clone + UnwindSafe,
-    R: RangeBounds<K> + UnwindSafe,
+    R: RangeBounds<Q> + UnwindSafe,
+    Q: ?Sized,
 {
 }
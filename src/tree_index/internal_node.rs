@@ -1,17 +1,17 @@
-use super::leaf::{InsertResult, Leaf, RemoveResult, Scanner, DIMENSION};
+use super::leaf::{InsertResult, Leaf, ModifyResult, RemoveResult, Scanner, DIMENSION};
 use super::leaf_node::RemoveRangeState;
 use super::leaf_node::{LOCKED, RETIRED};
 use super::node::Node;
+use crate::atomic::{AtomicPtr, AtomicU8};
 use crate::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
 use crate::exit_guard::ExitGuard;
 use crate::wait_queue::{DeriveAsyncWait, WaitQueue};
-use std::borrow::Borrow;
+use crate::Comparable;
 use std::cmp::Ordering::{Equal, Greater, Less};
 use std::mem::forget;
 use std::ops::RangeBounds;
 use std::ptr;
 use std::sync::atomic::Ordering::{self, Acquire, Relaxed, Release};
-use std::sync::atomic::{AtomicPtr, AtomicU8};
 
 /// Internal node.
 ///
@@ -102,8 +102,19 @@ where
     #[inline]
     pub(super) fn search<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<&'g V>
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
+    {
+        self.search_entry(key, guard).map(|(_, v)| v)
+    }
+
+    /// Searches for an entry associated with the given key, and returns the stored key and the
+    /// value.
+    #[inline]
+    pub(super) fn search_entry<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<(&'g K, &'g V)>
+    where
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         loop {
             let (child, metadata) = self.children.min_greater_equal(key);
@@ -111,14 +122,14 @@ where
                 if let Some(child) = child.load(Acquire, guard).as_ref() {
                     if self.children.validate(metadata) {
                         // Data race resolution - see `LeafNode::search`.
-                        return child.search(key, guard);
+                        return child.search_entry(key, guard);
                     }
                 }
             } else {
                 let unbounded_ptr = self.unbounded_child.load(Acquire, guard);
                 if let Some(unbounded) = unbounded_ptr.as_ref() {
                     if self.children.validate(metadata) {
-                        return unbounded.search(key, guard);
+                        return unbounded.search_entry(key, guard);
                     }
                 } else {
                     return None;
@@ -127,6 +138,34 @@ where
         }
     }
 
+    /// Returns the [`Leaf`] that the given key belongs to.
+    #[inline]
+    pub(super) fn search_leaf<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Ptr<'g, Leaf<K, V>>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        loop {
+            let (child, metadata) = self.children.min_greater_equal(key);
+            if let Some((_, child)) = child {
+                if let Some(child) = child.load(Acquire, guard).as_ref() {
+                    if self.children.validate(metadata) {
+                        // Data race resolution - see `LeafNode::search`.
+                        return child.search_leaf(key, guard);
+                    }
+                }
+            } else {
+                let unbounded_ptr = self.unbounded_child.load(Acquire, guard);
+                if let Some(unbounded) = unbounded_ptr.as_ref() {
+                    if self.children.validate(metadata) {
+                        return unbounded.search_leaf(key, guard);
+                    }
+                } else {
+                    return Ptr::null();
+                }
+            }
+        }
+    }
+
     /// Returns the minimum key entry.
     #[inline]
     pub(super) fn min<'g>(&self, guard: &'g Guard) -> Option<Scanner<'g, K, V>> {
@@ -163,6 +202,38 @@ where
         }
     }
 
+    /// Returns a [`Scanner`] for the [`Leaf`] that has no upper key bound.
+    ///
+    /// The [`Leaf`] may not contain any entries if all of them have been removed.
+    #[inline]
+    pub(super) fn max<'g>(&self, guard: &'g Guard) -> Option<Scanner<'g, K, V>> {
+        self.unbounded_child
+            .load(Acquire, guard)
+            .as_ref()?
+            .max(guard)
+    }
+
+    /// Returns a [`Scanner`] for a randomly chosen [`Leaf`].
+    #[inline]
+    pub(super) fn random<'g, R: FnMut(usize) -> usize>(
+        &self,
+        rng: &mut R,
+        guard: &'g Guard,
+    ) -> Option<Scanner<'g, K, V>> {
+        let num_children = self.children.len() + 1;
+        let index = rng(num_children) % num_children;
+        if let Some(child) = Scanner::new(&self.children)
+            .nth(index)
+            .and_then(|(_, child)| child.load(Acquire, guard).as_ref())
+        {
+            return child.random(rng, guard);
+        }
+        self.unbounded_child
+            .load(Acquire, guard)
+            .as_ref()?
+            .random(rng, guard)
+    }
+
     /// Returns a [`Scanner`] pointing to an entry that is close enough to the entry with the
     /// maximum key among those keys smaller than or equal to the given key.
     ///
@@ -171,8 +242,8 @@ where
     #[inline]
     pub(super) fn max_le_appr<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<Scanner<'g, K, V>>
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         loop {
             if let Some(scanner) = Scanner::max_less(&self.children, key) {
@@ -200,12 +271,12 @@ where
         min_scanner.next();
         loop {
             if let Some((k, _)) = min_scanner.get() {
-                if k.borrow() <= key {
+                if key.compare(k).is_ge() {
                     return Some(min_scanner);
                 }
                 break;
             }
-            min_scanner = min_scanner.jump(None, guard)?;
+            min_scanner = min_scanner.jump::<K>(None, guard)?;
         }
 
         None
@@ -213,6 +284,7 @@ where
 
     /// Inserts a key-value pair.
     #[inline]
+    #[allow(clippy::too_many_lines)]
     pub(super) fn insert<D: DeriveAsyncWait>(
         &self,
         mut key: K,
@@ -229,11 +301,11 @@ where
                         // Data race resolution - see `LeafNode::search`.
                         let insert_result = child_ref.insert(key, val, async_wait, guard)?;
                         match insert_result {
-                            InsertResult::Success
+                            InsertResult::Success(..)
                             | InsertResult::Duplicate(..)
                             | InsertResult::Frozen(..) => return Ok(insert_result),
                             InsertResult::Full(k, v) => {
-                                let split_result = self.split_node(
+                                let split_result = match self.split_node(
                                     k,
                                     v,
                                     Some(child_key),
@@ -242,7 +314,17 @@ where
                                     false,
                                     async_wait,
                                     guard,
-                                )?;
+                                ) {
+                                    Ok(split_result) => split_result,
+                                    Err((k, v)) => {
+                                        if self.can_retry(async_wait) {
+                                            key = k;
+                                            val = v;
+                                            continue;
+                                        }
+                                        return Err((k, v));
+                                    }
+                                };
                                 if let InsertResult::Retry(k, v) = split_result {
                                     key = k;
                                     val = v;
@@ -282,11 +364,11 @@ where
                 }
                 let insert_result = unbounded.insert(key, val, async_wait, guard)?;
                 match insert_result {
-                    InsertResult::Success
+                    InsertResult::Success(..)
                     | InsertResult::Duplicate(..)
                     | InsertResult::Frozen(..) => return Ok(insert_result),
                     InsertResult::Full(k, v) => {
-                        let split_result = self.split_node(
+                        let split_result = match self.split_node(
                             k,
                             v,
                             None,
@@ -295,7 +377,17 @@ where
                             false,
                             async_wait,
                             guard,
-                        )?;
+                        ) {
+                            Ok(split_result) => split_result,
+                            Err((k, v)) => {
+                                if self.can_retry(async_wait) {
+                                    key = k;
+                                    val = v;
+                                    continue;
+                                }
+                                return Err((k, v));
+                            }
+                        };
                         if let InsertResult::Retry(k, v) = split_result {
                             key = k;
                             val = v;
@@ -340,8 +432,7 @@ where
         guard: &Guard,
     ) -> Result<RemoveResult, ()>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
         D: DeriveAsyncWait,
     {
         loop {
@@ -391,10 +482,109 @@ where
         }
     }
 
+    /// Replaces the value associated with the given key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a retry is required.
+    #[inline]
+    pub(super) fn modify<Q, F: FnMut(&V) -> Option<V>, D: DeriveAsyncWait>(
+        &self,
+        key: &Q,
+        updater: &mut F,
+        async_wait: &mut D,
+        guard: &Guard,
+    ) -> Result<ModifyResult<K, V>, ()>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        loop {
+            let (child, metadata) = self.children.min_greater_equal(key);
+            if let Some((child_key, child)) = child {
+                let child_ptr = child.load(Acquire, guard);
+                if let Some(child_ref) = child_ptr.as_ref() {
+                    if self.children.validate(metadata) {
+                        // Data race resolution - see `LeafNode::search`.
+                        match child_ref.modify(key, updater, async_wait, guard)? {
+                            ModifyResult::Full(k, v) => {
+                                match self.split_node(
+                                    k,
+                                    v,
+                                    Some(child_key),
+                                    child_ptr,
+                                    child,
+                                    false,
+                                    async_wait,
+                                    guard,
+                                ) {
+                                    Ok(InsertResult::Retry(..)) => continue,
+                                    Ok(InsertResult::Full(k, v)) => {
+                                        return Ok(ModifyResult::Full(k, v))
+                                    }
+                                    Ok(_) => return Ok(ModifyResult::Retired),
+                                    Err(_) => return Err(()),
+                                }
+                            }
+                            ModifyResult::Retired => {
+                                debug_assert!(child_ref.retired(Relaxed));
+                                if self.coalesce(guard) == RemoveResult::Retired {
+                                    debug_assert!(self.retired(Relaxed));
+                                    return Ok(ModifyResult::Retired);
+                                }
+                                return Err(());
+                            }
+                            result => return Ok(result),
+                        }
+                    }
+                }
+                // It is not a hot loop - see `LeafNode::search`.
+                continue;
+            }
+            let unbounded_ptr = self.unbounded_child.load(Acquire, guard);
+            if let Some(unbounded) = unbounded_ptr.as_ref() {
+                debug_assert!(unbounded_ptr.tag() == Tag::None);
+                if !self.children.validate(metadata) {
+                    // Data race resolution - see `LeafNode::search`.
+                    continue;
+                }
+                match unbounded.modify(key, updater, async_wait, guard)? {
+                    ModifyResult::Full(k, v) => {
+                        match self.split_node(
+                            k,
+                            v,
+                            None,
+                            unbounded_ptr,
+                            &self.unbounded_child,
+                            false,
+                            async_wait,
+                            guard,
+                        ) {
+                            Ok(InsertResult::Retry(..)) => continue,
+                            Ok(InsertResult::Full(k, v)) => return Ok(ModifyResult::Full(k, v)),
+                            Ok(_) => return Ok(ModifyResult::Retired),
+                            Err(_) => return Err(()),
+                        }
+                    }
+                    ModifyResult::Retired => {
+                        debug_assert!(unbounded.retired(Relaxed));
+                        if self.coalesce(guard) == RemoveResult::Retired {
+                            debug_assert!(self.retired(Relaxed));
+                            return Ok(ModifyResult::Retired);
+                        }
+                        return Err(());
+                    }
+                    result => return Ok(result),
+                }
+            }
+            return Ok(ModifyResult::Fail);
+        }
+    }
+
     /// Removes a range of entries.
     ///
     /// Returns the number of remaining children.
     #[inline]
+    #[allow(clippy::too_many_lines)]
     pub(super) fn remove_range<'g, R: RangeBounds<K>, D: DeriveAsyncWait>(
         &self,
         range: &R,
@@ -474,12 +664,27 @@ where
                 upper_node.remove_range(range, true, Some(lower_leaf), None, async_wait, guard)?;
             }
         } else if let Some(upper_node) = valid_upper_min_node {
-            debug_assert!(lower_border.is_some());
-            if let Some(lower_node) = lower_border.and_then(|n| n.load(Acquire, guard).as_ref()) {
+            debug_assert!(lower_border.is_some() || upper_border.is_some());
+            let lower_node = lower_border.and_then(|n| n.load(Acquire, guard).as_ref());
+            let unbounded_node = upper_border.and_then(|n| n.load(Acquire, guard).as_ref());
+            if let Some(lower_node) = lower_node {
+                // The unbounded child, if any, has to be linked in between.
                 lower_node.remove_range(
                     range,
                     start_unbounded,
                     None,
+                    Some(unbounded_node.unwrap_or(upper_node)),
+                    async_wait,
+                    guard,
+                )?;
+            }
+            if let Some(unbounded_node) = unbounded_node {
+                // The unbounded child is fully contained in the range since the maximum key of
+                // this node is contained in the range.
+                unbounded_node.remove_range(
+                    range,
+                    true,
+                    None,
                     Some(upper_node),
                     async_wait,
                     guard,
@@ -498,6 +703,11 @@ where
                 async_wait,
                 guard,
             )?;
+        } else if let Some(upper_node) = upper_border.and_then(|n| n.load(Acquire, guard).as_ref())
+        {
+            // The range starts in the upper border node, or no nodes are left below the range.
+            debug_assert!(lower_border.is_none());
+            upper_node.remove_range(range, start_unbounded, None, None, async_wait, guard)?;
         }
 
         Ok(num_children)
@@ -779,7 +989,7 @@ where
             },
             self.split_op.low_key_node.clone(Relaxed, guard),
         ) {
-            InsertResult::Success => (),
+            InsertResult::Success(..) => (),
             InsertResult::Duplicate(..) | InsertResult::Frozen(..) | InsertResult::Retry(..) => {
                 unreachable!()
             }
@@ -860,8 +1070,8 @@ where
     #[inline]
     pub(super) fn cleanup_link<'g, Q>(&self, key: &Q, traverse_max: bool, guard: &'g Guard) -> bool
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         if traverse_max {
             // It just has to search for the maximum leaf node in the tree.
@@ -897,11 +1107,7 @@ where
     }
 
     /// Tries to coalesce nodes.
-    fn coalesce<Q>(&self, guard: &Guard) -> RemoveResult
-    where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
-    {
+    fn coalesce(&self, guard: &Guard) -> RemoveResult {
         let mut node_deleted = false;
         while let Some(lock) = Locker::try_lock(self) {
             let mut max_key_entry = None;
@@ -909,7 +1115,7 @@ where
                 let node_ptr = node.load(Relaxed, guard);
                 let node_ref = node_ptr.as_ref().unwrap();
                 if node_ref.retired(Relaxed) {
-                    let result = self.children.remove_if(key.borrow(), &mut |_| true);
+                    let result = self.children.remove_if(key, &mut |_| true);
                     debug_assert_ne!(result, RemoveResult::Fail);
 
                     // Once the key is removed, it is safe to deallocate the node as the validation
@@ -940,7 +1146,7 @@ where
                             let _: bool = obsolete_node.release(guard);
                             node_deleted = true;
                         }
-                        let result = self.children.remove_if(key.borrow(), &mut |_| true);
+                        let result = self.children.remove_if(key, &mut |_| true);
                         debug_assert_ne!(result, RemoveResult::Fail);
                         if let Some(node) = max_key_child.swap((None, Tag::None), Release).0 {
                             let _: bool = node.release(guard);
@@ -1005,6 +1211,17 @@ where
         false
     }
 
+    /// Returns `true` if an operation that failed because the [`InternalNode`] was locked can be
+    /// retried on the [`InternalNode`] without traversing the tree again.
+    ///
+    /// The split of a child node is rolled back instead of waiting for the lock while the child
+    /// node is locked, since a thread holding the lock may be waiting for the child node to be
+    /// unlocked. Synchronous callers have already waited for the lock to be released, therefore
+    /// they can retry unless the [`InternalNode`] has been replaced with new ones.
+    fn can_retry<D: DeriveAsyncWait>(&self, async_wait: &mut D) -> bool {
+        async_wait.derive().is_none() && self.latch.load(Acquire) != RETIRED.into()
+    }
+
     /// Tries to lock the [`InternalNode`].
     fn try_lock(&self) -> bool {
         self.latch
@@ -1094,7 +1311,7 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::sync::atomic::AtomicBool;
+    use crate::atomic::AtomicBool;
     use tokio::sync::Barrier;
 
     fn new_level_3_node() -> InternalNode<usize, usize> {
@@ -1123,7 +1340,7 @@ mod test {
         for k in 0..8192 {
             match internal_node.insert(k, k, &mut (), &guard) {
                 Ok(result) => match result {
-                    InsertResult::Success => {
+                    InsertResult::Success(..) => {
                         assert_eq!(internal_node.search(&k, &guard), Some(&k));
                     }
                     InsertResult::Duplicate(..)
@@ -1191,7 +1408,7 @@ mod test {
                         loop {
                             if let Ok(r) = internal_node_clone.insert(id, id, &mut (), &guard) {
                                 match r {
-                                    InsertResult::Success => {
+                                    InsertResult::Success(..) => {
                                         match internal_node_clone.insert(id, id, &mut (), &guard) {
                                             Ok(InsertResult::Duplicate(..)) | Err(_) => (),
                                             _ => unreachable!(),
@@ -1288,7 +1505,7 @@ mod test {
                                 &mut (),
                                 &guard,
                             ) {
-                                Ok(InsertResult::Success) => {
+                                Ok(InsertResult::Success(..)) => {
                                     assert!(!inserted_clone.swap(true, Relaxed));
                                 }
                                 Ok(InsertResult::Full(_, _) | InsertResult::Retired(_, _)) => {
@@ -1328,8 +1545,11 @@ mod test {
                                     assert_eq!(*f, *v);
                                     assert!(*f <= fixed_point);
                                 } else {
-                                    let (f, v) =
-                                        min_scanner.jump(None, &guard).unwrap().get().unwrap();
+                                    let (f, v) = min_scanner
+                                        .jump::<usize>(None, &guard)
+                                        .unwrap()
+                                        .get()
+                                        .unwrap();
                                     assert_eq!(*f, *v);
                                     assert!(*f <= fixed_point);
                                 }
@@ -1,16 +1,18 @@
-use crate::ebr::{AtomicShared, Guard, Shared};
+use crate::atomic::AtomicUsize;
+use crate::ebr::{AtomicShared, Guard, Ptr, Shared};
+use crate::wait_queue::{DeriveAsyncWait, WaitQueue};
+use crate::Comparable;
 use crate::LinkedList;
-use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::fmt::{self, Debug};
-use std::mem::{needs_drop, MaybeUninit};
-use std::sync::atomic::AtomicUsize;
+use std::mem::{needs_drop, size_of, MaybeUninit};
 use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
 
 /// [`Leaf`] is an ordered array of key-value pairs.
 ///
 /// A constructed key-value pair entry is never dropped until the entire [`Leaf`] instance is
-/// dropped.
+/// dropped, except for an entry superseded by [`Leaf::modify`] which is dropped once no readers
+/// can reach it.
 pub struct Leaf<K, V>
 where
     K: 'static + Clone + Ord,
@@ -25,6 +27,7 @@ where
     ///
     /// The entry state transitions as follows.
     /// * `uninit -> removed -> rank -> removed`.
+    /// * `removed -> uninit` if the entry was superseded by [`Leaf::modify`].
     metadata: AtomicUsize,
 
     /// The array of key-value pairs.
@@ -32,6 +35,9 @@ where
 
     /// A pointer that points to the next adjacent [`Leaf`].
     link: AtomicShared<Leaf<K, V>>,
+
+    /// `wait_queue` for the [`Leaf`] to be thawed or deleted while it is frozen.
+    wait_queue: WaitQueue,
 }
 
 /// The number of entries and number of state bits per entry.
@@ -44,7 +50,10 @@ pub struct Dimension {
 /// The result of insertion.
 pub enum InsertResult<K, V> {
     /// Insertion succeeded.
-    Success,
+    ///
+    /// It contains a pointer to the inserted value which stays valid as long as the [`Leaf`] is
+    /// alive.
+    Success(*const V),
 
     /// Duplicate key found.
     Duplicate(K, V),
@@ -85,6 +94,26 @@ pub enum RemoveResult {
     Frozen,
 }
 
+/// The result of modification.
+pub enum ModifyResult<K, V> {
+    /// Modification succeeded.
+    Success,
+
+    /// The key was not found, or the updater declined to replace the value.
+    Fail,
+
+    /// No vacant slot for the new value.
+    ///
+    /// It contains a copy of the entry for the caller to split the [`Leaf`].
+    Full(K, V),
+
+    /// The [`Leaf`] is frozen.
+    Frozen,
+
+    /// Modification failed as the node has retired.
+    Retired,
+}
+
 impl<K, V> Leaf<K, V>
 where
     K: 'static + Clone + Ord,
@@ -98,13 +127,15 @@ where
             metadata: AtomicUsize::new(0),
             entry_array: unsafe { MaybeUninit::uninit().assume_init() },
             link: AtomicShared::null(),
+            wait_queue: WaitQueue::new(),
         }
     }
 
     /// Thaws the [`Leaf`].
     #[inline]
     pub(super) fn thaw(&self) -> bool {
-        self.metadata
+        let thawed = self
+            .metadata
             .fetch_update(Relaxed, Relaxed, |p| {
                 if Dimension::frozen(p) {
                     Some(Dimension::thaw(p))
@@ -112,7 +143,39 @@ where
                     None
                 }
             })
-            .is_ok()
+            .is_ok();
+        if thawed {
+            self.wait_queue.signal();
+        }
+        thawed
+    }
+
+    /// Deletes the frozen [`Leaf`] from the linked list after its entries have been moved to new
+    /// leaves.
+    #[inline]
+    pub(super) fn delete_frozen(&self) -> bool {
+        debug_assert!(self.is_frozen());
+        let deleted = self.delete_self(Release);
+        self.wait_queue.signal();
+        deleted
+    }
+
+    /// Waits for the frozen [`Leaf`] to be thawed or deleted.
+    #[inline]
+    pub(super) fn wait<D: DeriveAsyncWait>(&self, async_wait: &mut D) {
+        let waiter = || {
+            if self.is_frozen() && !self.is_deleted(Relaxed) {
+                // The `Leaf` is being split.
+                return Err(());
+            }
+            Ok(())
+        };
+
+        if let Some(async_wait) = async_wait.derive() {
+            let _result = self.wait_queue.push_async_entry(async_wait, waiter);
+        } else {
+            let _result = self.wait_queue.wait_sync(waiter);
+        }
     }
 
     /// Returns `true` if the [`Leaf`] has retired.
@@ -121,6 +184,30 @@ where
         Dimension::retired(self.metadata.load(Relaxed))
     }
 
+    /// Returns `true` if the [`Leaf`] is frozen.
+    #[inline]
+    pub(super) fn is_frozen(&self) -> bool {
+        Dimension::frozen(self.metadata.load(Relaxed))
+    }
+
+    /// Returns the number of reachable entries in the [`Leaf`].
+    #[inline]
+    pub(super) fn len(&self) -> usize {
+        let mut mutable_metadata = self.metadata.load(Acquire);
+        let mut len = 0;
+        for _ in 0..DIMENSION.num_entries {
+            if mutable_metadata == 0 {
+                break;
+            }
+            let rank = mutable_metadata % (1_usize << DIMENSION.num_bits_per_entry);
+            if rank != Dimension::uninit_rank() && rank != DIMENSION.removed_rank() {
+                len += 1;
+            }
+            mutable_metadata >>= DIMENSION.num_bits_per_entry;
+        }
+        len
+    }
+
     /// Returns `true` if the [`Leaf`] has no reachable entry.
     #[inline]
     pub(super) fn is_empty(&self) -> bool {
@@ -138,6 +225,12 @@ where
         true
     }
 
+    /// Prefetches the next [`Leaf`] into the CPU cache.
+    #[inline]
+    pub(super) fn prefetch_next(&self, guard: &Guard) {
+        prefetch(self.link.load(Relaxed, guard).as_ptr());
+    }
+
     /// Returns a reference to the max key.
     #[inline]
     pub(super) fn max_key(&self) -> Option<&K> {
@@ -228,8 +321,37 @@ where
         condition: &mut F,
     ) -> RemoveResult
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
+    {
+        self.remove_if_internal(key, condition, true)
+    }
+
+    /// Removes the key if the condition is met and the [`Leaf`] has other usable entries.
+    ///
+    /// Returns [`RemoveResult::Frozen`] without removing the entry if it is the last usable one,
+    /// since the [`Leaf`] would retire and it has to be cleaned up by its parent node.
+    #[inline]
+    pub(super) fn remove_unless_last<Q, F: FnMut(&V) -> bool>(
+        &self,
+        key: &Q,
+        condition: &mut F,
+    ) -> RemoveResult
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.remove_if_internal(key, condition, false)
+    }
+
+    /// Removes the key if the condition is met, and retires the [`Leaf`] if `retire` is `true`
+    /// and no usable entries are left.
+    fn remove_if_internal<Q, F: FnMut(&V) -> bool>(
+        &self,
+        key: &Q,
+        condition: &mut F,
+        retire: bool,
+    ) -> RemoveResult
+    where
+        Q: Comparable<K> + ?Sized,
     {
         let mut metadata = self.metadata.load(Acquire);
         if Dimension::frozen(metadata) {
@@ -281,6 +403,9 @@ where
                                 mutable_metadata >>= DIMENSION.num_bits_per_entry;
                             }
 
+                            if empty && !retire {
+                                return RemoveResult::Frozen;
+                            }
                             let mut new_metadata = metadata | DIMENSION.rank_mask(i);
                             if empty {
                                 new_metadata = Dimension::retire(new_metadata);
@@ -299,7 +424,9 @@ where
                                 }
                                 Err(actual) => {
                                     if DIMENSION.rank(actual, i) == DIMENSION.removed_rank() {
-                                        return RemoveResult::Fail;
+                                        // The entry may have been moved to another slot by
+                                        // `modify`.
+                                        return self.remove_if_internal(key, condition, retire);
                                     }
                                     if Dimension::frozen(actual) {
                                         return RemoveResult::Frozen;
@@ -317,23 +444,117 @@ where
         RemoveResult::Fail
     }
 
-    /// Returns a value associated with the key.
+    /// Replaces the value associated with the key with a new one derived from the current value.
+    ///
+    /// The new value is written into a vacant slot which then takes over the rank of the old
+    /// entry, therefore readers always see either the old or the new value. The old slot is made
+    /// vacant again after every [`Guard`] that may be reading the old value is dropped, so that
+    /// repeated modification of an entry does not fill the [`Leaf`]. The value is left intact if
+    /// `updater` returns `None`.
+    #[inline]
+    pub(super) fn modify<Q, F: FnMut(&V) -> Option<V>>(
+        leaf_ptr: Ptr<'_, Leaf<K, V>>,
+        key: &Q,
+        updater: &mut F,
+        guard: &Guard,
+    ) -> ModifyResult<K, V>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        let Some(leaf) = leaf_ptr.as_ref() else {
+            return ModifyResult::Fail;
+        };
+        match leaf.replace(key, updater) {
+            Ok(index) => {
+                if let Some(leaf) = leaf_ptr.get_shared() {
+                    let superseded = Superseded { leaf, index };
+                    guard.defer_execute(move || superseded.vacate());
+                }
+                ModifyResult::Success
+            }
+            Err(result) => result,
+        }
+    }
+
+    /// Writes a new value derived from the current value into a vacant slot, and hands over the
+    /// rank of the old entry to it.
+    ///
+    /// Returns the index of the old slot.
+    fn replace<Q, F: FnMut(&V) -> Option<V>>(
+        &self,
+        key: &Q,
+        updater: &mut F,
+    ) -> Result<usize, ModifyResult<K, V>>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        let mut new_entry: Option<(usize, K, V)> = None;
+        let mut metadata = self.metadata.load(Acquire);
+        'after_read_metadata: loop {
+            if Dimension::frozen(metadata) {
+                return Err(ModifyResult::Frozen);
+            }
+            let Some(index) = self.search_slot(key, metadata) else {
+                return Err(ModifyResult::Fail);
+            };
+
+            let mut mutable_metadata = metadata;
+            for free_slot_index in 0..DIMENSION.num_entries {
+                let rank = mutable_metadata % (1_usize << DIMENSION.num_bits_per_entry);
+                if rank == Dimension::uninit_rank() {
+                    // The new entry is created before reserving the slot in order not to leave the
+                    // slot uninitialized when `updater` panics.
+                    if !matches!(new_entry, Some((i, _, _)) if i == index) {
+                        let Some(val) = updater(self.value_at(index)) else {
+                            return Err(ModifyResult::Fail);
+                        };
+                        new_entry.replace((index, self.key_at(index).clone(), val));
+                    }
+                    let interim_metadata =
+                        DIMENSION.augment(metadata, free_slot_index, DIMENSION.removed_rank());
+                    if let Err(actual) =
+                        self.metadata
+                            .compare_exchange(metadata, interim_metadata, Acquire, Acquire)
+                    {
+                        metadata = actual;
+                        continue 'after_read_metadata;
+                    }
+
+                    if let Some((_, key, val)) = new_entry.take() {
+                        self.write(free_slot_index, key, val);
+                    }
+                    if self.post_modify(index, free_slot_index, interim_metadata) {
+                        return Ok(index);
+                    }
+                    metadata = self.metadata.load(Acquire);
+                    continue 'after_read_metadata;
+                }
+                mutable_metadata >>= DIMENSION.num_bits_per_entry;
+            }
+
+            return Err(ModifyResult::Full(
+                self.key_at(index).clone(),
+                self.value_at(index).clone(),
+            ));
+        }
+    }
+
+    /// Returns the stored key and the value associated with the key.
     #[inline]
-    pub(super) fn search<Q>(&self, key: &Q) -> Option<&V>
+    pub(super) fn search_entry<Q>(&self, key: &Q) -> Option<(&K, &V)>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         let metadata = self.metadata.load(Acquire);
-        self.search_slot(key, metadata).map(|i| self.value_at(i))
+        self.search_slot(key, metadata)
+            .map(|i| (self.key_at(i), self.value_at(i)))
     }
 
     /// Returns the index of the key-value pair that is smaller than the given key.
     #[inline]
     pub(super) fn max_less<Q>(&self, mut mutable_metadata: usize, key: &Q) -> usize
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         let mut min_max_rank = DIMENSION.removed_rank();
         let mut max_min_rank = 0;
@@ -373,8 +594,7 @@ where
     #[inline]
     pub(super) fn min_greater_equal<Q>(&self, key: &Q) -> (Option<(&K, &V)>, usize)
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         let metadata = self.metadata.load(Acquire);
         let mut min_max_rank = DIMENSION.removed_rank();
@@ -388,7 +608,7 @@ where
             let rank = mutable_metadata % (1_usize << DIMENSION.num_bits_per_entry);
             if rank < min_max_rank && rank > max_min_rank {
                 let k = self.key_at(i);
-                match k.borrow().cmp(key) {
+                match key.compare(k).reverse() {
                     Ordering::Less => {
                         if max_min_rank < rank {
                             max_min_rank = rank;
@@ -440,22 +660,22 @@ where
     }
 
     /// Freezes the [`Leaf`] and distribute entries to two new leaves.
+    ///
+    /// Returns `false` without distributing entries if the [`Leaf`] has already been frozen.
     #[inline]
     pub(super) fn freeze_and_distribute(
         &self,
         low_key_leaf: &mut Option<Shared<Leaf<K, V>>>,
         high_key_leaf: &mut Option<Shared<Leaf<K, V>>>,
-    ) {
-        let metadata = unsafe {
-            self.metadata
-                .fetch_update(AcqRel, Acquire, |p| {
-                    if Dimension::frozen(p) {
-                        None
-                    } else {
-                        Some(Dimension::freeze(p))
-                    }
-                })
-                .unwrap_unchecked()
+    ) -> bool {
+        let Ok(metadata) = self.metadata.fetch_update(AcqRel, Acquire, |p| {
+            if Dimension::frozen(p) {
+                None
+            } else {
+                Some(Dimension::freeze(p))
+            }
+        }) else {
+            return false;
         };
 
         let boundary = Self::optimal_boundary(metadata);
@@ -475,6 +695,7 @@ where
                     .insert_unchecked(k.clone(), v.clone(), i - boundary);
             };
         }
+        true
     }
 
     /// Returns the recommended number of entries that the left-side node shall store when a
@@ -516,8 +737,7 @@ where
     /// Searches for a slot in which the key is stored.
     fn search_slot<Q>(&self, key: &Q, mut mutable_metadata: usize) -> Option<usize>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         let mut min_max_rank = DIMENSION.removed_rank();
         let mut max_min_rank = 0;
@@ -598,10 +818,52 @@ where
                 continue;
             }
 
-            return InsertResult::Success;
+            return InsertResult::Success(self.value_at(free_slot_index));
+        }
+    }
+
+    /// Post-processing after writing a new value for an existing entry into a free slot.
+    ///
+    /// Returns `false` if the old entry was removed or the [`Leaf`] was frozen in the meantime;
+    /// the free slot is rolled back in that case.
+    fn post_modify(&self, index: usize, free_slot_index: usize, mut prev_metadata: usize) -> bool {
+        loop {
+            let rank = DIMENSION.rank(prev_metadata, index);
+            if rank == DIMENSION.removed_rank() || Dimension::frozen(prev_metadata) {
+                self.take(free_slot_index);
+                self.metadata
+                    .fetch_and(!DIMENSION.rank_mask(free_slot_index), Relaxed);
+                return false;
+            }
+
+            // Hand over the rank of the old entry to the new one.
+            let new_metadata = DIMENSION.augment(
+                DIMENSION.augment(prev_metadata, free_slot_index, rank),
+                index,
+                DIMENSION.removed_rank(),
+            );
+            if let Err(actual) =
+                self.metadata
+                    .compare_exchange(prev_metadata, new_metadata, AcqRel, Acquire)
+            {
+                prev_metadata = actual;
+                continue;
+            }
+            return true;
         }
     }
 
+    /// Drops the superseded entry and makes the slot vacant.
+    fn vacate(&self, index: usize) {
+        debug_assert_eq!(
+            DIMENSION.rank(self.metadata.load(Relaxed), index),
+            DIMENSION.removed_rank()
+        );
+        self.take(index);
+        self.metadata
+            .fetch_and(!DIMENSION.rank_mask(index), Release);
+    }
+
     fn rollback(&self, index: usize) -> InsertResult<K, V> {
         let (key, val) = self.take(index);
         let result = self
@@ -619,10 +881,9 @@ where
 
     fn compare<Q>(&self, index: usize, key: &Q) -> Ordering
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
-        self.key_at(index).borrow().cmp(key)
+        key.compare(self.key_at(index)).reverse()
     }
 
     fn take(&self, index: usize) -> (K, V) {
@@ -805,13 +1066,45 @@ pub const DIMENSION: Dimension = match usize::BITS / 8 {
     },
 };
 
-/// Each constructed entry in an `EntryArray` is never dropped until the [`Leaf`] is dropped.
+/// Each constructed entry in an `EntryArray` is never dropped until the [`Leaf`] is dropped or
+/// the entry is superseded by [`Leaf::modify`].
 pub type EntryArray<K, V> = (
     [MaybeUninit<K>; DIMENSION.num_entries],
     [MaybeUninit<V>; DIMENSION.num_entries],
 );
 
+/// An entry superseded by [`Leaf::modify`] which is dropped once no readers can reach it.
+struct Superseded<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    leaf: Shared<Leaf<K, V>>,
+    index: usize,
+}
+
+impl<K, V> Superseded<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Drops the entry and makes the slot vacant.
+    fn vacate(self) {
+        self.leaf.vacate(self.index);
+    }
+}
+
+// The entry is only accessed by the thread executing the deferred closure, in the same manner as
+// retired `Leaf` instances are dropped.
+unsafe impl<K, V> Sync for Superseded<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+}
+
 /// Leaf scanner.
+#[derive(Clone)]
 pub struct Scanner<'l, K, V>
 where
     K: 'static + Clone + Ord,
@@ -840,8 +1133,7 @@ where
     #[inline]
     pub(super) fn max_less<Q>(leaf: &'l Leaf<K, V>, key: &Q) -> Option<Scanner<'l, K, V>>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         let metadata = leaf.metadata.load(Acquire);
         let index = leaf.max_less(metadata, key);
@@ -874,6 +1166,12 @@ where
         ))
     }
 
+    /// Returns a reference to the [`Leaf`] being scanned.
+    #[inline]
+    pub(super) fn leaf(&self) -> &'l Leaf<K, V> {
+        self.leaf
+    }
+
     /// Returns a reference to the max key.
     #[inline]
     pub(super) fn max_key(&self) -> Option<&'l K> {
@@ -888,11 +1186,12 @@ where
         guard: &'g Guard,
     ) -> Option<Scanner<'g, K, V>>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         let mut next_leaf_ptr = self.leaf.next_ptr(Acquire, guard);
         while let Some(next_leaf_ref) = next_leaf_ptr.as_ref() {
+            // Scans are memory-latency-bound, so fetch the leaf after the next in advance.
+            next_leaf_ref.prefetch_next(guard);
             let mut leaf_scanner = Scanner::new(next_leaf_ref);
             if let Some(key) = min_allowed_key {
                 if !self.leaf.is_clear(Relaxed) {
@@ -901,7 +1200,7 @@ where
                     // There is a chance that the current leaf has been deleted, and smaller
                     // keys have been inserted into the next leaf.
                     while let Some(entry) = leaf_scanner.next() {
-                        if key.cmp(entry.0.borrow()) == Ordering::Less {
+                        if key.compare(entry.0) == Ordering::Less {
                             return Some(leaf_scanner);
                         }
                     }
@@ -959,11 +1258,50 @@ where
     }
 }
 
+/// Prefetches the first cache lines of the instance that the pointer points to.
+///
+/// Prefetching is a hint that never faults, therefore the pointer does not have to be valid.
+#[inline]
+fn prefetch<T>(ptr: *const T) {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse"
+    ))]
+    {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::{_mm_prefetch, _MM_HINT_T0};
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+
+        /// The size of a cache line.
+        const CACHE_LINE_SIZE: usize = 64;
+
+        /// The maximum number of bytes to prefetch.
+        const MAX_PREFETCH_SIZE: usize = CACHE_LINE_SIZE * 8;
+
+        if ptr.is_null() {
+            return;
+        }
+        let ptr = ptr.cast::<i8>();
+        for offset in (0..size_of::<T>().min(MAX_PREFETCH_SIZE)).step_by(CACHE_LINE_SIZE) {
+            unsafe {
+                _mm_prefetch::<_MM_HINT_T0>(ptr.wrapping_add(offset));
+            }
+        }
+    }
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse"
+    )))]
+    let _ = ptr;
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::atomic::AtomicBool;
+    use crate::ebr::Tag;
     use proptest::prelude::*;
-    use std::sync::atomic::AtomicBool;
     use tokio::sync::Barrier;
 
     #[cfg_attr(miri, ignore)]
@@ -972,14 +1310,21 @@ mod test {
         let leaf: Leaf<String, String> = Leaf::new();
         assert!(matches!(
             leaf.insert("MY GOODNESS!".to_owned(), "OH MY GOD!!".to_owned()),
-            InsertResult::Success
+            InsertResult::Success(..)
         ));
         assert!(matches!(
             leaf.insert("GOOD DAY".to_owned(), "OH MY GOD!!".to_owned()),
-            InsertResult::Success
+            InsertResult::Success(..)
         ));
-        assert_eq!(leaf.search("MY GOODNESS!").unwrap(), "OH MY GOD!!");
-        assert_eq!(leaf.search("GOOD DAY").unwrap(), "OH MY GOD!!");
+        assert_eq!(
+            leaf.search_entry("MY GOODNESS!").map(|(_, v)| v).unwrap(),
+            "OH MY GOD!!"
+        );
+        assert_eq!(
+            leaf.search_entry("GOOD DAY").map(|(_, v)| v).unwrap(),
+            "OH MY GOD!!"
+        );
+        assert_eq!(leaf.len(), 2);
 
         for i in 0..DIMENSION.num_entries {
             if let InsertResult::Full(k, v) = leaf.insert(i.to_string(), i.to_string()) {
@@ -988,8 +1333,12 @@ mod test {
                 assert_eq!(v, i.to_string());
                 break;
             }
-            assert_eq!(leaf.search(&i.to_string()).unwrap(), &i.to_string());
+            assert_eq!(
+                leaf.search_entry(&i.to_string()).map(|(_, v)| v).unwrap(),
+                &i.to_string()
+            );
         }
+        assert_eq!(leaf.len(), DIMENSION.num_entries);
 
         for i in 0..DIMENSION.num_entries {
             let result = leaf.remove_if(&i.to_string(), &mut |_| i >= 10);
@@ -1008,13 +1357,13 @@ mod test {
             leaf.remove_if("GOOD DAY", &mut |v| v == "OH MY GOD!!"),
             RemoveResult::Success
         );
-        assert!(leaf.search("GOOD DAY").is_none());
+        assert!(leaf.search_entry("GOOD DAY").map(|(_, v)| v).is_none());
         assert_eq!(
             leaf.remove_if("MY GOODNESS!", &mut |_| true),
             RemoveResult::Success
         );
-        assert!(leaf.search("MY GOODNESS!").is_none());
-        assert!(leaf.search("1").is_some());
+        assert!(leaf.search_entry("MY GOODNESS!").map(|(_, v)| v).is_none());
+        assert!(leaf.search_entry("1").map(|(_, v)| v).is_some());
         assert!(matches!(
             leaf.insert("1".to_owned(), "1".to_owned()),
             InsertResult::Duplicate(..)
@@ -1038,6 +1387,7 @@ mod test {
             }
         }
 
+        assert_eq!(leaf.len(), 0);
         assert!(matches!(
             leaf.insert("200".to_owned(), "200".to_owned()),
             InsertResult::Retired(..)
@@ -1049,7 +1399,7 @@ mod test {
     fn calculate_boundary() {
         let leaf: Leaf<usize, usize> = Leaf::new();
         for i in 0..DIMENSION.num_entries {
-            assert!(matches!(leaf.insert(i, i), InsertResult::Success));
+            assert!(matches!(leaf.insert(i, i), InsertResult::Success(..)));
         }
         assert_eq!(
             Leaf::<usize, usize>::optimal_boundary(leaf.metadata.load(Relaxed)),
@@ -1058,7 +1408,7 @@ mod test {
 
         let leaf: Leaf<usize, usize> = Leaf::new();
         for i in (0..DIMENSION.num_entries).rev() {
-            assert!(matches!(leaf.insert(i, i), InsertResult::Success));
+            assert!(matches!(leaf.insert(i, i), InsertResult::Success(..)));
         }
         assert_eq!(
             Leaf::<usize, usize>::optimal_boundary(leaf.metadata.load(Relaxed)),
@@ -1070,10 +1420,10 @@ mod test {
             if i < DIMENSION.num_entries / 2 {
                 assert!(matches!(
                     leaf.insert(usize::MAX - i, usize::MAX - i),
-                    InsertResult::Success
+                    InsertResult::Success(..)
                 ));
             } else {
-                assert!(matches!(leaf.insert(i, i), InsertResult::Success));
+                assert!(matches!(leaf.insert(i, i), InsertResult::Success(..)));
             }
         }
         assert_eq!(
@@ -1086,21 +1436,34 @@ mod test {
     #[test]
     fn special() {
         let leaf: Leaf<usize, usize> = Leaf::new();
-        assert!(matches!(leaf.insert(11, 17), InsertResult::Success));
-        assert!(matches!(leaf.insert(17, 11), InsertResult::Success));
+        assert!(matches!(leaf.insert(11, 17), InsertResult::Success(..)));
+        assert!(matches!(leaf.insert(17, 11), InsertResult::Success(..)));
 
         let mut leaf1 = None;
         let mut leaf2 = None;
-        leaf.freeze_and_distribute(&mut leaf1, &mut leaf2);
-        assert_eq!(leaf1.as_ref().and_then(|l| l.search(&11)), Some(&17));
-        assert_eq!(leaf1.as_ref().and_then(|l| l.search(&17)), Some(&11));
+        assert!(leaf.freeze_and_distribute(&mut leaf1, &mut leaf2));
+        assert!(leaf.is_frozen());
+        assert!(!leaf.freeze_and_distribute(&mut None, &mut None));
+        assert_eq!(
+            leaf1
+                .as_ref()
+                .and_then(|l| l.search_entry(&11).map(|(_, v)| v)),
+            Some(&17)
+        );
+        assert_eq!(
+            leaf1
+                .as_ref()
+                .and_then(|l| l.search_entry(&17).map(|(_, v)| v)),
+            Some(&11)
+        );
         assert!(leaf2.is_none());
         assert!(matches!(leaf.insert(1, 7), InsertResult::Frozen(..)));
         assert_eq!(leaf.remove_if(&17, &mut |_| true), RemoveResult::Frozen);
         assert!(matches!(leaf.insert(3, 5), InsertResult::Frozen(..)));
 
         assert!(leaf.thaw());
-        assert!(matches!(leaf.insert(1, 7), InsertResult::Success));
+        assert!(!leaf.is_frozen());
+        assert!(matches!(leaf.insert(1, 7), InsertResult::Success(..)));
 
         assert_eq!(leaf.remove_if(&1, &mut |_| true), RemoveResult::Success);
         assert_eq!(leaf.remove_if(&17, &mut |_| true), RemoveResult::Success);
@@ -1109,6 +1472,92 @@ mod test {
         assert!(matches!(leaf.insert(5, 3), InsertResult::Retired(..)));
     }
 
+    #[test]
+    fn prefetch_next() {
+        let guard = Guard::new();
+        let leaf: Leaf<usize, usize> = Leaf::new();
+        leaf.prefetch_next(&guard);
+        let next_leaf: Shared<Leaf<usize, usize>> = Shared::new(Leaf::new());
+        assert!(matches!(next_leaf.insert(1, 1), InsertResult::Success(..)));
+        leaf.link.swap((Some(next_leaf), Tag::None), Release);
+        leaf.prefetch_next(&guard);
+        let scanner = Scanner::new(&leaf);
+        let next_scanner = scanner.jump::<usize>(None, &guard).unwrap();
+        assert_eq!(next_scanner.get(), Some((&1, &1)));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn modify() {
+        let leaf: Shared<Leaf<usize, usize>> = Shared::new(Leaf::new());
+        let guard = Guard::new();
+        let leaf_ptr = leaf.get_guarded_ptr(&guard);
+        assert!(matches!(
+            Leaf::modify(leaf_ptr, &1, &mut |v| Some(v + 1), &guard),
+            ModifyResult::Fail
+        ));
+        assert!(matches!(leaf.insert(1, 1), InsertResult::Success(..)));
+        assert!(matches!(leaf.insert(2, 2), InsertResult::Success(..)));
+        assert!(matches!(
+            Leaf::modify(leaf_ptr, &1, &mut |_| None, &guard),
+            ModifyResult::Fail
+        ));
+
+        let old = leaf.search_entry(&1).map(|(_, v)| v).unwrap();
+        for i in 2..DIMENSION.num_entries {
+            assert!(matches!(
+                Leaf::modify(leaf_ptr, &1, &mut |v| Some(v + 1), &guard),
+                ModifyResult::Success
+            ));
+            assert_eq!(leaf.search_entry(&1).map(|(_, v)| v), Some(&i));
+        }
+        assert_eq!(*old, 1);
+        assert!(matches!(
+            Leaf::modify(leaf_ptr, &1, &mut |v| Some(v + 1), &guard),
+            ModifyResult::Full(1, v) if v == DIMENSION.num_entries - 1
+        ));
+
+        let mut scanner = Scanner::new(&leaf);
+        assert_eq!(scanner.next(), Some((&1, &(DIMENSION.num_entries - 1))));
+        assert_eq!(scanner.next(), Some((&2, &2)));
+        assert!(scanner.next().is_none());
+        drop(guard);
+
+        // The superseded slots are made vacant once the readers are gone.
+        loop {
+            let guard = Guard::new();
+            if matches!(
+                Leaf::modify(
+                    leaf.get_guarded_ptr(&guard),
+                    &1,
+                    &mut |v| Some(v + 1),
+                    &guard
+                ),
+                ModifyResult::Success
+            ) {
+                break;
+            }
+            guard.accelerate();
+        }
+        assert_eq!(
+            leaf.search_entry(&1).map(|(_, v)| v),
+            Some(&DIMENSION.num_entries)
+        );
+        assert_eq!(leaf.len(), 2);
+
+        let guard = Guard::new();
+        assert!(leaf.freeze());
+        assert!(matches!(
+            Leaf::modify(
+                leaf.get_guarded_ptr(&guard),
+                &2,
+                &mut |v| Some(v + 1),
+                &guard
+            ),
+            ModifyResult::Frozen
+        ));
+    }
+
     proptest! {
         #[cfg_attr(miri, ignore)]
         #[test]
@@ -1116,7 +1565,7 @@ mod test {
             let leaf: Leaf<usize, usize> = Leaf::new();
             assert!(leaf.is_empty());
             for i in 0..insert {
-                assert!(matches!(leaf.insert(i, i), InsertResult::Success));
+                assert!(matches!(leaf.insert(i, i), InsertResult::Success(..)));
                 if i != 0 {
                     let result = leaf.max_less(leaf.metadata.load(Relaxed), &i);
                     assert_eq!(*leaf.key_at(result), i - 1);
@@ -1137,7 +1586,7 @@ mod test {
                 assert_eq!(result.0, Some((&i, &i)));
             }
             for i in 0..insert {
-                assert_eq!(*leaf.search(&i).unwrap(), i);
+                assert_eq!(*leaf.search_entry(&i).map(|(_, v)| v).unwrap(), i);
             }
             if insert == DIMENSION.num_entries {
                 assert!(matches!(leaf.insert(usize::MAX, usize::MAX), InsertResult::Full(..)));
@@ -1179,8 +1628,8 @@ mod test {
                 task_handles.push(tokio::spawn(async move {
                     barrier_clone.wait().await;
                     let inserted = match leaf_clone.insert(t, t) {
-                        InsertResult::Success => {
-                            assert_eq!(*leaf_clone.search(&t).unwrap(), t);
+                        InsertResult::Success(..) => {
+                            assert_eq!(*leaf_clone.search_entry(&t).map(|(_, v)| v).unwrap(), t);
                             true
                         }
                         InsertResult::Duplicate(_, _)
@@ -1209,7 +1658,7 @@ mod test {
                     barrier_clone.wait().await;
                     assert_eq!((*full_clone).load(Relaxed), num_excess);
                     if inserted {
-                        assert_eq!(*leaf_clone.search(&t).unwrap(), t);
+                        assert_eq!(*leaf_clone.search_entry(&t).map(|(_, v)| v).unwrap(), t);
                     }
                     {
                         let scanner = Scanner::new(&leaf_clone);
@@ -1252,7 +1701,7 @@ mod test {
                     task_handles.push(tokio::spawn(async move {
                         {
                             barrier_clone.wait().await;
-                            if let InsertResult::Success = leaf_clone.insert(k, k) {
+                            if let InsertResult::Success(..) = leaf_clone.insert(k, k) {
                                 assert!(!inserted_clone.swap(true, Relaxed));
                             }
                         }
@@ -1263,11 +1712,17 @@ mod test {
                                     let _result = leaf_clone.insert(i, i);
                                 }
                                 assert!(!leaf_clone.is_retired());
-                                assert_eq!(leaf_clone.search(&k).unwrap(), &k);
+                                assert_eq!(
+                                    leaf_clone.search_entry(&k).map(|(_, v)| v).unwrap(),
+                                    &k
+                                );
                             }
                             for i in 0..workload_size {
                                 let _result = leaf_clone.remove_if(&i, &mut |v| *v != k);
-                                assert_eq!(leaf_clone.search(&k).unwrap(), &k);
+                                assert_eq!(
+                                    leaf_clone.search_entry(&k).map(|(_, v)| v).unwrap(),
+                                    &k
+                                );
                             }
                         }
                     }));
@@ -1,16 +1,16 @@
-use super::leaf::{InsertResult, RemoveResult, Scanner, DIMENSION};
+use super::leaf::{InsertResult, ModifyResult, RemoveResult, Scanner, DIMENSION};
 use super::node::Node;
 use super::Leaf;
+use crate::atomic::{AtomicPtr, AtomicU8};
 use crate::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
 use crate::exit_guard::ExitGuard;
 use crate::wait_queue::{DeriveAsyncWait, WaitQueue};
+use crate::Comparable;
 use crate::LinkedList;
-use std::borrow::Borrow;
 use std::cmp::Ordering::{Equal, Greater, Less};
 use std::ops::{Bound, RangeBounds};
 use std::ptr;
 use std::sync::atomic::Ordering::{self, AcqRel, Acquire, Relaxed, Release};
-use std::sync::atomic::{AtomicPtr, AtomicU8};
 
 /// [`Tag::First`] indicates the corresponding node has retired.
 pub const RETIRED: Tag = Tag::First;
@@ -116,8 +116,19 @@ where
     #[inline]
     pub(super) fn search<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<&'g V>
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
+    {
+        self.search_entry(key, guard).map(|(_, v)| v)
+    }
+
+    /// Searches for an entry associated with the given key, and returns the stored key and the
+    /// value.
+    #[inline]
+    pub(super) fn search_entry<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<(&'g K, &'g V)>
+    where
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         loop {
             let (child, metadata) = self.children.min_greater_equal(key);
@@ -133,7 +144,7 @@ where
                         // Consequently, the reader may miss keys in the low key leaf.
                         //
                         // Resolution: metadata validation.
-                        return child.search(key);
+                        return child.search_entry(key);
                     }
                 }
 
@@ -145,7 +156,7 @@ where
                 let unbounded_ptr = self.unbounded_child.load(Acquire, guard);
                 if let Some(unbounded) = unbounded_ptr.as_ref() {
                     if self.children.validate(metadata) {
-                        return unbounded.search(key);
+                        return unbounded.search_entry(key);
                     }
                 } else {
                     return None;
@@ -154,6 +165,29 @@ where
         }
     }
 
+    /// Returns the [`Leaf`] that the given key belongs to.
+    #[inline]
+    pub(super) fn search_leaf<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Ptr<'g, Leaf<K, V>>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        loop {
+            let (child, metadata) = self.children.min_greater_equal(key);
+            if let Some((_, child)) = child {
+                let child_ptr = child.load(Acquire, guard);
+                if !child_ptr.is_null() && self.children.validate(metadata) {
+                    // Data race resolution - see `LeafNode::search`.
+                    return child_ptr;
+                }
+            } else {
+                let unbounded_ptr = self.unbounded_child.load(Acquire, guard);
+                if unbounded_ptr.is_null() || self.children.validate(metadata) {
+                    return unbounded_ptr;
+                }
+            }
+        }
+    }
+
     /// Returns the minimum key entry.
     #[inline]
     pub(super) fn min<'g>(&self, guard: &'g Guard) -> Option<Scanner<'g, K, V>> {
@@ -182,6 +216,38 @@ where
         }
     }
 
+    /// Returns a [`Scanner`] for the [`Leaf`] that has no upper key bound.
+    ///
+    /// The [`Leaf`] may not contain any entries if all of them have been removed.
+    #[inline]
+    pub(super) fn max<'g>(&self, guard: &'g Guard) -> Option<Scanner<'g, K, V>> {
+        self.unbounded_child
+            .load(Acquire, guard)
+            .as_ref()
+            .map(Scanner::new)
+    }
+
+    /// Returns a [`Scanner`] for a randomly chosen [`Leaf`].
+    #[inline]
+    pub(super) fn random<'g, R: FnMut(usize) -> usize>(
+        &self,
+        rng: &mut R,
+        guard: &'g Guard,
+    ) -> Option<Scanner<'g, K, V>> {
+        let num_children = self.children.len() + 1;
+        let index = rng(num_children) % num_children;
+        if let Some(child) = Scanner::new(&self.children)
+            .nth(index)
+            .and_then(|(_, child)| child.load(Acquire, guard).as_ref())
+        {
+            return Some(Scanner::new(child));
+        }
+        self.unbounded_child
+            .load(Acquire, guard)
+            .as_ref()
+            .map(Scanner::new)
+    }
+
     /// Returns a [`Scanner`] pointing to an entry that is close enough to the entry with the
     /// maximum key among those keys smaller than the given key.
     ///
@@ -190,8 +256,8 @@ where
     #[inline]
     pub(super) fn max_le_appr<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<Scanner<'g, K, V>>
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         loop {
             if let Some(scanner) = Scanner::max_less(&self.children, key) {
@@ -219,12 +285,12 @@ where
         min_scanner.next();
         loop {
             if let Some((k, _)) = min_scanner.get() {
-                if k.borrow() <= key {
+                if key.compare(k).is_ge() {
                     return Some(min_scanner);
                 }
                 break;
             }
-            min_scanner = min_scanner.jump(None, guard)?;
+            min_scanner = min_scanner.jump::<K>(None, guard)?;
         }
 
         None
@@ -236,6 +302,7 @@ where
     ///
     /// Returns an error if a retry is required.
     #[inline]
+    #[allow(clippy::too_many_lines)]
     pub(super) fn insert<D: DeriveAsyncWait>(
         &self,
         mut key: K,
@@ -252,11 +319,11 @@ where
                         // Data race resolution - see `LeafNode::search`.
                         let insert_result = child_ref.insert(key, val);
                         match insert_result {
-                            InsertResult::Success
+                            InsertResult::Success(..)
                             | InsertResult::Duplicate(..)
                             | InsertResult::Retry(..) => return Ok(insert_result),
                             InsertResult::Full(k, v) | InsertResult::Retired(k, v) => {
-                                let split_result = self.split_leaf(
+                                let split_result = match self.split_leaf(
                                     k,
                                     v,
                                     Some(child_key),
@@ -264,7 +331,17 @@ where
                                     child,
                                     async_wait,
                                     guard,
-                                )?;
+                                ) {
+                                    Ok(split_result) => split_result,
+                                    Err((k, v)) => {
+                                        if self.can_retry(async_wait) {
+                                            key = k;
+                                            val = v;
+                                            continue;
+                                        }
+                                        return Err((k, v));
+                                    }
+                                };
                                 if let InsertResult::Retry(k, v) = split_result {
                                     key = k;
                                     val = v;
@@ -274,7 +351,12 @@ where
                             }
                             InsertResult::Frozen(k, v) => {
                                 // The `Leaf` is being split: retry.
-                                self.wait(async_wait);
+                                child_ref.wait(async_wait);
+                                if self.can_retry(async_wait) {
+                                    key = k;
+                                    val = v;
+                                    continue;
+                                }
                                 return Err((k, v));
                             }
                         };
@@ -308,11 +390,11 @@ where
                 }
                 let insert_result = unbounded.insert(key, val);
                 match insert_result {
-                    InsertResult::Success
+                    InsertResult::Success(..)
                     | InsertResult::Duplicate(..)
                     | InsertResult::Retry(..) => return Ok(insert_result),
                     InsertResult::Full(k, v) | InsertResult::Retired(k, v) => {
-                        let split_result = self.split_leaf(
+                        let split_result = match self.split_leaf(
                             k,
                             v,
                             None,
@@ -320,7 +402,17 @@ where
                             &self.unbounded_child,
                             async_wait,
                             guard,
-                        )?;
+                        ) {
+                            Ok(split_result) => split_result,
+                            Err((k, v)) => {
+                                if self.can_retry(async_wait) {
+                                    key = k;
+                                    val = v;
+                                    continue;
+                                }
+                                return Err((k, v));
+                            }
+                        };
                         if let InsertResult::Retry(k, v) = split_result {
                             key = k;
                             val = v;
@@ -329,7 +421,12 @@ where
                         return Ok(split_result);
                     }
                     InsertResult::Frozen(k, v) => {
-                        self.wait(async_wait);
+                        unbounded.wait(async_wait);
+                        if self.can_retry(async_wait) {
+                            key = k;
+                            val = v;
+                            continue;
+                        }
                         return Err((k, v));
                     }
                 };
@@ -352,8 +449,7 @@ where
         guard: &Guard,
     ) -> Result<RemoveResult, ()>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
     {
         loop {
             let (child, metadata) = self.children.min_greater_equal(key);
@@ -366,7 +462,7 @@ where
                         if result == RemoveResult::Frozen {
                             // When a `Leaf` is frozen, its entries may be being copied to new
                             // `Leaves`.
-                            self.wait(async_wait);
+                            child.wait(async_wait);
                             return Err(());
                         } else if result == RemoveResult::Retired {
                             return Ok(self.coalesce(guard));
@@ -386,7 +482,7 @@ where
                 }
                 let result = unbounded.remove_if(key, condition);
                 if result == RemoveResult::Frozen {
-                    self.wait(async_wait);
+                    unbounded.wait(async_wait);
                     return Err(());
                 } else if result == RemoveResult::Retired {
                     return Ok(self.coalesce(guard));
@@ -397,6 +493,95 @@ where
         }
     }
 
+    /// Replaces the value associated with the given key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a retry is required.
+    #[inline]
+    pub(super) fn modify<Q, F: FnMut(&V) -> Option<V>, D: DeriveAsyncWait>(
+        &self,
+        key: &Q,
+        updater: &mut F,
+        async_wait: &mut D,
+        guard: &Guard,
+    ) -> Result<ModifyResult<K, V>, ()>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        loop {
+            let (child, metadata) = self.children.min_greater_equal(key);
+            if let Some((child_key, child)) = child {
+                let child_ptr = child.load(Acquire, guard);
+                if let Some(child_ref) = child_ptr.as_ref() {
+                    if self.children.validate(metadata) {
+                        // Data race resolution - see `LeafNode::search`.
+                        match Leaf::modify(child_ptr, key, updater, guard) {
+                            ModifyResult::Full(k, v) => {
+                                // The `Leaf` needs to be split in order to make a vacant slot.
+                                match self.split_leaf(
+                                    k,
+                                    v,
+                                    Some(child_key),
+                                    child_ptr,
+                                    child,
+                                    async_wait,
+                                    guard,
+                                ) {
+                                    Ok(InsertResult::Retry(..)) => continue,
+                                    Ok(InsertResult::Full(k, v)) => {
+                                        return Ok(ModifyResult::Full(k, v))
+                                    }
+                                    Ok(_) => return Ok(ModifyResult::Retired),
+                                    Err(_) => return Err(()),
+                                }
+                            }
+                            ModifyResult::Frozen => {
+                                child_ref.wait(async_wait);
+                                return Err(());
+                            }
+                            result => return Ok(result),
+                        }
+                    }
+                }
+                // It is not a hot loop - see `LeafNode::search`.
+                continue;
+            }
+            let unbounded_ptr = self.unbounded_child.load(Acquire, guard);
+            if let Some(unbounded) = unbounded_ptr.as_ref() {
+                debug_assert!(unbounded_ptr.tag() == Tag::None);
+                if !self.children.validate(metadata) {
+                    // Data race resolution - see `LeafNode::search`.
+                    continue;
+                }
+                match Leaf::modify(unbounded_ptr, key, updater, guard) {
+                    ModifyResult::Full(k, v) => {
+                        match self.split_leaf(
+                            k,
+                            v,
+                            None,
+                            unbounded_ptr,
+                            &self.unbounded_child,
+                            async_wait,
+                            guard,
+                        ) {
+                            Ok(InsertResult::Retry(..)) => continue,
+                            Ok(InsertResult::Full(k, v)) => return Ok(ModifyResult::Full(k, v)),
+                            Ok(_) => return Ok(ModifyResult::Retired),
+                            Err(_) => return Err(()),
+                        }
+                    }
+                    ModifyResult::Frozen => {
+                        unbounded.wait(async_wait);
+                        return Err(());
+                    }
+                    result => return Ok(result),
+                }
+            }
+            return Ok(ModifyResult::Fail);
+        }
+    }
+
     /// Removes a range of entries.
     ///
     /// Returns the number of remaining children.
@@ -607,7 +792,7 @@ where
         // Unfreeze both leaves.
         if let Some(origin_leaf) = self.split_op.origin_leaf.swap((None, Tag::None), Relaxed).0 {
             // Make the origin leaf unreachable before making the new leaves updatable.
-            origin_leaf.delete_self(Relaxed);
+            origin_leaf.delete_frozen();
             let _: bool = origin_leaf.release(guard);
         }
         let low_key_leaf = self
@@ -695,8 +880,8 @@ where
     #[inline]
     pub(super) fn cleanup_link<'g, Q>(&self, key: &Q, tranverse_max: bool, guard: &'g Guard) -> bool
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         let scanner = if tranverse_max {
             if let Some(unbounded) = self.unbounded_child.load(Acquire, guard).as_ref() {
@@ -720,10 +905,72 @@ where
 
         // It *would* be the maximum leaf node among those that containing keys smaller than the
         // target key. Hopefully, two jumps will be sufficient.
-        scanner.jump(None, guard).map(|s| s.jump(None, guard));
+        scanner
+            .jump::<K>(None, guard)
+            .map(|s| s.jump::<K>(None, guard));
+        true
+    }
+
+    /// Links the minimum [`Leaf`] of `next` to the [`Leaf`] that has no upper key bound.
+    ///
+    /// Returns `false` if either [`LeafNode`] does not have a [`Leaf`]. The [`LeafNode`] instances
+    /// must not be accessible to any other threads.
+    pub(super) fn link_leaves(&self, next: &LeafNode<K, V>, guard: &Guard) -> bool {
+        let Some(max_leaf) = self.unbounded_child.load(Acquire, guard).as_ref() else {
+            return false;
+        };
+        let min_leaf = Scanner::new(&next.children)
+            .next()
+            .map_or(&next.unbounded_child, |(_, child)| child);
+        let Some(min_leaf) = min_leaf.get_shared(Acquire, guard) else {
+            return false;
+        };
+
+        // Deleted leaves that have yet to be unlinked are dropped.
+        if let Some(unlinked) = max_leaf
+            .link_ref()
+            .swap((Some(min_leaf), Tag::None), Release)
+            .0
+        {
+            let _: bool = unlinked.release(guard);
+        }
         true
     }
 
+    /// Pushes a [`Leaf`] containing keys greater than those in the [`LeafNode`], and the [`Leaf`]
+    /// becomes the one that has no upper key bound.
+    ///
+    /// Returns the [`Leaf`] back if the [`LeafNode`] is full. The [`LeafNode`] must not be
+    /// accessible to any other threads.
+    pub(super) fn push_leaf(
+        &self,
+        leaf: Shared<Leaf<K, V>>,
+        guard: &Guard,
+    ) -> Result<(), Shared<Leaf<K, V>>> {
+        if let Some(unbounded) = self.unbounded_child.load(Relaxed, guard).as_ref() {
+            let Some(max_key) = unbounded.max_key() else {
+                return Err(leaf);
+            };
+            let result = self
+                .children
+                .insert(max_key.clone(), self.unbounded_child.clone(Relaxed, guard));
+            if !matches!(result, InsertResult::Success(..)) {
+                return Err(leaf);
+            }
+            unbounded
+                .link_ref()
+                .swap((Some(leaf.clone()), Tag::None), Relaxed);
+        }
+        if let Some(unbounded) = self
+            .unbounded_child
+            .swap((Some(leaf), Tag::None), Release)
+            .0
+        {
+            let _: bool = unbounded.release(guard);
+        }
+        Ok(())
+    }
+
     /// Waits for the lock on the [`LeafNode`] to be released.
     #[inline]
     pub(super) fn wait<D: DeriveAsyncWait>(&self, async_wait: &mut D) {
@@ -758,16 +1005,43 @@ where
         async_wait: &mut D,
         guard: &Guard,
     ) -> Result<InsertResult<K, V>, (K, V)> {
-        if !self.try_lock() {
-            self.wait(async_wait);
+        let target = full_leaf_ptr.as_ref().unwrap();
+        let mut low_key_leaf_shared = None;
+        let mut high_key_leaf_shared = None;
+
+        // Freezing the full leaf grants the right to split it, therefore entries are distributed
+        // to two leaves before the `LeafNode` is locked, and the `LeafNode` is only locked to make
+        // the new leaves reachable.
+        let mut exit_guard = ExitGuard::new((true, false), |(rollback, locked)| {
+            if rollback {
+                target.thaw();
+                if locked {
+                    self.split_op.reset();
+                    self.unlock();
+                }
+            }
+        });
+        if !target.freeze_and_distribute(&mut low_key_leaf_shared, &mut high_key_leaf_shared) {
+            // The full leaf is being split by another thread.
+            exit_guard.0 = false;
+            target.wait(async_wait);
             return Err((key, val));
         }
+
+        while !self.try_lock() {
+            if self.latch.load(Relaxed) == RETIRED.into() {
+                return Err((key, val));
+            }
+            self.wait(async_wait);
+            if async_wait.derive().is_some() {
+                return Err((key, val));
+            }
+        }
+        exit_guard.1 = true;
         if self.retired(Relaxed) {
-            self.unlock();
             return Ok(InsertResult::Retired(key, val));
         }
         if full_leaf_ptr != full_leaf.load(Relaxed, guard) {
-            self.unlock();
             return Err((key, val));
         }
 
@@ -784,20 +1058,6 @@ where
                 .store((full_leaf_key as *const K).cast_mut(), Relaxed);
         }
 
-        let target = full_leaf_ptr.as_ref().unwrap();
-        let mut low_key_leaf_shared = None;
-        let mut high_key_leaf_shared = None;
-
-        // Distribute entries to two leaves after make the target retired.
-        let mut exit_guard = ExitGuard::new(true, |rollback| {
-            if rollback {
-                target.thaw();
-                self.split_op.reset();
-                self.unlock();
-            }
-        });
-        target.freeze_and_distribute(&mut low_key_leaf_shared, &mut high_key_leaf_shared);
-
         if let Some(low_key_leaf) = low_key_leaf_shared.take() {
             self.split_op
                 .low_key_leaf
@@ -844,7 +1104,7 @@ where
                 max_key.clone(),
                 self.split_op.low_key_leaf.clone(Relaxed, guard),
             ) {
-                InsertResult::Success => (),
+                InsertResult::Success(..) => (),
                 InsertResult::Duplicate(..)
                 | InsertResult::Frozen(..)
                 | InsertResult::Retry(..) => unreachable!(),
@@ -852,7 +1112,7 @@ where
                     // Need to freeze the other leaf.
                     let frozen = high_key_leaf.freeze();
                     debug_assert!(frozen);
-                    *exit_guard = false;
+                    exit_guard.0 = false;
                     return Ok(InsertResult::Full(key, val));
                 }
             };
@@ -862,7 +1122,7 @@ where
             // If the order is reversed, there emerges a possibility that entries were removed from
             // the replaced leaf node whereas those entries still remain in `unused_leaf`; if that
             // happens, iterators may see the removed entries momentarily.
-            let deleted = target.delete_self(Release);
+            let deleted = target.delete_frozen();
             debug_assert!(deleted);
 
             // Unfreeze the leaf.
@@ -886,7 +1146,7 @@ where
             debug_assert!(result.is_ok());
 
             // Mark the full leaf deleted before making the new one reachable and updatable.
-            let deleted = target.delete_self(Release);
+            let deleted = target.delete_frozen();
             debug_assert!(deleted);
 
             full_leaf
@@ -896,7 +1156,7 @@ where
                 )
                 .0
         };
-        *exit_guard = false;
+        exit_guard.0 = false;
 
         let origin = self.split_op.reset();
         self.unlock();
@@ -908,11 +1168,7 @@ where
     }
 
     /// Tries to coalesce empty or obsolete leaves after a successful removal of an entry.
-    fn coalesce<Q>(&self, guard: &Guard) -> RemoveResult
-    where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
-    {
+    fn coalesce(&self, guard: &Guard) -> RemoveResult {
         let mut uncleaned_leaf = false;
         let mut prev_valid_leaf = None;
         while let Some(lock) = Locker::try_lock(self) {
@@ -923,7 +1179,7 @@ where
                 if leaf.is_retired() {
                     let deleted = leaf.delete_self(Relaxed);
                     debug_assert!(deleted);
-                    let result = self.children.remove_if(entry.0.borrow(), &mut |_| true);
+                    let result = self.children.remove_if(entry.0, &mut |_| true);
                     debug_assert_ne!(result, RemoveResult::Fail);
 
                     // The pointer is nullified after the metadata of `self.children` is updated so
@@ -932,7 +1188,7 @@ where
                         let _: bool = leaf.release(guard);
                         if let Some(prev_leaf) = prev_valid_leaf.as_ref() {
                             // One jump is sufficient.
-                            Scanner::new(*prev_leaf).jump(None, guard);
+                            Scanner::new(*prev_leaf).jump::<K>(None, guard);
                         } else {
                             uncleaned_leaf = true;
                         }
@@ -1010,6 +1266,15 @@ where
         false
     }
 
+    /// Returns `true` if an operation that failed because a [`Leaf`] was being split can be
+    /// retried on the [`LeafNode`] without traversing the tree again.
+    ///
+    /// Synchronous callers have already waited for the split to complete, therefore they can
+    /// retry unless the [`LeafNode`] has been replaced with new ones.
+    fn can_retry<D: DeriveAsyncWait>(&self, async_wait: &mut D) -> bool {
+        async_wait.derive().is_none() && self.latch.load(Acquire) != RETIRED.into()
+    }
+
     /// Tries to lock the [`LeafNode`].
     fn try_lock(&self) -> bool {
         self.latch
@@ -1139,7 +1404,7 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::sync::atomic::AtomicBool;
+    use crate::atomic::AtomicBool;
     use tokio::sync::Barrier;
 
     #[cfg_attr(miri, ignore)]
@@ -1154,7 +1419,7 @@ mod test {
                 &mut (),
                 &guard
             ),
-            Ok(InsertResult::Success)
+            Ok(InsertResult::Success(..))
         ));
         assert!(matches!(
             leaf_node.insert(
@@ -1163,7 +1428,7 @@ mod test {
                 &mut (),
                 &guard
             ),
-            Ok(InsertResult::Success)
+            Ok(InsertResult::Success(..))
         ));
         assert_eq!(
             leaf_node.search("MY GOODNESS!", &guard).unwrap(),
@@ -1208,7 +1473,7 @@ mod test {
                 result = leaf_node.insert(k, k, &mut (), &guard);
             }
             match result.unwrap() {
-                InsertResult::Success => {
+                InsertResult::Success(..) => {
                     assert_eq!(leaf_node.search(&k, &guard), Some(&k));
                     continue;
                 }
@@ -1239,6 +1504,62 @@ mod test {
         }
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn split_while_locked() {
+        let num_entries = DIMENSION.num_entries;
+        let leaf_node: Shared<LeafNode<usize, usize>> = Shared::new(LeafNode::new());
+        for k in 0..num_entries {
+            assert!(matches!(
+                leaf_node.insert(k, k, &mut (), &Guard::new()),
+                Ok(InsertResult::Success(..))
+            ));
+        }
+
+        // The entries of the full leaf are copied while the `LeafNode` is locked.
+        let lock = Locker::try_lock(&leaf_node).unwrap();
+        let leaf_node_clone = leaf_node.clone();
+        let thread_handle = std::thread::spawn(move || {
+            let guard = Guard::new();
+            matches!(
+                leaf_node_clone.insert(num_entries, num_entries, &mut (), &guard),
+                Ok(InsertResult::Success(..))
+            )
+        });
+        let guard = Guard::new();
+        let full_leaf = leaf_node.unbounded_child.load(Acquire, &guard);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+        while !full_leaf.as_ref().unwrap().is_frozen() {
+            assert!(std::time::Instant::now() < deadline);
+            std::thread::yield_now();
+        }
+        assert_eq!(leaf_node.search(&0, &guard), Some(&0));
+        assert!(leaf_node.unbounded_child.load(Acquire, &guard) == full_leaf);
+
+        // Removals wait for the frozen leaf to be replaced.
+        let leaf_node_clone = leaf_node.clone();
+        let remove_handle = std::thread::spawn(move || {
+            let guard = Guard::new();
+            loop {
+                if let Ok(result) =
+                    leaf_node_clone.remove_if::<_, _, _>(&0, &mut |_| true, &mut (), &guard)
+                {
+                    break result;
+                }
+            }
+        });
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(!remove_handle.is_finished());
+        drop(lock);
+
+        assert!(thread_handle.join().unwrap());
+        assert_eq!(remove_handle.join().unwrap(), RemoveResult::Success);
+        assert_eq!(leaf_node.search(&0, &guard), None);
+        for k in 1..=num_entries {
+            assert_eq!(leaf_node.search(&k, &guard), Some(&k));
+        }
+    }
+
     #[cfg_attr(miri, ignore)]
     #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
     async fn parallel() {
@@ -1263,7 +1584,7 @@ mod test {
                         loop {
                             if let Ok(r) = leaf_node_clone.insert(id, id, &mut (), &guard) {
                                 match r {
-                                    InsertResult::Success => {
+                                    InsertResult::Success(..) => {
                                         match leaf_node_clone.insert(id, id, &mut (), &guard) {
                                             Ok(InsertResult::Duplicate(..)) | Err(_) => (),
                                             _ => unreachable!(),
@@ -1353,7 +1674,7 @@ mod test {
                             barrier_clone.wait().await;
                             let guard = Guard::new();
                             match leaf_node_clone.insert(k, k, &mut (), &guard) {
-                                Ok(InsertResult::Success) => {
+                                Ok(InsertResult::Success(..)) => {
                                     assert!(!inserted_clone.swap(true, Relaxed));
                                 }
                                 Ok(InsertResult::Full(_, _) | InsertResult::Retired(_, _)) => {
@@ -1384,8 +1705,11 @@ mod test {
                                     assert_eq!(*k_ref, *v_ref);
                                     assert!(*k_ref <= k);
                                 } else {
-                                    let (k_ref, v_ref) =
-                                        min_scanner.jump(None, &guard).unwrap().get().unwrap();
+                                    let (k_ref, v_ref) = min_scanner
+                                        .jump::<usize>(None, &guard)
+                                        .unwrap()
+                                        .get()
+                                        .unwrap();
                                     assert_eq!(*k_ref, *v_ref);
                                     assert!(*k_ref <= k);
                                 }
@@ -1,9 +1,10 @@
 use super::internal_node::{self, InternalNode};
-use super::leaf::{InsertResult, Leaf, RemoveResult, Scanner};
+use super::leaf::{InsertResult, Leaf, ModifyResult, RemoveResult, Scanner};
 use super::leaf_node::{self, LeafNode};
+use super::Statistics;
 use crate::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
 use crate::wait_queue::DeriveAsyncWait;
-use std::borrow::Borrow;
+use crate::Comparable;
 use std::fmt::{self, Debug};
 use std::ops::RangeBounds;
 use std::sync::atomic::Ordering::{self, Acquire, Relaxed, Release};
@@ -60,8 +61,8 @@ where
     #[inline]
     pub(super) fn search<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<&'g V>
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         match &self {
             Self::Internal(internal_node) => internal_node.search(key, guard),
@@ -69,6 +70,34 @@ where
         }
     }
 
+    /// Searches for an entry associated with the given key, and returns the stored key and the
+    /// value.
+    #[inline]
+    pub(super) fn search_entry<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<(&'g K, &'g V)>
+    where
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
+    {
+        match &self {
+            Self::Internal(internal_node) => internal_node.search_entry(key, guard),
+            Self::Leaf(leaf_node) => leaf_node.search_entry(key, guard),
+        }
+    }
+
+    /// Returns the [`Leaf`] that the given key belongs to.
+    ///
+    /// Returns a null pointer if the [`Leaf`] has not been created.
+    #[inline]
+    pub(super) fn search_leaf<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Ptr<'g, Leaf<K, V>>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        match &self {
+            Self::Internal(internal_node) => internal_node.search_leaf(key, guard),
+            Self::Leaf(leaf_node) => leaf_node.search_leaf(key, guard),
+        }
+    }
+
     /// Returns the minimum key-value pair.
     ///
     /// This method is not linearizable.
@@ -80,6 +109,33 @@ where
         }
     }
 
+    /// Returns a [`Scanner`] for the [`Leaf`] that has no upper key bound.
+    ///
+    /// This method is not linearizable.
+    #[inline]
+    pub(super) fn max<'g>(&self, guard: &'g Guard) -> Option<Scanner<'g, K, V>> {
+        match &self {
+            Self::Internal(internal_node) => internal_node.max(guard),
+            Self::Leaf(leaf_node) => leaf_node.max(guard),
+        }
+    }
+
+    /// Returns a [`Scanner`] for a randomly chosen [`Leaf`].
+    ///
+    /// `rng` is supplied with the number of candidates, and is expected to return a random number
+    /// less than it. This method is not linearizable.
+    #[inline]
+    pub(super) fn random<'g, R: FnMut(usize) -> usize>(
+        &self,
+        rng: &mut R,
+        guard: &'g Guard,
+    ) -> Option<Scanner<'g, K, V>> {
+        match &self {
+            Self::Internal(internal_node) => internal_node.random(rng, guard),
+            Self::Leaf(leaf_node) => leaf_node.random(rng, guard),
+        }
+    }
+
     /// Returns a [`Scanner`] pointing to an entry that is close enough to the entry with the
     /// maximum key among those keys smaller than or equal to the given key.
     ///
@@ -87,8 +143,8 @@ where
     #[inline]
     pub(super) fn max_le_appr<'g, Q>(&self, key: &Q, guard: &'g Guard) -> Option<Scanner<'g, K, V>>
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         match &self {
             Self::Internal(internal_node) => internal_node.max_le_appr(key, guard),
@@ -96,6 +152,74 @@ where
         }
     }
 
+    /// Collects the keys separating sub-trees down to the specified depth in ascending order.
+    ///
+    /// This method is not linearizable.
+    #[cfg(feature = "rayon")]
+    pub(super) fn collect_separators<'g>(
+        &'g self,
+        depth: usize,
+        keys: &mut Vec<&'g K>,
+        guard: &'g Guard,
+    ) {
+        if depth == 0 {
+            return;
+        }
+        match &self {
+            Self::Internal(internal_node) => {
+                for (key, child) in Scanner::new(&internal_node.children) {
+                    if let Some(child) = child.load(Acquire, guard).as_ref() {
+                        child.collect_separators(depth - 1, keys, guard);
+                    }
+                    keys.push(key);
+                }
+                if let Some(child) = internal_node.unbounded_child.load(Acquire, guard).as_ref() {
+                    child.collect_separators(depth - 1, keys, guard);
+                }
+            }
+            Self::Leaf(leaf_node) => {
+                keys.extend(Scanner::new(&leaf_node.children).map(|(key, _)| key));
+            }
+        }
+    }
+
+    /// Collects the statistics of the sub-tree rooted at the node.
+    ///
+    /// This method is not linearizable.
+    pub(super) fn collect_statistics(
+        &self,
+        level: usize,
+        statistics: &mut Statistics,
+        guard: &Guard,
+    ) {
+        if statistics.num_nodes.len() <= level {
+            statistics.num_nodes.resize(level + 1, 0);
+        }
+        statistics.num_nodes[level] += 1;
+        match &self {
+            Self::Internal(internal_node) => {
+                for (_, child) in Scanner::new(&internal_node.children) {
+                    if let Some(child) = child.load(Acquire, guard).as_ref() {
+                        child.collect_statistics(level + 1, statistics, guard);
+                    }
+                }
+                if let Some(child) = internal_node.unbounded_child.load(Acquire, guard).as_ref() {
+                    child.collect_statistics(level + 1, statistics, guard);
+                }
+            }
+            Self::Leaf(leaf_node) => {
+                for (_, leaf) in Scanner::new(&leaf_node.children) {
+                    if let Some(leaf) = leaf.load(Acquire, guard).as_ref() {
+                        statistics.add_leaf(leaf);
+                    }
+                }
+                if let Some(leaf) = leaf_node.max(guard).map(|scanner| scanner.leaf()) {
+                    statistics.add_leaf(leaf);
+                }
+            }
+        }
+    }
+
     /// Inserts a key-value pair.
     #[inline]
     pub(super) fn insert<D: DeriveAsyncWait>(
@@ -121,8 +245,7 @@ where
         guard: &Guard,
     ) -> Result<RemoveResult, ()>
     where
-        K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<K> + ?Sized,
         D: DeriveAsyncWait,
     {
         match &self {
@@ -135,6 +258,25 @@ where
         }
     }
 
+    /// Replaces the value associated with the given key.
+    #[inline]
+    pub(super) fn modify<Q, F: FnMut(&V) -> Option<V>, D>(
+        &self,
+        key: &Q,
+        updater: &mut F,
+        async_wait: &mut D,
+        guard: &Guard,
+    ) -> Result<ModifyResult<K, V>, ()>
+    where
+        Q: Comparable<K> + ?Sized,
+        D: DeriveAsyncWait,
+    {
+        match &self {
+            Self::Internal(internal_node) => internal_node.modify(key, updater, async_wait, guard),
+            Self::Leaf(leaf_node) => leaf_node.modify(key, updater, async_wait, guard),
+        }
+    }
+
     /// Removes a range of entries.
     ///
     /// Returns the number of remaining children.
@@ -305,6 +447,167 @@ where
         true
     }
 
+    /// Links the [`Leaf`] that has no upper key bound in `self` to the minimum [`Leaf`] in `next`.
+    ///
+    /// Returns `false` if either of them does not exist. The trees must not be accessible to any
+    /// other threads.
+    pub(super) fn link_leaves(&self, next: &Self, guard: &Guard) -> bool {
+        let mut internal_nodes = Vec::new();
+        self.border(true, &mut internal_nodes, guard)
+            .zip(next.border(false, &mut internal_nodes, guard))
+            .map_or(false, |(leaf_node, next)| {
+                leaf_node.link_leaves(next, guard)
+            })
+    }
+
+    /// Pushes a [`Leaf`] containing keys greater than those in the [`LeafNode`].
+    ///
+    /// Returns the [`Leaf`] back if the [`LeafNode`] is full. The [`LeafNode`] must not be
+    /// accessible to any other threads.
+    pub(super) fn push_leaf(
+        &self,
+        leaf: Shared<Leaf<K, V>>,
+        guard: &Guard,
+    ) -> Result<(), Shared<Leaf<K, V>>> {
+        match &self {
+            Self::Internal(_) => Err(leaf),
+            Self::Leaf(leaf_node) => leaf_node.push_leaf(leaf, guard),
+        }
+    }
+
+    /// Grafts two trees, and returns the root of the resulting tree.
+    ///
+    /// The keys in `left` must be less than or equal to `max_key`, and those in `right` must be
+    /// greater than `max_key`. The shorter tree is attached to the border of the taller one at the
+    /// level where it fits, therefore only the nodes on the border are visited, and a new root is
+    /// created only if no node on the border can accommodate the shorter tree.
+    ///
+    /// The trees must not be accessible to any other threads, and their leaves must have been
+    /// linked by [`Node::link_leaves`].
+    pub(super) fn graft(
+        left: Shared<Node<K, V>>,
+        right: Shared<Node<K, V>>,
+        max_key: &K,
+        guard: &Guard,
+    ) -> Shared<Node<K, V>> {
+        let mut left_border = Vec::new();
+        let mut right_border = Vec::new();
+        left.get_guarded_ref(guard)
+            .border(true, &mut left_border, guard);
+        right
+            .get_guarded_ref(guard)
+            .border(false, &mut right_border, guard);
+
+        let left_depth = left_border.len() + 1;
+        let right_depth = right_border.len() + 1;
+        if left_depth >= right_depth {
+            // Attach `right` to the max border of `left`, and the node that has no upper key
+            // bound becomes bounded by `max_key`.
+            let mut graft = right;
+            for (index, internal_node) in left_border[..left_depth - right_depth]
+                .iter()
+                .enumerate()
+                .rev()
+            {
+                let fits = left_border[..=index]
+                    .iter()
+                    .all(|n| n.children.max_key().map_or(true, |k| k < max_key));
+                if fits {
+                    if let InsertResult::Success(..) = internal_node.children.insert(
+                        max_key.clone(),
+                        internal_node.unbounded_child.clone(Relaxed, guard),
+                    ) {
+                        if let Some(unbounded) = internal_node
+                            .unbounded_child
+                            .swap((Some(graft), Tag::None), Release)
+                            .0
+                        {
+                            let _: bool = unbounded.release(guard);
+                        }
+                        return left;
+                    }
+                }
+                graft = Self::new_parent(None, graft);
+            }
+            Self::new_parent(Some((max_key, left)), graft)
+        } else {
+            // Attach `left` to the min border of `right`.
+            let mut graft = left;
+            for (index, internal_node) in right_border[..right_depth - left_depth]
+                .iter()
+                .enumerate()
+                .rev()
+            {
+                let fits = right_border[..=index].iter().all(|n| {
+                    Scanner::new(&n.children)
+                        .next()
+                        .map_or(true, |(k, _)| max_key < k)
+                });
+                if fits {
+                    let child = AtomicShared::null();
+                    child.swap((Some(graft), Tag::None), Relaxed);
+                    match internal_node.children.insert(max_key.clone(), child) {
+                        InsertResult::Success(..) => return right,
+                        InsertResult::Duplicate(_, child)
+                        | InsertResult::Full(_, child)
+                        | InsertResult::Frozen(_, child)
+                        | InsertResult::Retired(_, child)
+                        | InsertResult::Retry(_, child) => {
+                            graft = child.into_shared(Relaxed).unwrap();
+                        }
+                    }
+                }
+                graft = Self::new_parent(None, graft);
+            }
+            Self::new_parent(Some((max_key, graft)), right)
+        }
+    }
+
+    /// Pushes the [`InternalNode`] instances on the max or min border of the tree from the top,
+    /// and returns the [`LeafNode`] at the bottom of the border.
+    fn border<'g>(
+        &'g self,
+        max: bool,
+        internal_nodes: &mut Vec<&'g InternalNode<K, V>>,
+        guard: &'g Guard,
+    ) -> Option<&'g LeafNode<K, V>> {
+        let mut node = self;
+        loop {
+            match node {
+                Self::Internal(internal_node) => {
+                    internal_nodes.push(internal_node);
+                    let child = if max {
+                        None
+                    } else {
+                        Scanner::new(&internal_node.children).next()
+                    };
+                    let child = child.map_or(&internal_node.unbounded_child, |(_, c)| c);
+                    node = child.load(Acquire, guard).as_ref()?;
+                }
+                Self::Leaf(leaf_node) => return Some(leaf_node),
+            }
+        }
+    }
+
+    /// Creates a new [`InternalNode`] that has `unbounded` as the child without an upper key
+    /// bound, and `bounded` as the only other child.
+    fn new_parent(
+        bounded: Option<(&K, Shared<Node<K, V>>)>,
+        unbounded: Shared<Node<K, V>>,
+    ) -> Shared<Node<K, V>> {
+        let internal_node = InternalNode::new();
+        if let Some((key, child)) = bounded {
+            let bounded_child = AtomicShared::null();
+            bounded_child.swap((Some(child), Tag::None), Relaxed);
+            let result = internal_node.children.insert(key.clone(), bounded_child);
+            debug_assert!(matches!(result, InsertResult::Success(..)));
+        }
+        internal_node
+            .unbounded_child
+            .swap((Some(unbounded), Tag::None), Relaxed);
+        Shared::new(Self::Internal(internal_node))
+    }
+
     /// Commits an on-going structural change.
     #[inline]
     pub(super) fn commit(&self, guard: &Guard) {
@@ -329,8 +632,8 @@ where
     #[inline]
     pub(super) fn cleanup_link<'g, Q>(&self, key: &Q, traverse_max: bool, guard: &'g Guard) -> bool
     where
-        K: 'g + Borrow<Q>,
-        Q: Ord + ?Sized,
+        K: 'g,
+        Q: Comparable<K> + ?Sized,
     {
         match &self {
             Self::Internal(internal_node) => internal_node.cleanup_link(key, traverse_max, guard),
@@ -0,0 +1,467 @@
+//! [`TreeMultiIndex`] is a concurrent and asynchronous B-plus tree allowing duplicate keys.
+
+use super::tree_index::{self, Range};
+use super::TreeIndex;
+use crate::atomic::AtomicUsize;
+use crate::ebr::Guard;
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::ops::RangeInclusive;
+use std::panic::UnwindSafe;
+use std::sync::atomic::Ordering::Relaxed;
+
+/// Scalable concurrent B-plus tree allowing duplicate keys.
+///
+/// [`TreeMultiIndex`] is a special version of [`TreeIndex`] where each key can be associated
+/// with multiple values. Values associated with the same key are kept in the order of insertion.
+pub struct TreeMultiIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    tree_index: TreeIndex<(K, usize), V>,
+    sequence: AtomicUsize,
+}
+
+/// An iterator over the entries of a [`TreeMultiIndex`].
+pub struct Iter<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    iter: tree_index::Iter<'t, 'g, (K, usize), V>,
+}
+
+/// An iterator over the values associated with a key in a [`TreeMultiIndex`].
+pub struct Values<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    range: Range<'t, 'g, (K, usize), V, RangeInclusive<(K, usize)>>,
+}
+
+impl<K, V> TreeMultiIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Creates an empty [`TreeMultiIndex`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            tree_index: TreeIndex::new(),
+            sequence: AtomicUsize::new(0),
+        }
+    }
+
+    /// Inserts a key-value pair.
+    ///
+    /// The value is placed after all the values that have been associated with the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// treemultiindex.insert(1, 10);
+    /// treemultiindex.insert(1, 11);
+    /// assert_eq!(treemultiindex.count(&1), 2);
+    /// ```
+    #[inline]
+    pub fn insert(&self, key: K, val: V) {
+        let sequence = self.sequence.fetch_add(1, Relaxed);
+        let result = self.tree_index.insert((key, sequence), val);
+        debug_assert!(result.is_ok());
+    }
+
+    /// Inserts a key-value pair.
+    ///
+    /// The value is placed after all the values that have been associated with the key. It is an
+    /// asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    /// let future_insert = treemultiindex.insert_async(1, 10);
+    /// ```
+    #[inline]
+    pub async fn insert_async(&self, key: K, val: V) {
+        let sequence = self.sequence.fetch_add(1, Relaxed);
+        let result = self.tree_index.insert_async((key, sequence), val).await;
+        debug_assert!(result.is_ok());
+    }
+
+    /// Removes all the values associated with the key.
+    ///
+    /// Returns the number of removed values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// treemultiindex.insert(1, 10);
+    /// treemultiindex.insert(1, 11);
+    /// assert_eq!(treemultiindex.remove(&1), 2);
+    /// assert_eq!(treemultiindex.remove(&1), 0);
+    /// ```
+    #[inline]
+    pub fn remove(&self, key: &K) -> usize {
+        self.remove_if(key, |_| true)
+    }
+
+    /// Removes all the values associated with the key.
+    ///
+    /// Returns the number of removed values. It is an asynchronous method returning an
+    /// `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    /// let future_remove = treemultiindex.remove_async(&1);
+    /// ```
+    #[inline]
+    pub async fn remove_async(&self, key: &K) -> usize {
+        self.remove_if_async(key, |_| true).await
+    }
+
+    /// Removes the values associated with the key if they satisfy the given condition.
+    ///
+    /// Returns the number of removed values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// treemultiindex.insert(1, 10);
+    /// treemultiindex.insert(1, 11);
+    /// treemultiindex.insert(1, 12);
+    /// assert_eq!(treemultiindex.remove_if(&1, |v| *v % 2 == 0), 2);
+    /// assert_eq!(treemultiindex.count(&1), 1);
+    /// ```
+    #[inline]
+    pub fn remove_if<F: FnMut(&V) -> bool>(&self, key: &K, mut condition: F) -> usize {
+        let guard = Guard::new();
+        self.tree_index
+            .range(Self::bounds(key), &guard)
+            .filter(|(k, _)| self.tree_index.remove_if(*k, &mut condition))
+            .count()
+    }
+
+    /// Removes the values associated with the key if they satisfy the given condition.
+    ///
+    /// Returns the number of removed values. It is an asynchronous method returning an
+    /// `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    /// let future_remove = treemultiindex.remove_if_async(&1, |v| *v == 0);
+    /// ```
+    #[inline]
+    pub async fn remove_if_async<F: FnMut(&V) -> bool>(&self, key: &K, mut condition: F) -> usize {
+        let keys: Vec<(K, usize)> = {
+            let guard = Guard::new();
+            self.tree_index
+                .range(Self::bounds(key), &guard)
+                .map(|(k, _)| k.clone())
+                .collect()
+        };
+        let mut num_removed = 0;
+        for k in keys {
+            if self.tree_index.remove_if_async(&k, &mut condition).await {
+                num_removed += 1;
+            }
+        }
+        num_removed
+    }
+
+    /// Returns an iterator over the values associated with the key in the order of insertion.
+    ///
+    /// The returned references can survive as long as the associated [`Guard`] is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// treemultiindex.insert(1, 11);
+    /// treemultiindex.insert(2, 20);
+    /// treemultiindex.insert(1, 10);
+    ///
+    /// let guard = Guard::new();
+    /// let mut values = treemultiindex.peek(&1, &guard);
+    /// assert_eq!(values.next(), Some(&11));
+    /// assert_eq!(values.next(), Some(&10));
+    /// assert!(values.next().is_none());
+    /// ```
+    #[inline]
+    pub fn peek<'t, 'g>(&'t self, key: &K, guard: &'g Guard) -> Values<'t, 'g, K, V> {
+        Values {
+            range: self.tree_index.range(Self::bounds(key), guard),
+        }
+    }
+
+    /// Returns `true` if the [`TreeMultiIndex`] contains the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// assert!(!treemultiindex.contains(&1));
+    /// treemultiindex.insert(1, 0);
+    /// assert!(treemultiindex.contains(&1));
+    /// ```
+    #[inline]
+    pub fn contains(&self, key: &K) -> bool {
+        self.peek(key, &Guard::new()).next().is_some()
+    }
+
+    /// Returns the number of values associated with the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// treemultiindex.insert(1, 0);
+    /// treemultiindex.insert(1, 0);
+    /// assert_eq!(treemultiindex.count(&1), 2);
+    /// ```
+    #[inline]
+    pub fn count(&self, key: &K) -> usize {
+        self.tree_index.count_range(Self::bounds(key))
+    }
+
+    /// Clears the [`TreeMultiIndex`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// treemultiindex.insert(1, 0);
+    /// treemultiindex.clear();
+    /// assert!(treemultiindex.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&self) {
+        self.tree_index.clear();
+    }
+
+    /// Returns the number of key-value pairs in the [`TreeMultiIndex`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// treemultiindex.insert(1, 0);
+    /// treemultiindex.insert(1, 1);
+    /// assert_eq!(treemultiindex.len(), 2);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tree_index.len()
+    }
+
+    /// Returns `true` if the [`TreeMultiIndex`] is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// assert!(treemultiindex.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tree_index.is_empty()
+    }
+
+    /// Returns an [`Iter`].
+    ///
+    /// Key-value pairs are sorted by key, and values associated with the same key are in the
+    /// order of insertion. The returned [`Iter`] is a [`FusedIterator`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    /// use scc::ebr::Guard;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::new();
+    ///
+    /// treemultiindex.insert(2, 20);
+    /// treemultiindex.insert(1, 11);
+    /// treemultiindex.insert(1, 10);
+    ///
+    /// let guard = Guard::new();
+    /// let mut iter = treemultiindex.iter(&guard);
+    /// assert_eq!(iter.next(), Some((&1, &11)));
+    /// assert_eq!(iter.next(), Some((&1, &10)));
+    /// assert_eq!(iter.next(), Some((&2, &20)));
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[inline]
+    pub fn iter<'t, 'g>(&'t self, guard: &'g Guard) -> Iter<'t, 'g, K, V> {
+        Iter {
+            iter: self.tree_index.iter(guard),
+        }
+    }
+
+    /// Returns the range of internal keys associated with the key.
+    fn bounds(key: &K) -> RangeInclusive<(K, usize)> {
+        (key.clone(), 0)..=(key.clone(), usize::MAX)
+    }
+}
+
+impl<K, V> Debug for TreeMultiIndex<K, V>
+where
+    K: 'static + Clone + Debug + Ord,
+    V: 'static + Clone + Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = Guard::new();
+        f.debug_list().entries(self.iter(&guard)).finish()
+    }
+}
+
+impl<K, V> Default for TreeMultiIndex<K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    /// Creates a [`TreeMultiIndex`] with the default parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::TreeMultiIndex;
+    ///
+    /// let treemultiindex: TreeMultiIndex<u64, u32> = TreeMultiIndex::default();
+    /// ```
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'t, 'g, K, V> Debug for Iter<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").field("iter", &self.iter).finish()
+    }
+}
+
+impl<'t, 'g, K, V> Iterator for Iter<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    type Item = (&'g K, &'g V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|((k, _), v)| (k, v))
+    }
+}
+
+impl<'t, 'g, K, V> FusedIterator for Iter<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+}
+
+impl<'t, 'g, K, V> UnwindSafe for Iter<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord + UnwindSafe,
+    V: 'static + Clone + UnwindSafe,
+{
+}
+
+impl<'t, 'g, K, V> Debug for Values<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Values")
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl<'t, 'g, K, V> Iterator for Values<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+    type Item = &'g V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|(_, v)| v)
+    }
+}
+
+impl<'t, 'g, K, V> FusedIterator for Values<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord,
+    V: 'static + Clone,
+{
+}
+
+impl<'t, 'g, K, V> UnwindSafe for Values<'t, 'g, K, V>
+where
+    K: 'static + Clone + Ord + UnwindSafe,
+    V: 'static + Clone + UnwindSafe,
+{
+}
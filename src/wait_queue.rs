@@ -1,10 +1,11 @@
+use crate::atomic::AtomicUsize;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::{AcqRel, Relaxed};
 use std::sync::{Condvar, Mutex};
 use std::task::{Context, Poll, Waker};
 use std::thread;
+use std::time::Instant;
 
 use crate::ebr::Guard;
 
@@ -24,9 +25,27 @@ pub(crate) struct WaitQueue {
 }
 
 impl WaitQueue {
+    /// Creates a new [`WaitQueue`].
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Self {
+            wait_queue: AtomicUsize::new(0),
+        }
+    }
+
     /// Waits for the condition to be met or signaled.
     #[inline]
     pub(crate) fn wait_sync<T, F: FnOnce() -> Result<T, ()>>(&self, f: F) -> Result<T, ()> {
+        self.wait_sync_until(f, None)
+    }
+
+    /// Waits for the condition to be met or signaled until the deadline is reached.
+    #[inline]
+    pub(crate) fn wait_sync_until<T, F: FnOnce() -> Result<T, ()>>(
+        &self,
+        f: F,
+        deadline: Option<Instant>,
+    ) -> Result<T, ()> {
         let mut current = self.wait_queue.load(Relaxed);
         let mut entry = SyncWait::new(current);
         let mut entry_mut = Pin::new(&mut entry);
@@ -47,7 +66,11 @@ impl WaitQueue {
             self.signal();
         }
 
-        entry_mut.wait();
+        if !entry_mut.wait(deadline) {
+            // Pull `entry` out of the `WaitQueue` since another thread may be reading it.
+            self.signal();
+            entry_mut.wait(None);
+        }
         result
     }
 
@@ -281,13 +304,29 @@ impl SyncWait {
         }
     }
 
-    /// Waits for a signal.
-    fn wait(&self) {
+    /// Waits for a signal until the deadline is reached.
+    ///
+    /// Returns `false` if the deadline was reached before receiving a signal.
+    fn wait(&self, deadline: Option<Instant>) -> bool {
         #[allow(clippy::mutex_atomic)]
         let mut completed = unsafe { self.mutex.lock().unwrap_unchecked() };
         while !*completed {
-            completed = unsafe { self.condvar.wait(completed).unwrap_unchecked() };
+            if let Some(deadline) = deadline {
+                let now = Instant::now();
+                if now >= deadline {
+                    return false;
+                }
+                completed = unsafe {
+                    self.condvar
+                        .wait_timeout(completed, deadline - now)
+                        .unwrap_unchecked()
+                        .0
+                };
+            } else {
+                completed = unsafe { self.condvar.wait(completed).unwrap_unchecked() };
+            }
         }
+        true
     }
 
     /// Sends a signal.
@@ -349,6 +388,34 @@ mod test {
             .for_each(|t| assert!(t.join().is_ok()));
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_sync_timeout() {
+        let num_tasks = 8;
+        let barrier = Arc::new(Barrier::new(num_tasks));
+        let wait_queue = Arc::new(WaitQueue::default());
+        let mut task_handles = Vec::with_capacity(num_tasks);
+        for task_id in 0..num_tasks {
+            let barrier_clone = barrier.clone();
+            let wait_queue_clone = wait_queue.clone();
+            task_handles.push(std::thread::spawn(move || {
+                barrier_clone.wait();
+                for _ in 0..16 {
+                    let deadline = Instant::now() + std::time::Duration::from_micros(100);
+                    assert!(wait_queue_clone
+                        .wait_sync_until(|| Err::<(), ()>(()), Some(deadline))
+                        .is_err());
+                    if task_id % 2 == 0 {
+                        wait_queue_clone.signal();
+                    }
+                }
+            }));
+        }
+        task_handles
+            .into_iter()
+            .for_each(|t| assert!(t.join().is_ok()));
+    }
+
     #[cfg_attr(miri, ignore)]
     #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
     async fn wait_queue_async() {
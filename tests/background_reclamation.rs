@@ -0,0 +1,56 @@
+//! Background reclamation changes the process-wide state of the garbage collector, therefore it is
+//! tested in a separate test binary.
+
+use scc::ebr::{set_background_reclamation, Guard, Shared};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+
+fn is_reclaimer_thread() -> bool {
+    std::thread::current().name() == Some("scc-ebr-reclaimer")
+}
+
+fn release_and_wait<T: 'static>(instance: T, dropped: &AtomicBool) {
+    let shared = Shared::new(instance);
+    let guard = Guard::new();
+    assert!(shared.release(&guard));
+    drop(guard);
+
+    while !dropped.load(Relaxed) {
+        let guard = Guard::new();
+        guard.accelerate();
+        drop(guard);
+        std::thread::yield_now();
+    }
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn background_reclamation() {
+    static PANICKED_IN_BACKGROUND: AtomicBool = AtomicBool::new(false);
+    static DROPPED_IN_BACKGROUND: AtomicBool = AtomicBool::new(false);
+
+    struct P;
+    impl Drop for P {
+        fn drop(&mut self) {
+            if is_reclaimer_thread() {
+                PANICKED_IN_BACKGROUND.store(true, Relaxed);
+                panic!("the reclaimer thread has to survive it");
+            }
+        }
+    }
+
+    struct B;
+    impl Drop for B {
+        fn drop(&mut self) {
+            DROPPED_IN_BACKGROUND.store(is_reclaimer_thread(), Relaxed);
+        }
+    }
+
+    assert!(!set_background_reclamation(true));
+    let thread_handle = std::thread::spawn(|| {
+        release_and_wait(P, &PANICKED_IN_BACKGROUND);
+        release_and_wait(B, &DROPPED_IN_BACKGROUND);
+    });
+    assert!(thread_handle.join().is_ok());
+    assert!(set_background_reclamation(false));
+}
@@ -0,0 +1,42 @@
+//! The garbage limit is a process-wide setting of the garbage collector, therefore it is tested in
+//! a separate test binary.
+
+use scc::ebr::{set_garbage_limit, statistics, Guard, Shared};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn garbage_limit() {
+    static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+    struct D;
+    impl Drop for D {
+        fn drop(&mut self) {
+            DESTROYED.store(true, Relaxed);
+        }
+    }
+
+    set_garbage_limit(0, 0);
+    let shared = Shared::new(D);
+    let guard = Guard::new();
+    assert!(shared.release(&guard));
+    drop(guard);
+
+    while !DESTROYED.load(Relaxed) {
+        drop(Guard::new());
+    }
+
+    // The global epoch is updated without dropping the `Guard`.
+    loop {
+        let guard = Guard::new();
+        let Some(epoch) = statistics().map(|s| s.epoch()) else {
+            continue;
+        };
+        assert!(Shared::new(1).release(&guard));
+        if statistics().map_or(false, |s| s.epoch() != epoch) {
+            break;
+        }
+    }
+    set_garbage_limit(usize::MAX, usize::MAX);
+}